@@ -106,7 +106,7 @@ impl<const N_BITS: u32, const LIMB_SIZE: u32> BigIntImpl<N_BITS, LIMB_SIZE> {
 
             // When we got {limb} {base} {carry} on the stack, we drop the base
             OP_NIP // {limb} {carry}
-            { n + 9 } OP_PICK { limb_double_with_carry_allow_overflow(Self::HEAD_OFFSET) }
+            { n + Self::N_LIMBS } OP_PICK { limb_double_with_carry_allow_overflow(Self::HEAD_OFFSET) }
 
             // Take all limbs from the alt stack to the main stack
             for _ in 0..Self::N_LIMBS - 1 {
@@ -493,6 +493,7 @@ fn limb_lshift_with_carry_prevent_overflow(bits: u32, head: u32) -> Script {
 #[cfg(test)]
 mod test {
     use crate::bigint::{U254, U64};
+    use crate::stack_effect::assert_stack_effect;
     use crate::treepp::*;
     use core::ops::{Rem, Shl};
     use num_bigint::{BigUint, RandomBits};
@@ -503,6 +504,8 @@ mod test {
     #[test]
     fn test_add() {
         println!("U254.add: {} bytes", U254::add(1, 0).len());
+        assert_stack_effect(U254::add(1, 0), 18, 9);
+        assert_stack_effect(U254::add(2, 0), 27, 18);
         let mut prng = ChaCha20Rng::seed_from_u64(0);
         for _ in 0..100 {
             let a: BigUint = prng.sample(RandomBits::new(254));
@@ -540,6 +543,7 @@ mod test {
     #[test]
     fn test_double() {
         println!("U254.double: {} bytes", U254::double(0).len());
+        assert_stack_effect(U254::double(0), 9, 9);
         let mut prng = ChaCha20Rng::seed_from_u64(0);
         for _ in 0..100 {
             let a: BigUint = prng.sample(RandomBits::new(254));
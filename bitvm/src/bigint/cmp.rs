@@ -109,6 +109,7 @@ impl<const N_BITS: u32, const LIMB_SIZE: u32> BigIntImpl<N_BITS, LIMB_SIZE> {
 #[cfg(test)]
 mod test {
     use crate::bigint::{U254, U64};
+    use crate::stack_effect::assert_stack_effect;
     use crate::treepp::*;
     use core::cmp::Ordering;
     use num_bigint::{BigUint, RandomBits};
@@ -117,6 +118,9 @@ mod test {
 
     #[test]
     fn test_u254_cmp() {
+        assert_stack_effect(U254::lessthan(1, 0), 18, 1);
+        assert_stack_effect(U254::equal(1, 0), 18, 1);
+        assert_stack_effect(U254::equalverify(1, 0), 18, 0);
         let mut prng = ChaCha20Rng::seed_from_u64(2);
 
         for _ in 0..100 {
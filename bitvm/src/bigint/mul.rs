@@ -42,6 +42,7 @@ impl<const N_BITS: u32, const LIMB_SIZE: u32> BigIntImpl<N_BITS, LIMB_SIZE> {
 #[cfg(test)]
 mod test {
     use crate::bigint::{U254, U64};
+    use crate::stack_effect::assert_stack_effect;
     use crate::treepp::*;
     use core::ops::{Mul, Rem, Shl};
     use num_bigint::{BigUint, RandomBits};
@@ -51,6 +52,7 @@ mod test {
 
     #[test]
     fn test_mul() {
+        assert_stack_effect(U254::mul(), 18, 9);
         let mut prng = ChaCha20Rng::seed_from_u64(0);
         for _ in 0..3 {
             let a: BigUint = prng.sample(RandomBits::new(254));
@@ -69,6 +69,7 @@ pub fn limb_sub_noborrow(head_offset: u32) -> Script {
 #[cfg(test)]
 mod test {
     use crate::bigint::{U254, U64};
+    use crate::stack_effect::assert_stack_effect;
     use crate::treepp::*;
     use core::ops::{Rem, Shl};
     use num_bigint::{BigUint, RandomBits};
@@ -78,6 +79,7 @@ mod test {
 
     #[test]
     fn test_sub() {
+        assert_stack_effect(U254::sub(1, 0), 18, 9);
         let mut prng = ChaCha20Rng::seed_from_u64(0);
 
         for _ in 0..100 {
@@ -0,0 +1,57 @@
+use ark_serialize::CanonicalDeserialize;
+use bitvm::profiler::{compare_profiles, profile_chunk_scripts, profile_gadgets, GadgetProfile};
+use clap::{arg, command, value_parser};
+use std::{error::Error, fs, process::ExitCode};
+
+fn main() -> Result<ExitCode, Box<dyn Error>> {
+    let matches = command!()
+        .about("Profile the compiled size, maximum stack size and opcodes of the main gadgets")
+        .arg(arg!(--vk <FILE> "Uncompressed Groth16 verifying key to also profile the chunk scripts of").required(false))
+        .arg(arg!(-o --output <FILE> "Write the profiles to a JSON file instead of stdout").required(false))
+        .arg(arg!(--baseline <FILE> "Compare the profiles against a JSON baseline").required(false))
+        .arg(
+            arg!(--threshold <PERCENT> "Allowed growth in percent before a gadget counts as a regression")
+                .required(false)
+                .default_value("1")
+                .value_parser(value_parser!(f64)),
+        )
+        .get_matches();
+
+    let mut profiles = profile_gadgets();
+    if let Some(path) = matches.get_one::<String>("vk") {
+        let vk = ark_groth16::VerifyingKey::<ark_bn254::Bn254>::deserialize_uncompressed(
+            fs::read(path)?.as_slice(),
+        )?;
+        profiles.extend(profile_chunk_scripts(&vk));
+    }
+
+    let failed = profiles
+        .iter()
+        .filter(|profile| profile.success == Some(false))
+        .collect::<Vec<_>>();
+    for profile in &failed {
+        eprintln!("{}: failed on its sample inputs", profile.name);
+    }
+
+    let json = serde_json::to_string_pretty(&profiles)?;
+    match matches.get_one::<String>("output") {
+        Some(path) => fs::write(path, json)?,
+        None => println!("{}", json),
+    }
+
+    if let Some(path) = matches.get_one::<String>("baseline") {
+        let baseline: Vec<GadgetProfile> = serde_json::from_slice(&fs::read(path)?)?;
+        let threshold = *matches.get_one::<f64>("threshold").unwrap();
+        let comparison = compare_profiles(&baseline, &profiles, threshold);
+        eprint!("{}", comparison);
+        if !comparison.is_clean() {
+            return Ok(ExitCode::FAILURE);
+        }
+    }
+
+    if failed.is_empty() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
@@ -0,0 +1,193 @@
+//! Bitcoin block header verification in script.
+//!
+//! Headers and block hashes are kept as bytes on the stack with the first byte on top, i.e. in
+//! the consensus serialization order. The block hash is therefore in its internal byte order,
+//! which is the reverse of its hex display.
+
+use bitcoin::{block::Header, consensus::serialize, hashes::Hash, BlockHash};
+
+use crate::hash::sha256::{sha256, sha256_80bytes};
+use crate::treepp::{script, Script};
+
+/// Byte length of a serialized block header
+pub const HEADER_LEN: usize = 80;
+
+/// Offset of the previous block hash in a serialized block header
+const PREV_BLOCKHASH_OFFSET: usize = 4;
+
+/// Offset of `nBits` in a serialized block header
+const BITS_OFFSET: usize = 72;
+
+/// Pushes the serialized header with its first byte on top.
+pub fn push_block_header(header: &Header) -> Script {
+    let bytes = serialize(header);
+    script! {
+        for byte in bytes.iter().rev() {
+            { *byte }
+        }
+    }
+}
+
+/// Pushes the block hash in its internal byte order with its first byte on top.
+pub fn push_block_hash(hash: &BlockHash) -> Script {
+    script! {
+        for byte in hash.as_byte_array().iter().rev() {
+            { *byte }
+        }
+    }
+}
+
+/// Double SHA-256 of the header on top of the stack
+/// input: [header (80 bytes)]
+/// output: [block hash (32 bytes)]
+pub fn block_hash() -> Script {
+    script! {
+        {sha256_80bytes()}
+        {sha256(32)}
+    }
+}
+
+/// Verifies the proof of work of the header on top of the stack, i.e. that its hash is at most
+/// the target encoded in its `nBits`. Targets that are negative or do not fit into 256 bits are
+/// rejected. Whether `nBits` is the expected difficulty is not checked.
+///
+/// If `check_prev_blockhash` is set, also verifies that the header's previous block hash equals
+/// the block hash below the header, for example the output of this script for the previous
+/// header.
+///
+/// input: [prev block hash (32 bytes, only if `check_prev_blockhash`), header (80 bytes)]
+/// output: [block hash (32 bytes)]
+pub fn verify_block_header(check_prev_blockhash: bool) -> Script {
+    script! {
+        if check_prev_blockhash {
+            for i in 0..32 {
+                {PREV_BLOCKHASH_OFFSET + i} OP_PICK
+                {HEADER_LEN + 1} OP_ROLL
+                OP_EQUALVERIFY
+            }
+        }
+
+        // copy nBits to the alt stack, the least significant byte on top
+        for i in (BITS_OFFSET..BITS_OFFSET + 4).rev() {
+            {i} OP_PICK
+            OP_TOALTSTACK
+        }
+
+        {block_hash()}
+        for _ in 0..4 {
+            OP_FROMALTSTACK
+        }
+        // [hash, m0, m1, m2, exponent]
+        OP_SWAP OP_2SWAP OP_SWAP
+        // [hash, exponent, m2, m1, m0]
+
+        // the target must be non-negative and fit into 256 bits
+        2 OP_PICK 0x80 OP_LESSTHAN OP_VERIFY
+        3 OP_PICK 33 OP_LESSTHAN OP_VERIFY
+
+        // compare from the least significant byte on: hash <= target holds up to byte i if
+        // hash[i] < target[i] or hash[i] == target[i] and it holds up to byte i - 1
+        OP_TRUE
+        for i in 0..32 {
+            // [hash, exponent, m2, m1, m0, result]
+            {5 + i} OP_PICK
+
+            // target[i] is the mantissa byte i + 3 - exponent, or zero if there is none
+            {i + 3} 6 OP_PICK OP_SUB
+            OP_DUP 0 3 OP_WITHIN
+            OP_IF
+                2 OP_ADD OP_PICK
+            OP_ELSE
+                OP_DROP 0
+            OP_ENDIF
+
+            // [hash, exponent, m2, m1, m0, result, hash[i], target[i]]
+            OP_2DUP OP_LESSTHAN OP_TOALTSTACK
+            OP_EQUAL OP_BOOLAND
+            OP_FROMALTSTACK OP_BOOLOR
+        }
+        OP_VERIFY
+        OP_2DROP OP_2DROP
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::treepp::{execute_script, run};
+    use bitcoin::hex::FromHex;
+    use bitcoin::{blockdata::constants::genesis_block, consensus::deserialize, Network};
+
+    fn block_1_header() -> Header {
+        deserialize(&Vec::<u8>::from_hex("010000006fe28c0ab6f1b372c1a6a246ae63f74f931e8365e15a089c68d6190000000000982051fd1e4ba744bbbe680e1fee14677ba1a3c3540bf7b1cdb606e857233e0e61bc6649ffff001d01e36299").unwrap()).unwrap()
+    }
+
+    fn verify_block_hash(hash: &BlockHash) -> Script {
+        script! {
+            {push_block_hash(hash)}
+            for i in 0..32 {
+                {32 - i} OP_ROLL OP_EQUALVERIFY
+            }
+        }
+    }
+
+    #[test]
+    fn test_block_hash() {
+        let header = genesis_block(Network::Bitcoin).header;
+        run(script! {
+            {push_block_header(&header)}
+            {block_hash()}
+            {verify_block_hash(&header.block_hash())}
+            OP_TRUE
+        });
+    }
+
+    #[test]
+    fn test_verify_block_header() {
+        println!(
+            "verify_block_header: {} bytes",
+            verify_block_header(true).len()
+        );
+        let genesis = genesis_block(Network::Bitcoin).header;
+        let header = block_1_header();
+        assert_eq!(header.prev_blockhash, genesis.block_hash());
+
+        // the output of the previous header is the input for the next one
+        run(script! {
+            {push_block_header(&genesis)}
+            {verify_block_header(false)}
+            {push_block_header(&header)}
+            {verify_block_header(true)}
+            {verify_block_hash(&header.block_hash())}
+            OP_TRUE
+        });
+
+        // wrong previous block hash
+        let result = execute_script(script! {
+            {push_block_hash(&header.block_hash())}
+            {push_block_header(&header)}
+            {verify_block_header(true)}
+            for _ in 0..32 {
+                OP_DROP
+            }
+            OP_TRUE
+        });
+        assert!(!result.success);
+
+        // insufficient proof of work
+        let mut invalid_header = header;
+        invalid_header.nonce += 1;
+        assert!(invalid_header
+            .validate_pow(invalid_header.target())
+            .is_err());
+        let result = execute_script(script! {
+            {push_block_header(&invalid_header)}
+            {verify_block_header(false)}
+            for _ in 0..32 {
+                OP_DROP
+            }
+            OP_TRUE
+        });
+        assert!(!result.success);
+    }
+}
@@ -3,6 +3,7 @@ use crate::bigint::sub::limb_sub_borrow;
 use crate::bigint::U254;
 use crate::bn254::fq::Fq;
 use crate::bn254::utils::Hint;
+use crate::source_map::labeled;
 use crate::treepp::*;
 use ark_ff::PrimeField;
 use bitcoin_script::script;
@@ -16,6 +17,9 @@ pub trait Fp254Impl {
     const N_LIMBS: u32 = U254::N_LIMBS;
     const N_BITS: u32 = U254::N_BITS;
 
+    /// Name of the field, used to label scripts.
+    const NAME: &'static str;
+
     const MODULUS: &'static str;
     const MODULUS_LIMBS: [u32; U254::N_LIMBS as usize];
 
@@ -594,7 +598,10 @@ pub trait Fp254Impl {
         };
         hints.push(Hint::BigIntegerTmulLC1(q));
 
-        (script, hints)
+        (
+            labeled(&format!("{}::hinted_mul", Self::NAME), script),
+            hints,
+        )
     }
 
     // TODO: Optimize by using the constant feature
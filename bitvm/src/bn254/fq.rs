@@ -10,6 +10,8 @@ use crate::treepp::*;
 pub struct Fq;
 
 impl Fp254Impl for Fq {
+    const NAME: &'static str = "Fq";
+
     const MODULUS: &'static str =
         "30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47";
 
@@ -425,6 +427,7 @@ fp_lc_mul!(Mul4LC, 3, 3, [true, true, true, true]);
 mod test {
     use super::*;
     use crate::bn254::fq::Fq;
+    use crate::stack_effect::assert_stack_effect;
     use crate::{bn254::fp254impl::Fp254Impl, ExecuteInfo};
     use ark_ff::AdditiveGroup;
     use ark_ff::Field;
@@ -463,6 +466,7 @@ mod test {
     #[test]
     fn test_add() {
         println!("Fq.add: {} bytes", Fq::add(0, 1).len());
+        assert_stack_effect(Fq::add(1, 0), 18, 9);
 
         let m = BigUint::from_str_radix(Fq::MODULUS, 16).unwrap();
 
@@ -3,6 +3,7 @@ use crate::bn254::fq::Fq;
 use crate::bn254::fq2::Fq2;
 use crate::bn254::fq6::Fq6;
 use crate::bn254::utils::Hint;
+use crate::source_map::labeled;
 use crate::treepp::{script, Script};
 use ark_ff::{Field, Fp12Config};
 use num_bigint::BigUint;
@@ -146,7 +147,7 @@ impl Fq12 {
         hints.extend(hint2);
         hints.extend(hint3);
 
-        (script, hints)
+        (labeled("Fq12::hinted_mul", script), hints)
     }
 
     // input:
@@ -288,6 +289,7 @@ impl Fq12 {
 mod test {
     use crate::bn254::fq12::Fq12;
     use crate::bn254::fq2::Fq2;
+    use crate::stack_effect::assert_stack_effect;
     use crate::treepp::*;
     use ark_ff::AdditiveGroup;
     use ark_ff::Field;
@@ -299,6 +301,8 @@ mod test {
     #[test]
     fn test_bn254_fq12_add() {
         println!("Fq12.add: {} bytes", Fq12::add(12, 0).len());
+        assert_stack_effect(Fq12::add(12, 0), 216, 108);
+        assert_stack_effect(Fq12::sub(12, 0), 216, 108);
         let mut prng = ChaCha20Rng::seed_from_u64(0);
 
         for _ in 0..50 {
@@ -321,6 +325,7 @@ mod test {
     #[test]
     fn test_bn254_fq12_double() {
         println!("Fq12.double: {} bytes", Fq12::double(0).len());
+        assert_stack_effect(Fq12::double(0), 108, 108);
         let mut prng = ChaCha20Rng::seed_from_u64(0);
 
         for _ in 0..50 {
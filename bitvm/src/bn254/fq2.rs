@@ -1,6 +1,7 @@
 use crate::bn254::fp254impl::Fp254Impl;
 use crate::bn254::fq::Fq;
 use crate::bn254::utils::Hint;
+use crate::source_map::labeled;
 use crate::treepp::{script, Script};
 use ark_ff::Fp2Config;
 use num_bigint::BigUint;
@@ -189,7 +190,7 @@ impl Fq2 {
         hints.extend(hint1);
         hints.extend(hint2);
 
-        (script, hints)
+        (labeled("Fq2::hinted_mul", script), hints)
     }
 
     // Given Fq2 elements a and b, compute their product
@@ -355,6 +356,7 @@ impl Fq2 {
 #[cfg(test)]
 mod test {
     use crate::bn254::fq2::Fq2;
+    use crate::stack_effect::assert_stack_effect;
     use crate::treepp::*;
     use ark_ff::AdditiveGroup;
     use ark_ff::Field;
@@ -366,6 +368,7 @@ mod test {
     #[test]
     fn test_bn254_fq2_add() {
         println!("Fq2.add: {} bytes", Fq2::add(2, 0).len());
+        assert_stack_effect(Fq2::add(2, 0), 36, 18);
         let mut prng = ChaCha20Rng::seed_from_u64(0);
 
         for _ in 0..50 {
@@ -398,6 +401,8 @@ mod test {
     #[test]
     fn test_bn254_fq2_sub() {
         println!("Fq2.sub: {} bytes", Fq2::sub(2, 0).len());
+        assert_stack_effect(Fq2::sub(2, 0), 36, 18);
+        assert_stack_effect(Fq2::sub(0, 2), 36, 18);
         let mut prng = ChaCha20Rng::seed_from_u64(0);
 
         for _ in 0..50 {
@@ -430,6 +435,8 @@ mod test {
     #[test]
     fn test_bn254_fq2_double() {
         println!("Fq2.double: {} bytes", Fq2::double(0).len());
+        assert_stack_effect(Fq2::double(0), 18, 18);
+        assert_stack_effect(Fq2::neg(0), 18, 18);
         let mut prng = ChaCha20Rng::seed_from_u64(0);
 
         for _ in 0..50 {
@@ -583,6 +583,7 @@ impl Fq6 {
 mod test {
     use crate::bn254::fq2::Fq2;
     use crate::bn254::fq6::Fq6;
+    use crate::stack_effect::assert_stack_effect;
     use crate::treepp::*;
     use ark_ff::AdditiveGroup;
     use ark_ff::Field;
@@ -594,6 +595,7 @@ mod test {
     #[test]
     fn test_bn254_fq6_add() {
         println!("Fq6.add: {} bytes", Fq6::add(6, 0).len());
+        assert_stack_effect(Fq6::add(6, 0), 108, 54);
         let mut prng = ChaCha20Rng::seed_from_u64(0);
 
         for _ in 0..50 {
@@ -616,6 +618,8 @@ mod test {
     #[test]
     fn test_bn254_fq6_sub() {
         println!("Fq6.sub: {} bytes", Fq6::sub(6, 0).len());
+        assert_stack_effect(Fq6::sub(6, 0), 108, 54);
+        assert_stack_effect(Fq6::sub(0, 6), 108, 54);
         let mut prng = ChaCha20Rng::seed_from_u64(0);
 
         for _ in 0..50 {
@@ -648,6 +652,9 @@ mod test {
     #[test]
     fn test_bn254_fq6_double() {
         println!("Fq6.double: {} bytes", Fq6::double(0).len());
+        assert_stack_effect(Fq6::double(0), 54, 54);
+        assert_stack_effect(Fq6::neg(0), 54, 54);
+        assert_stack_effect(Fq6::mul_fq2_by_nonresidue(), 18, 18);
         let mut prng = ChaCha20Rng::seed_from_u64(0);
 
         for _ in 0..50 {
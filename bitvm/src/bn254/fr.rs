@@ -9,6 +9,8 @@ use crate::treepp::Script;
 pub struct Fr;
 
 impl Fp254Impl for Fr {
+    const NAME: &'static str = "Fr";
+
     const MODULUS: &'static str =
         "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001";
 
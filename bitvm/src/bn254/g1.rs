@@ -183,7 +183,7 @@ impl G1Affine {
         element
     }
 
-     pub fn hinted_check_add_prevent_degenerate(
+    pub fn hinted_check_add_prevent_degenerate(
         t: ark_bn254::G1Affine,
         q: ark_bn254::G1Affine,
     ) -> (Script, Vec<Hint>) {
@@ -656,6 +656,7 @@ mod test {
     use crate::bn254::g2::G2Affine;
 
     use super::*;
+    use crate::stack_effect::assert_stack_effect;
     use crate::{treepp::*, ExecuteInfo};
     use ark_ec::CurveGroup;
     use ark_ff::Field;
@@ -944,6 +945,7 @@ mod test {
     fn test_affine_equalverify() {
         let equalverify = G1Affine::equalverify();
         println!("G1Affine.equalverify: {} bytes", equalverify.len());
+        assert_stack_effect(equalverify.clone(), 36, 0);
 
         let mut prng = ChaCha20Rng::seed_from_u64(0);
 
@@ -964,6 +966,15 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_affine_stack_effect() {
+        assert_stack_effect(G1Affine::identity(), 0, 18);
+        assert_stack_effect(G1Affine::is_zero(), 18, 1);
+        assert_stack_effect(G1Affine::is_zero_keep_element(), 18, 19);
+        assert_stack_effect(G1Affine::roll(1), 36, 36);
+        assert_stack_effect(G1Affine::drop(), 18, 0);
+    }
+
     #[test]
     fn test_hinted_affine_is_on_curve() {
         let mut prng = ChaCha20Rng::seed_from_u64(0);
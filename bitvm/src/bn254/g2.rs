@@ -5,6 +5,7 @@ use crate::bn254::fp254impl::Fp254Impl;
 use crate::bn254::fq::Fq;
 use crate::bn254::fq12::Fq12;
 use crate::bn254::fq2::Fq2;
+use crate::source_map::labeled;
 use crate::treepp::{script, Script};
 use ark_ec::bn::BnConfig;
 use ark_ec::AffineRepr;
@@ -534,7 +535,7 @@ pub fn hinted_affine_add_line(
     hints.extend(hint0);
     hints.extend(hint1);
 
-    (script, hints)
+    (labeled("g2::hinted_affine_add_line", script), hints)
 }
 
 /// double a point T:
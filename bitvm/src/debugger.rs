@@ -0,0 +1,522 @@
+//! Step-through execution of scripts, for finding where a failing script diverges from a
+//! passing one.
+//!
+//! Breakpoints are placed with [`breakpoint`], a named no-op marker that can be dropped into any
+//! `script!`:
+//!
+//! ```ignore
+//! script! {
+//!     { Fq::mul() }
+//!     { breakpoint("after_mul") }
+//!     { Fq::add(1, 0) }
+//! }
+//! ```
+
+use core::fmt;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use bitcoin::{hex::DisplayHex, script::Instruction, Script, ScriptBuf};
+use bitcoin_scriptexec::{Exec, Stack};
+
+use crate::{execute_info, new_exec, treepp, ExecuteInfo};
+
+/// Prefix of the data pushed by a breakpoint marker, followed by the name of the breakpoint.
+const BREAKPOINT_PREFIX: &[u8] = b"bitvm-breakpoint:";
+
+/// A named breakpoint. Pushes its name and drops it, so it does not change the result of the
+/// script, only its size.
+pub fn breakpoint(name: &str) -> treepp::Script {
+    let marker = [BREAKPOINT_PREFIX, name.as_bytes()].concat();
+    // MAX_SCRIPT_ELEMENT_SIZE
+    assert!(marker.len() <= 520, "Breakpoint name is too long");
+    treepp::script! {
+        { marker }
+        OP_DROP
+    }
+}
+
+/// Returns the name of the breakpoint at the start of `script`, if there is one.
+fn breakpoint_name(script: &Script) -> Option<String> {
+    let mut instructions = script.instructions();
+    let Some(Ok(Instruction::PushBytes(push))) = instructions.next() else {
+        return None;
+    };
+    let name = push.as_bytes().strip_prefix(BREAKPOINT_PREFIX)?;
+    match instructions.next() {
+        Some(Ok(Instruction::Op(bitcoin::opcodes::all::OP_DROP))) => {
+            Some(String::from_utf8_lossy(name).into_owned())
+        }
+        _ => None,
+    }
+}
+
+/// An executed instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep {
+    /// Byte offset of the instruction in the script.
+    pub offset: usize,
+    /// The instruction, in ASM.
+    pub instruction: String,
+}
+
+/// A copy of the stack and the altstack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stacks {
+    pub stack: Vec<Vec<u8>>,
+    pub altstack: Vec<Vec<u8>>,
+}
+
+impl Stacks {
+    fn of(exec: &Exec) -> Self {
+        Self {
+            stack: snapshot(exec.stack()),
+            altstack: snapshot(exec.altstack()),
+        }
+    }
+}
+
+/// The stacks when a breakpoint marker is reached, before the marker is executed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breakpoint {
+    pub name: String,
+    /// Index of the first step of the marker.
+    pub step: usize,
+    pub stacks: Stacks,
+}
+
+/// An execution trace of a script.
+///
+/// Only the executed instructions and the stacks at the breakpoints are recorded. The stacks
+/// after any other step are rebuilt on demand by executing the script again, see `stacks_after`,
+/// so tracing scripts with large stacks doesn't copy the whole stack at every opcode.
+#[derive(Debug)]
+pub struct Trace {
+    pub steps: Vec<TraceStep>,
+    pub breakpoints: Vec<Breakpoint>,
+    pub info: ExecuteInfo,
+    script: ScriptBuf,
+    witness: Vec<Vec<u8>>,
+    stack_limit: bool,
+}
+
+impl Trace {
+    /// Returns the first breakpoint with the given name.
+    pub fn breakpoint(&self, name: &str) -> Option<&Breakpoint> {
+        self.breakpoints
+            .iter()
+            .find(|breakpoint| breakpoint.name == name)
+    }
+
+    /// Returns the last breakpoint reached before `step`.
+    pub fn last_breakpoint_before(&self, step: usize) -> Option<&Breakpoint> {
+        self.breakpoints
+            .iter()
+            .rev()
+            .find(|breakpoint| breakpoint.step <= step)
+    }
+
+    /// Returns the stacks after executing `step`, `None` if the trace ended before it.
+    pub fn stacks_after(&self, step: usize) -> Option<Stacks> {
+        if step >= self.steps.len() {
+            return None;
+        }
+        let mut exec = self.replay();
+        for _ in 0..=step {
+            let _ = exec.exec_next();
+        }
+        Some(Stacks::of(&exec))
+    }
+
+    /// Writes the trace, one line per step with the stacks after it, to `path`.
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        write!(writer, "{}", self)?;
+        writer.flush()
+    }
+
+    /// Creates an executor at the start of the traced script.
+    fn replay(&self) -> Exec {
+        new_exec(self.script.clone(), self.witness.clone(), self.stack_limit)
+    }
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut exec = self.replay();
+        let mut breakpoints = self.breakpoints.iter().peekable();
+        for (index, step) in self.steps.iter().enumerate() {
+            while let Some(breakpoint) = breakpoints.next_if(|b| b.step == index) {
+                writeln!(f, "# breakpoint {}", breakpoint.name)?;
+            }
+            let _ = exec.exec_next();
+            writeln!(
+                f,
+                "{}\t{}\t{}\tstack: {}\taltstack: {}",
+                index,
+                step.offset,
+                step.instruction,
+                FmtItems(&snapshot(exec.stack())),
+                FmtItems(&snapshot(exec.altstack()))
+            )?;
+        }
+        write!(f, "{}", self.info)
+    }
+}
+
+/// Formats stack items the same way as `FmtStack`, on a single line.
+struct FmtItems<'a>(&'a [Vec<u8>]);
+
+impl fmt::Display for FmtItems<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        for (index, item) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            fmt_item(f, item)?;
+        }
+        write!(f, "]")
+    }
+}
+
+fn fmt_item(f: &mut fmt::Formatter<'_>, item: &[u8]) -> fmt::Result {
+    if item.is_empty() {
+        write!(f, "[]")
+    } else {
+        let mut item = item.to_vec();
+        item.reverse();
+        write!(f, "0x{}", item.as_hex())
+    }
+}
+
+fn snapshot(stack: &Stack) -> Vec<Vec<u8>> {
+    stack.iter_str().collect()
+}
+
+fn stacks_equal(a: &Exec, b: &Exec) -> bool {
+    a.stack().len() == b.stack().len()
+        && a.altstack().len() == b.altstack().len()
+        && a.stack().iter_str().eq(b.stack().iter_str())
+        && a.altstack().iter_str().eq(b.altstack().iter_str())
+}
+
+/// Executes the script step by step, recording the executed instructions and breakpoints.
+pub fn trace_script(script: treepp::Script) -> Trace {
+    trace_script_buf_with_inputs(script.compile(), vec![])
+}
+
+/// Same as `trace_script`, starting with `witness` on the stack.
+pub fn trace_script_with_inputs(script: treepp::Script, witness: Vec<Vec<u8>>) -> Trace {
+    trace_script_buf_with_inputs(script.compile(), witness)
+}
+
+/// Same as `trace_script_with_inputs`, without the `MAX_STACK_SIZE` limit. Only for testing
+/// purposes, like `execute_script_without_stack_limit`.
+pub fn trace_script_without_stack_limit(script: treepp::Script, witness: Vec<Vec<u8>>) -> Trace {
+    trace_script_buf_optional_stack_limit(script.compile(), witness, false)
+}
+
+pub fn trace_script_buf_with_inputs(script: ScriptBuf, witness: Vec<Vec<u8>>) -> Trace {
+    trace_script_buf_optional_stack_limit(script, witness, true)
+}
+
+pub fn trace_script_buf_optional_stack_limit(
+    script: ScriptBuf,
+    witness: Vec<Vec<u8>>,
+    stack_limit: bool,
+) -> Trace {
+    let script_len = script.len();
+    let mut exec = new_exec(script.clone(), witness.clone(), stack_limit);
+
+    let mut steps = vec![];
+    let mut breakpoints = vec![];
+    loop {
+        let remaining_script = exec.remaining_script();
+        let offset = script_len - remaining_script.len();
+        let next_instruction = remaining_script
+            .instructions()
+            .next()
+            .and_then(Result::ok)
+            .map(|instruction| instruction_to_asm(&instruction));
+        let Some(instruction) = next_instruction else {
+            // Let the executor report the end of the script or the decoding error.
+            let _ = exec.exec_next();
+            break;
+        };
+        if let Some(name) = breakpoint_name(remaining_script) {
+            breakpoints.push(Breakpoint {
+                name,
+                step: steps.len(),
+                stacks: Stacks::of(&exec),
+            });
+        }
+
+        let result = exec.exec_next();
+        steps.push(TraceStep {
+            offset,
+            instruction,
+        });
+        if result.is_err() {
+            break;
+        }
+    }
+
+    Trace {
+        steps,
+        breakpoints,
+        info: execute_info(&exec),
+        script,
+        witness,
+        stack_limit,
+    }
+}
+
+fn instruction_to_asm(instruction: &Instruction) -> String {
+    match instruction {
+        Instruction::Op(opcode) => opcode.to_string(),
+        Instruction::PushBytes(push) if push.is_empty() => "OP_0".to_string(),
+        Instruction::PushBytes(push) => {
+            format!("OP_PUSHBYTES_{} {}", push.len(), push.as_bytes().as_hex())
+        }
+    }
+}
+
+/// The first step at which two traces of the same script differ.
+#[derive(Debug)]
+pub struct TraceDiff<'a> {
+    pub step: usize,
+    /// The step of the expected trace, `None` if it ended before `step`.
+    pub expected: Option<&'a TraceStep>,
+    /// The step of the actual trace, `None` if it ended before `step`.
+    pub actual: Option<&'a TraceStep>,
+    /// The stacks of the expected trace after `step`, `None` if it ended before `step`.
+    pub expected_stacks: Option<Stacks>,
+    /// The stacks of the actual trace after `step`, `None` if it ended before `step`.
+    pub actual_stacks: Option<Stacks>,
+    /// The last breakpoint of the expected trace reached before the divergence.
+    pub last_breakpoint: Option<&'a Breakpoint>,
+}
+
+/// Compares an expected and an actual trace step by step. Returns `None` if they are identical.
+///
+/// Both scripts are executed again side by side, and the stacks are only copied at the step
+/// where they diverge.
+pub fn diff_traces<'a>(expected: &'a Trace, actual: &'a Trace) -> Option<TraceDiff<'a>> {
+    let (mut expected_exec, mut actual_exec) = (expected.replay(), actual.replay());
+    for step in 0..expected.steps.len().max(actual.steps.len()) {
+        let (expected_step, actual_step) = (expected.steps.get(step), actual.steps.get(step));
+        if expected_step.is_some() {
+            let _ = expected_exec.exec_next();
+        }
+        if actual_step.is_some() {
+            let _ = actual_exec.exec_next();
+        }
+        if expected_step == actual_step && stacks_equal(&expected_exec, &actual_exec) {
+            continue;
+        }
+        return Some(TraceDiff {
+            step,
+            expected: expected_step,
+            actual: actual_step,
+            expected_stacks: expected_step.map(|_| Stacks::of(&expected_exec)),
+            actual_stacks: actual_step.map(|_| Stacks::of(&actual_exec)),
+            last_breakpoint: expected.last_breakpoint_before(step),
+        });
+    }
+    None
+}
+
+impl fmt::Display for TraceDiff<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Traces diverge at step {}", self.step)?;
+        if let Some(breakpoint) = self.last_breakpoint {
+            write!(f, ", after breakpoint {}", breakpoint.name)?;
+        }
+        writeln!(f)?;
+
+        let (expected, actual, expected_stacks, actual_stacks) = match (
+            self.expected,
+            self.actual,
+            &self.expected_stacks,
+            &self.actual_stacks,
+        ) {
+            (Some(expected), Some(actual), Some(expected_stacks), Some(actual_stacks)) => {
+                (expected, actual, expected_stacks, actual_stacks)
+            }
+            (Some(step), None, ..) => {
+                return writeln!(f, "Actual trace ended before {}", step.instruction)
+            }
+            (None, Some(step), ..) => {
+                return writeln!(f, "Expected trace ended before {}", step.instruction)
+            }
+            _ => unreachable!(),
+        };
+        if expected.offset != actual.offset || expected.instruction != actual.instruction {
+            writeln!(
+                f,
+                "Expected instruction at {}: {}",
+                expected.offset, expected.instruction
+            )?;
+            writeln!(
+                f,
+                "Actual instruction at {}: {}",
+                actual.offset, actual.instruction
+            )?;
+        } else {
+            writeln!(
+                f,
+                "Instruction at {}: {}",
+                expected.offset, expected.instruction
+            )?;
+        }
+        write_stack_diff(f, "Stack", &expected_stacks.stack, &actual_stacks.stack)?;
+        write_stack_diff(
+            f,
+            "Altstack",
+            &expected_stacks.altstack,
+            &actual_stacks.altstack,
+        )
+    }
+}
+
+/// Writes the items that differ between the two stacks, indexed like `FmtStack`.
+fn write_stack_diff(
+    f: &mut fmt::Formatter<'_>,
+    name: &str,
+    expected: &[Vec<u8>],
+    actual: &[Vec<u8>],
+) -> fmt::Result {
+    if expected == actual {
+        return Ok(());
+    }
+    writeln!(
+        f,
+        "{} ({} expected items, {} actual items):",
+        name,
+        expected.len(),
+        actual.len()
+    )?;
+    for index in 0..expected.len().max(actual.len()) {
+        let (expected_item, actual_item) = (expected.get(index), actual.get(index));
+        if expected_item == actual_item {
+            continue;
+        }
+        write!(f, "{}:\t\t- ", index)?;
+        match expected_item {
+            Some(item) => fmt_item(f, item)?,
+            None => write!(f, "<none>")?,
+        }
+        write!(f, "\n\t\t+ ")?;
+        match actual_item {
+            Some(item) => fmt_item(f, item)?,
+            None => write!(f, "<none>")?,
+        }
+        writeln!(f)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::treepp::*;
+
+    fn sum_script() -> Script {
+        script! {
+            OP_ADD
+            { breakpoint("after_add") }
+            OP_DUP
+            OP_TOALTSTACK
+            { breakpoint("after_toaltstack") }
+            OP_FROMALTSTACK
+            OP_DROP
+            OP_EQUAL
+        }
+    }
+
+    #[test]
+    fn test_trace_script() {
+        let trace = trace_script_with_inputs(sum_script(), vec![vec![5], vec![2], vec![3]]);
+        assert!(trace.info.success);
+        // Two markers of two instructions each, and six opcodes.
+        assert_eq!(trace.steps.len(), 10);
+        assert_eq!(trace.steps[0].instruction, "OP_ADD");
+        assert_eq!(trace.stacks_after(0).unwrap().stack, vec![vec![5], vec![5]]);
+        assert_eq!(trace.stacks_after(5).unwrap().altstack, vec![vec![5]]);
+        assert!(trace.stacks_after(10).is_none());
+
+        let after_add = trace.breakpoint("after_add").unwrap();
+        assert_eq!(after_add.step, 1);
+        assert_eq!(after_add.stacks.stack, vec![vec![5], vec![5]]);
+        assert!(after_add.stacks.altstack.is_empty());
+
+        let after_toaltstack = trace.breakpoint("after_toaltstack").unwrap();
+        assert_eq!(after_toaltstack.stacks.stack, vec![vec![5], vec![5]]);
+        assert_eq!(after_toaltstack.stacks.altstack, vec![vec![5]]);
+        assert_eq!(
+            trace.stacks_after(after_toaltstack.step - 1).as_ref(),
+            Some(&after_toaltstack.stacks)
+        );
+        assert_eq!(trace.last_breakpoint_before(9), Some(after_toaltstack));
+
+        let trace_file = trace.to_string();
+        assert!(trace_file.contains("# breakpoint after_add"));
+        assert!(trace_file.contains("OP_TOALTSTACK"));
+    }
+
+    #[test]
+    fn test_breakpoints_do_not_change_execution() {
+        let script = script! {
+            { breakpoint("start") }
+            OP_TRUE
+        };
+        let trace = trace_script(script.clone());
+        assert!(trace.info.success);
+        assert!(execute_script(script).success);
+        assert_eq!(trace.breakpoints.len(), 1);
+    }
+
+    #[test]
+    fn test_diff_traces() {
+        let expected = trace_script_with_inputs(sum_script(), vec![vec![5], vec![2], vec![3]]);
+        assert!(diff_traces(&expected, &expected).is_none());
+
+        let actual = trace_script_with_inputs(sum_script(), vec![vec![5], vec![2], vec![4]]);
+        assert!(!actual.info.success);
+
+        let diff = diff_traces(&expected, &actual).unwrap();
+        assert_eq!(diff.step, 0);
+        assert!(diff.last_breakpoint.is_none());
+        assert_eq!(diff.expected_stacks.unwrap().stack[1], vec![5]);
+        assert_eq!(diff.actual_stacks.unwrap().stack[1], vec![6]);
+
+        let diff = diff_traces(&expected, &actual).unwrap().to_string();
+        assert!(diff.contains("Traces diverge at step 0"));
+        assert!(diff.contains("1:\t\t- 0x05\n\t\t+ 0x06"));
+    }
+
+    #[test]
+    fn test_stack_limit() {
+        // One more item than `MAX_STACK_SIZE`.
+        let script = script! {
+            for _ in 0..1001 {
+                OP_1
+            }
+            for _ in 0..1001 {
+                OP_DROP
+            }
+            OP_TRUE
+        };
+        let trace = trace_script(script.clone());
+        assert!(!trace.info.success);
+        assert_eq!(trace.steps.len(), 1001);
+
+        let trace = trace_script_without_stack_limit(script, vec![]);
+        assert!(trace.info.success);
+        assert_eq!(trace.stacks_after(1000).unwrap().stack.len(), 1001);
+    }
+}
@@ -0,0 +1,610 @@
+//! Arithmetic modulo arbitrary primes on top of [`BigIntImpl`].
+//!
+//! A field is a type implementing [`PrimeField`] with its modulus. Elements are kept as
+//! `BigIntImpl<N_BITS, LIMB_SIZE>` in canonical form, i.e. reduced modulo the prime.
+//! `T_BITS` is the width of the accumulator used to check hinted products, see
+//! [`PrimeField::tmul`].
+//!
+//! Hinted operations return their hints, which are pushed with [`PrimeField::push`] at the
+//! bottom of the stack in order before the script runs, as for the bn254 fields.
+
+use num_bigint::BigUint;
+use num_traits::{Num, ToPrimitive};
+
+use crate::bigint::BigIntImpl;
+use crate::pseudo::NMUL;
+use crate::source_map::labeled;
+use crate::treepp::*;
+
+pub trait PrimeField<const N_BITS: u32, const LIMB_SIZE: u32, const T_BITS: u32> {
+    /// Name of the field, used to label scripts.
+    const NAME: &'static str;
+
+    /// The prime in hex, it must fit into `N_BITS`.
+    const MODULUS: &'static str;
+
+    fn modulus() -> BigUint {
+        BigUint::from_str_radix(Self::MODULUS, 16).unwrap()
+    }
+
+    fn push(a: &BigUint) -> Script {
+        BigIntImpl::<N_BITS, LIMB_SIZE>::push_u32_le(&a.to_u32_digits())
+    }
+
+    fn push_modulus() -> Script {
+        BigIntImpl::<N_BITS, LIMB_SIZE>::push_hex(Self::MODULUS)
+    }
+
+    fn push_zero() -> Script {
+        BigIntImpl::<N_BITS, LIMB_SIZE>::push_zero()
+    }
+
+    fn push_one() -> Script {
+        BigIntImpl::<N_BITS, LIMB_SIZE>::push_one()
+    }
+
+    fn copy(a: u32) -> Script {
+        BigIntImpl::<N_BITS, LIMB_SIZE>::copy(a)
+    }
+
+    fn roll(a: u32) -> Script {
+        BigIntImpl::<N_BITS, LIMB_SIZE>::roll(a)
+    }
+
+    fn drop() -> Script {
+        BigIntImpl::<N_BITS, LIMB_SIZE>::drop()
+    }
+
+    fn toaltstack() -> Script {
+        BigIntImpl::<N_BITS, LIMB_SIZE>::toaltstack()
+    }
+
+    fn fromaltstack() -> Script {
+        BigIntImpl::<N_BITS, LIMB_SIZE>::fromaltstack()
+    }
+
+    fn equal(a: u32, b: u32) -> Script {
+        BigIntImpl::<N_BITS, LIMB_SIZE>::equal(a, b)
+    }
+
+    fn equalverify(a: u32, b: u32) -> Script {
+        BigIntImpl::<N_BITS, LIMB_SIZE>::equalverify(a, b)
+    }
+
+    fn is_zero(a: u32) -> Script {
+        BigIntImpl::<N_BITS, LIMB_SIZE>::is_zero(a)
+    }
+
+    /// Checks that the element on top of the stack is less than the modulus.
+    fn is_field() -> Script {
+        script! {
+            { Self::push_modulus() }
+            { BigIntImpl::<N_BITS, LIMB_SIZE>::lessthan(1, 0) }
+        }
+    }
+
+    /// Moves the elements at depths `a` and `b` to the top, `b` on top.
+    fn roll_pair(a: u32, b: u32) -> Script {
+        assert_ne!(a, b);
+        if (a, b) == (1, 0) {
+            return script! {};
+        }
+        script! {
+            { Self::roll(a) }
+            { Self::roll(if b < a { b + 1 } else { b }) }
+        }
+    }
+
+    // A - B mod M
+    fn sub(a: u32, b: u32) -> Script {
+        script! {
+            { Self::roll_pair(a, b) }
+            { Self::copy(1) }
+            { Self::copy(1) }
+            { BigIntImpl::<N_BITS, LIMB_SIZE>::lessthan(1, 0) }
+            OP_TOALTSTACK
+            // the difference wraps around modulo 2^N_BITS if A < B
+            { BigIntImpl::<N_BITS, LIMB_SIZE>::sub(1, 0) }
+            OP_FROMALTSTACK
+            OP_IF
+                { Self::push_modulus() }
+                { BigIntImpl::<N_BITS, LIMB_SIZE>::add(1, 0) }
+            OP_ENDIF
+        }
+    }
+
+    // A + B mod M, computed as A - (M - B) so that nothing overflows N_BITS
+    fn add(a: u32, b: u32) -> Script {
+        script! {
+            { Self::roll_pair(a, b) }
+            { Self::push_modulus() }
+            { BigIntImpl::<N_BITS, LIMB_SIZE>::sub(0, 1) }
+            { Self::sub(1, 0) }
+        }
+    }
+
+    fn double(a: u32) -> Script {
+        script! {
+            { Self::roll(a) }
+            { Self::copy(0) }
+            { Self::add(1, 0) }
+        }
+    }
+
+    /// Multiplication by double-and-add without hints
+    /// input: [a, b]
+    /// output: [a * b]
+    fn mul() -> Script {
+        script! {
+            { BigIntImpl::<N_BITS, LIMB_SIZE>::convert_to_be_bits_toaltstack() }
+            { Self::push_zero() }
+            for i in 0..N_BITS {
+                if i > 0 {
+                    { Self::double(0) }
+                }
+                OP_FROMALTSTACK
+                OP_IF
+                    { Self::copy(1) }
+                    { Self::add(1, 0) }
+                OP_ENDIF
+            }
+            { Self::roll(1) }
+            { Self::drop() }
+        }
+    }
+
+    /// Checks the hinted quotient of a product and computes the remainder. All inputs must be
+    /// less than the modulus. The difference `a * b - q * M` is accumulated over `T_BITS` with
+    /// windows of `WINDOW` bits of `b` and `M`, as in the bn254 `tmul`, and it is the remainder
+    /// only if it is non-negative and less than `M`. `T_BITS` must be at least `N_BITS + 7`
+    /// with at most 27 bits in the head limb.
+    /// input: [q, a, b]
+    /// output: [a * b - q * M]
+    fn tmul() -> Script {
+        assert!(T_BITS >= N_BITS + 7, "the accumulator does not fit");
+        // The head limb of the accumulator is shifted by a whole window in a script number
+        assert!(
+            BigIntImpl::<T_BITS, LIMB_SIZE>::HEAD + WINDOW <= 31,
+            "the head limb of the accumulator is too wide"
+        );
+        let modulus = Self::modulus();
+        let n_windows = N_BITS.div_ceil(WINDOW);
+        let mod_window = |index: u32| {
+            let shift_by = WINDOW * (n_windows - index - 1);
+            ((&modulus >> shift_by) % (1u32 << WINDOW))
+                .to_u32()
+                .unwrap()
+        };
+        let table_size: u32 = (1 << WINDOW) - 1;
+
+        script! {
+            for i in 0..3 {
+                { Self::copy(i) }
+                { Self::is_field() }
+                OP_VERIFY
+            }
+            { BigIntImpl::<N_BITS, LIMB_SIZE>::toaltstack() }
+            { BigIntImpl::<N_BITS, LIMB_SIZE>::toaltstack() }
+            // q is a hint of N_BITS, so it is never negative
+            { BigIntImpl::<N_BITS, LIMB_SIZE>::resize::<T_BITS>() }
+            { BigIntImpl::<T_BITS, LIMB_SIZE>::push_zero() }
+            { BigIntImpl::<T_BITS, LIMB_SIZE>::sub(0, 1) }
+            { init_table::<T_BITS, LIMB_SIZE>() }
+            { BigIntImpl::<N_BITS, LIMB_SIZE>::fromaltstack() }
+            { BigIntImpl::<N_BITS, LIMB_SIZE>::resize::<T_BITS>() }
+            { init_table::<T_BITS, LIMB_SIZE>() }
+            { BigIntImpl::<N_BITS, LIMB_SIZE>::fromaltstack() }
+            { BigIntImpl::<N_BITS, LIMB_SIZE>::resize::<T_BITS>() }
+            // {-q_table} {a_table} {b}
+            { BigIntImpl::<T_BITS, LIMB_SIZE>::push_zero() }
+
+            for i in 1..=n_windows {
+                // z += a * b[i]
+                { var_window::<T_BITS, LIMB_SIZE>(n_windows, i - 1) }
+                OP_DUP OP_NOT
+                OP_IF
+                    OP_DROP
+                OP_ELSE
+                    { 2 + table_size }
+                    OP_SWAP
+                    OP_SUB
+                    if i == 1 {
+                        // replace the zero accumulator by a copy of the table entry
+                        for _ in 0..BigIntImpl::<T_BITS, LIMB_SIZE>::N_LIMBS {
+                            OP_NIP
+                        }
+                        { NMUL(BigIntImpl::<T_BITS, LIMB_SIZE>::N_LIMBS) }
+                        OP_DUP OP_PICK
+                        for _ in 0..BigIntImpl::<T_BITS, LIMB_SIZE>::N_LIMBS - 1 {
+                            OP_SWAP
+                            OP_DUP OP_PICK
+                        }
+                        OP_NIP
+                    } else {
+                        { BigIntImpl::<T_BITS, LIMB_SIZE>::add_ref_stack() }
+                    }
+                OP_ENDIF
+                // z -= q * M[i]
+                if mod_window(i - 1) != 0 {
+                    { BigIntImpl::<T_BITS, LIMB_SIZE>::add_ref(2 + 2 * table_size - mod_window(i - 1)) }
+                }
+                if i < n_windows {
+                    { BigIntImpl::<T_BITS, LIMB_SIZE>::lshift_prevent_overflow(WINDOW) }
+                }
+            }
+
+            { BigIntImpl::<T_BITS, LIMB_SIZE>::toaltstack() }
+            for _ in 0..1 + 2 * table_size {
+                { BigIntImpl::<T_BITS, LIMB_SIZE>::drop() }
+            }
+            { BigIntImpl::<T_BITS, LIMB_SIZE>::fromaltstack() }
+
+            // a negative difference is not less than M either
+            { BigIntImpl::<T_BITS, LIMB_SIZE>::copy(0) }
+            { BigIntImpl::<T_BITS, LIMB_SIZE>::push_hex(Self::MODULUS) }
+            { BigIntImpl::<T_BITS, LIMB_SIZE>::lessthan(1, 0) }
+            OP_VERIFY
+            { BigIntImpl::<T_BITS, LIMB_SIZE>::resize::<N_BITS>() }
+        }
+    }
+
+    /// Multiplies the elements at depths `a_depth` and `b_depth` with the quotient as a hint.
+    fn hinted_mul(
+        mut a_depth: u32,
+        a: &BigUint,
+        mut b_depth: u32,
+        b: &BigUint,
+    ) -> (Script, Vec<BigUint>) {
+        assert_ne!(a_depth, b_depth);
+        if a_depth > b_depth {
+            (a_depth, b_depth) = (b_depth, a_depth);
+        }
+
+        let q = a * b / Self::modulus();
+        let script = script! {
+            for _ in 0..BigIntImpl::<N_BITS, LIMB_SIZE>::N_LIMBS {
+                OP_DEPTH OP_1SUB OP_ROLL // hints
+            }
+            { Self::roll(a_depth + 1) }
+            { Self::roll(b_depth + 1) }
+            { Self::tmul() }
+        };
+
+        (
+            labeled(&format!("{}::hinted_mul", Self::NAME), script),
+            vec![q],
+        )
+    }
+
+    /// Replaces the element on top of the stack by its inverse, which is given as a hint.
+    fn hinted_inv(a: &BigUint) -> (Script, Vec<BigUint>) {
+        let modulus = Self::modulus();
+        let y = a.modinv(&modulus).expect("zero has no inverse");
+        let (mul_script, mul_hints) = Self::hinted_mul(1, &y, 0, a);
+
+        let script = script! {
+            for _ in 0..BigIntImpl::<N_BITS, LIMB_SIZE>::N_LIMBS {
+                OP_DEPTH OP_1SUB OP_ROLL // hints
+            }
+            // x, y
+            { Self::copy(0) }
+            { Self::roll(2) }
+            // y, y, x
+            { mul_script }
+            // y, 1
+            { Self::push_one() }
+            { Self::equalverify(1, 0) }
+        };
+
+        let mut hints = vec![y];
+        hints.extend(mul_hints);
+        (
+            labeled(&format!("{}::hinted_inv", Self::NAME), script),
+            hints,
+        )
+    }
+}
+
+/// Width of the windows of `b` and of the modulus in [`PrimeField::tmul`]
+const WINDOW: u32 = 4;
+
+/// Pushes the multiples `2x, ..., (2^WINDOW - 1)x` of the element `x` on top of the stack
+fn init_table<const T_BITS: u32, const LIMB_SIZE: u32>() -> Script {
+    script! {
+        for i in 2..=WINDOW {
+            for j in 1 << (i - 1)..1 << i {
+                if j % 2 == 0 {
+                    { BigIntImpl::<T_BITS, LIMB_SIZE>::double_allow_overflow_keep_element(
+                        (j / 2 - 1) * BigIntImpl::<T_BITS, LIMB_SIZE>::N_LIMBS
+                    ) }
+                } else {
+                    { BigIntImpl::<T_BITS, LIMB_SIZE>::add_ref_with_top(j - 2) }
+                }
+            }
+        }
+    }
+}
+
+/// Pushes the window `index`, counted from the most significant one, of the element below
+/// the accumulator. The bits of the current limb which are left are kept on the altstack
+/// until the last window.
+fn var_window<const T_BITS: u32, const LIMB_SIZE: u32>(n_windows: u32, index: u32) -> Script {
+    let stack_top = BigIntImpl::<T_BITS, LIMB_SIZE>::N_LIMBS;
+    let iter = n_windows - index;
+
+    let s_bit = iter * WINDOW - 1; // start bit
+    let e_bit = (iter - 1) * WINDOW; // end bit
+
+    let s_limb = s_bit / LIMB_SIZE; // start bit limb
+    let e_limb = e_bit / LIMB_SIZE; // end bit limb
+
+    let st = if (e_bit % LIMB_SIZE == 0) || (s_limb > e_limb) {
+        (s_bit % LIMB_SIZE) + 1
+    } else {
+        0
+    };
+
+    script! {
+        if iter == n_windows { // initialize accumulator to track reduced limb
+            { stack_top + s_limb } OP_PICK
+        } else if (s_bit + 1) % LIMB_SIZE == 0 { // drop current and initialize next accumulator
+            OP_FROMALTSTACK OP_DROP
+            { stack_top + s_limb } OP_PICK
+        } else {
+            OP_FROMALTSTACK // load accumulator from altstack
+        }
+
+        if (e_bit % LIMB_SIZE == 0) || (s_limb > e_limb) {
+            if s_limb > e_limb {
+                { NMUL(2) }
+            } else {
+                0
+            }
+        }
+        for i in st..WINDOW {
+            if s_limb > e_limb && i % LIMB_SIZE == (s_bit % LIMB_SIZE) + 1 {
+                // window is split between multiple limbs
+                { stack_top + e_limb + 1 } OP_PICK
+            }
+            if i == 0 {
+                { 1 << ((s_bit - i) % LIMB_SIZE) }
+                OP_2DUP
+                OP_GREATERTHANOREQUAL
+                OP_IF
+                    OP_SUB
+                    2
+                OP_ELSE
+                    OP_DROP
+                    0
+                OP_ENDIF
+                OP_SWAP
+            } else if (s_bit - i) % LIMB_SIZE > 7 {
+                { 1 << ((s_bit - i) % LIMB_SIZE) }
+                OP_2DUP
+                OP_GREATERTHANOREQUAL
+                OP_IF
+                    OP_SUB
+                    OP_SWAP OP_1ADD
+                OP_ELSE
+                    OP_DROP
+                    OP_SWAP
+                OP_ENDIF
+                if i < WINDOW - 1 { { NMUL(2) } }
+                OP_SWAP
+            } else {
+                OP_TUCK
+                { (1 << ((s_bit - i) % LIMB_SIZE)) - 1 }
+                OP_GREATERTHAN
+                OP_TUCK
+                OP_ADD
+                if i < WINDOW - 1 { { NMUL(2) } }
+                OP_ROT OP_ROT
+                OP_IF
+                    { 1 << ((s_bit - i) % LIMB_SIZE) }
+                    OP_SUB
+                OP_ENDIF
+            }
+        }
+
+        if iter == 1 { OP_DROP } else { OP_TOALTSTACK }
+    }
+}
+
+/// Base field of secp256k1
+pub struct Secp256k1Fp;
+
+impl PrimeField<256, 29, 270> for Secp256k1Fp {
+    const NAME: &'static str = "Secp256k1Fp";
+    const MODULUS: &'static str =
+        "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f";
+}
+
+/// Base field of BLS12-381
+pub struct Bls12381Fp;
+
+impl PrimeField<381, 29, 388> for Bls12381Fp {
+    const NAME: &'static str = "Bls12381Fp";
+    const MODULUS: &'static str = "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab";
+}
+
+/// Goldilocks field, modulo 2^64 - 2^32 + 1
+pub struct Goldilocks;
+
+impl PrimeField<64, 16, 71> for Goldilocks {
+    const NAME: &'static str = "Goldilocks";
+    const MODULUS: &'static str = "ffffffff00000001";
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use num_bigint::RandomBits;
+    use num_traits::{One, Zero};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    /// Random samples of each operation for each field
+    const ITERATIONS: usize = 100;
+
+    fn random_element(prng: &mut ChaCha20Rng, modulus: &BigUint) -> BigUint {
+        let a: BigUint = prng.sample(RandomBits::new(modulus.bits()));
+        a % modulus
+    }
+
+    /// Random elements together with the edge cases 0, 1 and M - 1
+    fn samples(prng: &mut ChaCha20Rng, modulus: &BigUint, n: usize) -> Vec<(BigUint, BigUint)> {
+        let max = modulus - BigUint::one();
+        let mut samples = vec![
+            (BigUint::zero(), max.clone()),
+            (max.clone(), max.clone()),
+            (BigUint::one(), max),
+        ];
+        for _ in 0..n {
+            samples.push((random_element(prng, modulus), random_element(prng, modulus)));
+        }
+        samples
+    }
+
+    fn check_add_sub<F: PrimeField<N, L, W>, const N: u32, const L: u32, const W: u32>() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let p = F::modulus();
+        for (a, b) in samples(&mut prng, &p, ITERATIONS) {
+            let sum = (&a + &b) % &p;
+            let diff = (&a + &p - &b) % &p;
+            run(script! {
+                { F::push(&a) }
+                { F::push(&b) }
+                { F::add(1, 0) }
+                { F::push(&sum) }
+                { F::equalverify(1, 0) }
+
+                { F::push(&a) }
+                { F::push(&b) }
+                { F::sub(1, 0) }
+                { F::push(&diff) }
+                { F::equalverify(1, 0) }
+
+                // operands in the other order
+                { F::push(&b) }
+                { F::push(&a) }
+                { F::sub(0, 1) }
+                { F::push(&diff) }
+                { F::equal(1, 0) }
+            });
+        }
+    }
+
+    fn check_mul<F: PrimeField<N, L, W>, const N: u32, const L: u32, const W: u32>() {
+        let mut prng = ChaCha20Rng::seed_from_u64(1);
+        let p = F::modulus();
+        let mul = F::mul();
+        for (a, b) in samples(&mut prng, &p, ITERATIONS) {
+            let product = &a * &b % &p;
+            run(script! {
+                { F::push(&a) }
+                { F::push(&b) }
+                { mul.clone() }
+                { F::push(&product) }
+                { F::equal(1, 0) }
+            });
+        }
+    }
+
+    fn check_hinted_mul<F: PrimeField<N, L, W>, const N: u32, const L: u32, const W: u32>() {
+        let mut prng = ChaCha20Rng::seed_from_u64(4);
+        let p = F::modulus();
+        for (a, b) in samples(&mut prng, &p, ITERATIONS) {
+            let product = &a * &b % &p;
+            let (hinted_mul, hints) = F::hinted_mul(1, &a, 0, &b);
+            run(script! {
+                for hint in hints.iter() {
+                    { F::push(hint) }
+                }
+                { F::push(&a) }
+                { F::push(&b) }
+                { hinted_mul }
+                { F::push(&product) }
+                { F::equal(1, 0) }
+            });
+        }
+    }
+
+    fn check_hinted_mul_wrong_hint<
+        F: PrimeField<N, L, W>,
+        const N: u32,
+        const L: u32,
+        const W: u32,
+    >() {
+        let mut prng = ChaCha20Rng::seed_from_u64(2);
+        let p = F::modulus();
+        let a = random_element(&mut prng, &p);
+        let b = random_element(&mut prng, &p);
+        let (hinted_mul, hints) = F::hinted_mul(1, &a, 0, &b);
+        for wrong_q in [&hints[0] + BigUint::one(), &hints[0] - BigUint::one()] {
+            let result = execute_script(script! {
+                { F::push(&wrong_q) }
+                { F::push(&a) }
+                { F::push(&b) }
+                { hinted_mul.clone() }
+                { F::drop() }
+                OP_TRUE
+            });
+            assert!(!result.success);
+        }
+    }
+
+    fn check_inv<F: PrimeField<N, L, W>, const N: u32, const L: u32, const W: u32>() {
+        let mut prng = ChaCha20Rng::seed_from_u64(3);
+        let p = F::modulus();
+        for (a, _) in samples(&mut prng, &p, ITERATIONS) {
+            if a.is_zero() {
+                continue;
+            }
+            let (hinted_inv, hints) = F::hinted_inv(&a);
+            run(script! {
+                for hint in hints.iter() {
+                    { F::push(hint) }
+                }
+                { F::push(&a) }
+                { hinted_inv }
+                { F::push(&a.modinv(&p).unwrap()) }
+                { F::equal(1, 0) }
+            });
+        }
+    }
+
+    #[test]
+    fn test_add_sub() {
+        check_add_sub::<Secp256k1Fp, 256, 29, 270>();
+        check_add_sub::<Bls12381Fp, 381, 29, 388>();
+        check_add_sub::<Goldilocks, 64, 16, 71>();
+    }
+
+    #[test]
+    fn test_mul() {
+        check_mul::<Secp256k1Fp, 256, 29, 270>();
+        check_mul::<Bls12381Fp, 381, 29, 388>();
+        check_mul::<Goldilocks, 64, 16, 71>();
+    }
+
+    #[test]
+    fn test_hinted_mul() {
+        check_hinted_mul::<Secp256k1Fp, 256, 29, 270>();
+        check_hinted_mul::<Bls12381Fp, 381, 29, 388>();
+        check_hinted_mul::<Goldilocks, 64, 16, 71>();
+    }
+
+    #[test]
+    fn test_hinted_mul_wrong_hint() {
+        check_hinted_mul_wrong_hint::<Secp256k1Fp, 256, 29, 270>();
+        check_hinted_mul_wrong_hint::<Bls12381Fp, 381, 29, 388>();
+        check_hinted_mul_wrong_hint::<Goldilocks, 64, 16, 71>();
+    }
+
+    #[test]
+    fn test_inv() {
+        check_inv::<Secp256k1Fp, 256, 29, 270>();
+        check_inv::<Bls12381Fp, 381, 29, 388>();
+        check_inv::<Goldilocks, 64, 16, 71>();
+    }
+}
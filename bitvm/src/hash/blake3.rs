@@ -1,6 +1,5 @@
 use std::collections::HashMap;
 
-use bitcoin::hex::FromHex;
 use bitcoin_script_stack::stack::StackTracker;
 use itertools::Itertools;
 
@@ -8,7 +7,71 @@ pub use bitcoin_script::builder::StructuredScript as Script;
 pub use bitcoin_script::script;
 
 use crate::bigint::U256;
-use crate::hash::blake3_utils::{compress, get_flags_for_block, TablesVars};
+use crate::hash::blake3_utils::{
+    compress, compress_parent, get_flags_for_block, TablesVars, DERIVE_KEY_MATERIAL, IV,
+    KEYED_HASH, ROOT,
+};
+use crate::source_map::labeled;
+
+/// Number of message bytes in a chunk, i.e. a leaf of the hash tree
+const CHUNK_LEN: u32 = 1024;
+
+/// Number of 64-byte blocks in a chunk
+const BLOCKS_PER_CHUNK: u32 = CHUNK_LEN / 64;
+
+/// BLAKE3 mode of operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Blake3Mode {
+    /// Default hash mode.
+    Hash,
+    /// Keyed hash mode with the given 32-byte key.
+    KeyedHash([u8; 32]),
+    /// Key derivation mode with the given context key, i.e. the hash of the context string.
+    /// Use [`Blake3Mode::derive_key`] to create it from the context string.
+    DeriveKey([u8; 32]),
+}
+
+impl Blake3Mode {
+    /// Key derivation mode for the given context string.
+    pub fn derive_key(context: &str) -> Self {
+        Blake3Mode::DeriveKey(::blake3::hazmat::hash_derive_key_context(context))
+    }
+
+    /// Computes the hash of the message in this mode off-chain.
+    pub fn hash(&self, message: &[u8]) -> [u8; 32] {
+        use ::blake3::hazmat::HasherExt;
+
+        let hash = match self {
+            Blake3Mode::Hash => ::blake3::hash(message),
+            Blake3Mode::KeyedHash(key) => ::blake3::keyed_hash(key, message),
+            Blake3Mode::DeriveKey(context_key) => {
+                ::blake3::Hasher::new_from_context_key(context_key)
+                    .update(message)
+                    .finalize()
+            }
+        };
+        *hash.as_bytes()
+    }
+
+    /// Chaining value at the start of each chunk and parent node
+    fn key_words(&self) -> [u32; 8] {
+        match self {
+            Blake3Mode::Hash => IV,
+            Blake3Mode::KeyedHash(key) | Blake3Mode::DeriveKey(key) => std::array::from_fn(|i| {
+                u32::from_le_bytes(key[4 * i..4 * i + 4].try_into().unwrap())
+            }),
+        }
+    }
+
+    /// Flags that are set on every compression
+    fn flags(&self) -> u32 {
+        match self {
+            Blake3Mode::Hash => 0,
+            Blake3Mode::KeyedHash(_) => KEYED_HASH,
+            Blake3Mode::DeriveKey(_) => DERIVE_KEY_MATERIAL,
+        }
+    }
+}
 
 /// Internal BLAKE3 implementation.
 ///
@@ -25,13 +88,12 @@ fn blake3(
     define_var: bool,
     use_full_tables: bool,
     limb_len: u8,
+    mode: &Blake3Mode,
 ) {
     // this assumes that the stack is empty
     if msg_len == 0 {
-        // af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262
         //hardcoded hash of empty msg
-        let empty_msg_hash = "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262";
-        let empty_msg_hash_bytearray = <[u8; 32]>::from_hex(empty_msg_hash).unwrap();
+        let empty_msg_hash_bytearray = mode.hash(&[]);
 
         stack.custom(
             script!(
@@ -51,11 +113,6 @@ fn blake3(
         return;
     }
 
-    // We require message take atmost a chunk. i.e, 1024 bytes.
-    assert!(
-        msg_len <= 1024,
-        "msg length must be less than or equal to 1024 bytes"
-    );
     assert!(
         (4..32).contains(&limb_len),
         "limb length must be in the range [4, 32)"
@@ -63,6 +120,8 @@ fn blake3(
 
     //number of msg blocks
     let num_blocks = msg_len.div_ceil(64);
+    let num_chunks = msg_len.div_ceil(CHUNK_LEN);
+    let key = mode.key_words();
 
     // If the compact form of message is on stack but not associated with variable, convert it to StackVariable
     if define_var {
@@ -88,8 +147,15 @@ fn blake3(
         stack.from_altstack();
     }
 
+    // number of chaining values of complete subtrees on the alt stack, below the current chunk
+    let mut cv_stack_len = 0;
+
     //process each msg_block
     for i in 0..num_blocks {
+        let chunk = i / BLOCKS_PER_CHUNK;
+        let block_in_chunk = i % BLOCKS_PER_CHUNK;
+        let chunk_blocks = (num_blocks - chunk * BLOCKS_PER_CHUNK).min(BLOCKS_PER_CHUNK);
+
         // unpack the compact form of message
         stack.custom(
             script!(
@@ -178,16 +244,23 @@ fn blake3(
             message.insert(m as u8, original_message[m as usize]);
         }
 
+        // only a single chunk is the root of the tree
+        let mut flags = mode.flags() | get_flags_for_block(block_in_chunk, chunk_blocks);
+        if num_chunks > 1 {
+            flags &= !ROOT;
+        }
+
         compress(
             stack,
-            i != 0,
-            0,
+            block_in_chunk != 0,
+            &key,
+            chunk,
             msg_len.min(64),
-            get_flags_for_block(i, num_blocks),
+            flags,
             message,
             &tables,
             8,
-            i == num_blocks - 1,
+            num_chunks == 1 && i == num_blocks - 1,
         );
 
         //delete the intermediate states
@@ -198,6 +271,23 @@ fn blake3(
         if msg_len > 64 {
             msg_len -= 64;
         }
+
+        // merge the chaining values of complete subtrees, same as `add_chunk_chaining_value` and `finalize` in the reference implementation
+        if num_chunks > 1 && block_in_chunk == chunk_blocks - 1 {
+            if chunk == num_chunks - 1 {
+                for j in (0..cv_stack_len).rev() {
+                    compress_parent(stack, &key, mode.flags(), &tables, j == 0);
+                }
+            } else {
+                let mut total_chunks = chunk + 1;
+                while total_chunks & 1 == 0 {
+                    compress_parent(stack, &key, mode.flags(), &tables, false);
+                    cv_stack_len -= 1;
+                    total_chunks >>= 1;
+                }
+                cv_stack_len += 1;
+            }
+        }
     }
     // drop tables
     tables.drop(stack);
@@ -228,16 +318,7 @@ fn chunk_message(message_bytes: &[u8]) -> Vec<[u8; 64]> {
 ///
 /// The script transforms the message into the correct format
 /// and pushes the result onto the stack.
-///
-/// ## Panics
-///
-/// This function panics if the message is longer than 1024 bytes,
-/// since our BLAKE3 implementation doesn't support longer messages.
 pub fn blake3_push_message_script_with_limb(message_bytes: &[u8], limb_len: u8) -> Script {
-    assert!(
-        message_bytes.len() <= 1024,
-        "This BLAKE3 implementation doesn't support messages longer than 1024 bytes"
-    );
     let chunks = chunk_message(message_bytes);
 
     script! {
@@ -266,7 +347,7 @@ const UNPACKED_BLOCK: usize = 128;
 const MAX_BLAKE3_ELEMENT_COUNT: usize =
     SUM_OF_FULL_TABLES + UNPACKED_BLOCK + /* Extra BLAKE3 variables */ 132;
 
-/// Calculates the maximum number of altstack elements one can have using the [`blake3_compute_script`] function with the following formula:
+/// Calculates the maximum number of altstack elements one can have using the [`blake3_compute_script`] function on messages of at most 1024 bytes with the following formula:
 /// ```text
 ///  n (number of blocks) = ⌈msg_len / 64⌉
 ///  limb_count (number of limbs in a block) = ⌈256 / limb_len⌉ * 2
@@ -274,7 +355,16 @@ const MAX_BLAKE3_ELEMENT_COUNT: usize =
 ///  Since BLAKE3 requires an empty stack and we've calculated the usage for the message and the algorithm:
 ///  MAX_NUMBEROF_ALTSTACK_ELEMENTS = 1000 (max stack limit) - m - 644 (Maximum number of elements used during BLAKE3)
 /// ```
+///
+/// ## Panics
+///
+/// - If `message_len` is more than 1024 bytes, as longer messages also keep the chaining values of
+///   the hashed chunks in the alt stack, which the formula does not account for
 pub fn maximum_number_of_altstack_elements_using_blake3(message_len: usize, limb_len: u8) -> i32 {
+    assert!(
+        message_len <= CHUNK_LEN as usize,
+        "the bound is only valid for messages of at most 1024 bytes"
+    );
     let n = message_len.div_ceil(64);
     let limb_count = 256usize.div_ceil(limb_len as usize) * 2;
     let m = (n - 1) * limb_count;
@@ -288,7 +378,8 @@ pub fn maximum_number_of_altstack_elements_using_blake3(message_len: usize, limb
 ///
 /// ## Parameters
 ///
-/// - `msg_len`: Length of the message. (excluding the padding, number of bytes)
+/// - `msg_len`: Length of the message. (excluding the padding, number of bytes) \
+///   Messages longer than 1024 bytes are split into chunks that are hashed as a tree, which keeps the chaining values of complete subtrees in the alt stack
 /// - `limb_len`: Limb length (number of bits per element) that the input in the stack is packed, for example it is 29 for current field elements
 ///
 /// ## Message Format Requirements
@@ -299,7 +390,7 @@ pub fn maximum_number_of_altstack_elements_using_blake3(message_len: usize, limb
 /// - __BLAKE3 uses exactly [`MAX_BLAKE3_ELEMENT_COUNT`] = 644 elements at maximum, including the tables__ \
 ///   With the max stack limit 1000, __you are allowed to have at most 356 elements including the message (excluding the first block of it) in stack (in total, of altstack and stack)__ \
 ///   Note that smaller `limb_len`'s means more elements, hence more stack usage \
-///   For a more certain number, you can look into and use [`maximum_number_of_altstack_elements_using_blake3`]
+///   For a more certain number on messages of at most 1024 bytes, you can look into and use [`maximum_number_of_altstack_elements_using_blake3`]
 /// - A message of `n` blocks is expected in the following format:
 ///
 /// ```text
@@ -311,8 +402,7 @@ pub fn maximum_number_of_altstack_elements_using_blake3(message_len: usize, limb
 /// ```
 /// ## Panics
 ///
-/// - If `msg_len` is greater than 1024 bytes, the function panics with an assertion error.
-/// - Given script might not also fit on the max stack limit with messages smaller than 1024 bytes \
+/// - Given script might not fit on the max stack limit with messages longer than 1024 bytes, or with smaller messages \
 ///   if the `limb_len` is small or input stacks has other elements (in the altstack)
 /// - If `limb_len` is not in the range [4, 32)
 /// - If the input doesn't unpack to a multiple of 128 nibbles with the given limb length parameter.
@@ -334,15 +424,27 @@ pub fn maximum_number_of_altstack_elements_using_blake3(message_len: usize, limb
 /// - Temporarily uses the alternate stack for intermediate results and hash computation tables.
 /// - Final result is left on the main stack as a BLAKE3 hash value. (in nibbles)
 pub fn blake3_compute_script_with_limb(message_len: usize, limb_len: u8) -> Script {
-    assert!(
-        message_len <= 1024,
-        "This BLAKE3 implementation doesn't support messages longer than 1024 bytes"
-    );
+    blake3_compute_script_with_mode(message_len, limb_len, &Blake3Mode::Hash)
+}
+
+/// Same as [`blake3_compute_script_with_limb`], but computes the hash in the given mode.
+pub fn blake3_compute_script_with_mode(
+    message_len: usize,
+    limb_len: u8,
+    mode: &Blake3Mode,
+) -> Script {
     let mut stack = StackTracker::new();
     let use_full_tables = true;
-    let message_len = message_len as u32; // safety: message_len <= 1024 << u32::MAX
-    blake3(&mut stack, message_len, true, use_full_tables, limb_len);
-    stack.get_script()
+    let message_len = u32::try_from(message_len).expect("message length must fit in u32");
+    blake3(
+        &mut stack,
+        message_len,
+        true,
+        use_full_tables,
+        limb_len,
+        mode,
+    );
+    labeled("blake3_compute_script", stack.get_script())
 }
 
 /// Uses [`blake3_compute_script_with_limb`].with limb length 29, see the documentation of it for more details
@@ -375,8 +477,10 @@ pub fn blake3_verify_output_script(expected_output: [u8; 32]) -> Script {
 mod tests {
     use super::*;
     use crate::{execute_script, execute_script_buf_without_stack_limit};
+    use bitcoin::hex::FromHex;
     use bitcoin::ScriptBuf;
     use bitcoin_script_stack::optimizer;
+    use std::ops::RangeInclusive;
 
     // All tests are run with `USEFUL_LIMB_LENGTHS` lengths, and ignored tests are run with `ALL_POSSIBLE_LIMB_LENGTHS`
     // If any changes are done to the BLAKE3 code, running the tests with all possible lengths is a good idea
@@ -392,12 +496,21 @@ mod tests {
     ];
 
     fn verify_blake_output_with_limbs(message: &[u8], expected_hash: [u8; 32], limb_lens: &[u8]) {
+        verify_blake_output_with_mode(message, expected_hash, limb_lens, &Blake3Mode::Hash)
+    }
+
+    fn verify_blake_output_with_mode(
+        message: &[u8],
+        expected_hash: [u8; 32],
+        limb_lens: &[u8],
+        mode: &Blake3Mode,
+    ) {
         for limb_len in limb_lens.iter().copied() {
             let mut bytes = blake3_push_message_script_with_limb(message, limb_len)
                 .compile()
                 .to_bytes();
             let optimized = optimizer::optimize(
-                blake3_compute_script_with_limb(message.len(), limb_len).compile(),
+                blake3_compute_script_with_mode(message.len(), limb_len, mode).compile(),
             );
             bytes.extend(optimized.to_bytes());
             bytes.extend(
@@ -456,15 +569,34 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(
-        expected = "This BLAKE3 implementation doesn't support messages longer than 1024 bytes"
-    )]
-    fn test_too_long() {
+    fn test_multiple_chunks() {
         let message = [0x00; 1025];
         let expected_hash = *blake3::hash(&message).as_bytes();
         verify_blake_output_with_limbs(&message, expected_hash, &USEFUL_LIMB_LENGTHS);
     }
 
+    #[test]
+    fn test_keyed_hash_and_derive_key() {
+        let key = [0x42; 32];
+        let message = [0x01; 100];
+        let expected_hash = *blake3::keyed_hash(&key, &message).as_bytes();
+        verify_blake_output_with_mode(
+            &message,
+            expected_hash,
+            &USEFUL_LIMB_LENGTHS,
+            &Blake3Mode::KeyedHash(key),
+        );
+
+        let context = "BitVM 2025-01-01 blake3 derive key test";
+        let expected_key = blake3::derive_key(context, &message);
+        verify_blake_output_with_mode(
+            &message,
+            expected_key,
+            &USEFUL_LIMB_LENGTHS,
+            &Blake3Mode::derive_key(context),
+        );
+    }
+
     #[test]
     fn test_single_byte() {
         let messages: Vec<[u8; 1]> = (0..=255).map(|byte| [byte]).collect();
@@ -489,13 +621,15 @@ mod tests {
         );
     }
 
-    fn test_official_test_vectors_with_limbs(limb_lens: &[u8]) {
+    fn test_official_test_vectors_with_limbs(limb_lens: &[u8], input_lens: RangeInclusive<usize>) {
         use serde::Deserialize;
         use std::fs::File;
         use std::io::BufReader;
 
         #[derive(Debug, Deserialize)]
         struct TestVectors {
+            key: String,
+            context_string: String,
             cases: Vec<TestVector>,
         }
 
@@ -503,40 +637,70 @@ mod tests {
         struct TestVector {
             input_len: usize,
             hash: String,
+            keyed_hash: String,
+            derive_key: String,
         }
 
-        fn read_test_vectors() -> Vec<(Vec<u8>, [u8; 32])> {
+        fn read_test_vectors(
+            input_lens: RangeInclusive<usize>,
+        ) -> Vec<(Vec<u8>, [u8; 32], Blake3Mode)> {
             let path = "src/hash/blake3_official_test_vectors.json";
             let file = File::open(path).unwrap();
             let reader = BufReader::new(file);
 
             let test_vectors: TestVectors = serde_json::from_reader(reader).unwrap();
+            let modes = [
+                Blake3Mode::Hash,
+                Blake3Mode::KeyedHash(test_vectors.key.as_bytes().try_into().unwrap()),
+                Blake3Mode::derive_key(&test_vectors.context_string),
+            ];
             test_vectors
                 .cases
                 .iter()
-                .filter(|vector| vector.input_len <= 1024)
-                .map(|vector| {
-                    let message = (0..251u8).cycle().take(vector.input_len).collect();
-                    let expected_hash = <[u8; 32]>::from_hex(&vector.hash[0..64]).unwrap();
-                    (message, expected_hash)
+                .filter(|vector| input_lens.contains(&vector.input_len))
+                .flat_map(|vector| {
+                    let message: Vec<u8> = (0..251u8).cycle().take(vector.input_len).collect();
+                    [&vector.hash, &vector.keyed_hash, &vector.derive_key]
+                        .into_iter()
+                        .zip(modes)
+                        .map(move |(output, mode)| {
+                            let expected_hash = <[u8; 32]>::from_hex(&output[0..64]).unwrap();
+                            (message.clone(), expected_hash, mode)
+                        })
                 })
                 .collect()
         }
 
-        let test_vectors = read_test_vectors();
-        for (message, expected_hash) in test_vectors {
-            verify_blake_output_with_limbs(&message, expected_hash, limb_lens);
+        let test_vectors = read_test_vectors(input_lens);
+        for (message, expected_hash, mode) in test_vectors {
+            assert_eq!(mode.hash(&message), expected_hash);
+            verify_blake_output_with_mode(&message, expected_hash, limb_lens, &mode);
         }
     }
 
     #[test]
     fn test_official_test_vectors() {
-        test_official_test_vectors_with_limbs(&USEFUL_LIMB_LENGTHS)
+        // covers the chunk boundaries up to four chunks, see `test_official_test_vectors_long`
+        test_official_test_vectors_with_limbs(&USEFUL_LIMB_LENGTHS, 0..=4097)
+    }
+
+    #[test]
+    #[ignore]
+    fn test_official_test_vectors_long() {
+        // up to 100 KiB, which takes several minutes
+        test_official_test_vectors_with_limbs(&USEFUL_LIMB_LENGTHS, 4098..=usize::MAX)
     }
 
     #[test]
     fn test_official_test_vectors_with_all_limbs() {
-        test_official_test_vectors_with_limbs(&ALL_POSSIBLE_LIMB_LENGTHS)
+        // longer messages only repeat the chunk logic, which does not depend on the limb length
+        test_official_test_vectors_with_limbs(&ALL_POSSIBLE_LIMB_LENGTHS, 0..=1024)
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 1024 bytes")]
+    fn test_maximum_alstack_element_calculation_too_long() {
+        maximum_number_of_altstack_elements_using_blake3(1025, 29);
     }
 
     fn test_blake3_stack_space(
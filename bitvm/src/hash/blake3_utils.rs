@@ -8,13 +8,21 @@ use std::collections::HashMap;
 // Each u32 is represented as 8 u4's, function and variable names generally follow the referance implementation
 
 /// Starting constants, same notation as the papers (last four values are not used)
-const IV: [u32; 8] = [
+pub(crate) const IV: [u32; 8] = [
     0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
 ];
 
 /// Permutation order for the after of each blake3 round, from the Table 2 in the paper
 const MSG_PERMUTATION: [u8; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
 
+/// Domain separation flags, from the Table 3 in the paper
+pub(crate) const CHUNK_START: u32 = 1 << 0;
+pub(crate) const CHUNK_END: u32 = 1 << 1;
+pub(crate) const PARENT: u32 = 1 << 2;
+pub(crate) const ROOT: u32 = 1 << 3;
+pub(crate) const KEYED_HASH: u32 = 1 << 4;
+pub(crate) const DERIVE_KEY_MATERIAL: u32 = 1 << 6;
+
 /// For the blake3, a modulo, quotient, shift and xor table is used. Also xor table has 2 variants due to the large size of the operation space (16 * 16). For more details, you can refer to the code in the src/u4 folder.
 #[derive(Clone, Debug, Copy)]
 pub(crate) struct TablesVars {
@@ -432,10 +440,11 @@ fn permutate(message_var_map: &HashMap<u8, StackVariable>) -> HashMap<u8, StackV
     ret
 }
 
-/// Initializes the internal state, uses the same variable names as the paper
+/// Initializes the internal state, uses the same variable names as the paper. The chaining value is either taken from the alt stack or is the given key.
 fn init_state(
     stack: &mut StackTracker,
     chaining: bool,
+    key: &[u32; 8],
     counter: u32,
     block_len: u32,
     flags: u32,
@@ -447,8 +456,8 @@ fn init_state(
             state.push(stack.from_altstack_joined(8, &format!("prev-hash[{}]", i)));
         }
     } else {
-        for u32 in IV {
-            state.push(stack.number_u32(u32));
+        for u32 in key {
+            state.push(stack.number_u32(*u32));
         }
     }
     for u32 in &IV[0..4] {
@@ -472,6 +481,7 @@ fn init_state(
 pub(crate) fn compress(
     stack: &mut StackTracker,
     chaining: bool,
+    key: &[u32; 8],
     counter: u32,
     block_len: u32,
     flags: u32,
@@ -483,7 +493,7 @@ pub(crate) fn compress(
     //chaining value needs to be copied for multiple blocks
     //every time that is provided
 
-    let mut state = init_state(stack, chaining, counter, block_len, flags);
+    let mut state = init_state(stack, chaining, key, counter, block_len, flags);
 
     for _ in 0..6 {
         round(stack, &mut state, &message, tables, false);
@@ -513,15 +523,45 @@ pub(crate) fn compress(
     }
 }
 
+/// Compresses the two chaining values on top of the alt stack (right child on top) into the chaining value of their parent, or into the final value if `root` is set
+pub(crate) fn compress_parent(
+    stack: &mut StackTracker,
+    key: &[u32; 8],
+    flags: u32,
+    tables: &TablesVars,
+    root: bool,
+) {
+    let right: Vec<_> = (0..8)
+        .map(|i| stack.from_altstack_joined(8, &format!("right-cv[{}]", i)))
+        .collect();
+    let left: Vec<_> = (0..8)
+        .map(|i| stack.from_altstack_joined(8, &format!("left-cv[{}]", i)))
+        .collect();
+
+    let mut message = HashMap::new();
+    for (i, (l, r)) in left.into_iter().zip(right).enumerate() {
+        message.insert(i as u8, l);
+        message.insert(i as u8 + 8, r);
+    }
+
+    let flags = flags | PARENT | if root { ROOT } else { 0 };
+    compress(stack, false, key, 0, 64, flags, message, tables, 8, root);
+
+    //delete the intermediate states
+    for _ in 0..8 {
+        stack.drop(stack.get_var_from_stack(0));
+    }
+}
+
 pub(crate) fn get_flags_for_block(i: u32, num_blocks: u32) -> u32 {
     if num_blocks == 1 {
-        return 0b00001011;
+        return CHUNK_START | CHUNK_END | ROOT;
     }
     if i == 0 {
-        return 0b00000001;
+        return CHUNK_START;
     }
     if i == num_blocks - 1 {
-        return 0b00001010;
+        return CHUNK_END | ROOT;
     }
     0
 }
@@ -1,5 +1,7 @@
 pub mod blake3;
 pub mod blake3_utils;
+pub mod ripemd160;
 pub mod sha256;
+pub mod sha256_chunked;
 pub mod sha256_u4;
 pub mod sha256_u4_stack;
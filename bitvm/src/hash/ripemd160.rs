@@ -0,0 +1,312 @@
+use crate::hash::sha256::{sha256, u32_not};
+use crate::treepp::{script, Script};
+use crate::u32::{
+    u32_add::u32_add_drop,
+    u32_and::u32_and,
+    u32_rrot::u32_rrot,
+    u32_std::{u32_drop, u32_fromaltstack, u32_pick, u32_push, u32_roll, u32_toaltstack},
+    u32_xor::{u32_xor, u8_drop_xor_table, u8_push_xor_table},
+};
+
+const INITSTATE: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+const K_LEFT: [u32; 5] = [0x00000000, 0x5a827999, 0x6ed9eba1, 0x8f1bbcdc, 0xa953fd4e];
+const K_RIGHT: [u32; 5] = [0x50a28be6, 0x5c4dd124, 0x6d703ef3, 0x7a6d76e9, 0x00000000];
+
+/// Message word selection of the left line
+const R_LEFT: [u32; 80] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, //
+    7, 4, 13, 1, 10, 6, 15, 3, 12, 0, 9, 5, 2, 14, 11, 8, //
+    3, 10, 14, 4, 9, 15, 8, 1, 2, 7, 0, 6, 13, 11, 5, 12, //
+    1, 9, 11, 10, 0, 8, 12, 4, 13, 3, 7, 15, 14, 5, 6, 2, //
+    4, 0, 5, 9, 7, 12, 2, 10, 14, 1, 3, 8, 11, 6, 15, 13,
+];
+
+/// Message word selection of the right line
+const R_RIGHT: [u32; 80] = [
+    5, 14, 7, 0, 9, 2, 11, 4, 13, 6, 15, 8, 1, 10, 3, 12, //
+    6, 11, 3, 7, 0, 13, 5, 10, 14, 15, 8, 12, 4, 9, 1, 2, //
+    15, 5, 1, 3, 7, 14, 6, 9, 11, 8, 12, 2, 10, 0, 4, 13, //
+    8, 6, 4, 1, 3, 11, 15, 0, 5, 12, 2, 13, 9, 7, 10, 14, //
+    12, 15, 10, 4, 1, 5, 8, 7, 6, 2, 13, 14, 0, 3, 9, 11,
+];
+
+/// Left rotations of the left line
+const S_LEFT: [usize; 80] = [
+    11, 14, 15, 12, 5, 8, 7, 9, 11, 13, 14, 15, 6, 7, 9, 8, //
+    7, 6, 8, 13, 11, 9, 7, 15, 7, 12, 15, 9, 11, 7, 13, 12, //
+    11, 13, 6, 7, 14, 9, 13, 15, 14, 8, 13, 6, 5, 12, 7, 5, //
+    11, 12, 14, 15, 14, 15, 9, 8, 9, 14, 5, 6, 8, 6, 5, 12, //
+    9, 15, 5, 11, 6, 8, 13, 12, 5, 12, 13, 14, 11, 8, 5, 6,
+];
+
+/// Left rotations of the right line
+const S_RIGHT: [usize; 80] = [
+    8, 9, 9, 11, 13, 15, 15, 5, 7, 7, 8, 11, 14, 14, 12, 6, //
+    9, 13, 15, 7, 12, 8, 9, 11, 7, 7, 12, 7, 6, 15, 13, 11, //
+    9, 7, 15, 11, 8, 6, 6, 14, 12, 13, 5, 14, 13, 13, 7, 5, //
+    15, 5, 8, 11, 14, 14, 6, 14, 6, 9, 12, 9, 12, 5, 15, 8, //
+    8, 5, 12, 9, 12, 5, 14, 6, 8, 13, 6, 5, 15, 13, 11, 11,
+];
+
+/// ripemd160 takes `num_bytes` bytes with the first byte on top of the stack and returns the
+/// 20 byte digest in the same order
+pub fn ripemd160(num_bytes: usize) -> Script {
+    let padding = ripemd160_padding(num_bytes);
+    let num_blocks = (num_bytes + padding.len()) / 64;
+
+    script! {
+        for _ in 0..num_bytes {
+            OP_TOALTSTACK
+        }
+        {u8_push_xor_table()}
+        for byte in padding.iter().rev() {
+            {*byte}
+        }
+        for _ in 0..num_bytes {
+            OP_FROMALTSTACK
+        }
+        // top of stack: [ [64 byte blocks]... ], every 4 bytes form a little endian u32
+
+        for h in INITSTATE.iter().rev() {
+            {u32_push(*h)}
+        }
+        for i in 0..num_blocks {
+            {ripemd160_compress(((num_blocks - 1 - i) * 16) as u32)}
+        }
+
+        for _ in 0..5 {
+            {u32_toaltstack()}
+        }
+        {u8_drop_xor_table()}
+        for _ in 0..5 {
+            {u32_fromaltstack()}
+        }
+    }
+}
+
+/// RIPEMD-160 of SHA-256 of the `num_bytes` bytes on top of the stack, i.e. Bitcoin's HASH160
+pub fn hash160(num_bytes: usize) -> Script {
+    script! {
+        {sha256(num_bytes)}
+        {ripemd160(32)}
+    }
+}
+
+/// 0x80, zeros and the message length in bits as little endian u64
+fn ripemd160_padding(num_bytes: usize) -> Vec<u8> {
+    let mut padding = vec![0x80];
+    while (num_bytes + padding.len()) % 64 != 56 {
+        padding.push(0);
+    }
+    padding.extend_from_slice(&(num_bytes as u64 * 8).to_le_bytes());
+    padding
+}
+
+/// RIPEMD-160 compression function
+/// input: [`remaining` u32, X[15], X[14], ..., X[0], h[4], h[3], ..., h[0]]
+/// output: [`remaining` u32, h[4], h[3], ..., h[0]]
+fn ripemd160_compress(remaining: u32) -> Script {
+    // number of u32 elements above the xor table while a line runs
+    let depth = remaining + 16 + 5 + 5;
+    // depths of (h[i], left[j], right[k]) in [h, E', D', C', B', A', E, D, C, B, A] for the
+    // new state from h[4] to h[0]
+    let combine: [(u32, u32, u32); 5] =
+        [(10, 1, 7), (14, 0, 6), (13, 4, 5), (12, 3, 9), (11, 2, 8)];
+
+    script! {
+        {ripemd160_line(&R_LEFT, &S_LEFT, &K_LEFT, false, depth)}
+        for _ in 0..5 {
+            {u32_toaltstack()}
+        }
+        {ripemd160_line(&R_RIGHT, &S_RIGHT, &K_RIGHT, true, depth)}
+        for _ in 0..5 {
+            {u32_fromaltstack()}
+        }
+
+        // [h, E', D', C', B', A', E, D, C, B, A]
+        for (i, (h, left, right)) in combine.into_iter().enumerate() {
+            {u32_pick(h + i as u32)}
+            {u32_pick(left + i as u32 + 1)}
+            {u32_add_drop(0, 1)}
+            {u32_pick(right + i as u32 + 1)}
+            {u32_add_drop(0, 1)}
+        }
+
+        for _ in 0..5 {
+            {u32_toaltstack()}
+        }
+        for _ in 0..15 + 16 {
+            {u32_drop()}
+        }
+        for _ in 0..5 {
+            {u32_fromaltstack()}
+        }
+    }
+}
+
+/// Runs one of the two parallel lines of the compression function on a copy of the state
+/// input: [X[15], X[14], ..., X[0], h[4], h[3], ..., h[0]]
+/// output: [X[15], X[14], ..., X[0], h[4], h[3], ..., h[0], E, D, C, B, A]
+fn ripemd160_line(r: &[u32; 80], s: &[usize; 80], k: &[u32; 5], right: bool, depth: u32) -> Script {
+    script! {
+        for _ in 0..5 {
+            {u32_pick(4)}
+        }
+
+        for j in 0..80 {
+            // [E, D, C, B, A]
+            {ripemd160_f(if right { 4 - j / 16 } else { j / 16 }, depth)}
+            {u32_add_drop(0, 1)}
+            {u32_pick(10 + r[j])}
+            {u32_add_drop(0, 1)}
+            if k[j / 16] != 0 {
+                {u32_push(k[j / 16])}
+                {u32_add_drop(0, 1)}
+            }
+            {u32_rrot(32 - s[j])}
+            {u32_pick(4)}
+            {u32_add_drop(0, 1)}
+
+            // [E, D, C, B, T]
+            {u32_roll(2)}
+            {u32_rrot(22)}
+            {u32_roll(2)}
+            {u32_roll(2)}
+            {u32_roll(4)}
+            // [D, ROTLEFT(C, 10), B, T, E]
+        }
+    }
+}
+
+/// Pushes f_i(B, C, D) for the stack [E, D, C, B, A], where `stack_depth` is the number of u32
+/// elements above the xor table
+fn ripemd160_f(i: usize, stack_depth: u32) -> Script {
+    match i {
+        // B ^ C ^ D
+        0 => script! {
+            {u32_pick(1)}
+            {u32_pick(3)}
+            {u32_xor_drop(stack_depth + 3)}
+            {u32_pick(4)}
+            {u32_xor_drop(stack_depth + 3)}
+        },
+        // (B & C) | (~B & D)
+        1 => script! {
+            {u32_pick(1)}
+            {u32_pick(3)}
+            {u32_and_drop(stack_depth + 3)}
+            {u32_pick(2)}
+            {u32_not()}
+            {u32_pick(5)}
+            {u32_and_drop(stack_depth + 4)}
+            {u32_xor_drop(stack_depth + 3)}
+        },
+        // (B | ~C) ^ D == ~(~B & C) ^ D
+        2 => script! {
+            {u32_pick(1)}
+            {u32_not()}
+            {u32_pick(3)}
+            {u32_and_drop(stack_depth + 3)}
+            {u32_not()}
+            {u32_pick(4)}
+            {u32_xor_drop(stack_depth + 3)}
+        },
+        // (B & D) | (C & ~D)
+        3 => script! {
+            {u32_pick(1)}
+            {u32_pick(4)}
+            {u32_and_drop(stack_depth + 3)}
+            {u32_pick(3)}
+            {u32_pick(5)}
+            {u32_not()}
+            {u32_and_drop(stack_depth + 4)}
+            {u32_xor_drop(stack_depth + 3)}
+        },
+        // B ^ (C | ~D) == B ^ ~(~C & D)
+        _ => script! {
+            {u32_pick(2)}
+            {u32_not()}
+            {u32_pick(4)}
+            {u32_and_drop(stack_depth + 3)}
+            {u32_not()}
+            {u32_pick(2)}
+            {u32_xor_drop(stack_depth + 3)}
+        },
+    }
+}
+
+/// Replaces the top two u32 elements with their bitwise AND
+fn u32_and_drop(stack_size: u32) -> Script {
+    script! {
+        {u32_and(0, 1, stack_size)}
+        {u32_toaltstack()}
+        {u32_drop()}
+        {u32_fromaltstack()}
+    }
+}
+
+/// Replaces the top two u32 elements with their bitwise XOR
+fn u32_xor_drop(stack_size: u32) -> Script {
+    script! {
+        {u32_xor(0, 1, stack_size)}
+        {u32_toaltstack()}
+        {u32_drop()}
+        {u32_fromaltstack()}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::treepp::run;
+    use bitcoin::hashes::{hash160, ripemd160, Hash};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    fn push_bytes(bytes: &[u8]) -> Script {
+        script! {
+            for byte in bytes.iter().rev() {
+                {*byte}
+            }
+        }
+    }
+
+    fn verify_digest(digest: &[u8]) -> Script {
+        script! {
+            {push_bytes(digest)}
+            for i in 0..digest.len() {
+                {digest.len() - i} OP_ROLL OP_EQUALVERIFY
+            }
+        }
+    }
+
+    #[test]
+    fn test_ripemd160() {
+        println!("ripemd160(32): {} bytes", ripemd160(32).len());
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        for num_bytes in [0, 1, 20, 32, 55, 56, 64, 100] {
+            let message: Vec<u8> = (0..num_bytes).map(|_| prng.gen()).collect();
+            let digest = ripemd160::Hash::hash(&message);
+            run(script! {
+                {push_bytes(&message)}
+                {ripemd160(num_bytes)}
+                {verify_digest(digest.as_byte_array())}
+                OP_TRUE
+            });
+        }
+    }
+
+    #[test]
+    fn test_hash160() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let public_key: Vec<u8> = (0..33).map(|_| prng.gen()).collect();
+        let digest = hash160::Hash::hash(&public_key);
+        run(script! {
+            {push_bytes(&public_key)}
+            {hash160(33)}
+            {verify_digest(digest.as_byte_array())}
+            OP_TRUE
+        });
+    }
+}
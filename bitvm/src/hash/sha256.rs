@@ -11,7 +11,7 @@ use crate::u32::{
     u32_xor::{u32_xor, u8_drop_xor_table, u8_push_xor_table},
 };
 
-const K: [u32; 64] = [
+pub(crate) const K: [u32; 64] = [
     0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
     0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
     0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
@@ -22,7 +22,7 @@ const K: [u32; 64] = [
     0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
 ];
 
-const INITSTATE: [u32; 8] = [
+pub(crate) const INITSTATE: [u32; 8] = [
     0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
 ];
 
@@ -68,6 +68,21 @@ pub fn sha256(num_bytes: usize) -> Script {
     }
 }
 
+/// Double SHA-256 of the `num_bytes` bytes on top of the stack, i.e. Bitcoin's HASH256
+pub fn hash256(num_bytes: usize) -> Script {
+    script! {
+        {sha256(num_bytes)}
+        {sha256(32)}
+    }
+}
+
+/// Txid of the transaction serialized without witness data in the `num_bytes` bytes on top of
+/// the stack. The txid is left in its internal byte order as used in outpoints, which is the
+/// reverse of its hex display.
+pub fn txid(num_bytes: usize) -> Script {
+    hash256(num_bytes)
+}
+
 pub fn sha256_32bytes() -> Script {
     script! {
         {push_reverse_bytes_to_alt(32)}
@@ -985,6 +1000,47 @@ mod tests {
         assert!(res.success);
     }
 
+    #[test]
+    fn test_txid() {
+        use bitcoin::{
+            absolute::LockTime, consensus, hashes::Hash, transaction::Version, Amount, OutPoint,
+            ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
+        };
+
+        let tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::new(Txid::from_byte_array([0x42; 32]), 1),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(100_000),
+                script_pubkey: ScriptBuf::from_bytes(vec![0x51, 0x20, 0x17, 0x99, 0x4e, 0xff]),
+            }],
+        };
+        let bytes = consensus::serialize(&tx);
+        let expected = tx.compute_txid().to_byte_array();
+
+        let script = script! {
+            for byte in bytes.iter().rev() {
+                { *byte }
+            }
+            {txid(bytes.len())}
+            for byte in expected.iter().rev() {
+                { *byte }
+            }
+            for i in 0..32 {
+                {32 - i} OP_ROLL OP_EQUALVERIFY
+            }
+            OP_TRUE
+        };
+        let res = execute_script(script);
+        assert!(res.success);
+    }
+
     #[test]
     fn test_padding_add_roll() {
         let hex_in = "6162636462636465636465666465666765666768666768696768696a68696a6b696a6b6c6a6b6c6d6b6c6d6e6c6d6e6f6d6e6f706e6f7071";
@@ -0,0 +1,408 @@
+//! SHA-256 over messages that are too long for a single tapscript.
+//!
+//! The padded message is split into steps of a few 64-byte blocks each. Every step runs the
+//! compression function on its blocks starting from the midstate of the previous step, so the
+//! midstate can be committed (e.g. with Winternitz) at the end of one tapscript and resumed in
+//! the next one. The midstate of the last step is the digest.
+//!
+//! On the stack, a midstate is kept in the same form as the output of [`sha256`]: 32 bytes with
+//! byte 0 of its big endian encoding on top.
+//!
+//! [`sha256`]: crate::hash::sha256::sha256
+
+use crate::hash::sha256::{sha256_final, sha256_k, sha256_transform, INITSTATE, K};
+use crate::signatures::winternitz::{
+    generate_public_key, ListpickVerifier, Parameters, PublicKey, SecretKey, ToBytesConverter,
+    Winternitz,
+};
+use crate::signatures::LOG2_BASE;
+use crate::treepp::{script, Script};
+use crate::u32::{
+    u32_std::{u32_drop, u32_fromaltstack, u32_toaltstack},
+    u32_xor::{u8_drop_xor_table, u8_push_xor_table},
+};
+use bitcoin::Witness;
+
+/// State of the SHA-256 compression function between two blocks.
+pub type Sha256Midstate = [u32; 8];
+
+/// Winternitz parameters for committing to a midstate.
+pub const MIDSTATE_PARAMETERS: Parameters = Parameters::new_by_bit_length(256, LOG2_BASE);
+const WINTERNITZ: Winternitz<ListpickVerifier, ToBytesConverter> = Winternitz::new();
+
+/// Runs the compression function on `num_blocks` 64-byte blocks.
+///
+/// input: [block bytes..., midstate] with byte 0 of the first block right below the midstate
+/// output: [midstate]
+///
+/// More than two blocks per script may exceed the stack limit.
+pub fn sha256_compress_blocks(num_blocks: usize) -> Script {
+    assert!(num_blocks > 0, "at least one block is required");
+    let num_words = num_blocks * 16;
+
+    script! {
+        // bring the midstate into the u32 byte order
+        {sha256_final()}
+        for _ in 0..8 {
+            {u32_toaltstack()}
+        }
+        for _ in 0..num_words {
+            OP_SWAP
+            OP_2SWAP
+            OP_SWAP
+            {u32_toaltstack()}
+        }
+
+        {u8_push_xor_table()}
+        {sha256_k()}
+        for _ in 0..num_words + 8 {
+            {u32_fromaltstack()}
+        }
+        // top of stack: [ [64 byte blocks]..., state[0-7]]
+        for i in 0..num_blocks {
+            {sha256_transform(8 + ((num_blocks - i) as u32) * 16 + 64 + 1, 8 + ((num_blocks - i) as u32) * 16)}
+        }
+
+        {sha256_final()}
+        for _ in 0..8 {
+            {u32_toaltstack()}
+        }
+        for _ in 0..64 {
+            {u32_drop()}
+        }
+        {u8_drop_xor_table()}
+
+        for _ in 0..8 {
+            {u32_fromaltstack()}
+        }
+    }
+}
+
+/// Pushes `bytes` so that byte 0 ends up on top.
+fn push_bytes(bytes: &[u8]) -> Script {
+    script! {
+        for byte in bytes.iter().rev() {
+            { *byte }
+        }
+    }
+}
+
+/// Pushes a midstate in the form used by [`sha256_compress_blocks`].
+pub fn push_sha256_midstate(state: &Sha256Midstate) -> Script {
+    push_bytes(&midstate_to_bytes(state))
+}
+
+/// Big endian encoding of a midstate. For the final midstate this is the digest.
+pub fn midstate_to_bytes(state: &Sha256Midstate) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    for (chunk, word) in bytes.chunks_mut(4).zip(state) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    bytes
+}
+
+/// Appends the SHA-256 padding, so the result is a multiple of 64 bytes.
+pub fn sha256_pad(message: &[u8]) -> Vec<u8> {
+    let mut padded = message.to_vec();
+    padded.push(0x80);
+    while padded.len() % 64 != 56 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&(message.len() as u64 * 8).to_be_bytes());
+    padded
+}
+
+/// Off-chain SHA-256 compression function.
+pub fn sha256_compress(state: &Sha256Midstate, block: &[u8; 64]) -> Sha256Midstate {
+    let mut w = [0u32; 64];
+    for (word, bytes) in w.iter_mut().zip(block.chunks(4)) {
+        *word = u32::from_be_bytes(bytes.try_into().unwrap());
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for (k, w) in K.iter().zip(w.iter()) {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(*k)
+            .wrapping_add(*w);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+
+    let mut result = *state;
+    for (word, v) in result.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *word = word.wrapping_add(v);
+    }
+    result
+}
+
+/// One step of a chunked SHA-256 computation, i.e. the work done by one tapscript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sha256Step {
+    pub state_in: Sha256Midstate,
+    pub blocks: Vec<[u8; 64]>,
+    pub state_out: Sha256Midstate,
+}
+
+impl Sha256Step {
+    /// Pushes the blocks of this step in the form used by [`sha256_compress_blocks`].
+    pub fn push_blocks(&self) -> Script {
+        push_bytes(&self.blocks.concat())
+    }
+}
+
+/// Splits the padded `message` into steps of at most `blocks_per_step` blocks each. The input
+/// midstate of each step is the output midstate of the previous one.
+pub fn sha256_steps(message: &[u8], blocks_per_step: usize) -> Vec<Sha256Step> {
+    assert!(
+        blocks_per_step > 0,
+        "at least one block per step is required"
+    );
+
+    let mut state = INITSTATE;
+    sha256_pad(message)
+        .chunks(64 * blocks_per_step)
+        .map(|chunk| {
+            let blocks: Vec<[u8; 64]> = chunk
+                .chunks(64)
+                .map(|block| block.try_into().unwrap())
+                .collect();
+            let state_in = state;
+            for block in &blocks {
+                state = sha256_compress(&state, block);
+            }
+            Sha256Step {
+                state_in,
+                blocks,
+                state_out: state,
+            }
+        })
+        .collect()
+}
+
+/// Public key for committing to a midstate.
+pub fn midstate_public_key(secret_key: &SecretKey) -> PublicKey {
+    generate_public_key(&MIDSTATE_PARAMETERS, secret_key)
+}
+
+/// Signs a midstate commitment.
+pub fn sign_midstate(secret_key: &SecretKey, state: &Sha256Midstate) -> Witness {
+    WINTERNITZ.sign(&MIDSTATE_PARAMETERS, secret_key, &midstate_to_bytes(state))
+}
+
+/// Verifies a midstate commitment and leaves the midstate in the form used by
+/// [`sha256_compress_blocks`].
+pub fn verify_midstate_commitment(public_key: &PublicKey) -> Script {
+    WINTERNITZ.checksig_verify(&MIDSTATE_PARAMETERS, public_key)
+}
+
+/// Winternitz parameters for committing to the blocks of a step of `num_blocks` blocks.
+pub const fn blocks_parameters(num_blocks: usize) -> Parameters {
+    Parameters::new_by_bit_length(num_blocks as u32 * 512, LOG2_BASE)
+}
+
+/// Public key for committing to the blocks of a step of `num_blocks` blocks.
+pub fn blocks_public_key(secret_key: &SecretKey, num_blocks: usize) -> PublicKey {
+    generate_public_key(&blocks_parameters(num_blocks), secret_key)
+}
+
+/// Signs a commitment to the blocks of a step.
+pub fn sign_blocks(secret_key: &SecretKey, blocks: &[[u8; 64]]) -> Witness {
+    WINTERNITZ.sign(
+        &blocks_parameters(blocks.len()),
+        secret_key,
+        &blocks.concat(),
+    )
+}
+
+/// Verifies a commitment to `num_blocks` blocks and leaves the blocks in the form used by
+/// [`sha256_compress_blocks`]. The Winternitz verifier only accepts digits in range, so the
+/// blocks are left as bytes.
+pub fn verify_blocks_commitment(num_blocks: usize, public_key: &PublicKey) -> Script {
+    WINTERNITZ.checksig_verify(&blocks_parameters(num_blocks), public_key)
+}
+
+/// Disprove script for one step of a chunked SHA-256 computation whose blocks and midstates are
+/// committed. Succeeds iff the committed output midstate is not the compression of the
+/// committed `num_blocks` blocks starting from the committed input midstate.
+///
+/// input: [signature of the blocks, signature of state_in, signature of state_out]
+pub fn sha256_step_disprove(
+    num_blocks: usize,
+    blocks_public_key: &PublicKey,
+    state_in_public_key: &PublicKey,
+    state_out_public_key: &PublicKey,
+) -> Script {
+    script! {
+        {verify_midstate_commitment(state_out_public_key)}
+        for _ in 0..32 {
+            OP_TOALTSTACK
+        }
+        {verify_midstate_commitment(state_in_public_key)}
+        for _ in 0..32 {
+            OP_TOALTSTACK
+        }
+        {verify_blocks_commitment(num_blocks, blocks_public_key)}
+        for _ in 0..32 {
+            OP_FROMALTSTACK
+        }
+        {sha256_compress_blocks(num_blocks)}
+        for _ in 0..32 {
+            OP_FROMALTSTACK
+        }
+
+        for i in 0..32 {
+            {32 - i} OP_ROLL OP_EQUAL OP_TOALTSTACK
+        }
+        OP_FROMALTSTACK
+        for _ in 1..32 {
+            OP_FROMALTSTACK OP_BOOLAND
+        }
+        OP_NOT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::treepp::{execute_script, run};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+    use sha2::{Digest, Sha256};
+
+    fn random_message(len: usize) -> Vec<u8> {
+        let mut prng = ChaCha20Rng::seed_from_u64(len as u64);
+        (0..len).map(|_| prng.gen()).collect()
+    }
+
+    #[test]
+    fn test_sha256_steps() {
+        for len in [0, 55, 56, 64, 119, 200, 1000] {
+            let message = random_message(len);
+            for blocks_per_step in [1, 2, 3] {
+                let steps = sha256_steps(&message, blocks_per_step);
+                assert_eq!(steps[0].state_in, INITSTATE);
+                for pair in steps.windows(2) {
+                    assert_eq!(pair[0].state_out, pair[1].state_in);
+                }
+                assert_eq!(
+                    midstate_to_bytes(&steps.last().unwrap().state_out).to_vec(),
+                    Sha256::digest(&message).to_vec()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_sha256_compress_blocks() {
+        // 150 bytes pad to three blocks, i.e. a step with two blocks and a step with one
+        let message = random_message(150);
+        let steps = sha256_steps(&message, 2);
+        assert_eq!(steps.len(), 2);
+
+        for step in steps {
+            let script = script! {
+                {step.push_blocks()}
+                {push_sha256_midstate(&step.state_in)}
+                {sha256_compress_blocks(step.blocks.len())}
+                {push_sha256_midstate(&step.state_out)}
+                for i in 0..32 {
+                    {32 - i} OP_ROLL OP_EQUALVERIFY
+                }
+                OP_TRUE
+            };
+            run(script);
+        }
+    }
+
+    #[test]
+    fn test_sha256_step_disprove() {
+        let blocks_secret_key = b"sha256 step blocks".to_vec();
+        let in_secret_key = b"sha256 step in".to_vec();
+        let out_secret_key = b"sha256 step out".to_vec();
+        let step = sha256_steps(&random_message(100), 1).remove(1);
+        let disprove = sha256_step_disprove(
+            1,
+            &blocks_public_key(&blocks_secret_key, 1),
+            &midstate_public_key(&in_secret_key),
+            &midstate_public_key(&out_secret_key),
+        );
+
+        let mut wrong_state_out = step.state_out;
+        wrong_state_out[7] ^= 1;
+        for (state_out, disproved) in [(step.state_out, false), (wrong_state_out, true)] {
+            let script = script! {
+                {sign_blocks(&blocks_secret_key, &step.blocks)}
+                {sign_midstate(&in_secret_key, &step.state_in)}
+                {sign_midstate(&out_secret_key, &state_out)}
+                {disprove.clone()}
+            };
+            assert_eq!(execute_script(script).success, disproved);
+        }
+    }
+
+    #[test]
+    fn test_sha256_step_disprove_tampered_blocks() {
+        let blocks_secret_key = b"sha256 step blocks".to_vec();
+        let in_secret_key = b"sha256 step in".to_vec();
+        let out_secret_key = b"sha256 step out".to_vec();
+        let step = sha256_steps(&random_message(100), 1).remove(1);
+        let disprove = sha256_step_disprove(
+            1,
+            &blocks_public_key(&blocks_secret_key, 1),
+            &midstate_public_key(&in_secret_key),
+            &midstate_public_key(&out_secret_key),
+        );
+
+        // Blocks whose compression differs from the honest state_out, committed with another key
+        let mut tampered_blocks = step.blocks.clone();
+        tampered_blocks[0][0] ^= 1;
+        assert_ne!(
+            sha256_compress(&step.state_in, &tampered_blocks[0]),
+            step.state_out
+        );
+        let script = script! {
+            {sign_blocks(&b"challenger".to_vec(), &tampered_blocks)}
+            {sign_midstate(&in_secret_key, &step.state_in)}
+            {sign_midstate(&out_secret_key, &step.state_out)}
+            {disprove.clone()}
+        };
+        assert!(!execute_script(script).success);
+
+        // The honest commitment with one signature element replaced
+        let mut signature: Vec<Vec<u8>> = sign_blocks(&blocks_secret_key, &step.blocks)
+            .iter()
+            .map(|element| element.to_vec())
+            .collect();
+        signature[0][0] ^= 1;
+        let script = script! {
+            for element in signature {
+                {element}
+            }
+            {sign_midstate(&in_secret_key, &step.state_in)}
+            {sign_midstate(&out_secret_key, &step.state_out)}
+            {disprove}
+        };
+        assert!(!execute_script(script).success);
+    }
+}
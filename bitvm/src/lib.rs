@@ -18,12 +18,19 @@ use bitcoin::{
 use bitcoin_scriptexec::{Exec, ExecCtx, ExecError, ExecStats, Options, Stack, TxTemplate};
 
 pub mod bigint;
+pub mod block_header;
 pub mod bn254;
 pub mod chunk;
+pub mod debugger;
+pub mod field;
 pub mod groth16;
 pub mod hash;
+pub mod profiler;
 pub mod pseudo;
+pub mod secp256k1;
 pub mod signatures;
+pub mod source_map;
+pub mod stack_effect;
 pub mod u32;
 pub mod u4;
 
@@ -138,11 +145,18 @@ fn execute_script_buf_optional_stack_limit(
     script: bitcoin::ScriptBuf,
     stack_limit: bool,
 ) -> ExecuteInfo {
+    let mut exec = new_exec(script, vec![], stack_limit);
+    while exec.exec_next().is_ok() {}
+    execute_info(&exec)
+}
+
+/// Creates an executor for `script` in an empty tapscript context, with `witness` on the stack.
+pub(crate) fn new_exec(script: ScriptBuf, witness: Vec<Vec<u8>>, stack_limit: bool) -> Exec {
     let opts = Options {
         enforce_stack_limit: stack_limit,
         ..Default::default()
     };
-    let mut exec = Exec::new(
+    Exec::new(
         ExecCtx::Tapscript,
         opts,
         TxTemplate {
@@ -157,15 +171,13 @@ fn execute_script_buf_optional_stack_limit(
             taproot_annex_scriptleaf: Some((TapLeafHash::all_zeros(), None)),
         },
         script,
-        vec![],
+        witness,
     )
-    .expect("error creating exec");
+    .expect("error creating exec")
+}
 
-    loop {
-        if exec.exec_next().is_err() {
-            break;
-        }
-    }
+/// Returns the result of an executor that has run until the end of its script or an error.
+pub(crate) fn execute_info(exec: &Exec) -> ExecuteInfo {
     let res = exec.result().unwrap();
     ExecuteInfo {
         success: res.success,
@@ -221,22 +233,8 @@ pub fn dry_run_taproot_input(
     )
     .expect("error creating exec");
 
-    loop {
-        if exec.exec_next().is_err() {
-            break;
-        }
-    }
-    let res = exec.result().unwrap();
-    let info = ExecuteInfo {
-        success: res.success,
-        error: res.error.clone(),
-        last_opcode: res.opcode,
-        final_stack: FmtStack(exec.stack().clone()),
-        remaining_script: exec.remaining_script().to_asm_string(),
-        stats: exec.stats().clone(),
-    };
-
-    info
+    while exec.exec_next().is_ok() {}
+    execute_info(&exec)
 }
 
 /// Dry-runs all taproot input scripts. Return Ok(()) if all scripts execute successfully,
@@ -277,57 +275,10 @@ pub fn run(script: treepp::Script) {
 }
 
 pub fn execute_raw_script_with_inputs(script: Vec<u8>, witness: Vec<Vec<u8>>) -> ExecuteInfo {
-    // Get the default options for the script exec.
     // Do not enforce the stack limit.
-    let opts = Options {
-        enforce_stack_limit: false,
-        ..Default::default()
-    };
-
-    let mut exec = Exec::new(
-        ExecCtx::Tapscript,
-        opts,
-        TxTemplate {
-            tx: Transaction {
-                version: bitcoin::transaction::Version::TWO,
-                lock_time: bitcoin::locktime::absolute::LockTime::ZERO,
-                input: vec![],
-                output: vec![],
-            },
-            prevouts: vec![],
-            input_idx: 0,
-            taproot_annex_scriptleaf: Some((TapLeafHash::all_zeros(), None)),
-        },
-        ScriptBuf::from_bytes(script),
-        witness,
-    )
-    .expect("error creating exec");
-
-    loop {
-        let temp_res = exec.exec_next();
-        match temp_res {
-            Ok(()) => (),
-            Err(err) => {
-                if !err.success {
-                    // println!("temp_res: {:?}", temp_res);
-                }
-                break;
-            }
-        }
-    }
-
-    let res = exec.result().unwrap();
-    let execute_info = ExecuteInfo {
-        success: res.success,
-        error: res.error.clone(),
-        last_opcode: res.opcode,
-        final_stack: FmtStack(exec.stack().clone()),
-        // alt_stack: FmtStack(exec.altstack().clone()),
-        remaining_script: exec.remaining_script().to_owned().to_asm_string(),
-        stats: exec.stats().clone(),
-    };
-
-    execute_info
+    let mut exec = new_exec(ScriptBuf::from_bytes(script), witness, false);
+    while exec.exec_next().is_ok() {}
+    execute_info(&exec)
 }
 
 pub fn execute_script_with_inputs(script: treepp::Script, witness: Vec<Vec<u8>>) -> ExecuteInfo {
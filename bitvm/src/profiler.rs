@@ -0,0 +1,383 @@
+//! Script size and opcode profiles of the main gadgets, and their comparison against a baseline
+//! to catch size regressions.
+//!
+//! Run with `cargo run --release -p bitvm --features profiler --bin profile -- --help`.
+
+use core::fmt;
+use std::collections::BTreeMap;
+
+use ark_ff::UniformRand;
+use bitcoin::{Opcode, ScriptBuf};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bigint::U254,
+    bn254::{fp254impl::Fp254Impl, fq::Fq, fq12::Fq12},
+    chunk::api::api_generate_partial_script,
+    hash::{blake3::blake3_compute_script, blake3::blake3_push_message_script_with_limb, sha256},
+    new_exec,
+    signatures::{Wots, Wots16, Wots32},
+    treepp::{script, Script},
+};
+
+/// Compiled size, maximum stack size and opcode histogram of a gadget.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GadgetProfile {
+    pub name: String,
+    pub size: usize,
+    /// Maximum number of stack items while running the gadget on sample inputs. `None` if the
+    /// gadget was not executed.
+    pub max_stack: Option<usize>,
+    /// Whether every opcode of the gadget ran without failing on the sample inputs. The gadget
+    /// may leave its outputs on the stack. `None` if the gadget was not executed.
+    pub success: Option<bool>,
+    pub opcodes: BTreeMap<String, usize>,
+}
+
+/// Profiles `script`. If `inputs` is given, the script is run on top of it to find the maximum
+/// stack size and whether it succeeds.
+pub fn profile_script(name: &str, script: ScriptBuf, inputs: Option<Script>) -> GadgetProfile {
+    let mut opcodes = BTreeMap::new();
+    for (index, _) in script.instruction_indices().flatten() {
+        let opcode = Opcode::from(script.as_bytes()[index]);
+        *opcodes.entry(opcode.to_string()).or_insert(0) += 1;
+    }
+
+    let (max_stack, success) = match inputs {
+        Some(inputs) => {
+            let mut bytes = inputs.compile().into_bytes();
+            bytes.extend_from_slice(script.as_bytes());
+            let script = ScriptBuf::from_bytes(bytes);
+            let instructions = script.instructions().count();
+
+            // Every instruction that doesn't fail is one `Ok` step, the end of the script is
+            // reported as an error.
+            let mut exec = new_exec(script, vec![], false);
+            let mut executed = 0;
+            while exec.exec_next().is_ok() {
+                executed += 1;
+            }
+            (
+                Some(exec.stats().max_nb_stack_items),
+                Some(executed == instructions),
+            )
+        }
+        None => (None, None),
+    };
+
+    GadgetProfile {
+        name: name.to_string(),
+        size: script.len(),
+        max_stack,
+        success,
+        opcodes,
+    }
+}
+
+/// Profiles the bigint, field, hash and signature gadgets.
+pub fn profile_gadgets() -> Vec<GadgetProfile> {
+    let mut prng = ChaCha20Rng::seed_from_u64(0);
+    let mut profiles = vec![];
+
+    profiles.push(profile_script(
+        "U254::mul",
+        U254::mul().compile(),
+        Some(script! {
+            { U254::push_one() }
+            { U254::push_one() }
+        }),
+    ));
+
+    let (a, b) = (
+        ark_bn254::Fq::rand(&mut prng),
+        ark_bn254::Fq::rand(&mut prng),
+    );
+    let (hinted_mul, hints) = Fq::hinted_mul(1, a, 0, b);
+    profiles.push(profile_script(
+        "Fq::hinted_mul",
+        hinted_mul.compile(),
+        Some(script! {
+            for hint in hints {
+                { hint.push() }
+            }
+            { Fq::push(a) }
+            { Fq::push(b) }
+        }),
+    ));
+
+    let a = ark_bn254::Fq12::rand(&mut prng);
+    let (hinted_square, hints) = Fq12::hinted_square(a);
+    profiles.push(profile_script(
+        "Fq12::hinted_square",
+        hinted_square.compile(),
+        Some(script! {
+            for hint in hints {
+                { hint.push() }
+            }
+            { Fq12::push(a) }
+        }),
+    ));
+
+    for num_bytes in [32, 80] {
+        profiles.push(profile_script(
+            &format!("sha256({})", num_bytes),
+            sha256::sha256(num_bytes).compile(),
+            Some(script! {
+                for _ in 0..num_bytes {
+                    0
+                }
+            }),
+        ));
+    }
+
+    for message_len in [64, 512] {
+        profiles.push(profile_script(
+            &format!("blake3_compute_script({})", message_len),
+            blake3_compute_script(message_len).compile(),
+            Some(blake3_push_message_script_with_limb(
+                &vec![0; message_len],
+                29,
+            )),
+        ));
+    }
+
+    let secret_key = b"profiler".to_vec();
+    let public_key = Wots16::generate_public_key(&secret_key);
+    let witness = Wots16::sign_to_raw_witness(&secret_key, &[0; 16]);
+    profiles.push(profile_script(
+        "Wots16::checksig_verify",
+        Wots16::checksig_verify(&public_key).compile(),
+        Some(script! {
+            for item in witness.iter() {
+                { item.to_vec() }
+            }
+        }),
+    ));
+    let public_key = Wots32::generate_public_key(&secret_key);
+    let witness = Wots32::sign_to_raw_witness(&secret_key, &[0; 32]);
+    profiles.push(profile_script(
+        "Wots32::checksig_verify",
+        Wots32::checksig_verify(&public_key).compile(),
+        Some(script! {
+            for item in witness.iter() {
+                { item.to_vec() }
+            }
+        }),
+    ));
+
+    profiles
+}
+
+/// Profiles the partial disprove scripts of the chunked Groth16 verifier for `vk`. The scripts
+/// are not executed.
+pub fn profile_chunk_scripts(
+    vk: &ark_groth16::VerifyingKey<ark_bn254::Bn254>,
+) -> Vec<GadgetProfile> {
+    api_generate_partial_script(vk)
+        .into_iter()
+        .enumerate()
+        .map(|(index, script)| {
+            profile_script(&format!("chunk::partial_script[{}]", index), script, None)
+        })
+        .collect()
+}
+
+/// A gadget whose size or maximum stack size grew beyond the threshold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regression {
+    pub name: String,
+    /// `size` or `max_stack`.
+    pub metric: &'static str,
+    pub baseline: usize,
+    pub current: usize,
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} grew from {} to {} (+{:.2}%)",
+            self.name,
+            self.metric,
+            self.baseline,
+            self.current,
+            (self.current as f64 / self.baseline as f64 - 1.0) * 100.0
+        )
+    }
+}
+
+/// The differences between profiles and a baseline.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Comparison {
+    /// Gadgets whose size or maximum stack size grew beyond the threshold.
+    pub regressions: Vec<Regression>,
+    /// Gadgets missing from the baseline.
+    pub added: Vec<String>,
+    /// Gadgets of the baseline that were not profiled.
+    pub removed: Vec<String>,
+}
+
+impl Comparison {
+    /// Whether the profiles match the gadgets of the baseline without regressions.
+    pub fn is_clean(&self) -> bool {
+        self.regressions.is_empty() && self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for regression in &self.regressions {
+            writeln!(f, "{}", regression)?;
+        }
+        for name in &self.added {
+            writeln!(f, "{}: missing from the baseline", name)?;
+        }
+        for name in &self.removed {
+            writeln!(f, "{}: removed since the baseline", name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the profiles against a baseline. Reports the gadgets whose size or maximum stack
+/// size grew by more than `threshold` percent, and the gadgets added or removed since the
+/// baseline.
+pub fn compare_profiles(
+    baseline: &[GadgetProfile],
+    current: &[GadgetProfile],
+    threshold: f64,
+) -> Comparison {
+    let exceeds = |baseline: usize, current: usize| {
+        current as f64 > baseline as f64 * (1.0 + threshold / 100.0)
+    };
+
+    let mut comparison = Comparison {
+        removed: baseline
+            .iter()
+            .filter(|base| !current.iter().any(|profile| profile.name == base.name))
+            .map(|base| base.name.clone())
+            .collect(),
+        ..Default::default()
+    };
+    let regressions = &mut comparison.regressions;
+    for profile in current {
+        let Some(base) = baseline.iter().find(|base| base.name == profile.name) else {
+            comparison.added.push(profile.name.clone());
+            continue;
+        };
+        if exceeds(base.size, profile.size) {
+            regressions.push(Regression {
+                name: profile.name.clone(),
+                metric: "size",
+                baseline: base.size,
+                current: profile.size,
+            });
+        }
+        if let (Some(base_max_stack), Some(max_stack)) = (base.max_stack, profile.max_stack) {
+            if exceeds(base_max_stack, max_stack) {
+                regressions.push(Regression {
+                    name: profile.name.clone(),
+                    metric: "max_stack",
+                    baseline: base_max_stack,
+                    current: max_stack,
+                });
+            }
+        }
+    }
+    comparison
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_profile_script() {
+        let profile = profile_script(
+            "test",
+            script! {
+                OP_DUP
+                OP_ADD
+                OP_DUP
+                { vec![0xab; 20] }
+                OP_DROP
+            }
+            .compile(),
+            Some(script! { 1 }),
+        );
+        assert_eq!(profile.size, 25);
+        assert_eq!(profile.max_stack, Some(3));
+        assert_eq!(profile.success, Some(true));
+        assert_eq!(profile.opcodes["OP_DUP"], 2);
+        assert_eq!(profile.opcodes["OP_ADD"], 1);
+        assert_eq!(profile.opcodes["OP_PUSHBYTES_20"], 1);
+        assert_eq!(profile.opcodes.values().sum::<usize>(), 5);
+
+        let profile = profile_script("test", script! { OP_DUP }.compile(), None);
+        assert_eq!(profile.max_stack, None);
+        assert_eq!(profile.success, None);
+
+        let profile = profile_script(
+            "test",
+            script! { OP_VERIFY OP_1 }.compile(),
+            Some(script! { 0 }),
+        );
+        assert_eq!(profile.success, Some(false));
+    }
+
+    #[test]
+    fn test_compare_profiles() {
+        let profile = |name: &str, size, max_stack| GadgetProfile {
+            name: name.to_string(),
+            size,
+            max_stack,
+            success: None,
+            opcodes: BTreeMap::new(),
+        };
+        let baseline = vec![
+            profile("a", 1000, Some(100)),
+            profile("b", 1000, Some(100)),
+            profile("c", 1000, None),
+            profile("old", 1000, None),
+        ];
+        let current = vec![
+            profile("a", 1010, Some(100)),
+            profile("b", 1000, Some(120)),
+            profile("c", 2000, Some(100)),
+            profile("new", 5000, Some(500)),
+        ];
+
+        let comparison = compare_profiles(&baseline, &current, 1.0);
+        assert_eq!(comparison.added, vec!["new".to_string()]);
+        assert_eq!(comparison.removed, vec!["old".to_string()]);
+        assert!(!comparison.is_clean());
+        assert_eq!(
+            comparison.regressions,
+            vec![
+                Regression {
+                    name: "b".to_string(),
+                    metric: "max_stack",
+                    baseline: 100,
+                    current: 120,
+                },
+                Regression {
+                    name: "c".to_string(),
+                    metric: "size",
+                    baseline: 1000,
+                    current: 2000,
+                },
+            ]
+        );
+        assert_eq!(
+            compare_profiles(&baseline, &current, 0.5).regressions.len(),
+            3
+        );
+        assert!(compare_profiles(&baseline, &baseline, 0.0).is_clean());
+
+        let report = comparison.to_string();
+        assert!(report.contains("new: missing from the baseline"));
+        assert!(report.contains("old: removed since the baseline"));
+    }
+}
@@ -0,0 +1,338 @@
+//! BIP-340 Schnorr signature verification split across chunks.
+//!
+//! The public key, the message and the signature are given as bytes with byte 0 on top. The
+//! setup chunk computes the challenge `e` and lifts the public key `P`, the scalar
+//! multiplication chunks compute `R = s * G - e * P` and the final chunk checks that `R` has an
+//! even y coordinate and the x coordinate `r`.
+
+use bitcoin::secp256k1::{schnorr::Signature, XOnlyPublicKey};
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use super::curve::{check_even_y, hinted_lift_x, AffinePoint};
+use super::scalar_mul::{hinted_double_scalar_mul, offset_point};
+use super::{bytes_to_u256, push_bytes, reduce_scalar, Chunk, Fp, Fr};
+use crate::field::PrimeField;
+use crate::hash::sha256::sha256;
+use crate::treepp::*;
+
+const CHALLENGE_TAG: &[u8] = b"BIP0340/challenge";
+
+fn challenge(r: &[u8], public_key: &[u8], message: &[u8]) -> BigUint {
+    let tag = Sha256::digest(CHALLENGE_TAG);
+    let digest = Sha256::new()
+        .chain_update(tag.as_slice())
+        .chain_update(tag.as_slice())
+        .chain_update(r)
+        .chain_update(public_key)
+        .chain_update(message)
+        .finalize();
+    BigUint::from_bytes_be(&digest) % Fr::modulus()
+}
+
+/// Checks the ranges of `s` and `r`, computes the challenge and lifts the public key.
+/// input: [message (32 bytes), public key (32 bytes), s (32 bytes), r (32 bytes)]
+/// output: [r, e, x, -y, offset, s, offset]
+fn hinted_setup(public_key: &AffinePoint) -> (Script, Vec<BigUint>) {
+    let (lift_x, hints) = hinted_lift_x(public_key);
+    let offset = offset_point();
+
+    let script = script! {
+        // s < n
+        for _ in 0..32 {
+            63 OP_ROLL
+        }
+        { bytes_to_u256() }
+        { Fr::copy(0) }
+        { Fr::is_field() }
+        OP_VERIFY
+        { Fr::toaltstack() }
+
+        // r < p and x < p, the bytes stay as the input of the challenge
+        for _ in 0..64 {
+            63 OP_PICK
+        }
+        for _ in 0..2 {
+            { bytes_to_u256() }
+            { Fp::copy(0) }
+            { Fp::is_field() }
+            OP_VERIFY
+            { Fp::toaltstack() }
+        }
+
+        // e = SHA256(SHA256(tag) || SHA256(tag) || r || x || message) mod n
+        { push_bytes(&Sha256::digest(CHALLENGE_TAG)) }
+        { push_bytes(&Sha256::digest(CHALLENGE_TAG)) }
+        { sha256(160) }
+        { bytes_to_u256() }
+        { reduce_scalar() }
+
+        // -P
+        { Fp::fromaltstack() }
+        { lift_x }
+        { Fp::push_zero() }
+        { Fp::roll(1) }
+        { Fp::sub(1, 0) }
+
+        // e x -y r | s
+        { Fp::fromaltstack() }
+        for _ in 0..3 {
+            { Fp::roll(3) }
+        }
+        { offset.push() }
+        { Fr::fromaltstack() }
+        { offset.push() }
+    };
+    (script, hints)
+}
+
+/// Checks `R` against `r`.
+/// input: [r, x, y]
+fn verify_r() -> Script {
+    script! {
+        { check_even_y() }
+        { Fp::drop() }
+        { Fp::equalverify(1, 0) }
+    }
+}
+
+/// Chunks verifying a BIP-340 signature. The input of the first chunk are the public key, the
+/// message and the signature as bytes, see [`push_input`]. The last chunk leaves nothing on the
+/// stack and fails if the signature is invalid.
+///
+/// Panics if the public key or the signature is malformed.
+pub fn hinted_bip340_verify(
+    public_key: &XOnlyPublicKey,
+    message: &[u8; 32],
+    signature: &Signature,
+) -> Vec<Chunk> {
+    let signature = signature.serialize();
+    let (r, s) = (
+        BigUint::from_bytes_be(&signature[..32]),
+        BigUint::from_bytes_be(&signature[32..]),
+    );
+    let x = BigUint::from_bytes_be(&public_key.serialize());
+    let p = AffinePoint::lift_x(&x).expect("invalid public key");
+    let e = challenge(&signature[..32], &public_key.serialize(), message);
+    let offset = offset_point();
+
+    let (setup, hints) = hinted_setup(&p);
+    let neg_p = p.neg();
+    let mut chunks = vec![Chunk {
+        script: setup,
+        hints,
+        output: vec![
+            r.clone(),
+            e.clone(),
+            neg_p.x.clone(),
+            neg_p.y.clone(),
+            offset.x.clone(),
+            offset.y.clone(),
+            s.clone(),
+            offset.x,
+            offset.y,
+        ],
+    }];
+
+    for mut chunk in hinted_double_scalar_mul(&s, &e, &neg_p) {
+        chunk.output.insert(0, r.clone());
+        chunks.push(chunk);
+    }
+
+    let last = chunks.last_mut().unwrap();
+    last.script = script! {
+        { last.script.clone() }
+        { verify_r() }
+    };
+    last.output.clear();
+    chunks
+}
+
+/// Pushes the input of the first chunk of [`hinted_bip340_verify`].
+pub fn push_input(
+    public_key: &XOnlyPublicKey,
+    message: &[u8; 32],
+    signature: &Signature,
+) -> Script {
+    let signature = signature.serialize();
+    script! {
+        { push_bytes(message) }
+        { push_bytes(&public_key.serialize()) }
+        { push_bytes(&signature[32..]) }
+        { push_bytes(&signature[..32]) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bitcoin::secp256k1::{Keypair, Message, Secp256k1};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    fn sign(prng: &mut ChaCha20Rng) -> (XOnlyPublicKey, [u8; 32], Signature) {
+        let secp = Secp256k1::new();
+        let keypair = Keypair::from_seckey_slice(&secp, &prng.gen::<[u8; 32]>()).unwrap();
+        let message: [u8; 32] = prng.gen();
+        let signature = secp.sign_schnorr_no_aux_rand(&Message::from_digest(message), &keypair);
+        let (public_key, _) = keypair.x_only_public_key();
+        secp.verify_schnorr(&signature, &Message::from_digest(message), &public_key)
+            .unwrap();
+        (public_key, message, signature)
+    }
+
+    fn check_chunk(chunk: &Chunk, input: Script) {
+        let output_len = chunk.output.len() as u32;
+        run(script! {
+            { chunk.push_hints() }
+            { input }
+            { chunk.script.clone() }
+            { chunk.push_output() }
+            for i in (0..output_len).rev() {
+                { Fp::equalverify(i + 1, 0) }
+            }
+            OP_TRUE
+        });
+    }
+
+    #[test]
+    fn test_setup() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let (public_key, message, signature) = sign(&mut prng);
+        let chunks = hinted_bip340_verify(&public_key, &message, &signature);
+        println!("bip340 setup: {} bytes", chunks[0].script.len());
+        println!("bip340 chunks: {}", chunks.len());
+        check_chunk(&chunks[0], push_input(&public_key, &message, &signature));
+
+        // a different message gives a different challenge
+        let mut wrong_message = message;
+        wrong_message[0] ^= 1;
+        let result = execute_script(script! {
+            { chunks[0].push_hints() }
+            { push_input(&public_key, &wrong_message, &signature) }
+            { chunks[0].script.clone() }
+            { chunks[0].push_output() }
+            for i in (0..chunks[0].output.len() as u32).rev() {
+                { Fp::equalverify(i + 1, 0) }
+            }
+            OP_TRUE
+        });
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_chunks() {
+        let mut prng = ChaCha20Rng::seed_from_u64(1);
+        let (public_key, message, signature) = sign(&mut prng);
+        let chunks = hinted_bip340_verify(&public_key, &message, &signature);
+
+        // a fixed-base chunk, the finish of the fixed-base multiplication, a variable-base chunk
+        for i in [1, 33, 34] {
+            check_chunk(&chunks[i], chunks[i - 1].push_output());
+        }
+
+        let last = chunks.len() - 1;
+        println!("bip340 final: {} bytes", chunks[last].script.len());
+        check_chunk(&chunks[last], chunks[last - 1].push_output());
+
+        // the final chunk rejects a wrong r
+        let mut wrong_input = chunks[last - 1].clone();
+        wrong_input.output[0] += 1u32;
+        let result = execute_script(script! {
+            { chunks[last].push_hints() }
+            { wrong_input.push_output() }
+            { chunks[last].script.clone() }
+            OP_TRUE
+        });
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_forged_signature() {
+        let mut prng = ChaCha20Rng::seed_from_u64(3);
+        let (public_key, message, _) = sign(&mut prng);
+        let (_, _, forged_signature) = sign(&mut prng);
+        let chunks = hinted_bip340_verify(&public_key, &message, &forged_signature);
+
+        // the chunks before only compute R = s * G - e * P
+        let last = chunks.len() - 1;
+        let result = execute_script(script! {
+            { chunks[last].push_hints() }
+            { chunks[last - 1].push_output() }
+            { chunks[last].script.clone() }
+            OP_TRUE
+        });
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_s_out_of_range() {
+        let mut prng = ChaCha20Rng::seed_from_u64(4);
+        let (public_key, message, signature) = sign(&mut prng);
+        let chunks = hinted_bip340_verify(&public_key, &message, &signature);
+
+        // s = n is the same scalar as s = 0
+        let mut bytes = signature.serialize();
+        bytes[32..].copy_from_slice(&Fr::modulus().to_bytes_be());
+        let wrong_signature = Signature::from_slice(&bytes).unwrap();
+        let result = execute_script(script! {
+            { chunks[0].push_hints() }
+            { push_input(&public_key, &message, &wrong_signature) }
+            { chunks[0].script.clone() }
+            for _ in 0..chunks[0].output.len() {
+                { Fp::drop() }
+            }
+            OP_TRUE
+        });
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_public_key_not_on_curve() {
+        let mut prng = ChaCha20Rng::seed_from_u64(5);
+        let (_, message, signature) = sign(&mut prng);
+
+        let mut x = BigUint::from(prng.gen::<u64>());
+        while AffinePoint::lift_x(&x).is_some() {
+            x += 1u32;
+        }
+        // no y coordinate passes the curve check, even with consistent hints
+        let point = AffinePoint {
+            x: x.clone(),
+            y: BigUint::from(2u32),
+        };
+        let (setup, hints) = hinted_setup(&point);
+        let mut x_bytes = [0u8; 32];
+        let x_be = x.to_bytes_be();
+        x_bytes[32 - x_be.len()..].copy_from_slice(&x_be);
+        let signature = signature.serialize();
+        let result = execute_script(script! {
+            for hint in hints.iter() {
+                { Fp::push(hint) }
+            }
+            { push_bytes(&message) }
+            { push_bytes(&x_bytes) }
+            { push_bytes(&signature[32..]) }
+            { push_bytes(&signature[..32]) }
+            { setup }
+            for _ in 0..9 {
+                { Fp::drop() }
+            }
+            OP_TRUE
+        });
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_bip340_verify() {
+        let mut prng = ChaCha20Rng::seed_from_u64(2);
+        let (public_key, message, signature) = sign(&mut prng);
+        let chunks = hinted_bip340_verify(&public_key, &message, &signature);
+
+        let mut input = push_input(&public_key, &message, &signature);
+        for chunk in chunks.iter() {
+            check_chunk(chunk, input);
+            input = chunk.push_output();
+        }
+    }
+}
@@ -0,0 +1,461 @@
+//! Affine points of secp256k1 and hinted point addition and doubling.
+//!
+//! A point is kept on the stack as its coordinates `[x, y]`. The formulas are incomplete:
+//! adding points with the same x coordinate is rejected, which also rules out the point at
+//! infinity.
+
+use bitcoin::secp256k1::PublicKey;
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use super::Fp;
+use crate::bigint::U256;
+use crate::field::PrimeField;
+use crate::treepp::*;
+
+const GENERATOR_X: &str = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+const GENERATOR_Y: &str = "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+
+/// Off-chain affine point
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AffinePoint {
+    pub x: BigUint,
+    pub y: BigUint,
+}
+
+fn sub_mod(a: &BigUint, b: &BigUint) -> BigUint {
+    let modulus = Fp::modulus();
+    (a + &modulus - b) % modulus
+}
+
+impl AffinePoint {
+    pub fn generator() -> Self {
+        AffinePoint {
+            x: BigUint::parse_bytes(GENERATOR_X.as_bytes(), 16).unwrap(),
+            y: BigUint::parse_bytes(GENERATOR_Y.as_bytes(), 16).unwrap(),
+        }
+    }
+
+    pub fn from_public_key(public_key: &PublicKey) -> Self {
+        let bytes = public_key.serialize_uncompressed();
+        AffinePoint {
+            x: BigUint::from_bytes_be(&bytes[1..33]),
+            y: BigUint::from_bytes_be(&bytes[33..65]),
+        }
+    }
+
+    /// The point with the given x coordinate and an even y coordinate, if there is one.
+    pub fn lift_x(x: &BigUint) -> Option<Self> {
+        let modulus = Fp::modulus();
+        if x >= &modulus {
+            return None;
+        }
+        let c = (x.modpow(&BigUint::from(3u32), &modulus) + 7u32) % &modulus;
+        let mut y = c.modpow(&((&modulus + 1u32) >> 2), &modulus);
+        if &y * &y % &modulus != c {
+            return None;
+        }
+        if y.bit(0) {
+            y = &modulus - y;
+        }
+        Some(AffinePoint { x: x.clone(), y })
+    }
+
+    pub fn is_on_curve(&self) -> bool {
+        let modulus = Fp::modulus();
+        &self.y * &self.y % &modulus
+            == (self.x.modpow(&BigUint::from(3u32), &modulus) + 7u32) % &modulus
+    }
+
+    pub fn neg(&self) -> Self {
+        AffinePoint {
+            x: self.x.clone(),
+            y: sub_mod(&BigUint::zero(), &self.y),
+        }
+    }
+
+    fn with_slope(&self, other: &Self, lambda: &BigUint) -> Self {
+        let modulus = Fp::modulus();
+        let x = sub_mod(&sub_mod(&(lambda * lambda % &modulus), &self.x), &other.x);
+        let y = sub_mod(&(lambda * sub_mod(&self.x, &x) % &modulus), &self.y);
+        AffinePoint { x, y }
+    }
+
+    fn add_slope(&self, other: &Self) -> BigUint {
+        assert_ne!(self.x, other.x, "points with the same x coordinate");
+        let modulus = Fp::modulus();
+        let dx = sub_mod(&other.x, &self.x);
+        sub_mod(&other.y, &self.y) * dx.modinv(&modulus).unwrap() % modulus
+    }
+
+    fn double_slope(&self) -> BigUint {
+        let modulus = Fp::modulus();
+        let numerator = BigUint::from(3u32) * &self.x * &self.x % &modulus;
+        let denominator = (&self.y << 1) % &modulus;
+        numerator * denominator.modinv(&modulus).unwrap() % modulus
+    }
+
+    /// Panics if the points have the same x coordinate.
+    pub fn add(&self, other: &Self) -> Self {
+        self.with_slope(other, &self.add_slope(other))
+    }
+
+    pub fn double(&self) -> Self {
+        self.with_slope(self, &self.double_slope())
+    }
+
+    /// Scalar multiplication, `None` is the point at infinity.
+    pub fn mul(&self, k: &BigUint) -> Option<Self> {
+        let mut acc: Option<Self> = None;
+        for i in (0..k.bits()).rev() {
+            acc = acc.map(|p| p.double());
+            if k.bit(i) {
+                acc = match acc {
+                    None => Some(self.clone()),
+                    Some(p) if p.x != self.x => Some(p.add(self)),
+                    Some(p) if p.y == self.y => Some(p.double()),
+                    Some(_) => None,
+                };
+            }
+        }
+        acc
+    }
+
+    pub fn push(&self) -> Script {
+        script! {
+            { Fp::push(&self.x) }
+            { Fp::push(&self.y) }
+        }
+    }
+}
+
+fn pull_hint() -> Script {
+    script! {
+        for _ in 0..U256::N_LIMBS {
+            OP_DEPTH OP_1SUB OP_ROLL // hints
+        }
+    }
+}
+
+/// input: [x1, y1, x2, y2]
+/// output: [x3, y3]
+pub fn hinted_add(p: &AffinePoint, q: &AffinePoint) -> (Script, Vec<BigUint>) {
+    let lambda = p.add_slope(q);
+    let r = p.with_slope(q, &lambda);
+    let dx = sub_mod(&q.x, &p.x);
+    let d = sub_mod(&p.x, &r.x);
+    let (check_lambda, check_lambda_hints) = Fp::hinted_mul(1, &dx, 0, &lambda);
+    let (lambda_sq, lambda_sq_hints) = Fp::hinted_mul(1, &lambda, 0, &lambda);
+    let (lambda_d, lambda_d_hints) = Fp::hinted_mul(1, &d, 0, &lambda);
+
+    let script = script! {
+        { pull_hint() }
+        // x1 y1 x2 y2 λ
+        { Fp::copy(2) }
+        { Fp::copy(5) }
+        { Fp::sub(1, 0) }
+        // x1 y1 x2 y2 λ x2-x1
+        { Fp::copy(0) }
+        { Fp::is_zero(0) }
+        OP_NOT OP_VERIFY
+        { Fp::copy(2) }
+        { Fp::copy(5) }
+        { Fp::sub(1, 0) }
+        { Fp::toaltstack() }
+        // x1 y1 x2 y2 λ x2-x1 | y2-y1
+        { Fp::copy(1) }
+        { check_lambda }
+        { Fp::fromaltstack() }
+        { Fp::equalverify(1, 0) }
+        // x1 y1 x2 y2 λ
+        { Fp::roll(1) }
+        { Fp::drop() }
+        { Fp::copy(0) }
+        { Fp::copy(0) }
+        { lambda_sq }
+        // x1 y1 x2 λ λ²
+        { Fp::roll(2) }
+        { Fp::sub(1, 0) }
+        { Fp::copy(3) }
+        { Fp::sub(1, 0) }
+        // x1 y1 λ x3
+        { Fp::roll(3) }
+        { Fp::copy(1) }
+        { Fp::sub(1, 0) }
+        // y1 λ x3 x1-x3
+        { Fp::roll(2) }
+        { lambda_d }
+        // y1 x3 λ(x1-x3)
+        { Fp::roll(2) }
+        { Fp::sub(1, 0) }
+    };
+
+    let mut hints = vec![lambda];
+    hints.extend(check_lambda_hints);
+    hints.extend(lambda_sq_hints);
+    hints.extend(lambda_d_hints);
+    (script, hints)
+}
+
+/// input: [x, y]
+/// output: [x3, y3]
+pub fn hinted_double(p: &AffinePoint) -> (Script, Vec<BigUint>) {
+    let modulus = Fp::modulus();
+    let lambda = p.double_slope();
+    let r = p.double();
+    let double_y = (&p.y << 1) % &modulus;
+    let d = sub_mod(&p.x, &r.x);
+    let (x_sq, x_sq_hints) = Fp::hinted_mul(1, &p.x, 0, &p.x);
+    let (check_lambda, check_lambda_hints) = Fp::hinted_mul(1, &double_y, 0, &lambda);
+    let (lambda_sq, lambda_sq_hints) = Fp::hinted_mul(1, &lambda, 0, &lambda);
+    let (lambda_d, lambda_d_hints) = Fp::hinted_mul(1, &d, 0, &lambda);
+
+    let script = script! {
+        { pull_hint() }
+        // x y λ
+        { Fp::copy(2) }
+        { Fp::copy(0) }
+        { x_sq }
+        { Fp::copy(0) }
+        { Fp::double(0) }
+        { Fp::add(1, 0) }
+        { Fp::toaltstack() }
+        // x y λ | 3x²
+        { Fp::copy(1) }
+        { Fp::double(0) }
+        { Fp::copy(0) }
+        { Fp::is_zero(0) }
+        OP_NOT OP_VERIFY
+        { Fp::copy(1) }
+        { check_lambda }
+        { Fp::fromaltstack() }
+        { Fp::equalverify(1, 0) }
+        // x y λ
+        { Fp::copy(0) }
+        { Fp::copy(0) }
+        { lambda_sq }
+        { Fp::copy(3) }
+        { Fp::double(0) }
+        { Fp::sub(1, 0) }
+        // x y λ x3
+        { Fp::roll(3) }
+        { Fp::copy(1) }
+        { Fp::sub(1, 0) }
+        // y λ x3 x-x3
+        { Fp::roll(2) }
+        { lambda_d }
+        // y x3 λ(x-x3)
+        { Fp::roll(2) }
+        { Fp::sub(1, 0) }
+    };
+
+    let mut hints = vec![lambda];
+    hints.extend(x_sq_hints);
+    hints.extend(check_lambda_hints);
+    hints.extend(lambda_sq_hints);
+    hints.extend(lambda_d_hints);
+    (script, hints)
+}
+
+/// Checks that the point on top of the stack is on the curve.
+/// input: [x, y]
+/// output: [x, y]
+pub fn hinted_check_on_curve(p: &AffinePoint) -> (Script, Vec<BigUint>) {
+    let modulus = Fp::modulus();
+    let x_sq = &p.x * &p.x % &modulus;
+    let (y_sq, y_sq_hints) = Fp::hinted_mul(1, &p.y, 0, &p.y);
+    let (x_sq_script, x_sq_hints) = Fp::hinted_mul(1, &p.x, 0, &p.x);
+    let (x_cube, x_cube_hints) = Fp::hinted_mul(1, &x_sq, 0, &p.x);
+
+    let script = script! {
+        { Fp::copy(0) }
+        { Fp::copy(0) }
+        { y_sq }
+        // x y y²
+        { Fp::copy(2) }
+        { Fp::copy(0) }
+        { x_sq_script }
+        { Fp::copy(3) }
+        { x_cube }
+        { Fp::push(&BigUint::from(7u32)) }
+        { Fp::add(1, 0) }
+        // x y y² x³+7
+        { Fp::equalverify(1, 0) }
+    };
+
+    let mut hints = y_sq_hints;
+    hints.extend(x_sq_hints);
+    hints.extend(x_cube_hints);
+    (script, hints)
+}
+
+/// Checks that the y coordinate on top of the stack is even.
+pub fn check_even_y() -> Script {
+    script! {
+        { Fp::copy(0) }
+        { U256::div2rem() }
+        OP_NOT OP_VERIFY
+        { Fp::drop() }
+    }
+}
+
+/// Lifts an x coordinate to the point with even y, which is given as a hint.
+/// input: [x]
+/// output: [x, y]
+pub fn hinted_lift_x(p: &AffinePoint) -> (Script, Vec<BigUint>) {
+    assert!(!p.y.bit(0), "y must be even");
+    let (check_on_curve, check_on_curve_hints) = hinted_check_on_curve(p);
+
+    let script = script! {
+        { pull_hint() }
+        { check_on_curve }
+        { check_even_y() }
+    };
+
+    let mut hints = vec![p.y.clone()];
+    hints.extend(check_on_curve_hints);
+    (script, hints)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bitcoin::secp256k1::{Scalar, Secp256k1, SecretKey};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    fn random_public_key(prng: &mut ChaCha20Rng) -> PublicKey {
+        let secret_key = SecretKey::from_slice(&prng.gen::<[u8; 32]>()).unwrap();
+        secret_key.public_key(&Secp256k1::new())
+    }
+
+    fn push_hints(hints: &[BigUint]) -> Script {
+        script! {
+            for hint in hints {
+                { Fp::push(hint) }
+            }
+        }
+    }
+
+    fn point_equalverify() -> Script {
+        script! {
+            { Fp::roll(2) }
+            { Fp::equalverify(1, 0) }
+            { Fp::equalverify(1, 0) }
+        }
+    }
+
+    #[test]
+    fn test_off_chain_arithmetic() {
+        let secp = Secp256k1::new();
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let g = AffinePoint::generator();
+        assert!(g.is_on_curve());
+        assert_eq!(AffinePoint::lift_x(&g.x), Some(g.clone()));
+
+        for _ in 0..5 {
+            let k: [u8; 32] = prng.gen();
+            let secret_key = SecretKey::from_slice(&k).unwrap();
+            let p = AffinePoint::from_public_key(&secret_key.public_key(&secp));
+            assert_eq!(g.mul(&BigUint::from_bytes_be(&k)), Some(p));
+        }
+    }
+
+    #[test]
+    fn test_hinted_add() {
+        let mut prng = ChaCha20Rng::seed_from_u64(1);
+        let a = random_public_key(&mut prng);
+        let b = random_public_key(&mut prng);
+        let (p, q) = (
+            AffinePoint::from_public_key(&a),
+            AffinePoint::from_public_key(&b),
+        );
+        let sum = AffinePoint::from_public_key(&a.combine(&b).unwrap());
+        assert_eq!(p.add(&q), sum);
+
+        let (add, hints) = hinted_add(&p, &q);
+        println!("secp256k1 hinted_add: {} bytes", add.len());
+        run(script! {
+            { push_hints(&hints) }
+            { p.push() }
+            { q.push() }
+            { add.clone() }
+            { sum.push() }
+            { point_equalverify() }
+            OP_TRUE
+        });
+
+        // a wrong slope is rejected
+        let mut wrong_hints = hints.clone();
+        wrong_hints[0] += 1u32;
+        let result = execute_script(script! {
+            { push_hints(&wrong_hints) }
+            { p.push() }
+            { q.push() }
+            { add }
+            { Fp::drop() }
+            { Fp::drop() }
+            OP_TRUE
+        });
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_hinted_double() {
+        let secp = Secp256k1::new();
+        let mut prng = ChaCha20Rng::seed_from_u64(2);
+        let a = random_public_key(&mut prng);
+        let p = AffinePoint::from_public_key(&a);
+        let two = Scalar::from_be_bytes({
+            let mut bytes = [0; 32];
+            bytes[31] = 2;
+            bytes
+        })
+        .unwrap();
+        let doubled = AffinePoint::from_public_key(&a.mul_tweak(&secp, &two).unwrap());
+        assert_eq!(p.double(), doubled);
+
+        let (double, hints) = hinted_double(&p);
+        println!("secp256k1 hinted_double: {} bytes", double.len());
+        run(script! {
+            { push_hints(&hints) }
+            { p.push() }
+            { double }
+            { doubled.push() }
+            { point_equalverify() }
+            OP_TRUE
+        });
+    }
+
+    #[test]
+    fn test_hinted_lift_x() {
+        let mut prng = ChaCha20Rng::seed_from_u64(3);
+        let (x_only, _) = random_public_key(&mut prng).x_only_public_key();
+        let x = BigUint::from_bytes_be(&x_only.serialize());
+        let p = AffinePoint::lift_x(&x).unwrap();
+
+        let (lift_x, hints) = hinted_lift_x(&p);
+        run(script! {
+            { push_hints(&hints) }
+            { Fp::push(&x) }
+            { lift_x.clone() }
+            { p.push() }
+            { point_equalverify() }
+            OP_TRUE
+        });
+
+        // the odd y is rejected
+        let odd = p.neg();
+        let mut wrong_hints = vec![odd.y.clone()];
+        wrong_hints.extend(hinted_check_on_curve(&odd).1);
+        let result = execute_script(script! {
+            { push_hints(&wrong_hints) }
+            { Fp::push(&x) }
+            { lift_x }
+            { Fp::drop() }
+            { Fp::drop() }
+            OP_TRUE
+        });
+        assert!(!result.success);
+    }
+}
@@ -0,0 +1,113 @@
+//! Arithmetic on secp256k1 and signature verification in script.
+//!
+//! Field elements and scalars are kept as [`U256`] in canonical form. Multiplications, inverses
+//! and slopes are given as hints, which a script pulls from the bottom of the stack in the order
+//! it uses them. The hinted functions compute the hints off-chain together with the script, but
+//! the script itself does not depend on the values.
+//!
+//! A scalar multiplication does not fit into a single script, so it is split into [`Chunk`]s.
+//! The stack a chunk leaves is the input of the next one and is meant to be committed in
+//! between, e.g. with Winternitz signatures.
+
+pub mod bip340;
+pub mod curve;
+pub mod scalar_mul;
+
+use num_bigint::BigUint;
+
+use crate::bigint::U256;
+use crate::field::PrimeField;
+pub use crate::field::Secp256k1Fp as Fp;
+use crate::treepp::*;
+
+/// Scalar field of secp256k1, modulo the group order
+pub struct Fr;
+
+impl PrimeField<256, 29, 270> for Fr {
+    const NAME: &'static str = "Secp256k1Fr";
+    const MODULUS: &'static str =
+        "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+}
+
+/// One script of a chunked computation.
+#[derive(Clone)]
+pub struct Chunk {
+    pub script: Script,
+    /// Hints to push before the input of the script
+    pub hints: Vec<BigUint>,
+    /// Field elements and scalars the script leaves on the stack, the deepest first
+    pub output: Vec<BigUint>,
+}
+
+impl Chunk {
+    pub fn push_hints(&self) -> Script {
+        script! {
+            for hint in self.hints.iter() {
+                { Fp::push(hint) }
+            }
+        }
+    }
+
+    pub fn push_output(&self) -> Script {
+        script! {
+            for element in self.output.iter() {
+                { Fp::push(element) }
+            }
+        }
+    }
+}
+
+/// Pushes `bytes` so that byte 0 ends up on top.
+pub fn push_bytes(bytes: &[u8]) -> Script {
+    script! {
+        for byte in bytes.iter().rev() {
+            { *byte }
+        }
+    }
+}
+
+/// Converts a 32-byte big endian number with byte 0 on top into a [`U256`]. The bytes are
+/// expected to be in range, e.g. because they are committed with Winternitz signatures.
+pub fn bytes_to_u256() -> Script {
+    script! {
+        // least significant byte on top
+        for i in 1..32 {
+            { i } OP_ROLL
+        }
+        { U256::transform_limbsize(8, 29) }
+    }
+}
+
+/// Reduces a number below `2n` on top of the stack modulo the group order, e.g. a hash or an x
+/// coordinate.
+fn reduce_scalar() -> Script {
+    script! {
+        { Fr::copy(0) }
+        { Fr::is_field() }
+        OP_NOTIF
+            { Fr::push_modulus() }
+            { U256::sub(1, 0) }
+        OP_ENDIF
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn test_bytes_to_u256() {
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        for _ in 0..10 {
+            let bytes: [u8; 32] = prng.gen();
+            run(script! {
+                { push_bytes(&bytes) }
+                { bytes_to_u256() }
+                { U256::push_u32_le(&BigUint::from_bytes_be(&bytes).to_u32_digits()) }
+                { U256::equal(1, 0) }
+            });
+        }
+    }
+}
@@ -0,0 +1,459 @@
+//! Scalar multiplication split across chunks.
+//!
+//! The accumulator starts at a fixed offset point with unknown discrete logarithm instead of the
+//! point at infinity, so the incomplete formulas only fail with negligible probability. The
+//! offset is subtracted again when the computation is finished.
+//!
+//! The bits of the scalar are processed from the most significant one. A variable-base chunk
+//! runs double-and-add over a range of bits, a fixed-base chunk adds precomputed multiples of
+//! the generator for a range of 4-bit windows.
+
+use std::ops::Range;
+
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use super::curve::{hinted_add, hinted_double, AffinePoint};
+use super::{Chunk, Fp, Fr};
+use crate::bigint::U256;
+use crate::field::PrimeField;
+use crate::treepp::*;
+
+pub const SCALAR_BITS: u32 = 256;
+
+/// Bits per window of a fixed-base multiplication
+pub const WINDOW_SIZE: u32 = 4;
+
+pub const NUM_WINDOWS: u32 = SCALAR_BITS / WINDOW_SIZE;
+
+/// Bits per chunk of a variable-base multiplication in a signature verification
+pub const VARIABLE_BASE_BITS_PER_CHUNK: u32 = 1;
+
+/// Windows per chunk of a fixed-base multiplication in a signature verification
+pub const FIXED_BASE_WINDOWS_PER_CHUNK: u32 = 2;
+
+/// Initial value of the accumulator, the first point whose x coordinate is at least
+/// SHA256("BitVM/secp256k1/offset").
+pub fn offset_point() -> AffinePoint {
+    let mut x = BigUint::from_bytes_be(&Sha256::digest(b"BitVM/secp256k1/offset")) % Fp::modulus();
+    loop {
+        if let Some(p) = AffinePoint::lift_x(&x) {
+            return p;
+        }
+        x += 1u32;
+    }
+}
+
+/// Value of the accumulator of a variable-base multiplication by zero, i.e. the offset doubled
+/// for every bit.
+pub fn variable_base_offset() -> AffinePoint {
+    let mut p = offset_point();
+    for _ in 0..SCALAR_BITS {
+        p = p.double();
+    }
+    p
+}
+
+/// Bit `i` of `k`, counted from the most significant bit
+fn scalar_bit(k: &BigUint, i: u32) -> bool {
+    k.bit((SCALAR_BITS - 1 - i) as u64)
+}
+
+/// Moves a copy of the scalar at depth `depth` to the alt stack as bits, skipping the bits
+/// before `start`.
+fn scalar_bits_toaltstack(depth: u32, start: u32) -> Script {
+    script! {
+        { Fr::copy(depth) }
+        { U256::convert_to_be_bits_toaltstack() }
+        for _ in 0..start {
+            OP_FROMALTSTACK OP_DROP
+        }
+    }
+}
+
+fn drop_scalar_bits(end: u32) -> Script {
+    script! {
+        for _ in end..SCALAR_BITS {
+            OP_FROMALTSTACK OP_DROP
+        }
+    }
+}
+
+/// Runs double-and-add for the bits in `bits`.
+/// input: [k, P, acc]
+/// output: [k, P, acc']
+pub fn hinted_variable_base_chunk(
+    k: &BigUint,
+    base: &AffinePoint,
+    acc: &AffinePoint,
+    bits: Range<u32>,
+) -> (Script, Vec<BigUint>, AffinePoint) {
+    assert!(bits.end <= SCALAR_BITS);
+    let mut acc = acc.clone();
+    let mut hints = Vec::new();
+    let mut steps = Vec::new();
+    for i in bits.clone() {
+        let (double, double_hints) = hinted_double(&acc);
+        hints.extend(double_hints);
+        acc = acc.double();
+
+        // the script adds the base in any case, only the hints depend on the bit
+        let (add, add_hints) = hinted_add(&acc, base);
+        if scalar_bit(k, i) {
+            hints.extend(add_hints);
+            acc = acc.add(base);
+        }
+        steps.push((double, add));
+    }
+
+    let script = script! {
+        { scalar_bits_toaltstack(4, bits.start) }
+        for (double, add) in steps {
+            { double }
+            OP_FROMALTSTACK
+            OP_IF
+                { Fp::copy(3) }
+                { Fp::copy(3) }
+                { add }
+            OP_ENDIF
+        }
+        { drop_scalar_bits(bits.end) }
+    };
+    (script, hints, acc)
+}
+
+/// Removes the offset from the accumulator once all bits are processed.
+/// input: [k, P, acc]
+/// output: [k * P]
+pub fn hinted_variable_base_finish(acc: &AffinePoint) -> (Script, Vec<BigUint>, AffinePoint) {
+    let correction = variable_base_offset().neg();
+    let (add, hints) = hinted_add(acc, &correction);
+
+    let script = script! {
+        { Fp::toaltstack() }
+        { Fp::toaltstack() }
+        { Fp::drop() }
+        { Fp::drop() }
+        { Fr::drop() }
+        { Fp::fromaltstack() }
+        { Fp::fromaltstack() }
+        { correction.push() }
+        { add }
+    };
+    (script, hints, acc.add(&correction))
+}
+
+/// Multiples `d * 16^(NUM_WINDOWS - 1 - window) * G` for `d` from 1 to 15
+pub fn fixed_base_table(window: u32) -> Vec<AffinePoint> {
+    let mut base = AffinePoint::generator();
+    for _ in 0..WINDOW_SIZE * (NUM_WINDOWS - 1 - window) {
+        base = base.double();
+    }
+    let mut table = vec![base.clone(), base.double()];
+    for _ in 3..1 << WINDOW_SIZE {
+        table.push(table.last().unwrap().add(&base));
+    }
+    table
+}
+
+/// Pops the bits of a window from the alt stack and pushes the table entry with a 1, or just
+/// a 0 if the window is zero.
+fn select_table_entry(table: &[AffinePoint], remaining_bits: u32, prefix: usize) -> Script {
+    if remaining_bits == 0 {
+        if prefix == 0 {
+            script! { 0 }
+        } else {
+            script! {
+                { table[prefix - 1].push() }
+                1
+            }
+        }
+    } else {
+        script! {
+            OP_FROMALTSTACK
+            OP_IF
+                { select_table_entry(table, remaining_bits - 1, 2 * prefix + 1) }
+            OP_ELSE
+                { select_table_entry(table, remaining_bits - 1, 2 * prefix) }
+            OP_ENDIF
+        }
+    }
+}
+
+/// Adds the table entries for the windows in `windows`.
+/// input: [k, acc]
+/// output: [k, acc']
+pub fn hinted_fixed_base_chunk(
+    k: &BigUint,
+    acc: &AffinePoint,
+    windows: Range<u32>,
+) -> (Script, Vec<BigUint>, AffinePoint) {
+    assert!(windows.end <= NUM_WINDOWS);
+    let mut acc = acc.clone();
+    let mut hints = Vec::new();
+    let mut steps = Vec::new();
+    for window in windows.clone() {
+        let table = fixed_base_table(window);
+        let d = (0..WINDOW_SIZE).fold(0, |d, i| {
+            2 * d + scalar_bit(k, window * WINDOW_SIZE + i) as usize
+        });
+
+        // the script is the same for every entry, only the hints depend on the window
+        let entry = &table[d.max(1) - 1];
+        let (add, add_hints) = hinted_add(&acc, entry);
+        if d != 0 {
+            hints.extend(add_hints);
+            acc = acc.add(entry);
+        }
+        steps.push((select_table_entry(&table, WINDOW_SIZE, 0), add));
+    }
+
+    let script = script! {
+        { scalar_bits_toaltstack(2, windows.start * WINDOW_SIZE) }
+        for (select, add) in steps {
+            { select }
+            OP_IF
+                { add }
+            OP_ENDIF
+        }
+        { drop_scalar_bits(windows.end * WINDOW_SIZE) }
+    };
+    (script, hints, acc)
+}
+
+/// Removes the offset from the accumulator once all windows are processed.
+/// input: [k, acc]
+/// output: [k * G]
+pub fn hinted_fixed_base_finish(acc: &AffinePoint) -> (Script, Vec<BigUint>, AffinePoint) {
+    let correction = offset_point().neg();
+    let (add, hints) = hinted_add(acc, &correction);
+
+    let script = script! {
+        { Fp::toaltstack() }
+        { Fp::toaltstack() }
+        { Fr::drop() }
+        { Fp::fromaltstack() }
+        { Fp::fromaltstack() }
+        { correction.push() }
+        { add }
+    };
+    (script, hints, acc.add(&correction))
+}
+
+/// Chunks computing `a * G + b * Q`. The outputs only list the elements above the input of the
+/// first chunk.
+/// input: [b, Q, offset, a, offset]
+/// output: [a * G + b * Q]
+pub fn hinted_double_scalar_mul(a: &BigUint, b: &BigUint, q: &AffinePoint) -> Vec<Chunk> {
+    let offset = offset_point();
+    let mut chunks = Vec::new();
+
+    let mut acc = offset.clone();
+    for start in (0..NUM_WINDOWS).step_by(FIXED_BASE_WINDOWS_PER_CHUNK as usize) {
+        let end = (start + FIXED_BASE_WINDOWS_PER_CHUNK).min(NUM_WINDOWS);
+        let (script, hints, next) = hinted_fixed_base_chunk(a, &acc, start..end);
+        acc = next;
+        chunks.push(Chunk {
+            script,
+            hints,
+            output: vec![
+                b.clone(),
+                q.x.clone(),
+                q.y.clone(),
+                offset.x.clone(),
+                offset.y.clone(),
+                a.clone(),
+                acc.x.clone(),
+                acc.y.clone(),
+            ],
+        });
+    }
+
+    let (finish, hints, a_g) = hinted_fixed_base_finish(&acc);
+    chunks.push(Chunk {
+        script: script! {
+            { finish }
+            // move a * G below the state of the variable-base multiplication
+            for _ in 0..5 {
+                { Fp::roll(6) }
+            }
+        },
+        hints,
+        output: vec![
+            a_g.x.clone(),
+            a_g.y.clone(),
+            b.clone(),
+            q.x.clone(),
+            q.y.clone(),
+            offset.x.clone(),
+            offset.y.clone(),
+        ],
+    });
+
+    let mut acc = offset;
+    for start in (0..SCALAR_BITS).step_by(VARIABLE_BASE_BITS_PER_CHUNK as usize) {
+        let end = (start + VARIABLE_BASE_BITS_PER_CHUNK).min(SCALAR_BITS);
+        let (script, hints, next) = hinted_variable_base_chunk(b, q, &acc, start..end);
+        acc = next;
+        chunks.push(Chunk {
+            script,
+            hints,
+            output: vec![
+                a_g.x.clone(),
+                a_g.y.clone(),
+                b.clone(),
+                q.x.clone(),
+                q.y.clone(),
+                acc.x.clone(),
+                acc.y.clone(),
+            ],
+        });
+    }
+
+    let (finish, mut hints, b_q) = hinted_variable_base_finish(&acc);
+    let (add, add_hints) = hinted_add(&a_g, &b_q);
+    hints.extend(add_hints);
+    let r = a_g.add(&b_q);
+    chunks.push(Chunk {
+        script: script! {
+            { finish }
+            { add }
+        },
+        hints,
+        output: vec![r.x, r.y],
+    });
+    chunks
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    fn random_scalar(prng: &mut ChaCha20Rng) -> (BigUint, SecretKey) {
+        let secret_key = SecretKey::from_slice(&prng.gen::<[u8; 32]>()).unwrap();
+        (
+            BigUint::from_bytes_be(&secret_key.secret_bytes()),
+            secret_key,
+        )
+    }
+
+    fn point_equalverify() -> Script {
+        script! {
+            { Fp::roll(2) }
+            { Fp::equalverify(1, 0) }
+            { Fp::equalverify(1, 0) }
+        }
+    }
+
+    #[test]
+    fn test_off_chain_chunks() {
+        let secp = Secp256k1::new();
+        let mut prng = ChaCha20Rng::seed_from_u64(0);
+        let (k, secret_key) = random_scalar(&mut prng);
+        let (_, base_key) = random_scalar(&mut prng);
+        let base = AffinePoint::from_public_key(&base_key.public_key(&secp));
+
+        for (i, entry) in fixed_base_table(NUM_WINDOWS - 1).iter().enumerate() {
+            assert_eq!(
+                AffinePoint::generator().mul(&BigUint::from(i + 1)),
+                Some(entry.clone())
+            );
+        }
+
+        let mut acc = offset_point();
+        for start in (0..NUM_WINDOWS).step_by(8) {
+            acc = hinted_fixed_base_chunk(&k, &acc, start..start + 8).2;
+        }
+        assert_eq!(
+            hinted_fixed_base_finish(&acc).2,
+            AffinePoint::from_public_key(&secret_key.public_key(&secp))
+        );
+
+        let mut acc = offset_point();
+        for start in (0..SCALAR_BITS).step_by(32) {
+            acc = hinted_variable_base_chunk(&k, &base, &acc, start..start + 32).2;
+        }
+        let expected: PublicKey = base_key
+            .public_key(&secp)
+            .mul_tweak(&secp, &secret_key.into())
+            .unwrap();
+        assert_eq!(
+            hinted_variable_base_finish(&acc).2,
+            AffinePoint::from_public_key(&expected)
+        );
+    }
+
+    #[test]
+    fn test_variable_base_chunk() {
+        let secp = Secp256k1::new();
+        let mut prng = ChaCha20Rng::seed_from_u64(1);
+        let (k, _) = random_scalar(&mut prng);
+        let (_, base_key) = random_scalar(&mut prng);
+        let base = AffinePoint::from_public_key(&base_key.public_key(&secp));
+
+        let mut acc = offset_point();
+        for start in 0..8 {
+            let (script, hints, next) =
+                hinted_variable_base_chunk(&k, &base, &acc, start..start + 1);
+            if start == 0 || start == 7 {
+                println!("variable-base chunk: {} bytes", script.len());
+                run(script! {
+                    for hint in hints.iter() {
+                        { Fp::push(hint) }
+                    }
+                    { Fr::push(&k) }
+                    { base.push() }
+                    { acc.push() }
+                    { script }
+                    { next.push() }
+                    { point_equalverify() }
+                    { base.push() }
+                    { point_equalverify() }
+                    { Fr::push(&k) }
+                    { Fr::equal(1, 0) }
+                });
+            }
+            acc = next;
+        }
+    }
+
+    #[test]
+    fn test_fixed_base_chunk() {
+        let mut prng = ChaCha20Rng::seed_from_u64(2);
+        let (k, _) = random_scalar(&mut prng);
+
+        let acc = offset_point();
+        let (script, hints, next) = hinted_fixed_base_chunk(&k, &acc, 0..2);
+        println!("fixed-base chunk: {} bytes", script.len());
+        run(script! {
+            for hint in hints.iter() {
+                { Fp::push(hint) }
+            }
+            { Fr::push(&k) }
+            { acc.push() }
+            { script }
+            { next.push() }
+            { point_equalverify() }
+            { Fr::push(&k) }
+            { Fr::equal(1, 0) }
+        });
+
+        // a zero window adds nothing
+        let k = BigUint::from(1u32) << (SCALAR_BITS - 1);
+        let (script, hints, next) = hinted_fixed_base_chunk(&k, &acc, 1..2);
+        assert!(hints.is_empty());
+        assert_eq!(next, acc);
+        run(script! {
+            { Fr::push(&k) }
+            { acc.push() }
+            { script }
+            { acc.push() }
+            { point_equalverify() }
+            { Fr::drop() }
+            OP_TRUE
+        });
+    }
+}
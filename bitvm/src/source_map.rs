@@ -0,0 +1,320 @@
+//! Source-mapped script compilation.
+//!
+//! Script generators wrap their output with [`labeled`]. Normally this is a no-op; inside
+//! [`compile_with_source_map`] it surrounds the script with label markers, which are stripped
+//! again after compilation and turned into a [`SourceMap`] from byte offsets of the compiled
+//! script to the labels of the generators that emitted them.
+
+use core::fmt;
+use std::{cell::Cell, collections::BTreeMap};
+
+use bitcoin::{opcodes::all::OP_DROP, script::Instruction, ScriptBuf};
+
+use crate::{execute_info, new_exec, treepp, ExecuteInfo};
+
+const LABEL_BEGIN_PREFIX: &[u8] = b"bitvm-label-begin:";
+const LABEL_END: &[u8] = b"bitvm-label-end";
+
+thread_local! {
+    static SOURCE_MAP_ENABLED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Labels `script` with the name of the function or step that generated it.
+pub fn labeled(label: &str, script: treepp::Script) -> treepp::Script {
+    if !SOURCE_MAP_ENABLED.with(Cell::get) {
+        return script;
+    }
+    let begin = [LABEL_BEGIN_PREFIX, label.as_bytes()].concat();
+    // MAX_SCRIPT_ELEMENT_SIZE
+    assert!(begin.len() <= 520, "Label is too long");
+    treepp::script! {
+        { begin }
+        OP_DROP
+        { script }
+        { LABEL_END.to_vec() }
+        OP_DROP
+    }
+}
+
+/// A range of the compiled script generated under a label.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub label: String,
+    /// Byte offset of the first opcode.
+    pub start: usize,
+    /// Byte offset after the last opcode.
+    pub end: usize,
+    /// Number of labeled spans this span is nested in.
+    pub depth: usize,
+    /// Whether an enclosing span has the same label, e.g. for recursive generators.
+    pub is_recursive: bool,
+}
+
+impl Span {
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// Maps byte offsets of a compiled script to the labels that generated them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    /// Spans ordered by start offset, enclosing spans first.
+    spans: Vec<Span>,
+}
+
+impl SourceMap {
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// Returns the labels of the spans containing `offset`, from the outermost to the innermost.
+    pub fn labels_at(&self, offset: usize) -> Vec<&str> {
+        self.spans
+            .iter()
+            .take_while(|span| span.start <= offset)
+            .filter(|span| offset < span.end)
+            .map(|span| span.label.as_str())
+            .collect()
+    }
+
+    /// Returns the label of the innermost span containing `offset`.
+    pub fn label_at(&self, offset: usize) -> Option<&str> {
+        self.labels_at(offset).pop()
+    }
+
+    /// Returns the number of spans and the number of bytes generated under each label. Spans
+    /// nested in a span with the same label are not counted again.
+    pub fn size_by_label(&self) -> BTreeMap<&str, (usize, usize)> {
+        let mut sizes = BTreeMap::new();
+        for span in self.spans.iter().filter(|span| !span.is_recursive) {
+            let (count, size) = sizes.entry(span.label.as_str()).or_insert((0, 0));
+            *count += 1;
+            *size += span.len();
+        }
+        sizes
+    }
+}
+
+/// Builds the script with labeling enabled, and compiles it into a script without the label
+/// markers and its source map.
+pub fn compile_with_source_map(build: impl FnOnce() -> treepp::Script) -> (ScriptBuf, SourceMap) {
+    let previous = SOURCE_MAP_ENABLED.with(|enabled| enabled.replace(true));
+    let script = build();
+    SOURCE_MAP_ENABLED.with(|enabled| enabled.set(previous));
+    strip_labels(&script.compile())
+}
+
+/// Removes the label markers of `script`, recording the spans between them.
+fn strip_labels(script: &ScriptBuf) -> (ScriptBuf, SourceMap) {
+    let instructions = script
+        .instruction_indices()
+        .map(|instruction| instruction.expect("Compiled script should be valid"))
+        .collect::<Vec<_>>();
+    let bytes = script.as_bytes();
+
+    let mut stripped = vec![];
+    let mut spans = vec![];
+    // Index in `spans` of the open spans.
+    let mut open: Vec<usize> = vec![];
+    let mut i = 0;
+    while i < instructions.len() {
+        let (index, instruction) = instructions[i];
+        let next_index = instructions
+            .get(i + 1)
+            .map_or(bytes.len(), |(index, _)| *index);
+
+        let is_followed_by_drop = matches!(
+            instructions.get(i + 1),
+            Some((_, Instruction::Op(opcode))) if *opcode == OP_DROP
+        );
+        if let (Instruction::PushBytes(push), true) = (instruction, is_followed_by_drop) {
+            if let Some(label) = push.as_bytes().strip_prefix(LABEL_BEGIN_PREFIX) {
+                let label = String::from_utf8_lossy(label).into_owned();
+                let is_recursive = open.iter().any(|&span| spans[span].label == label);
+                open.push(spans.len());
+                spans.push(Span {
+                    label,
+                    start: stripped.len(),
+                    end: stripped.len(),
+                    depth: open.len() - 1,
+                    is_recursive,
+                });
+                i += 2;
+                continue;
+            }
+            if push.as_bytes() == LABEL_END {
+                let span: usize = open.pop().expect("Unbalanced label markers");
+                spans[span].end = stripped.len();
+                i += 2;
+                continue;
+            }
+        }
+
+        stripped.extend_from_slice(&bytes[index..next_index]);
+        i += 1;
+    }
+    assert!(open.is_empty(), "Unbalanced label markers");
+
+    (ScriptBuf::from_bytes(stripped), SourceMap { spans })
+}
+
+/// The result of executing a source-mapped script.
+#[derive(Debug)]
+pub struct SourceMappedExecuteInfo {
+    pub info: ExecuteInfo,
+    /// Byte offset of the opcode that failed. `None` if the script succeeded, or if it ran to
+    /// the end and failed on the final stack.
+    pub failed_at: Option<usize>,
+    /// Labels at `failed_at`, from the outermost to the innermost.
+    pub labels: Vec<String>,
+}
+
+impl fmt::Display for SourceMappedExecuteInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(offset) = self.failed_at {
+            match self.labels.last() {
+                Some(label) => writeln!(f, "Failed in `{}` at offset {}", label, offset)?,
+                None => writeln!(f, "Failed at offset {}", offset)?,
+            }
+            if self.labels.len() > 1 {
+                writeln!(f, "Labels: {}", self.labels.join(" > "))?;
+            }
+        }
+        write!(f, "{}", self.info)
+    }
+}
+
+/// Executes a script compiled by `compile_with_source_map`, reporting where it failed.
+pub fn execute_script_with_source_map(
+    script: ScriptBuf,
+    source_map: &SourceMap,
+    witness: Vec<Vec<u8>>,
+) -> SourceMappedExecuteInfo {
+    let script_len = script.len();
+    let mut exec = new_exec(script, witness, true);
+
+    let mut failed_at = None;
+    loop {
+        let remaining_len = exec.remaining_script().len();
+        if let Err(res) = exec.exec_next() {
+            if !res.success && remaining_len > 0 {
+                failed_at = Some(script_len - remaining_len);
+            }
+            break;
+        }
+    }
+
+    let info = execute_info(&exec);
+    let labels = failed_at.map_or(vec![], |offset| {
+        source_map
+            .labels_at(offset)
+            .into_iter()
+            .map(str::to_owned)
+            .collect()
+    });
+    SourceMappedExecuteInfo {
+        info,
+        failed_at,
+        labels,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::treepp::*;
+
+    fn add_one() -> Script {
+        labeled(
+            "add_one",
+            script! {
+                OP_1ADD
+            },
+        )
+    }
+
+    fn add_two_and_check(expected: u32) -> Script {
+        labeled(
+            "add_two_and_check",
+            script! {
+                { add_one() }
+                { add_one() }
+                { expected }
+                OP_EQUALVERIFY
+            },
+        )
+    }
+
+    #[test]
+    fn test_labeled_is_noop_by_default() {
+        assert_eq!(
+            add_two_and_check(3).compile(),
+            script! { OP_1ADD OP_1ADD OP_3 OP_EQUALVERIFY }.compile()
+        );
+    }
+
+    #[test]
+    fn test_compile_with_source_map() {
+        let (script, source_map) = compile_with_source_map(|| {
+            script! {
+                OP_1
+                { add_two_and_check(3) }
+                OP_TRUE
+            }
+        });
+        assert_eq!(
+            script,
+            script! { OP_1 OP_1ADD OP_1ADD OP_3 OP_EQUALVERIFY OP_TRUE }.compile()
+        );
+
+        assert_eq!(source_map.spans().len(), 3);
+        assert_eq!(source_map.labels_at(0), Vec::<&str>::new());
+        assert_eq!(
+            source_map.labels_at(2),
+            vec!["add_two_and_check", "add_one"]
+        );
+        assert_eq!(source_map.label_at(3), Some("add_two_and_check"));
+        assert_eq!(source_map.label_at(5), None);
+
+        let sizes = source_map.size_by_label();
+        assert_eq!(sizes["add_one"], (2, 2));
+        assert_eq!(sizes["add_two_and_check"], (1, 4));
+
+        // Labeling is disabled again after compiling.
+        assert_eq!(add_one().compile(), script! { OP_1ADD }.compile());
+    }
+
+    #[test]
+    fn test_execute_script_with_source_map() {
+        let (script, source_map) = compile_with_source_map(|| {
+            script! {
+                OP_1
+                { add_two_and_check(3) }
+                OP_TRUE
+            }
+        });
+        let result = execute_script_with_source_map(script, &source_map, vec![]);
+        assert!(result.info.success);
+        assert_eq!(result.failed_at, None);
+
+        let (script, source_map) = compile_with_source_map(|| {
+            script! {
+                OP_1
+                { add_two_and_check(4) }
+                OP_TRUE
+            }
+        });
+        let result = execute_script_with_source_map(script, &source_map, vec![]);
+        assert!(!result.info.success);
+        assert_eq!(result.failed_at, Some(4));
+        assert_eq!(result.labels, vec!["add_two_and_check"]);
+        assert!(result
+            .to_string()
+            .starts_with("Failed in `add_two_and_check` at offset 4"));
+    }
+}
@@ -0,0 +1,682 @@
+//! Static stack-effect analysis of compiled scripts.
+//!
+//! [`stack_effect`] abstractly interprets a script from an unknown stack. Instead of values it
+//! tracks where each stack item came from, and the values of small numbers pushed by the script
+//! itself so that `OP_PICK` and `OP_ROLL` with constant depths can be followed.
+//!
+//! A conditional forks the interpretation into a path per branch, unless its condition is known
+//! or was already branched on earlier in the path. Paths that reach `OP_ENDIF` with the same
+//! stack shape are joined again, and all paths must end with the same stack effect.
+//!
+//! Only gadgets that access the stack at constant depths can be analyzed, like the bigint and
+//! bn254 field arithmetic without hints, the G1 stack helpers, the u32 rotations and the u4
+//! additions without tables. Table lookups (u4 tables, `u32_xor`, `u32_and`) compute the depth of
+//! their `OP_PICK` from the looked-up value, and hinted gadgets roll their hints up from the bottom
+//! of the stack with `OP_DEPTH`. Both fail with [`StackEffectError::DynamicDepth`].
+
+use core::fmt;
+
+use bitcoin::{opcodes::all::*, script::Instruction, Opcode, ScriptBuf};
+
+use crate::treepp::Script;
+
+/// Maximum number of paths interpreted at the same time.
+const MAX_PATHS: usize = 1024;
+
+/// Stack signature of a script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StackEffect {
+    /// Number of stack items the script reads or consumes.
+    pub inputs: usize,
+    /// Number of stack items the script leaves in place of its inputs.
+    pub outputs: usize,
+    /// Number of items the script moves to the altstack minus the number it takes from it.
+    pub altstack_delta: isize,
+    /// Maximum number of stack items, counting the inputs.
+    pub max_depth: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StackEffectError {
+    /// The script can't be decoded.
+    InvalidScript,
+    /// The opcode at `offset` can't be analyzed statically.
+    UnsupportedOpcode { offset: usize, opcode: Opcode },
+    /// The depth argument of the `OP_PICK` or `OP_ROLL` at `offset` is not a constant.
+    DynamicDepth { offset: usize },
+    /// `OP_ELSE` or `OP_ENDIF` at `offset` without `OP_IF`, or a second `OP_ELSE`.
+    UnmatchedConditional { offset: usize },
+    /// The script ends inside a conditional.
+    UnterminatedConditional,
+    /// The conditional at `offset` forks more than `MAX_PATHS` paths.
+    TooManyPaths { offset: usize },
+    /// The stack effect depends on the branches taken.
+    PathDependentEffect,
+    /// Every path through the script ends in `OP_RETURN`.
+    AlwaysFails,
+}
+
+impl fmt::Display for StackEffectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StackEffectError::InvalidScript => write!(f, "Invalid script"),
+            StackEffectError::UnsupportedOpcode { offset, opcode } => {
+                write!(f, "Unsupported opcode {} at offset {}", opcode, offset)
+            }
+            StackEffectError::DynamicDepth { offset } => write!(
+                f,
+                "Depth of the stack access at offset {} is not a constant",
+                offset
+            ),
+            StackEffectError::UnmatchedConditional { offset } => {
+                write!(f, "Unmatched conditional at offset {}", offset)
+            }
+            StackEffectError::UnterminatedConditional => write!(f, "Unterminated conditional"),
+            StackEffectError::TooManyPaths { offset } => {
+                write!(f, "Too many paths at the conditional at offset {}", offset)
+            }
+            StackEffectError::PathDependentEffect => {
+                write!(f, "Stack effect depends on the branches taken")
+            }
+            StackEffectError::AlwaysFails => write!(f, "Script always fails"),
+        }
+    }
+}
+
+impl std::error::Error for StackEffectError {}
+
+/// IDs of the stack inputs, numbered from the top.
+const INPUT_ID: u64 = 1 << 63;
+/// IDs of the altstack inputs, numbered from the top.
+const ALTSTACK_INPUT_ID: u64 = 1 << 62;
+/// IDs of items that differ between joined paths.
+const JOIN_ID: u64 = 1 << 61;
+
+/// Abstract stack item. Items produced by the script are identified by the offset of the
+/// instruction that produced them, copies share the ID of the original.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Item {
+    id: u64,
+    /// The value if it is a number known statically.
+    value: Option<i64>,
+}
+
+impl Item {
+    fn new(offset: usize, value: Option<i64>) -> Self {
+        Item {
+            id: offset as u64,
+            value,
+        }
+    }
+}
+
+/// State of one path through the script.
+#[derive(Debug, Clone)]
+struct State {
+    stack: Vec<Item>,
+    altstack: Vec<Item>,
+    /// Number of items below the script's own items that have been accessed.
+    inputs: usize,
+    altstack_inputs: usize,
+    /// Maximum of `stack.len() - inputs`.
+    max_growth: isize,
+    /// Values of the conditions branched on, by item ID.
+    conditions: Vec<(u64, bool)>,
+}
+
+impl State {
+    fn new() -> Self {
+        State {
+            stack: vec![],
+            altstack: vec![],
+            inputs: 0,
+            altstack_inputs: 0,
+            max_growth: 0,
+            conditions: vec![],
+        }
+    }
+
+    /// Makes sure there are at least `depth` items on the stack by taking more inputs.
+    fn ensure(&mut self, depth: usize) {
+        if self.stack.len() < depth {
+            let missing = depth - self.stack.len();
+            let inputs = (0..missing).rev().map(|i| Item {
+                id: INPUT_ID + (self.inputs + i) as u64,
+                value: None,
+            });
+            self.stack.splice(0..0, inputs);
+            self.inputs += missing;
+        }
+    }
+
+    fn ensure_altstack(&mut self, depth: usize) {
+        if self.altstack.len() < depth {
+            let missing = depth - self.altstack.len();
+            let inputs = (0..missing).rev().map(|i| Item {
+                id: ALTSTACK_INPUT_ID + (self.altstack_inputs + i) as u64,
+                value: None,
+            });
+            self.altstack.splice(0..0, inputs);
+            self.altstack_inputs += missing;
+        }
+    }
+
+    fn track_growth(&mut self) {
+        self.max_growth = self
+            .max_growth
+            .max(self.stack.len() as isize - self.inputs as isize);
+    }
+
+    fn push(&mut self, item: Item) {
+        self.stack.push(item);
+        self.track_growth();
+    }
+
+    fn pop(&mut self) -> Item {
+        self.ensure(1);
+        self.stack.pop().unwrap()
+    }
+
+    /// Returns the item `depth` items below the top.
+    fn peek(&mut self, depth: usize) -> Item {
+        self.ensure(depth + 1);
+        self.stack[self.stack.len() - 1 - depth]
+    }
+
+    /// Removes the item `depth` items below the top.
+    fn remove(&mut self, depth: usize) -> Item {
+        self.ensure(depth + 1);
+        let index = self.stack.len() - 1 - depth;
+        self.stack.remove(index)
+    }
+
+    fn pop_n(&mut self, n: usize) {
+        for _ in 0..n {
+            self.pop();
+        }
+    }
+
+    /// Pops `n` items and pushes an unknown item produced at `offset`.
+    fn reduce(&mut self, n: usize, offset: usize) {
+        self.pop_n(n);
+        self.push(Item::new(offset, None));
+    }
+
+    /// Returns the value of the condition `item`, if it is known on this path.
+    fn condition(&self, item: Item) -> Option<bool> {
+        item.value.map(|value| value != 0).or_else(|| {
+            self.conditions
+                .iter()
+                .find(|(id, _)| *id == item.id)
+                .map(|(_, value)| *value)
+        })
+    }
+
+    /// Joins `other` into this state. Both must have the same shape.
+    fn join(&mut self, other: State, joins: &mut u64) {
+        let mut join_items = |items: &mut [Item], others: &[Item]| {
+            for (item, other) in items.iter_mut().zip(others) {
+                if item.value != other.value {
+                    item.value = None;
+                }
+                if item.id != other.id {
+                    item.id = JOIN_ID + *joins;
+                    *joins += 1;
+                }
+            }
+        };
+        join_items(&mut self.stack, &other.stack);
+        join_items(&mut self.altstack, &other.altstack);
+        self.max_growth = self.max_growth.max(other.max_growth);
+        self.conditions
+            .retain(|condition| other.conditions.contains(condition));
+    }
+}
+
+/// Joins the paths with the same stack shape.
+fn join_paths(mut paths: Vec<State>, joins: &mut u64) -> Vec<State> {
+    // Paths that took fewer inputs left them untouched.
+    let inputs = paths.iter().map(|state| state.inputs).max().unwrap_or(0);
+    let altstack_inputs = paths
+        .iter()
+        .map(|state| state.altstack_inputs)
+        .max()
+        .unwrap_or(0);
+    for state in &mut paths {
+        state.ensure(state.stack.len() + inputs - state.inputs);
+        state.ensure_altstack(state.altstack.len() + altstack_inputs - state.altstack_inputs);
+    }
+
+    let mut joined: Vec<State> = vec![];
+    for state in paths {
+        match joined.iter_mut().find(|other| {
+            other.stack.len() == state.stack.len() && other.altstack.len() == state.altstack.len()
+        }) {
+            Some(other) => other.join(state, joins),
+            None => joined.push(state),
+        }
+    }
+    joined
+}
+
+/// An open conditional.
+struct Branch {
+    /// Paths that skip the first branch.
+    skipped: Vec<State>,
+    /// Paths at the end of the first branch, once `OP_ELSE` was reached.
+    first: Option<Vec<State>>,
+}
+
+/// Decodes a pushed number, if it is small enough to be used as one.
+fn decode_number(bytes: &[u8]) -> Option<i64> {
+    if bytes.len() > 4 {
+        return None;
+    }
+    let Some((&last, _)) = bytes.split_last() else {
+        return Some(0);
+    };
+    let mut value = 0i64;
+    for (i, byte) in bytes.iter().enumerate() {
+        value |= (*byte as i64) << (8 * i);
+    }
+    if last & 0x80 != 0 {
+        value &= !(0x80i64 << (8 * (bytes.len() - 1)));
+        value = -value;
+    }
+    Some(value)
+}
+
+/// Interprets a non-flow-control opcode. Returns `false` if the path fails.
+fn execute(state: &mut State, opcode: Opcode, offset: usize) -> Result<bool, StackEffectError> {
+    match opcode {
+        OP_PUSHNUM_NEG1 => state.push(Item::new(offset, Some(-1))),
+        _ if (OP_PUSHNUM_1.to_u8()..=OP_PUSHNUM_16.to_u8()).contains(&opcode.to_u8()) => {
+            let value = (opcode.to_u8() - OP_PUSHNUM_1.to_u8() + 1) as i64;
+            state.push(Item::new(offset, Some(value)))
+        }
+        OP_RETURN => return Ok(false),
+        OP_NOP | OP_CODESEPARATOR => {}
+        OP_CLTV | OP_CSV => state.ensure(1),
+        OP_VERIFY => state.pop_n(1),
+
+        OP_TOALTSTACK => {
+            let item = state.pop();
+            state.altstack.push(item);
+        }
+        OP_FROMALTSTACK => {
+            state.ensure_altstack(1);
+            let item = state.altstack.pop().unwrap();
+            state.push(item);
+        }
+
+        OP_DROP => state.pop_n(1),
+        OP_2DROP => state.pop_n(2),
+        OP_DUP => {
+            let item = state.peek(0);
+            state.push(item);
+        }
+        OP_2DUP => {
+            let (a, b) = (state.peek(1), state.peek(0));
+            state.push(a);
+            state.push(b);
+        }
+        OP_3DUP => {
+            let (a, b, c) = (state.peek(2), state.peek(1), state.peek(0));
+            state.push(a);
+            state.push(b);
+            state.push(c);
+        }
+        OP_OVER => {
+            let item = state.peek(1);
+            state.push(item);
+        }
+        OP_2OVER => {
+            let (a, b) = (state.peek(3), state.peek(2));
+            state.push(a);
+            state.push(b);
+        }
+        OP_NIP => {
+            state.remove(1);
+        }
+        OP_SWAP => {
+            let item = state.remove(1);
+            state.push(item);
+        }
+        OP_2SWAP => {
+            let a = state.remove(3);
+            let b = state.remove(2);
+            state.push(a);
+            state.push(b);
+        }
+        OP_ROT => {
+            let item = state.remove(2);
+            state.push(item);
+        }
+        OP_2ROT => {
+            let a = state.remove(5);
+            let b = state.remove(4);
+            state.push(a);
+            state.push(b);
+        }
+        OP_TUCK => {
+            let item = state.peek(0);
+            state.ensure(2);
+            let index = state.stack.len() - 2;
+            state.stack.insert(index, item);
+            state.track_growth();
+        }
+        OP_PICK | OP_ROLL => {
+            let depth = state
+                .pop()
+                .value
+                .filter(|depth| *depth >= 0)
+                .ok_or(StackEffectError::DynamicDepth { offset })? as usize;
+            let item = match opcode {
+                OP_PICK => state.peek(depth),
+                _ => state.remove(depth),
+            };
+            state.push(item);
+        }
+        OP_IFDUP => {
+            let item = state.peek(0);
+            match state.condition(item) {
+                Some(true) => state.push(item),
+                Some(false) => {}
+                None => return Err(StackEffectError::UnsupportedOpcode { offset, opcode }),
+            }
+        }
+        OP_DEPTH => state.push(Item::new(offset, None)),
+        OP_SIZE => {
+            state.ensure(1);
+            state.push(Item::new(offset, None));
+        }
+
+        OP_1ADD | OP_1SUB | OP_NEGATE | OP_ABS | OP_NOT | OP_0NOTEQUAL => {
+            let value = state.pop().value.and_then(|a| match opcode {
+                OP_1ADD => a.checked_add(1),
+                OP_1SUB => a.checked_sub(1),
+                OP_NEGATE => a.checked_neg(),
+                OP_ABS => a.checked_abs(),
+                OP_NOT => Some((a == 0) as i64),
+                _ => Some((a != 0) as i64),
+            });
+            state.push(Item::new(offset, value));
+        }
+        OP_ADD
+        | OP_SUB
+        | OP_BOOLAND
+        | OP_BOOLOR
+        | OP_NUMEQUAL
+        | OP_NUMNOTEQUAL
+        | OP_LESSTHAN
+        | OP_GREATERTHAN
+        | OP_LESSTHANOREQUAL
+        | OP_GREATERTHANOREQUAL
+        | OP_MIN
+        | OP_MAX => {
+            let b = state.pop().value;
+            let a = state.pop().value;
+            let value = a.zip(b).and_then(|(a, b)| match opcode {
+                OP_ADD => a.checked_add(b),
+                OP_SUB => a.checked_sub(b),
+                OP_BOOLAND => Some((a != 0 && b != 0) as i64),
+                OP_BOOLOR => Some((a != 0 || b != 0) as i64),
+                OP_NUMEQUAL => Some((a == b) as i64),
+                OP_NUMNOTEQUAL => Some((a != b) as i64),
+                OP_LESSTHAN => Some((a < b) as i64),
+                OP_GREATERTHAN => Some((a > b) as i64),
+                OP_LESSTHANOREQUAL => Some((a <= b) as i64),
+                OP_GREATERTHANOREQUAL => Some((a >= b) as i64),
+                OP_MIN => Some(a.min(b)),
+                _ => Some(a.max(b)),
+            });
+            state.push(Item::new(offset, value));
+        }
+        OP_NUMEQUALVERIFY | OP_EQUALVERIFY => state.pop_n(2),
+        OP_EQUAL => state.reduce(2, offset),
+        OP_WITHIN => state.reduce(3, offset),
+
+        OP_RIPEMD160 | OP_SHA1 | OP_SHA256 | OP_HASH160 | OP_HASH256 => state.reduce(1, offset),
+        OP_CHECKSIG => state.reduce(2, offset),
+        OP_CHECKSIGVERIFY => state.pop_n(2),
+        OP_CHECKSIGADD => state.reduce(3, offset),
+
+        _ => return Err(StackEffectError::UnsupportedOpcode { offset, opcode }),
+    }
+    Ok(true)
+}
+
+/// Computes the stack signature of `script`.
+pub fn stack_effect(script: &ScriptBuf) -> Result<StackEffect, StackEffectError> {
+    let mut paths = vec![State::new()];
+    let mut branches: Vec<Branch> = vec![];
+    let mut joins = 0;
+
+    for instruction in script.instruction_indices() {
+        let (offset, instruction) = instruction.map_err(|_| StackEffectError::InvalidScript)?;
+        let opcode = match instruction {
+            Instruction::PushBytes(push) => {
+                let item = Item::new(offset, decode_number(push.as_bytes()));
+                for state in &mut paths {
+                    state.push(item);
+                }
+                continue;
+            }
+            Instruction::Op(opcode) => opcode,
+        };
+
+        match opcode {
+            OP_IF | OP_NOTIF => {
+                let mut taken = vec![];
+                let mut skipped = vec![];
+                for mut state in paths {
+                    let item = state.pop();
+                    match state.condition(item) {
+                        Some(condition) if condition == (opcode == OP_IF) => taken.push(state),
+                        Some(_) => skipped.push(state),
+                        None => {
+                            let mut other = state.clone();
+                            state.conditions.push((item.id, opcode == OP_IF));
+                            other.conditions.push((item.id, opcode != OP_IF));
+                            taken.push(state);
+                            skipped.push(other);
+                        }
+                    }
+                }
+                if taken.len() + skipped.len() > MAX_PATHS {
+                    return Err(StackEffectError::TooManyPaths { offset });
+                }
+                paths = taken;
+                branches.push(Branch {
+                    skipped,
+                    first: None,
+                });
+            }
+            OP_ELSE => {
+                let branch = branches
+                    .last_mut()
+                    .filter(|branch| branch.first.is_none())
+                    .ok_or(StackEffectError::UnmatchedConditional { offset })?;
+                let skipped = core::mem::take(&mut branch.skipped);
+                branch.first = Some(core::mem::replace(&mut paths, skipped));
+            }
+            OP_ENDIF => {
+                let branch = branches
+                    .pop()
+                    .ok_or(StackEffectError::UnmatchedConditional { offset })?;
+                // Without `OP_ELSE`, the skipped paths went through an empty second branch.
+                paths.extend(branch.first.unwrap_or(branch.skipped));
+                paths = join_paths(paths, &mut joins);
+            }
+            _ => {
+                let mut next = Vec::with_capacity(paths.len());
+                for mut state in paths {
+                    if execute(&mut state, opcode, offset)? {
+                        next.push(state);
+                    }
+                }
+                paths = next;
+            }
+        }
+    }
+
+    if !branches.is_empty() {
+        return Err(StackEffectError::UnterminatedConditional);
+    }
+    let state = match join_paths(paths, &mut joins).as_slice() {
+        [] => return Err(StackEffectError::AlwaysFails),
+        [state] => state.clone(),
+        _ => return Err(StackEffectError::PathDependentEffect),
+    };
+    Ok(StackEffect {
+        inputs: state.inputs,
+        outputs: state.stack.len(),
+        altstack_delta: state.altstack.len() as isize - state.altstack_inputs as isize,
+        max_depth: (state.inputs as isize + state.max_growth) as usize,
+    })
+}
+
+/// Asserts that `script` replaces `inputs` stack items with `outputs` items and leaves the
+/// altstack as it found it. Returns the full stack effect.
+pub fn assert_stack_effect(script: Script, inputs: usize, outputs: usize) -> StackEffect {
+    let effect = stack_effect(&script.compile()).unwrap_or_else(|err| panic!("{}", err));
+    assert_eq!(
+        (effect.inputs, effect.outputs, effect.altstack_delta),
+        (inputs, outputs, 0),
+        "Unexpected stack effect (inputs, outputs, altstack delta)"
+    );
+    effect
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn254::{fp254impl::Fp254Impl, fq::Fq};
+    use crate::treepp::*;
+    use crate::u32::{u32_and::u32_and, u32_xor::u32_xor};
+    use crate::u4::u4_add::u4_add_with_table;
+
+    fn effect(script: Script) -> Result<StackEffect, StackEffectError> {
+        stack_effect(&script.compile())
+    }
+
+    fn signature(script: Script) -> Result<(usize, usize, isize), StackEffectError> {
+        effect(script).map(|effect| (effect.inputs, effect.outputs, effect.altstack_delta))
+    }
+
+    #[test]
+    fn test_stack_effect() {
+        assert_eq!(
+            effect(script! { OP_ADD OP_DUP }),
+            Ok(StackEffect {
+                inputs: 2,
+                outputs: 2,
+                altstack_delta: 0,
+                max_depth: 2,
+            })
+        );
+        assert_eq!(
+            effect(script! { 1 2 3 OP_2DROP OP_TOALTSTACK }),
+            Ok(StackEffect {
+                inputs: 0,
+                outputs: 0,
+                altstack_delta: 1,
+                max_depth: 3,
+            })
+        );
+        // The depth of `OP_ROLL` is followed through arithmetic.
+        assert_eq!(
+            effect(script! { 2 3 OP_ADD OP_ROLL OP_DROP }),
+            Ok(StackEffect {
+                inputs: 6,
+                outputs: 5,
+                altstack_delta: 0,
+                max_depth: 8,
+            })
+        );
+        assert_eq!(
+            signature(script! { OP_FROMALTSTACK OP_FROMALTSTACK OP_ADD OP_TOALTSTACK }),
+            Ok((0, 0, -1))
+        );
+        assert_eq!(
+            effect(script! { OP_DEPTH OP_PICK }),
+            Err(StackEffectError::DynamicDepth { offset: 1 })
+        );
+    }
+
+    #[test]
+    fn test_stack_effect_conditionals() {
+        assert_eq!(
+            signature(script! { OP_IF OP_ADD OP_ELSE OP_SUB OP_ENDIF }),
+            Ok((3, 1, 0))
+        );
+        // Branches taking different numbers of inputs.
+        assert_eq!(
+            signature(script! { OP_IF OP_DROP OP_ELSE OP_2DROP 0 OP_ENDIF }),
+            Ok((3, 1, 0))
+        );
+        // A failing branch does not need to balance.
+        assert_eq!(
+            signature(script! { OP_NOTIF OP_RETURN OP_ENDIF OP_DROP }),
+            Ok((2, 0, 0))
+        );
+        // Only the branch of a known condition is taken.
+        assert_eq!(
+            signature(script! { 0 OP_IF OP_DROP OP_ENDIF }),
+            Ok((0, 0, 0))
+        );
+        // Branches on the same condition are correlated.
+        assert_eq!(
+            signature(script! {
+                OP_DUP
+                OP_IF OP_FROMALTSTACK OP_DROP OP_ENDIF
+                OP_NOTIF OP_FROMALTSTACK OP_DROP OP_ENDIF
+            }),
+            Ok((1, 0, -1))
+        );
+
+        assert_eq!(
+            signature(script! { OP_IF OP_DROP OP_ENDIF }),
+            Err(StackEffectError::PathDependentEffect)
+        );
+        assert_eq!(
+            signature(script! { OP_IF OP_DROP }),
+            Err(StackEffectError::UnterminatedConditional)
+        );
+        assert_eq!(
+            signature(script! { OP_ENDIF }),
+            Err(StackEffectError::UnmatchedConditional { offset: 0 })
+        );
+        assert_eq!(
+            signature(script! { OP_RETURN }),
+            Err(StackEffectError::AlwaysFails)
+        );
+    }
+
+    #[test]
+    fn test_stack_effect_dynamic_gadgets() {
+        let is_dynamic =
+            |script: Script| matches!(effect(script), Err(StackEffectError::DynamicDepth { .. }));
+        assert!(is_dynamic(u32_xor(0, 1, 3)));
+        assert!(is_dynamic(u32_and(0, 1, 3)));
+        assert!(is_dynamic(u4_add_with_table(8, vec![0, 8], 16)));
+        let (hinted_mul, _) =
+            Fq::hinted_mul(1, ark_bn254::Fq::from(2u64), 0, ark_bn254::Fq::from(3u64));
+        assert!(is_dynamic(hinted_mul));
+    }
+
+    #[test]
+    fn test_assert_stack_effect() {
+        assert_stack_effect(
+            script! { OP_2DUP OP_ADD OP_TOALTSTACK OP_FROMALTSTACK },
+            2,
+            3,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Unexpected stack effect")]
+    fn test_assert_stack_effect_mismatch() {
+        assert_stack_effect(script! { OP_ADD }, 2, 2);
+    }
+}
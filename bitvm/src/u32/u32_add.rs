@@ -106,12 +106,15 @@ pub fn u32_add_drop(a: u32, b: u32) -> Script {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::stack_effect::assert_stack_effect;
     use crate::u32::u32_std::{u32_equal, u32_equalverify, u32_push};
     use rand::Rng;
 
     #[test]
     fn test_u32_add() {
         println!("u32_len: {}", u32_add_drop(1, 0).len());
+        assert_stack_effect(u32_add(1, 0), 8, 8);
+        assert_stack_effect(u32_add_drop(1, 0), 8, 4);
         let mut rng = rand::thread_rng();
         for _ in 0..1000 {
             let x = rng.gen();
@@ -221,6 +221,7 @@ pub fn u32_rrot(rot_num: usize) -> Script {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::stack_effect::assert_stack_effect;
     use crate::u32::u32_std::*;
     use rand::Rng;
 
@@ -236,6 +237,9 @@ mod tests {
         for i in 0..32 {
             println!("u32_rrot({}): {} bytes", i, u32_rrot(i).len());
         }
+        for i in 1..32 {
+            assert_stack_effect(u32_rrot(i), 4, 4);
+        }
         let mut rng = rand::thread_rng();
         for _ in 0..1000 {
             let x: u32 = rng.gen();
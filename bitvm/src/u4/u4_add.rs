@@ -232,6 +232,7 @@ pub fn u4_add(
 
 #[cfg(test)]
 mod tests {
+    use crate::stack_effect::{assert_stack_effect, stack_effect};
     use crate::treepp::*;
     use crate::u4::{u4_add::*, u4_std::u4_number_to_nibble};
     use rand::Rng;
@@ -259,6 +260,12 @@ mod tests {
         let calc = script! {
             { u4_add_no_table(8, vec![0, 8, 16, 24]) }
         };
+        // The sum is left on the altstack.
+        let effect = stack_effect(&calc.clone().compile()).unwrap();
+        assert_eq!(
+            (effect.inputs, effect.outputs, effect.altstack_delta),
+            (32, 0, 8)
+        );
         let mut rng = rand::thread_rng();
         for _ in 0..1000 {
             for len in 2..5 {
@@ -288,6 +295,8 @@ mod tests {
 
     #[test]
     fn test_add_with_table() {
+        assert_stack_effect(u4_push_add_tables(), 0, 130);
+        assert_stack_effect(u4_drop_add_tables(), 130, 0);
         let mut rng = rand::thread_rng();
         for _ in 0..1000 {
             for len in 2..5 {
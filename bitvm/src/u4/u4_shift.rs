@@ -143,9 +143,12 @@ pub fn u4_2_nib_rshift_n(n: u32, tables_offset: u32) -> Script {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::stack_effect::assert_stack_effect;
 
     #[test]
     fn test_lshift() {
+        assert_stack_effect(u4_push_lshift_tables(), 0, 48);
+        assert_stack_effect(u4_drop_lshift_tables(), 48, 0);
         for n in 1..4 {
             for x in 0..16 {
                 let script = script! {
@@ -146,11 +146,13 @@ impl CalculateOffset for i32 {
 mod tests {
     use super::*;
     use super::{u4_hex_to_nibbles, u4_repeat_number};
+    use crate::stack_effect::assert_stack_effect;
     use crate::u4::u4_std::u4_number_to_nibble;
 
     #[test]
     fn test_repeat() {
         for n in 0..30 {
+            assert_stack_effect(u4_repeat_number(1, n), 0, n as usize);
             let script = script! {
                 { u4_repeat_number(1, n) }
                 for _ in 0..n {
@@ -1,8 +1,10 @@
 use bitcoin::PublicKey;
 use bridge::client::cli::client_command::{ClientCommand, CommonArgs};
 use bridge::client::cli::key_command::KeysCommand;
+use bridge::client::cli::output::{Output, OutputFormat};
 use clap::{arg, command};
 use std::error::Error;
+use std::str::FromStr;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
@@ -25,6 +27,26 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 .env("VERIFIERS"),
         )
         .arg(arg!(-e --environment <ENVIRONMENT> "Specify the Bitcoin network environment (mainnet, testnet, regtest)").required(false).default_value("testnet").env("ENVIRONMENT"))
+        .arg(
+            arg!(--output <FORMAT> "Output format (text, json). In json mode, each command prints one JSON document to stdout and logs to stderr")
+                .required(false)
+                .global(true)
+                .default_value("text")
+                .value_parser(OutputFormat::from_str),
+        )
+        .arg(
+            arg!(--"signer-socket" <SOCKET> "Unix socket of a remote signer holding the keys of the signer roles, instead of the secrets in the key config")
+                .required(false)
+                .env("SIGNER_SOCKET"),
+        )
+        .arg(
+            arg!(--"signer-roles" <ROLES> "Comma-separated list of roles (depositor, operator, verifier, withdrawer) whose keys are held by the remote signer")
+                .required(false)
+                .value_delimiter(',')
+                .value_parser(["depositor", "operator", "verifier", "withdrawer"])
+                .default_values(["operator", "verifier"])
+                .env("SIGNER_ROLES"),
+        )
         .arg(arg!(-p --"user-profile" <USER_PROFILE> "Name of the protocol participant (e.g. 'operator_one', 'verifier_0'). Used as a namespace separator in the local file path for storing private and public client data").required(false).default_value("default_user").env("USER_PROFILE"))
         .subcommand(KeysCommand::get_command())
         .subcommand(ClientCommand::get_funding_amounts_command())
@@ -39,11 +61,15 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .subcommand(ClientCommand::get_mock_l2_pegout_event_command())
         .subcommand(ClientCommand::get_status_command())
         .subcommand(ClientCommand::get_broadcast_command())
+        .subcommand(ClientCommand::get_psbt_command())
         .subcommand(ClientCommand::get_automatic_command())
+        .subcommand(ClientCommand::get_daemon_command())
         .subcommand(ClientCommand::get_interactive_command());
 
     let matches = command.clone().get_matches();
 
+    let output = Output::stdout(*matches.get_one::<OutputFormat>("output").unwrap());
+
     let global_args = CommonArgs {
         key_dir: matches.get_one::<String>("key-dir").cloned(),
         verifiers: matches
@@ -51,64 +77,89 @@ async fn main() -> Result<(), Box<dyn Error>> {
             .map(|x| x.cloned().collect::<Vec<PublicKey>>()),
         environment: matches.get_one::<String>("environment").cloned(),
         path_prefix: matches.get_one::<String>("user-profile").cloned(),
+        signer_socket: matches.get_one::<String>("signer-socket").cloned(),
+        signer_roles: matches
+            .get_many::<String>("signer-roles")
+            .map(|x| x.cloned().collect())
+            .unwrap_or_default(),
+        output: output.clone(),
     };
 
-    if let Some(sub_matches) = matches.subcommand_matches("keys") {
-        let keys_command = KeysCommand::new(global_args.key_dir);
-        keys_command.handle_command(sub_matches)?;
-    } else if matches.subcommand_matches("get-funding-amounts").is_some() {
-        let client_command = ClientCommand::new(global_args).await;
-        let _ = client_command.handle_get_funding_amounts().await;
-    } else if matches.subcommand_matches("get-operator-address").is_some() {
-        let client_command = ClientCommand::new(global_args).await;
-        let _ = client_command.handle_get_operator_address().await;
-    } else if matches.subcommand_matches("get-operator-utxos").is_some() {
-        let mut client_command = ClientCommand::new(global_args).await;
-        let _ = client_command.handle_get_operator_utxos().await;
-    } else if matches
-        .subcommand_matches("get-depositor-address")
-        .is_some()
-    {
-        let client_command = ClientCommand::new(global_args).await;
-        let _ = client_command.handle_get_depositor_address().await;
-    } else if matches.subcommand_matches("get-depositor-utxos").is_some() {
-        let mut client_command = ClientCommand::new(global_args).await;
-        let _ = client_command.handle_get_depositor_utxos().await;
-    } else if let Some(sub_matches) = matches.subcommand_matches("initiate-peg-in") {
-        let mut client_command = ClientCommand::new(global_args).await;
-        let _ = client_command
-            .handle_initiate_peg_in_command(sub_matches)
-            .await;
-    } else if let Some(sub_matches) = matches.subcommand_matches("create-peg-out") {
-        let mut client_command = ClientCommand::new(global_args).await;
-        let _ = client_command
-            .handle_create_peg_out_graph_command(sub_matches)
-            .await;
-    } else if let Some(sub_matches) = matches.subcommand_matches("push-nonces") {
-        let mut client_command = ClientCommand::new(global_args).await;
-        let _ = client_command.handle_push_nonces_command(sub_matches).await;
-    } else if let Some(sub_matches) = matches.subcommand_matches("push-signatures") {
-        let mut client_command = ClientCommand::new(global_args).await;
-        let _ = client_command
-            .handle_push_signature_command(sub_matches)
-            .await;
-    } else if let Some(sub_matches) = matches.subcommand_matches("mock-l2-pegout-event") {
-        let mut client_command = ClientCommand::new(global_args).await;
-        let _ = client_command
-            .handle_mock_l2_pegout_event_command(sub_matches)
-            .await;
-    } else if matches.subcommand_matches("status").is_some() {
-        let mut client_command = ClientCommand::new(global_args).await;
-        let _ = client_command.handle_status_command().await;
-    } else if let Some(sub_matches) = matches.subcommand_matches("broadcast") {
-        let mut client_command = ClientCommand::new(global_args).await;
-        let _ = client_command.handle_broadcast_command(sub_matches).await;
-    } else if matches.subcommand_matches("automatic").is_some() {
-        let mut client_command = ClientCommand::new(global_args).await;
-        let _ = client_command.handle_automatic_command().await;
-    } else if matches.subcommand_matches("interactive").is_some() {
-        let mut client_command = ClientCommand::new(global_args).await;
-        let _ = client_command.handle_interactive_command(&command).await;
+    let result = async {
+        if let Some(sub_matches) = matches.subcommand_matches("keys") {
+            let keys_command = KeysCommand::new(global_args.key_dir);
+            keys_command.handle_command(sub_matches, &output)
+        } else if matches.subcommand_matches("get-funding-amounts").is_some() {
+            let client_command = ClientCommand::new(global_args).await?;
+            client_command.handle_get_funding_amounts().await
+        } else if matches.subcommand_matches("get-operator-address").is_some() {
+            let client_command = ClientCommand::new(global_args).await?;
+            client_command.handle_get_operator_address().await
+        } else if matches.subcommand_matches("get-operator-utxos").is_some() {
+            let mut client_command = ClientCommand::new(global_args).await?;
+            client_command.handle_get_operator_utxos().await
+        } else if matches
+            .subcommand_matches("get-depositor-address")
+            .is_some()
+        {
+            let client_command = ClientCommand::new(global_args).await?;
+            client_command.handle_get_depositor_address().await
+        } else if matches.subcommand_matches("get-depositor-utxos").is_some() {
+            let mut client_command = ClientCommand::new(global_args).await?;
+            client_command.handle_get_depositor_utxos().await
+        } else if let Some(sub_matches) = matches.subcommand_matches("initiate-peg-in") {
+            let mut client_command = ClientCommand::new(global_args).await?;
+            client_command
+                .handle_initiate_peg_in_command(sub_matches)
+                .await
+        } else if let Some(sub_matches) = matches.subcommand_matches("create-peg-out") {
+            let mut client_command = ClientCommand::new(global_args).await?;
+            client_command
+                .handle_create_peg_out_graph_command(sub_matches)
+                .await
+        } else if let Some(sub_matches) = matches.subcommand_matches("push-nonces") {
+            let mut client_command = ClientCommand::new(global_args).await?;
+            client_command.handle_push_nonces_command(sub_matches).await
+        } else if let Some(sub_matches) = matches.subcommand_matches("push-signatures") {
+            let mut client_command = ClientCommand::new(global_args).await?;
+            client_command
+                .handle_push_signature_command(sub_matches)
+                .await
+        } else if let Some(sub_matches) = matches.subcommand_matches("mock-l2-pegout-event") {
+            let mut client_command = ClientCommand::new(global_args).await?;
+            client_command
+                .handle_mock_l2_pegout_event_command(sub_matches)
+                .await
+        } else if matches.subcommand_matches("status").is_some() {
+            let mut client_command = ClientCommand::new(global_args).await?;
+            client_command.handle_status_command().await
+        } else if let Some(sub_matches) = matches.subcommand_matches("broadcast") {
+            let mut client_command = ClientCommand::new(global_args).await?;
+            client_command.handle_broadcast_command(sub_matches).await
+        } else if let Some(sub_matches) = matches.subcommand_matches("psbt") {
+            let mut client_command = ClientCommand::new(global_args).await?;
+            client_command.handle_psbt_command(sub_matches).await
+        } else if let Some(sub_matches) = matches.subcommand_matches("automatic") {
+            let mut client_command = ClientCommand::new(global_args).await?;
+            client_command.handle_automatic_command(sub_matches).await
+        } else if let Some(sub_matches) = matches.subcommand_matches("daemon") {
+            let mut client_command = ClientCommand::new(global_args).await?;
+            client_command.handle_daemon_command(sub_matches).await
+        } else if matches.subcommand_matches("interactive").is_some() {
+            let mut client_command = ClientCommand::new(global_args).await?;
+            client_command.handle_interactive_command(&command).await
+        } else {
+            Ok(())
+        }
+    }
+    .await;
+
+    if let Err(e) = result {
+        output.print_error(
+            matches.subcommand_name().unwrap_or_default(),
+            &e.to_string(),
+        );
+        std::process::exit(1);
     }
 
     Ok(())
@@ -1,22 +1,41 @@
-use super::key_command::{Config, KeysCommand};
+use super::key_command::KeysCommand;
+use super::output::{
+    AddressOutput, BroadcastOutput, FundingAmountsOutput, GraphOutput, MockL2PegOutEventOutput,
+    Output, OutputFormat, PegInOutput, PegOutGraphOutput, PsbtOutput, StatusOutput, UtxoOutput,
+    UtxosOutput,
+};
 use super::utils::get_mock_chain_service;
 use crate::client::chain::chain_adaptor::get_chain_adaptor;
 use crate::client::client::BitVMClient;
+use crate::client::daemon::{
+    collect_graph_statuses, Daemon, DaemonConfig, DaemonRole, DEFAULT_L2_POLL_INTERVAL,
+    DEFAULT_POLL_INTERVAL, DEFAULT_STATUS_ADDRESS,
+};
 use crate::client::esplora::get_esplora_url;
-use crate::commitments::CommitmentMessageId;
+use crate::client::policy::{Policy, PolicyEngine};
 use crate::common::ZkProofVerifyingKey;
 use crate::constants::DestinationNetwork;
-use crate::contexts::base::generate_keys_from_secret;
+use crate::error::Error;
 use crate::graphs::base::{PEG_IN_FEE, PEG_OUT_FEE};
-use crate::proof::{get_proof, invalidate_proof};
+use crate::proof::{get_dummy_proof, invalidate_proof};
+use crate::signers::config::SignerConfig;
 use crate::transactions::base::{Input, MIN_RELAY_FEE_PEG_OUT};
 use ark_serialize::CanonicalDeserialize;
+#[cfg(feature = "prover")]
+use borsh::BorshDeserialize;
+#[cfg(feature = "prover")]
+use prover::backend::backend_from_name;
+#[cfg(feature = "prover")]
+use risc0_zkvm::Receipt;
 
 use bitcoin::hex::FromHex;
-use bitcoin::{Address, Network, OutPoint, PublicKey};
+use bitcoin::{Address, Amount, Network, OutPoint, Psbt, PublicKey, ScriptBuf, Txid};
 use clap::{arg, ArgMatches, Command};
 use colored::Colorize;
+use std::fs;
 use std::io::{self, Write};
+use std::net::SocketAddr;
+use std::path::Path;
 use std::str::FromStr;
 use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, BufReader};
@@ -27,79 +46,117 @@ pub struct CommonArgs {
     pub verifiers: Option<Vec<PublicKey>>,
     pub environment: Option<String>,
     pub path_prefix: Option<String>,
+    // Unix socket of a remote signer holding the keys of `signer_roles`, whose secrets are
+    // then not read from the key config.
+    pub signer_socket: Option<String>,
+    pub signer_roles: Vec<String>,
+    pub output: Output,
 }
 
 pub struct ClientCommand {
     client: BitVMClient,
-    config: Config,
+    output: Output,
 }
 
 impl ClientCommand {
-    pub async fn new(common_args: CommonArgs) -> Self {
+    pub async fn new(common_args: CommonArgs) -> io::Result<Self> {
         let (source_network, destination_network) = match common_args.environment.as_deref() {
             Some("mainnet") => (Network::Bitcoin, DestinationNetwork::Ethereum),
             Some("testnet") => (Network::Testnet, DestinationNetwork::EthereumSepolia),
             Some("regtest") => (Network::Regtest, DestinationNetwork::Local),
             _ => {
-                eprintln!("Invalid environment. Use mainnet, testnet or regtest.");
-                std::process::exit(1);
+                return Err(Self::io_error(
+                    "Invalid environment. Use mainnet, testnet or regtest.".to_string(),
+                ))
             }
         };
 
         let keys_command = KeysCommand::new(common_args.key_dir);
         let config = keys_command
             .read_config()
-            .expect("Failed to read config file");
+            .map_err(|e| Self::io_error(format!("Failed to read config file: {e}")))?;
 
-        let n_of_n_public_keys = common_args.verifiers.expect("Error: Verifier public keys must be specified either in command line or environment variable.");
+        let n_of_n_public_keys = common_args.verifiers.ok_or(Self::io_error(
+            "Verifier public keys must be specified either in command line or environment variable."
+                .to_string(),
+        ))?;
 
         let mut verifying_key = None;
         if let Some(vk) = config.keys.verifying_key.clone() {
-            let bytes = Vec::<u8>::from_hex(&vk).unwrap();
-            verifying_key = Some(ZkProofVerifyingKey::deserialize_compressed(&*bytes).unwrap());
+            let bytes = Vec::<u8>::from_hex(&vk)
+                .map_err(|e| Self::io_error(format!("Invalid verifying key in config: {e}")))?;
+            verifying_key = Some(
+                ZkProofVerifyingKey::deserialize_compressed(&*bytes)
+                    .map_err(|e| Self::io_error(format!("Invalid verifying key in config: {e}")))?,
+            );
         }
 
+        let remote_signer = common_args
+            .signer_socket
+            .as_deref()
+            .map(SignerConfig::connect)
+            .transpose()
+            .map_err(|e| Self::io_error(format!("Failed to connect to signer: {e}")))?;
+        let signer_for = |role: &str, secret: &Option<String>| match &remote_signer {
+            Some(remote_signer) if common_args.signer_roles.iter().any(|r| r == role) => {
+                Some(remote_signer.clone())
+            }
+            _ => secret
+                .as_deref()
+                .map(|secret| SignerConfig::from_secret(source_network, secret)),
+        };
+
         let bitvm_client = BitVMClient::new(
             Some(get_esplora_url(source_network)),
             source_network,
             destination_network,
             Some(get_chain_adaptor(DestinationNetwork::Local, None, None)), // TODO: Will be replaced with a destination network specific adaptor once Ethereum support is added.
             &n_of_n_public_keys,
-            config.keys.depositor.as_deref(),
-            config.keys.operator.as_deref(),
-            config.keys.verifier.as_deref(),
-            config.keys.withdrawer.as_deref(),
+            signer_for("depositor", &config.keys.depositor),
+            signer_for("operator", &config.keys.operator),
+            signer_for("verifier", &config.keys.verifier),
+            signer_for("withdrawer", &config.keys.withdrawer),
             common_args.path_prefix.as_deref(),
             verifying_key,
         )
         .await;
 
-        Self {
+        Ok(Self {
             client: bitvm_client,
-            config,
-        }
+            output: common_args.output,
+        })
+    }
+
+    fn io_error(message: String) -> io::Error {
+        io::Error::new(io::ErrorKind::Other, message)
     }
 
     async fn get_funding_utxo_input(&self, utxo_arg: Option<&String>) -> io::Result<Input> {
-        let utxo = utxo_arg.expect("Missing UTXO argument, please see help.");
-        let outpoint = OutPoint::from_str(utxo)
-            .expect("Could not parse the provided UTXO, please see help for the correct format.");
+        let utxo = utxo_arg.ok_or(Self::io_error(
+            "Missing UTXO argument, please see help.".to_string(),
+        ))?;
+        let outpoint = parse_outpoint(utxo)?;
         let tx = self
             .client
             .esplora
             .get_tx(&outpoint.txid)
             .await
-            .map_err(|e| {
-                io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("Esplora failed to retrieve tx: {e}"),
-                )
-            })?;
-        let tx = tx.expect(&format!("Esplora did not find a txid {}", outpoint.txid));
+            .map_err(|e| Self::io_error(format!("Esplora failed to retrieve tx: {e}")))?
+            .ok_or(Self::io_error(format!(
+                "Esplora did not find a txid {}",
+                outpoint.txid
+            )))?;
+        let output = tx
+            .output
+            .get(outpoint.vout as usize)
+            .ok_or(Self::io_error(format!(
+                "Tx {} has no output {}",
+                outpoint.txid, outpoint.vout
+            )))?;
 
         Ok(Input {
             outpoint,
-            amount: tx.output[outpoint.vout as usize].value,
+            amount: output.value,
         })
     }
 
@@ -115,24 +172,40 @@ impl ClientCommand {
     pub async fn handle_get_funding_amounts(&self) -> io::Result<()> {
         const INITIAL_AMOUNT: u64 = 2 << 20; // 2097152
 
-        println!("Minimum required input amounts");
-        println!("------------------------------");
-        println!(
-            "'Peg-in deposit' tx input:  {} SAT (spendable by [DEPOSITOR])",
-            INITIAL_AMOUNT + PEG_IN_FEE
-        );
-        println!(
-            "'Peg-out confirm' tx input: {} SAT (spendable by [OPERATOR])",
-            INITIAL_AMOUNT + PEG_OUT_FEE
-        );
-        println!(
-            "'Peg-out' tx input:         {} SAT (spendable by [OPERATOR])",
-            INITIAL_AMOUNT + MIN_RELAY_FEE_PEG_OUT
-        );
+        let funding_amounts = FundingAmountsOutput {
+            peg_in_deposit: INITIAL_AMOUNT + PEG_IN_FEE,
+            peg_out_confirm: INITIAL_AMOUNT + PEG_OUT_FEE,
+            peg_out: INITIAL_AMOUNT + MIN_RELAY_FEE_PEG_OUT,
+            depositor_address: self.client.get_depositor_address().to_string(),
+            operator_address: self.client.get_operator_address().to_string(),
+        };
+        self.output
+            .print("get-funding-amounts", &funding_amounts, |funding_amounts| {
+                println!("Minimum required input amounts");
+                println!("------------------------------");
+                println!(
+                    "'Peg-in deposit' tx input:  {} SAT (spendable by [DEPOSITOR])",
+                    funding_amounts.peg_in_deposit
+                );
+                println!(
+                    "'Peg-out confirm' tx input: {} SAT (spendable by [OPERATOR])",
+                    funding_amounts.peg_out_confirm
+                );
+                println!(
+                    "'Peg-out' tx input:         {} SAT (spendable by [OPERATOR])",
+                    funding_amounts.peg_out
+                );
 
-        println!();
-        self.handle_get_depositor_address().await?;
-        self.handle_get_operator_address().await?;
+                println!();
+                println!(
+                    "[DEPOSITOR] address: {}",
+                    funding_amounts.depositor_address.green()
+                );
+                println!(
+                    "[OPERATOR] address: {}",
+                    funding_amounts.operator_address.green()
+                );
+            });
         Ok(())
     }
 
@@ -144,10 +217,14 @@ impl ClientCommand {
     }
 
     pub async fn handle_get_operator_address(&self) -> io::Result<()> {
-        println!(
-            "[OPERATOR] address: {}",
-            self.client.get_operator_address().to_string().green()
-        );
+        let address = AddressOutput {
+            role: "operator",
+            address: self.client.get_operator_address().to_string(),
+        };
+        self.output
+            .print("get-operator-address", &address, |address| {
+                println!("[OPERATOR] address: {}", address.address.green())
+            });
         Ok(())
     }
 
@@ -159,23 +236,18 @@ impl ClientCommand {
     }
 
     pub async fn handle_get_operator_utxos(&mut self) -> io::Result<()> {
-        let utxos = self.client.get_operator_utxos().await;
-        match utxos.len() {
-            0 => println!("No operator UTXOs found."),
-            utxo_count => {
-                println!(
-                    "{} operator UTXO{} found (<TXID>:<VOUT> <AMOUNT> <CONFIRMED>):",
-                    utxo_count,
-                    if utxo_count == 1 { "" } else { "s" }
-                );
-                for utxo in utxos {
-                    println!(
-                        "{}:{} {} {}",
-                        utxo.txid, utxo.vout, utxo.value, utxo.status.confirmed
-                    );
-                }
-            }
-        }
+        let utxos = UtxosOutput {
+            role: "operator",
+            utxos: self
+                .client
+                .get_operator_utxos()
+                .await
+                .iter()
+                .map(UtxoOutput::from)
+                .collect(),
+        };
+        self.output
+            .print("get-operator-utxos", &utxos, print_utxos_as_text);
 
         Ok(())
     }
@@ -188,10 +260,14 @@ impl ClientCommand {
     }
 
     pub async fn handle_get_depositor_address(&self) -> io::Result<()> {
-        println!(
-            "[DEPOSITOR] address: {}",
-            self.client.get_depositor_address().to_string().green()
-        );
+        let address = AddressOutput {
+            role: "depositor",
+            address: self.client.get_depositor_address().to_string(),
+        };
+        self.output
+            .print("get-depositor-address", &address, |address| {
+                println!("[DEPOSITOR] address: {}", address.address.green())
+            });
         Ok(())
     }
 
@@ -203,23 +279,18 @@ impl ClientCommand {
     }
 
     pub async fn handle_get_depositor_utxos(&mut self) -> io::Result<()> {
-        let utxos = self.client.get_depositor_utxos().await;
-        match utxos.len() {
-            0 => println!("No depositor UTXOs found."),
-            utxo_count => {
-                println!(
-                    "{} operator UTXO{} found (<TXID>:<VOUT> <AMOUNT> <CONFIRMED>):",
-                    utxo_count,
-                    if utxo_count == 1 { "" } else { "s" }
-                );
-                for utxo in utxos {
-                    println!(
-                        "{}:{} {} {}",
-                        utxo.txid, utxo.vout, utxo.value, utxo.status.confirmed
-                    );
-                }
-            }
-        }
+        let utxos = UtxosOutput {
+            role: "depositor",
+            utxos: self
+                .client
+                .get_depositor_utxos()
+                .await
+                .iter()
+                .map(UtxoOutput::from)
+                .collect(),
+        };
+        self.output
+            .print("get-depositor-utxos", &utxos, print_utxos_as_text);
 
         Ok(())
     }
@@ -247,17 +318,40 @@ impl ClientCommand {
         let input = self
             .get_funding_utxo_input(sub_matches.get_one::<String>("utxo"))
             .await?;
-        let peg_in_id = self.client.create_peg_in_graph(input, evm_address).await;
+        let peg_in_id = self
+            .client
+            .create_peg_in_graph(input, evm_address)
+            .await
+            .map_err(|e| Self::io_error(format!("Failed to create peg-in graph: {e}")))?;
 
         self.client.flush().await;
 
-        println!("Created peg-in graph with ID: {peg_in_id}");
-        println!("Broadcasting deposit...");
-
-        if let Err(e) = self.client.broadcast_peg_in_deposit(&peg_in_id).await {
-            eprintln!("Failed to broadcast peg-in deposit: {e}");
+        if self.output.format == OutputFormat::Text {
+            println!("Created peg-in graph with ID: {peg_in_id}");
+            println!("Broadcasting deposit...");
         }
 
+        let deposit_txid = self
+            .client
+            .broadcast_peg_in_deposit(&peg_in_id)
+            .await
+            .map_err(|e| {
+                Self::io_error(format!(
+                    "Created peg-in graph with ID: {peg_in_id}, but failed to broadcast peg-in deposit: {e}"
+                ))
+            })?;
+
+        let peg_in = PegInOutput {
+            peg_in_graph_id: peg_in_id,
+            deposit_txid,
+        };
+        self.output.print("initiate-peg-in", &peg_in, |peg_in| {
+            println!(
+                "Broadcasted peg-in deposit with txid: {}",
+                peg_in.deposit_txid
+            )
+        });
+
         Ok(())
     }
 
@@ -286,15 +380,23 @@ impl ClientCommand {
             .get_funding_utxo_input(sub_matches.get_one::<String>("utxo"))
             .await?;
 
-        let peg_out_id = self.client.create_peg_out_graph(
-            peg_in_id,
-            input,
-            CommitmentMessageId::generate_commitment_secrets(),
-        );
+        let peg_out_id = self
+            .client
+            .create_peg_out_graph_with_new_commitments(peg_in_id, input)
+            .map_err(|e| Self::io_error(format!("Failed to create peg-out graph: {e}")))?;
 
         self.client.flush().await;
 
-        println!("Created peg-out with ID: {peg_out_id}");
+        let peg_out_graph = PegOutGraphOutput {
+            peg_out_graph_id: peg_out_id,
+        };
+        self.output
+            .print("create-peg-out", &peg_out_graph, |peg_out_graph| {
+                println!(
+                    "Created peg-out with ID: {}",
+                    peg_out_graph.peg_out_graph_id
+                )
+            });
         Ok(())
     }
 
@@ -310,9 +412,18 @@ impl ClientCommand {
         let graph_id = sub_matches.get_one::<String>("id").unwrap();
 
         self.client.sync().await;
-        self.client.push_verifier_nonces(graph_id);
+        self.client
+            .push_verifier_nonces(graph_id)
+            .map_err(|e| Self::io_error(format!("Failed to push nonces: {e}")))?;
         self.client.flush().await;
 
+        let graph = GraphOutput {
+            graph_id: graph_id.clone(),
+        };
+        self.output.print("push-nonces", &graph, |graph| {
+            println!("Pushed nonces for graph with ID: {}", graph.graph_id)
+        });
+
         Ok(())
     }
 
@@ -331,9 +442,18 @@ impl ClientCommand {
         let graph_id = sub_matches.get_one::<String>("id").unwrap();
 
         self.client.sync().await;
-        self.client.push_verifier_signature(graph_id);
+        self.client
+            .push_verifier_signature(graph_id)
+            .map_err(|e| Self::io_error(format!("Failed to push signatures: {e}")))?;
         self.client.flush().await;
 
+        let graph = GraphOutput {
+            graph_id: graph_id.clone(),
+        };
+        self.output.print("push-signatures", &graph, |graph| {
+            println!("Pushed signatures for graph with ID: {}", graph.graph_id)
+        });
+
         Ok(())
     }
 
@@ -353,14 +473,13 @@ impl ClientCommand {
         sub_matches: &ArgMatches,
     ) -> io::Result<()> {
         let utxo = sub_matches.get_one::<String>("utxo").unwrap();
-        let outpoint = OutPoint::from_str(utxo).expect(
-            "Could not parse the provided UTXO, please see help for the correct format: {e}.",
-        );
-
-        if let Some(operator_secret) = &self.config.keys.operator {
-            let (_, operator_public_key) =
-                generate_keys_from_secret(self.client.source_network, operator_secret);
+        let outpoint = parse_outpoint(utxo)?;
 
+        if let Some(operator_public_key) = self
+            .client
+            .operator_context()
+            .map(|context| context.operator_public_key)
+        {
             self.client.sync().await;
             let mock_chain_service = get_mock_chain_service(outpoint, operator_public_key);
             self.client.set_chain_service(mock_chain_service);
@@ -373,6 +492,16 @@ impl ClientCommand {
             ));
         }
 
+        let event = MockL2PegOutEventOutput {
+            peg_in_confirm_outpoint: outpoint,
+        };
+        self.output.print("mock-l2-pegout-event", &event, |event| {
+            println!(
+                "Mock L2 peg-out event created for peg-in confirm output {}",
+                event.peg_in_confirm_outpoint
+            )
+        });
+
         Ok(())
     }
 
@@ -380,16 +509,50 @@ impl ClientCommand {
         Command::new("automatic")
             .short_flag('a')
             .about("Automatic mode: Poll for status updates and sign or broadcast transactions")
+            .arg(
+                arg!(--policy <FILE> "TOML policy deciding which peg-out actions to take")
+                    .required(false),
+            )
     }
 
-    pub async fn handle_automatic_command(&mut self) -> io::Result<()> {
+    fn load_policy_engine(&self, sub_matches: &ArgMatches) -> io::Result<Option<PolicyEngine>> {
+        sub_matches
+            .get_one::<String>("policy")
+            .map(|path| {
+                Policy::from_file(Path::new(path))
+                    .map(|policy| PolicyEngine::new(policy, self.client.local_file_path()))
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+            })
+            .transpose()
+    }
+
+    pub async fn handle_automatic_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let policy_engine = self.load_policy_engine(sub_matches)?;
+        let reward_script = match policy_engine {
+            Some(ref policy_engine) => policy_engine
+                .policy()
+                .reward_script(self.client.source_network)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+            None => None,
+        };
+
         loop {
             self.client.sync().await;
 
             let old_data = self.client.data().clone();
 
             self.client.process_peg_ins().await;
-            self.client.process_peg_outs().await;
+            match policy_engine {
+                Some(ref policy_engine) => {
+                    policy_engine
+                        .process_peg_outs_as_operator(&mut self.client)
+                        .await;
+                    policy_engine
+                        .process_peg_outs_as_verifier(&mut self.client, reward_script.as_ref())
+                        .await;
+                }
+                None => self.client.process_peg_outs().await,
+            }
 
             // A bit inefficient, but fine for now: only flush if data changed
             if self.client.data() != &old_data {
@@ -400,6 +563,82 @@ impl ClientCommand {
         }
     }
 
+    pub fn get_daemon_command() -> Command {
+        Command::new("daemon")
+            .short_flag('D')
+            .about("Daemon mode: Act on graph statuses for the configured roles and serve a status API")
+            .after_help("Polls esplora and the L2 adaptor on a schedule, persists progress after every poll and serves the status of all graphs as JSON at http://<STATUS_ADDRESS>/status.")
+            .arg(
+                arg!(-r --role <ROLE> "Role to act as, repeatable (depositor, operator, verifier)")
+                    .required(true)
+                    .num_args(1..)
+                    .value_delimiter(',')
+                    .value_parser(DaemonRole::from_str),
+            )
+            .arg(
+                arg!(--poll_interval <SECONDS> "Seconds between esplora polls")
+                    .required(false)
+                    .value_parser(clap::value_parser!(u64)),
+            )
+            .arg(
+                arg!(--l2_poll_interval <SECONDS> "Seconds between L2 adaptor polls")
+                    .required(false)
+                    .value_parser(clap::value_parser!(u64)),
+            )
+            .arg(
+                arg!(--status_address <ADDRESS> "Local address of the HTTP status endpoint")
+                    .required(false)
+                    .default_value(DEFAULT_STATUS_ADDRESS)
+                    .value_parser(clap::value_parser!(SocketAddr)),
+            )
+            .arg(
+                arg!(-a --address <ADDRESS> "Reward address for verifier timeout and disprove transactions")
+                    .required(false),
+            )
+            .arg(
+                arg!(--policy <FILE> "TOML policy deciding which peg-out actions to take")
+                    .required(false),
+            )
+    }
+
+    pub async fn handle_daemon_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        let policy_engine = self.load_policy_engine(sub_matches)?;
+        let reward_script = match sub_matches.get_one::<String>("address") {
+            Some(address) => Some(parse_reward_script(address)?),
+            None => match policy_engine {
+                Some(ref policy_engine) => policy_engine
+                    .policy()
+                    .reward_script(self.client.source_network)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?,
+                None => None,
+            },
+        };
+
+        let config = DaemonConfig {
+            roles: sub_matches
+                .get_many::<DaemonRole>("role")
+                .unwrap()
+                .cloned()
+                .collect(),
+            poll_interval: sub_matches
+                .get_one::<u64>("poll_interval")
+                .map(|seconds| Duration::from_secs(*seconds))
+                .unwrap_or(DEFAULT_POLL_INTERVAL),
+            l2_poll_interval: sub_matches
+                .get_one::<u64>("l2_poll_interval")
+                .map(|seconds| Duration::from_secs(*seconds))
+                .unwrap_or(DEFAULT_L2_POLL_INTERVAL),
+            status_address: *sub_matches.get_one::<SocketAddr>("status_address").unwrap(),
+            reward_script,
+            policy: policy_engine,
+        };
+
+        Daemon::new(config)
+            .run(&mut self.client)
+            .await
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+
     // TODO: there are verifier's commands missing here
     pub fn get_broadcast_command() -> Command {
         Command::new("broadcast")
@@ -428,7 +667,19 @@ impl ClientCommand {
                     .subcommand(Command::new("start_time").about("Broadcast start time"))
                     .subcommand(Command::new("assert_initial").about("Broadcast assert initial"))
                     .subcommand(
-                        Command::new("assert_commits").about("Broadcast assert commitments"),
+                        Command::new("assert_commits")
+                            .about("Broadcast assert commitments")
+                            .arg(
+                                arg!(--receipt <FILE> "Succinct receipt to assert the Groth16 proof of instead of a dummy proof (requires the prover feature)")
+                                    .required(false)
+                                    .requires("elf"),
+                            )
+                            .arg(arg!(--elf <FILE> "ELF of the guest the receipt is a proof of").required(false))
+                            .arg(
+                                arg!(--backend <BACKEND> "Prover backend wrapping the receipt into a Groth16 proof")
+                                    .value_parser(["docker", "mock"])
+                                    .default_value("docker"),
+                            ),
                     )
                     .subcommand(
                         Command::new("assert_commit_1").about("Broadcast assert commit 1"),
@@ -451,21 +702,66 @@ impl ClientCommand {
             .subcommand_required(true)
     }
 
+    // Wraps the receipt with the chosen backend and asserts the resulting Groth16 proof.
+    #[cfg(feature = "prover")]
+    async fn broadcast_assert_commits_with_receipt(
+        &mut self,
+        graph_id: &String,
+        receipt_path: &str,
+        sub_matches: &ArgMatches,
+    ) -> io::Result<Result<(Txid, Txid), Error>> {
+        let receipt = Receipt::try_from_slice(&fs::read(receipt_path)?)?;
+        let elf = fs::read(sub_matches.get_one::<String>("elf").unwrap())?;
+        let image_id = prover::image_id(&elf)
+            .map_err(|e| Self::io_error(format!("Invalid guest ELF: {e}")))?;
+        let backend = backend_from_name(sub_matches.get_one::<String>("backend").unwrap(), &[])
+            .map_err(|e| Self::io_error(format!("Invalid prover backend: {e}")))?;
+
+        Ok(self
+            .client
+            .broadcast_assert_commits_with_receipt(graph_id, backend.as_ref(), &receipt, image_id)
+            .await)
+    }
+
+    #[cfg(not(feature = "prover"))]
+    async fn broadcast_assert_commits_with_receipt(
+        &mut self,
+        _graph_id: &str,
+        _receipt_path: &str,
+        _sub_matches: &ArgMatches,
+    ) -> io::Result<Result<(Txid, Txid), Error>> {
+        Err(Self::io_error(
+            "Asserting a receipt requires building the bridge with the prover feature".to_string(),
+        ))
+    }
+
     pub async fn handle_broadcast_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
         self.client.sync().await;
 
         let subcommand = sub_matches.subcommand();
         let graph_id = subcommand.unwrap().1.get_one::<String>("graph_id").unwrap();
 
-        match subcommand.unwrap().1.subcommand() {
-            Some(("assert_commits", _)) => {
-                let result = self
-                    .client
-                    .broadcast_assert_commits(graph_id, &get_proof())
-                    .await;
-                if let Err(e) = result {
-                    println!("Failed to broadcast transaction: {e}");
-                }
+        let (transaction, txids) = match subcommand.unwrap().1.subcommand() {
+            Some(("assert_commits", assert_matches)) => {
+                let result = match assert_matches.get_one::<String>("receipt") {
+                    Some(receipt_path) => {
+                        self.broadcast_assert_commits_with_receipt(
+                            graph_id,
+                            receipt_path,
+                            assert_matches,
+                        )
+                        .await?
+                    }
+                    None => {
+                        self.client
+                            .broadcast_assert_commits(graph_id, &get_dummy_proof())
+                            .await
+                    }
+                };
+                (
+                    "assert_commits",
+                    result.map(|(commit_1_txid, commit_2_txid)| vec![commit_1_txid, commit_2_txid]),
+                )
             }
             Some((others, _)) => {
                 let result = match others {
@@ -487,12 +783,18 @@ impl ClientCommand {
                     "assert_initial" => self.client.broadcast_assert_initial(graph_id).await,
                     "assert_commit_1_invalid" => {
                         self.client
-                            .broadcast_assert_commit_1(graph_id, &invalidate_proof(&get_proof()))
+                            .broadcast_assert_commit_1(
+                                graph_id,
+                                &invalidate_proof(&get_dummy_proof()),
+                            )
                             .await
                     }
                     "assert_commit_2_invalid" => {
                         self.client
-                            .broadcast_assert_commit_2(graph_id, &invalidate_proof(&get_proof()))
+                            .broadcast_assert_commit_2(
+                                graph_id,
+                                &invalidate_proof(&get_dummy_proof()),
+                            )
                             .await
                     }
                     "assert_final" => self.client.broadcast_assert_final(graph_id).await,
@@ -500,8 +802,7 @@ impl ClientCommand {
                     "take_2" => self.client.broadcast_take_2(graph_id).await,
                     "disprove" => {
                         let address = subcommand.unwrap().1.get_one::<String>("address").unwrap();
-                        let reward_address = Address::from_str(address).unwrap();
-                        let reward_script = reward_address.assume_checked().script_pubkey(); // TODO: verify checked/unchecked address
+                        let reward_script = parse_reward_script(address)?;
 
                         self.client
                             .broadcast_disprove(graph_id, reward_script)
@@ -509,12 +810,86 @@ impl ClientCommand {
                     }
                     &_ => unreachable!(),
                 };
-                if let Err(e) = result {
-                    println!("Failed to broadcast transaction: {e}");
-                }
+                (others, result.map(|txid| vec![txid]))
             }
             _ => unreachable!(),
-        }
+        };
+
+        let broadcast = BroadcastOutput {
+            graph_id: graph_id.clone(),
+            transaction: transaction.to_string(),
+            txids: txids
+                .map_err(|e| Self::io_error(format!("Failed to broadcast transaction: {e}")))?,
+        };
+        self.output.print("broadcast", &broadcast, |broadcast| {
+            for txid in broadcast.txids.iter() {
+                println!("Broadcasted {} with txid: {txid}", broadcast.transaction)
+            }
+        });
+
+        Ok(())
+    }
+
+    pub fn get_psbt_command() -> Command {
+        Command::new("psbt")
+            .about("Export and import PSBTs of pre-signed transactions")
+            .after_help("Exchange BIP-174 PSBTs with signers that use external tooling. Files are binary PSBTs.")
+            .subcommand(
+                Command::new("export")
+                    .about("Export a transaction of a graph as a PSBT")
+                    .arg(arg!(-g --graph_id <GRAPH_ID> "Peg-in or peg-out graph ID").required(true))
+                    .arg(arg!(-t --txid <TXID> "Transaction ID").required(true))
+                    .arg(arg!(-f --file <FILE> "Path of the PSBT file to write").required(true)),
+            )
+            .subcommand(
+                Command::new("import")
+                    .about("Import a signed PSBT into a graph")
+                    .arg(arg!(-g --graph_id <GRAPH_ID> "Peg-in or peg-out graph ID").required(true))
+                    .arg(arg!(-f --file <FILE> "Path of the PSBT file to read").required(true)),
+            )
+            .subcommand_required(true)
+    }
+
+    pub async fn handle_psbt_command(&mut self, sub_matches: &ArgMatches) -> io::Result<()> {
+        self.client.sync().await;
+
+        let (subcommand, sub_matches) = sub_matches.subcommand().unwrap();
+        let graph_id = sub_matches.get_one::<String>("graph_id").unwrap();
+        let file = sub_matches.get_one::<String>("file").unwrap();
+
+        let txid = match subcommand {
+            "export" => {
+                let txid = Txid::from_str(sub_matches.get_one::<String>("txid").unwrap())
+                    .map_err(|e| Self::io_error(format!("Invalid txid: {e}")))?;
+                let psbt = self
+                    .client
+                    .export_psbt(graph_id, &txid)
+                    .map_err(|e| Self::io_error(format!("Failed to export PSBT: {e}")))?;
+                fs::write(file, psbt.serialize())?;
+                txid
+            }
+            "import" => {
+                let psbt = Psbt::deserialize(&fs::read(file)?)
+                    .map_err(|e| Self::io_error(format!("Invalid PSBT: {e}")))?;
+                let txid = self
+                    .client
+                    .import_psbt(graph_id, &psbt)
+                    .map_err(|e| Self::io_error(format!("Failed to import PSBT: {e}")))?;
+                self.client.flush().await;
+                txid
+            }
+            _ => unreachable!(),
+        };
+
+        let psbt = PsbtOutput {
+            graph_id: graph_id.clone(),
+            txid,
+            file: file.clone(),
+        };
+        self.output.print("psbt", &psbt, |psbt| match subcommand {
+            "export" => println!("Exported PSBT of {} to {}", psbt.txid, psbt.file),
+            _ => println!("Imported PSBT of {} from {}", psbt.txid, psbt.file),
+        });
 
         Ok(())
     }
@@ -528,7 +903,32 @@ impl ClientCommand {
 
     pub async fn handle_status_command(&mut self) -> io::Result<()> {
         self.client.sync().await;
-        self.client.status().await;
+        match self.output.format {
+            OutputFormat::Text => self.client.status().await,
+            OutputFormat::Json => {
+                let roles = [
+                    (
+                        DaemonRole::Depositor,
+                        self.client.depositor_context().is_some(),
+                    ),
+                    (
+                        DaemonRole::Operator,
+                        self.client.operator_context().is_some(),
+                    ),
+                    (
+                        DaemonRole::Verifier,
+                        self.client.verifier_context().is_some(),
+                    ),
+                ]
+                .into_iter()
+                .filter_map(|(role, has_context)| has_context.then_some(role))
+                .collect::<Vec<_>>();
+                let status = StatusOutput {
+                    graphs: collect_graph_statuses(&self.client, &roles).await,
+                };
+                self.output.print("status", &status, |_| {});
+            }
+        }
         Ok(())
     }
 
@@ -573,46 +973,60 @@ impl ClientCommand {
                 }
             };
 
-            if let Some(sub_matches) = matches.subcommand_matches("keys") {
+            let result = if let Some(sub_matches) = matches.subcommand_matches("keys") {
                 let key_dir = matches.get_one::<String>("key-dir").cloned();
                 let keys_command = KeysCommand::new(key_dir);
-                keys_command.handle_command(sub_matches)?;
+                keys_command.handle_command(sub_matches, &self.output)
+            } else if matches.subcommand_matches("get-funding-amounts").is_some() {
+                self.handle_get_funding_amounts().await
             } else if matches.subcommand_matches("get-operator-address").is_some() {
-                self.handle_get_operator_address().await?;
+                self.handle_get_operator_address().await
             } else if matches.subcommand_matches("get-operator-utxos").is_some() {
-                self.handle_get_operator_utxos().await?;
+                self.handle_get_operator_utxos().await
             } else if matches
                 .subcommand_matches("get-depositor-address")
                 .is_some()
             {
-                self.handle_get_depositor_address().await?;
+                self.handle_get_depositor_address().await
             } else if matches.subcommand_matches("get-depositor-utxos").is_some() {
-                self.handle_get_depositor_utxos().await?;
+                self.handle_get_depositor_utxos().await
             } else if let Some(sub_matches) = matches.subcommand_matches("initiate-peg-in") {
-                self.handle_initiate_peg_in_command(sub_matches).await?;
+                self.handle_initiate_peg_in_command(sub_matches).await
             } else if let Some(sub_matches) = matches.subcommand_matches("create-peg-out") {
-                self.handle_create_peg_out_graph_command(sub_matches)
-                    .await?;
+                self.handle_create_peg_out_graph_command(sub_matches).await
             } else if let Some(sub_matches) = matches.subcommand_matches("push-nonces") {
-                self.handle_push_nonces_command(sub_matches).await?;
+                self.handle_push_nonces_command(sub_matches).await
             } else if let Some(sub_matches) = matches.subcommand_matches("push-signatures") {
-                self.handle_push_signature_command(sub_matches).await?;
+                self.handle_push_signature_command(sub_matches).await
             } else if let Some(sub_matches) = matches.subcommand_matches("mock-l2-pegout-event") {
-                self.handle_mock_l2_pegout_event_command(sub_matches)
-                    .await?;
+                self.handle_mock_l2_pegout_event_command(sub_matches).await
             } else if matches.subcommand_matches("status").is_some() {
-                self.handle_status_command().await?;
+                self.handle_status_command().await
             } else if let Some(sub_matches) = matches.subcommand_matches("broadcast") {
-                self.handle_broadcast_command(sub_matches).await?;
-            } else if matches.subcommand_matches("automatic").is_some() {
-                self.handle_automatic_command().await?;
+                self.handle_broadcast_command(sub_matches).await
+            } else if let Some(sub_matches) = matches.subcommand_matches("psbt") {
+                self.handle_psbt_command(sub_matches).await
+            } else if let Some(sub_matches) = matches.subcommand_matches("automatic") {
+                self.handle_automatic_command(sub_matches).await
+            } else if let Some(sub_matches) = matches.subcommand_matches("daemon") {
+                self.handle_daemon_command(sub_matches).await
             } else if matches.subcommand_matches("interactive").is_some() {
                 println!("{}", "Already in interactive mode.".yellow());
+                Ok(())
             } else {
                 println!(
                     "{}",
                     "Unknown command. Type 'help' for a list of commands.".red()
                 );
+                Ok(())
+            };
+
+            // Keep the session alive on command errors.
+            if let Err(e) = result {
+                self.output.print_error(
+                    matches.subcommand_name().unwrap_or_default(),
+                    &e.to_string(),
+                );
             }
         }
 
@@ -620,3 +1034,43 @@ impl ClientCommand {
         Ok(())
     }
 }
+
+fn parse_outpoint(utxo: &str) -> io::Result<OutPoint> {
+    OutPoint::from_str(utxo).map_err(|e| {
+        ClientCommand::io_error(format!(
+            "Could not parse the provided UTXO, please see help for the correct format: {e}"
+        ))
+    })
+}
+
+fn parse_reward_script(address: &str) -> io::Result<ScriptBuf> {
+    Ok(Address::from_str(address)
+        .map_err(|e| {
+            ClientCommand::io_error(format!("Could not parse the provided reward address: {e}"))
+        })?
+        .assume_checked() // TODO: verify checked/unchecked address
+        .script_pubkey())
+}
+
+fn print_utxos_as_text(utxos: &UtxosOutput) {
+    match utxos.utxos.len() {
+        0 => println!("No {} UTXOs found.", utxos.role),
+        utxo_count => {
+            println!(
+                "{} {} UTXO{} found (<TXID>:<VOUT> <AMOUNT> <CONFIRMED>):",
+                utxo_count,
+                utxos.role,
+                if utxo_count == 1 { "" } else { "s" }
+            );
+            for utxo in utxos.utxos.iter() {
+                println!(
+                    "{}:{} {} {}",
+                    utxo.txid,
+                    utxo.vout,
+                    Amount::from_sat(utxo.amount),
+                    utxo.confirmed
+                );
+            }
+        }
+    }
+}
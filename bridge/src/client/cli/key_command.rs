@@ -8,6 +8,7 @@ use std::io::{self, Read, Write};
 use std::path::PathBuf;
 use toml;
 
+use super::output::{KeyOutput, KeySavedOutput, KeysOutput, Output};
 use crate::contexts::base::generate_keys_from_secret;
 
 #[derive(Serialize, Deserialize, Default)]
@@ -63,106 +64,122 @@ impl KeysCommand {
                 .args(["depositor", "operator", "verifier", "withdrawer"]))
     }
 
-    pub fn handle_command(&self, sub_matches: &ArgMatches) -> io::Result<()> {
+    pub fn handle_command(&self, sub_matches: &ArgMatches, output: &Output) -> io::Result<()> {
         let mut config = self.read_config()?;
 
         if !sub_matches.args_present() {
             // If no arguments are specified, output the current key configuration.
-            let keys = HashMap::from([
-                ("DEPOSITOR", &config.keys.depositor),
-                ("OPERATOR", &config.keys.operator),
-                ("VERIFIER", &config.keys.verifier),
-                ("WITHDRAWER", &config.keys.withdrawer),
-                ("VERIFYING KEY", &config.keys.verifying_key),
-            ]);
-
-            if keys.values().any(|k| k.is_some()) {
-                println!("Key configuration:");
-                println!();
-
-                let print_user_key = |private_key: &Option<String>, name: &str| {
-                    if let Some(prvkey) = private_key {
-                        println!("[{name}]:");
-                        println!("  Private key: {}", prvkey);
-                        println!("   Public key: {}", pubkey_of(prvkey));
-                        println!();
-                    }
-                };
-
-                let print_verifying_key = |verifying_key: &Option<String>, name: &str| {
-                    if let Some(vk) = verifying_key {
-                        println!("[{name}]:");
-                        println!("          Key: {}", vk);
-                        println!();
-                    }
-                };
-
-                let mut name = "DEPOSITOR";
-                print_user_key(keys.get(name).unwrap(), name);
-                name = "OPERATOR";
-                print_user_key(keys.get(name).unwrap(), name);
-                name = "VERIFIER";
-                print_user_key(keys.get(name).unwrap(), name);
-                name = "WITHDRAWER";
-                print_user_key(keys.get(name).unwrap(), name);
-                name = "VERIFYING KEY";
-                print_verifying_key(keys.get(name).unwrap(), name);
-            } else {
-                println!("No keys are configured.");
-                println!();
-            }
+            let keys = KeysOutput {
+                depositor: config.keys.depositor.as_deref().map(key_output_of),
+                operator: config.keys.operator.as_deref().map(key_output_of),
+                verifier: config.keys.verifier.as_deref().map(key_output_of),
+                withdrawer: config.keys.withdrawer.as_deref().map(key_output_of),
+                verifying_key: config.keys.verifying_key.clone(),
+            };
+            output.print("keys", &keys, |_| Self::print_config(&config));
 
             Ok(())
         } else {
-            if let Some(secret_key) = sub_matches.get_one::<String>("depositor") {
-                if self.validate_key(secret_key) {
-                    config.keys.depositor = Some(secret_key.clone());
-                    println!(
-                        "Secret key for depositor {} saved successfully!",
-                        pubkey_of(secret_key)
-                    );
-                } else {
-                    eprintln!("error: Invalid depositor secret key.");
+            let saved_key = if let Some(secret_key) = sub_matches.get_one::<String>("depositor") {
+                self.validate_key(secret_key, "depositor")?;
+                config.keys.depositor = Some(secret_key.clone());
+                KeySavedOutput {
+                    context: "depositor",
+                    public_key: Some(pubkey_of(secret_key)),
                 }
             } else if let Some(secret_key) = sub_matches.get_one::<String>("operator") {
-                if self.validate_key(secret_key) {
-                    config.keys.operator = Some(secret_key.clone());
-                    println!(
-                        "Secret key for operator {} saved successfully!",
-                        pubkey_of(secret_key)
-                    );
-                } else {
-                    eprintln!("error: Invalid operator secret key.");
+                self.validate_key(secret_key, "operator")?;
+                config.keys.operator = Some(secret_key.clone());
+                KeySavedOutput {
+                    context: "operator",
+                    public_key: Some(pubkey_of(secret_key)),
                 }
             } else if let Some(secret_key) = sub_matches.get_one::<String>("verifier") {
-                if self.validate_key(secret_key) {
-                    config.keys.verifier = Some(secret_key.clone());
-                    println!(
-                        "Secret key for verifier {} saved successfully!",
-                        pubkey_of(secret_key)
-                    );
-                } else {
-                    eprintln!("error: Invalid verifier secret key.");
+                self.validate_key(secret_key, "verifier")?;
+                config.keys.verifier = Some(secret_key.clone());
+                KeySavedOutput {
+                    context: "verifier",
+                    public_key: Some(pubkey_of(secret_key)),
                 }
             } else if let Some(secret_key) = sub_matches.get_one::<String>("withdrawer") {
-                if self.validate_key(secret_key) {
-                    config.keys.withdrawer = Some(secret_key.clone());
-                    println!(
-                        "Secret key for withdrawer {} saved successfully!",
-                        pubkey_of(secret_key)
-                    );
-                } else {
-                    eprintln!("error: Invalid withdrawer secret key.");
+                self.validate_key(secret_key, "withdrawer")?;
+                config.keys.withdrawer = Some(secret_key.clone());
+                KeySavedOutput {
+                    context: "withdrawer",
+                    public_key: Some(pubkey_of(secret_key)),
                 }
             } else if let Some(verifying_key) = sub_matches.get_one::<String>("vk") {
-                if self.validate_verifying_key(verifying_key) {
-                    config.keys.verifying_key = Some(verifying_key.clone());
-                    println!("ZK proof verifying key saved successfully!");
-                } else {
-                    eprintln!("error: Invalid ZK proof verifying key.");
+                if !self.validate_verifying_key(verifying_key) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "Invalid ZK proof verifying key.",
+                    ));
+                }
+                config.keys.verifying_key = Some(verifying_key.clone());
+                KeySavedOutput {
+                    context: "verifying_key",
+                    public_key: None,
+                }
+            } else {
+                unreachable!()
+            };
+            self.write_config(&config)?;
+
+            output.print("keys", &saved_key, |saved_key| match saved_key.public_key {
+                Some(public_key) => println!(
+                    "Secret key for {} {} saved successfully!",
+                    saved_key.context, public_key
+                ),
+                None => println!("ZK proof verifying key saved successfully!"),
+            });
+
+            Ok(())
+        }
+    }
+
+    fn print_config(config: &Config) {
+        let keys = HashMap::from([
+            ("DEPOSITOR", &config.keys.depositor),
+            ("OPERATOR", &config.keys.operator),
+            ("VERIFIER", &config.keys.verifier),
+            ("WITHDRAWER", &config.keys.withdrawer),
+            ("VERIFYING KEY", &config.keys.verifying_key),
+        ]);
+
+        if keys.values().any(|k| k.is_some()) {
+            println!("Key configuration:");
+            println!();
+
+            let print_user_key = |private_key: &Option<String>, name: &str| {
+                if let Some(prvkey) = private_key {
+                    println!("[{name}]:");
+                    println!("  Private key: {}", prvkey);
+                    println!("   Public key: {}", pubkey_of(prvkey));
+                    println!();
+                }
+            };
+
+            let print_verifying_key = |verifying_key: &Option<String>, name: &str| {
+                if let Some(vk) = verifying_key {
+                    println!("[{name}]:");
+                    println!("          Key: {}", vk);
+                    println!();
                 }
-            }
-            self.write_config(&config)
+            };
+
+            let mut name = "DEPOSITOR";
+            print_user_key(keys.get(name).unwrap(), name);
+            name = "OPERATOR";
+            print_user_key(keys.get(name).unwrap(), name);
+            name = "VERIFIER";
+            print_user_key(keys.get(name).unwrap(), name);
+            name = "WITHDRAWER";
+            print_user_key(keys.get(name).unwrap(), name);
+            name = "VERIFYING KEY";
+            print_verifying_key(keys.get(name).unwrap(), name);
+        } else {
+            println!("No keys are configured.");
+            println!();
         }
     }
 
@@ -187,8 +204,14 @@ impl KeysCommand {
         file.write_all(toml_string.as_bytes())
     }
 
-    fn validate_key(&self, key: &str) -> bool {
-        key.len() == 64 && key.chars().all(|c| c.is_ascii_hexdigit())
+    fn validate_key(&self, key: &str, context: &str) -> io::Result<()> {
+        match key.len() == 64 && key.chars().all(|c| c.is_ascii_hexdigit()) {
+            true => Ok(()),
+            false => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid {context} secret key."),
+            )),
+        }
     }
 
     // TODO: This is TBD. Verifying key validation is unclear at the moment.
@@ -203,3 +226,9 @@ impl KeysCommand {
 fn pubkey_of(private_key: &str) -> PublicKey {
     generate_keys_from_secret(Network::Bitcoin, private_key).1
 }
+
+fn key_output_of(private_key: &str) -> KeyOutput {
+    KeyOutput {
+        public_key: pubkey_of(private_key),
+    }
+}
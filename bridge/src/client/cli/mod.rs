@@ -1,5 +1,6 @@
 pub mod client_command;
 pub mod key_command;
+pub mod output;
 pub mod query_command;
 pub mod query_response;
 pub mod utils;
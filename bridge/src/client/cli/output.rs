@@ -0,0 +1,318 @@
+use bitcoin::{OutPoint, PublicKey, Txid};
+use esplora_client::Utxo;
+use serde::Serialize;
+use std::{
+    io::{self, Write},
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use crate::{client::daemon::GraphStatusEntry, graphs::base::GraphId};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("Unknown output format: {s}. Use text or json.")),
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStatus {
+    Ok,
+    Error,
+}
+
+/// Envelope of every document printed with `--output json`, one document per line. The client
+/// logs to stderr, so stdout only holds the documents:
+///
+/// `{"command":"get-operator-utxos","status":"ok","data":{...},"error":null}`
+///
+/// `data` is set if `status` is `ok`, `error` is set if `status` is `error`. Errors exit with code 1.
+#[derive(Serialize, Debug)]
+pub struct OutputDocument<'a, T: Serialize> {
+    pub command: &'a str,
+    pub status: OutputStatus,
+    pub data: Option<&'a T>,
+    pub error: Option<&'a str>,
+}
+
+/// Prints the result of a command in the selected format. JSON documents are written to the
+/// writer passed on construction, text is printed to stdout.
+#[derive(Clone)]
+pub struct Output {
+    pub format: OutputFormat,
+    writer: Arc<Mutex<dyn Write + Send>>,
+}
+
+impl Output {
+    pub fn new(format: OutputFormat, writer: impl Write + Send + 'static) -> Self {
+        Output {
+            format,
+            writer: Arc::new(Mutex::new(writer)),
+        }
+    }
+
+    pub fn stdout(format: OutputFormat) -> Self {
+        Self::new(format, io::stdout())
+    }
+
+    pub fn print<T: Serialize>(&self, command: &str, data: &T, print_text: impl FnOnce(&T)) {
+        match self.format {
+            OutputFormat::Text => print_text(data),
+            OutputFormat::Json => self.print_document(&OutputDocument {
+                command,
+                status: OutputStatus::Ok,
+                data: Some(data),
+                error: None,
+            }),
+        }
+    }
+
+    pub fn print_error(&self, command: &str, error: &str) {
+        match self.format {
+            OutputFormat::Text => eprintln!("error: {error}"),
+            OutputFormat::Json => self.print_document::<()>(&OutputDocument {
+                command,
+                status: OutputStatus::Error,
+                data: None,
+                error: Some(error),
+            }),
+        }
+    }
+
+    fn print_document<T: Serialize>(&self, document: &OutputDocument<T>) {
+        let line = serde_json::to_string(document).expect("Failed to serialize output document");
+        let mut writer = self.writer.lock().unwrap();
+        writeln!(writer, "{line}").expect("Failed to write output document");
+        writer.flush().expect("Failed to write output document");
+    }
+}
+
+/// `get-funding-amounts`. Amounts in sats.
+#[derive(Serialize, Debug)]
+pub struct FundingAmountsOutput {
+    pub peg_in_deposit: u64,
+    pub peg_out_confirm: u64,
+    pub peg_out: u64,
+    pub depositor_address: String,
+    pub operator_address: String,
+}
+
+/// `get-operator-address`, `get-depositor-address`.
+#[derive(Serialize, Debug)]
+pub struct AddressOutput {
+    pub role: &'static str,
+    pub address: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct UtxoOutput {
+    pub txid: Txid,
+    pub vout: u32,
+    // In sats.
+    pub amount: u64,
+    pub confirmed: bool,
+}
+
+impl From<&Utxo> for UtxoOutput {
+    fn from(utxo: &Utxo) -> Self {
+        UtxoOutput {
+            txid: utxo.txid,
+            vout: utxo.vout,
+            amount: utxo.value.to_sat(),
+            confirmed: utxo.status.confirmed,
+        }
+    }
+}
+
+/// `get-operator-utxos`, `get-depositor-utxos`.
+#[derive(Serialize, Debug)]
+pub struct UtxosOutput {
+    pub role: &'static str,
+    pub utxos: Vec<UtxoOutput>,
+}
+
+/// `initiate-peg-in`.
+#[derive(Serialize, Debug)]
+pub struct PegInOutput {
+    pub peg_in_graph_id: GraphId,
+    pub deposit_txid: Txid,
+}
+
+/// `create-peg-out`.
+#[derive(Serialize, Debug)]
+pub struct PegOutGraphOutput {
+    pub peg_out_graph_id: GraphId,
+}
+
+/// `push-nonces`, `push-signatures`.
+#[derive(Serialize, Debug)]
+pub struct GraphOutput {
+    pub graph_id: GraphId,
+}
+
+/// `mock-l2-pegout-event`.
+#[derive(Serialize, Debug)]
+pub struct MockL2PegOutEventOutput {
+    pub peg_in_confirm_outpoint: OutPoint,
+}
+
+/// `broadcast pegin <TX>`, `broadcast tx <TX>`. `txids` lists the transactions in broadcast order.
+#[derive(Serialize, Debug)]
+pub struct BroadcastOutput {
+    pub graph_id: GraphId,
+    pub transaction: String,
+    pub txids: Vec<Txid>,
+}
+
+/// `psbt export`, `psbt import`.
+#[derive(Serialize, Debug)]
+pub struct PsbtOutput {
+    pub graph_id: GraphId,
+    pub txid: Txid,
+    pub file: String,
+}
+
+/// `status`.
+#[derive(Serialize, Debug)]
+pub struct StatusOutput {
+    pub graphs: Vec<GraphStatusEntry>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct KeyOutput {
+    pub public_key: PublicKey,
+}
+
+/// `keys` without arguments. Secret keys are never included.
+#[derive(Serialize, Debug)]
+pub struct KeysOutput {
+    pub depositor: Option<KeyOutput>,
+    pub operator: Option<KeyOutput>,
+    pub verifier: Option<KeyOutput>,
+    pub withdrawer: Option<KeyOutput>,
+    pub verifying_key: Option<String>,
+}
+
+/// `keys` with a key argument. `public_key` is not set for the verifying key.
+#[derive(Serialize, Debug)]
+pub struct KeySavedOutput {
+    pub context: &'static str,
+    pub public_key: Option<PublicKey>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{self, Write},
+        sync::{Arc, Mutex},
+    };
+
+    use bitcoin::{hashes::Hash, Txid};
+    use serde_json::{json, Value};
+
+    use super::{BroadcastOutput, GraphOutput, Output, OutputFormat};
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn documents(&self) -> Vec<Value> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_json_success_document() {
+        let buffer = SharedBuffer::default();
+        let output = Output::new(OutputFormat::Json, buffer.clone());
+
+        output.print(
+            "broadcast",
+            &BroadcastOutput {
+                graph_id: "graph_id".to_string(),
+                transaction: "kick_off_1".to_string(),
+                txids: vec![Txid::all_zeros()],
+            },
+            |_| panic!("Text must not be printed in JSON mode"),
+        );
+
+        assert_eq!(
+            buffer.documents(),
+            vec![json!({
+                "command": "broadcast",
+                "status": "ok",
+                "data": {
+                    "graph_id": "graph_id",
+                    "transaction": "kick_off_1",
+                    "txids": [Txid::all_zeros().to_string()],
+                },
+                "error": null,
+            })]
+        );
+    }
+
+    #[test]
+    fn test_json_error_document() {
+        let buffer = SharedBuffer::default();
+        let output = Output::new(OutputFormat::Json, buffer.clone());
+
+        output.print_error("push-nonces", "Failed to push nonces");
+
+        assert_eq!(
+            buffer.documents(),
+            vec![json!({
+                "command": "push-nonces",
+                "status": "error",
+                "data": null,
+                "error": "Failed to push nonces",
+            })]
+        );
+    }
+
+    #[test]
+    fn test_text_mode_writes_no_documents() {
+        let buffer = SharedBuffer::default();
+        let output = Output::new(OutputFormat::Text, buffer.clone());
+        let mut printed = false;
+
+        output.print(
+            "push-nonces",
+            &GraphOutput {
+                graph_id: "graph_id".to_string(),
+            },
+            |_| printed = true,
+        );
+        output.print_error("push-nonces", "Failed to push nonces");
+
+        assert!(printed);
+        assert!(buffer.documents().is_empty());
+    }
+}
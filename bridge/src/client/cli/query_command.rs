@@ -17,6 +17,7 @@ use crate::{
     constants::DestinationNetwork,
     contexts::base::generate_keys_from_secret,
     scripts::generate_pay_to_pubkey_script_address,
+    signers::config::SignerConfig,
     transactions::base::Input,
 };
 
@@ -47,10 +48,10 @@ impl QueryCommand {
             destination_network,
             Some(get_chain_adaptor(DestinationNetwork::Local, None, None)), // TODO: Update this according to the requirements for query command.
             &n_of_n_public_keys,
-            Some(FAKE_SECRET),
-            Some(FAKE_SECRET),
-            Some(VERIFIER_0_SECRET),
-            Some(FAKE_SECRET),
+            Some(SignerConfig::from_secret(source_network, FAKE_SECRET)),
+            Some(SignerConfig::from_secret(source_network, FAKE_SECRET)),
+            Some(SignerConfig::from_secret(source_network, VERIFIER_0_SECRET)),
+            Some(SignerConfig::from_secret(source_network, FAKE_SECRET)),
             path_prefix.or(Some(QUERY_COMMAND_PATH_PREFIX)),
             None,
         )
@@ -181,17 +182,19 @@ impl QueryCommand {
         let outpoint = self
             .generate_stub_outpoint(&self.client, &taproot_address, amount)
             .await;
-        let result = self.client.generate_presign_pegin_confirm_tx(
+        match self.client.generate_presign_pegin_confirm_tx(
             self.network,
             recipient_address,
             amount,
             &depositor_taproot_key,
             outpoint,
-        );
-        Response::new(
-            ResponseStatus::OK,
-            Some(serde_json::to_value(result).unwrap()),
-        )
+        ) {
+            Ok(result) => Response::new(
+                ResponseStatus::OK,
+                Some(serde_json::to_value(result).unwrap()),
+            ),
+            Err(e) => Response::new(ResponseStatus::NOK(e.to_string()), None),
+        }
     }
 
     pub async fn handle_depositor(&mut self, matches: &ArgMatches) -> Response {
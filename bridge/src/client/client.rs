@@ -1,5 +1,5 @@
 use bitcoin::{
-    absolute::Height, consensus::encode::serialize_hex, Address, Amount, Network, OutPoint,
+    absolute::Height, consensus::encode::serialize_hex, Address, Amount, Network, OutPoint, Psbt,
     PublicKey, ScriptBuf, Transaction, Txid, XOnlyPublicKey,
 };
 use colored::Colorize;
@@ -14,6 +14,13 @@ use std::{
     path::{Path, PathBuf},
 };
 
+#[cfg(feature = "prover")]
+use crate::receipt::get_proof;
+#[cfg(feature = "prover")]
+use prover::backend::ProverBackend;
+#[cfg(feature = "prover")]
+use risc0_zkvm::Receipt;
+
 use crate::{
     client::{
         chain::ethereum_adaptor::EthereumAdaptor, esplora::get_esplora_url,
@@ -31,21 +38,22 @@ use crate::{
             REWARD_PRECISION,
         },
         peg_in::{PegInDepositorStatus, PegInVerifierStatus},
-        peg_out::PegOutOperatorStatus,
+        peg_out::{PegOutOperatorStatus, PegOutVerifierStatus},
     },
-    proof::get_proof,
+    proof::get_dummy_proof,
     scripts::generate_pay_to_pubkey_script_address,
     serialization::{serialize, try_deserialize_slice},
     transactions::{
         peg_in_confirm::PegInConfirmTransaction, peg_in_deposit::PegInDepositTransaction,
         peg_in_refund::PegInRefundTransaction, pre_signed_musig2::PreSignedMusig2Transaction,
+        signing_musig2::generate_musig2_session_id,
     },
 };
 
 use bitvm::{
     // chunker::disprove_execution::RawProof,
     chunk::api::type_conversion_utils::RawProof,
-    signatures::signing_winternitz::WinternitzSecret,
+    signatures::signing_winternitz::{WinternitzPublicKey, WinternitzSecret},
 };
 
 use super::{
@@ -59,6 +67,10 @@ use super::{
             peg_in::{generate_id as peg_in_generate_id, PegInGraph},
             peg_out::{generate_id as peg_out_generate_id, PegOutGraph},
         },
+        signers::{
+            base::{Musig2SessionId, Signer},
+            config::SignerConfig,
+        },
         transactions::{
             base::{Input, InputWithScript},
             pre_signed::PreSignedTransaction,
@@ -70,6 +82,7 @@ use super::{
         get_private_data_file_path, get_private_data_from_file, save_local_private_file,
         save_local_public_file, BRIDGE_DATA_DIRECTORY_NAME,
     },
+    policy::BroadcastLimits,
     sdk::{
         query::{ClientCliQuery, GraphCliQuery},
         query_contexts::depositor_signatures::DepositorSignatures,
@@ -99,16 +112,58 @@ impl BitVMClientPublicData {
     }
 }
 
-#[derive(Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Eq, PartialEq, Default)]
 pub struct BitVMClientPrivateData {
-    // Peg in and peg out nonces all go into the same file for now
-    // Verifier public key -> Graph ID -> Tx ID -> Input index -> Secret nonce
+    // Peg in and peg out MuSig2 sessions all go into the same file for now
+    // Verifier public key -> Graph ID -> Tx ID -> Input index -> MuSig2 session ID
     #[allow(clippy::type_complexity)]
-    pub secret_nonces: HashMap<PublicKey, HashMap<String, HashMap<Txid, HashMap<usize, SecNonce>>>>,
+    #[serde(default)]
+    pub musig2_session_ids:
+        HashMap<PublicKey, HashMap<String, HashMap<Txid, HashMap<usize, Musig2SessionId>>>>,
+    // Secret nonces of the local verifier signer that have not been used yet, so that signing
+    // can happen in a later run than pushing the nonces. Remote signers keep their own nonces.
+    // Verifier public key -> MuSig2 session ID -> Secret nonce
+    #[serde(default)]
+    pub musig2_secret_nonces: HashMap<PublicKey, HashMap<Musig2SessionId, SecNonce>>,
     // Operator Winternitz secrets for all the graphs.
     // Operator public key -> Graph ID -> Message ID -> Winternitz secret
+    #[serde(default)]
     pub commitment_secrets:
         HashMap<PublicKey, HashMap<String, HashMap<CommitmentMessageId, WinternitzSecret>>>,
+    // Secret nonces in the layout written before MuSig2 session IDs were introduced. Only read
+    // from old files, see `migrate_secret_nonces`.
+    // Verifier public key -> Graph ID -> Tx ID -> Input index -> Secret nonce
+    #[allow(clippy::type_complexity)]
+    #[serde(default, skip_serializing)]
+    pub secret_nonces: HashMap<PublicKey, HashMap<String, HashMap<Txid, HashMap<usize, SecNonce>>>>,
+}
+
+impl BitVMClientPrivateData {
+    /// Moves secret nonces of the old layout into `musig2_session_ids` and
+    /// `musig2_secret_nonces`, under the session IDs the pre-signed transactions use.
+    pub fn migrate_secret_nonces(&mut self) {
+        for (verifier_public_key, graphs) in std::mem::take(&mut self.secret_nonces) {
+            for (graph_id, txs) in graphs {
+                for (txid, inputs) in txs {
+                    for (input_index, secret_nonce) in inputs {
+                        let session_id = generate_musig2_session_id(txid, input_index);
+                        self.musig2_session_ids
+                            .entry(verifier_public_key)
+                            .or_default()
+                            .entry(graph_id.clone())
+                            .or_default()
+                            .entry(txid)
+                            .or_default()
+                            .insert(input_index, session_id.clone());
+                        self.musig2_secret_nonces
+                            .entry(verifier_public_key)
+                            .or_default()
+                            .insert(session_id, secret_nonce);
+                    }
+                }
+            }
+        }
+    }
 }
 
 pub struct BitVMClient {
@@ -120,6 +175,12 @@ pub struct BitVMClient {
     verifier_context: Option<VerifierContext>,
     withdrawer_context: Option<WithdrawerContext>,
 
+    // Handles on the signers of the contexts. Local signers get the operator's Winternitz
+    // secrets and the verifier's MuSig2 secret nonces from the private data file, remote
+    // signers keep their own.
+    operator_signer: Option<SignerConfig>,
+    verifier_signer: Option<SignerConfig>,
+
     data_store: DataStore,
     data: BitVMClientPublicData,
     latest_processed_file_name: Option<String>,
@@ -131,6 +192,8 @@ pub struct BitVMClient {
     chain_service: Chain,
 
     zkproof_verifying_key: Option<ZkProofVerifyingKey>,
+
+    broadcast_limits: Option<BroadcastLimits>,
 }
 
 impl BitVMClient {
@@ -141,24 +204,25 @@ impl BitVMClient {
         destination_network: DestinationNetwork,
         chain_adaptor: Option<Box<dyn ChainAdaptor>>,
         n_of_n_public_keys: &[PublicKey],
-        depositor_secret: Option<&str>,
-        operator_secret: Option<&str>,
-        verifier_secret: Option<&str>,
-        withdrawer_secret: Option<&str>,
+        depositor_signer: Option<SignerConfig>,
+        operator_signer: Option<SignerConfig>,
+        verifier_signer: Option<SignerConfig>,
+        withdrawer_signer: Option<SignerConfig>,
         file_path_prefix: Option<&str>,
         zkproof_verifying_key: Option<ZkProofVerifyingKey>,
     ) -> Self {
-        let depositor_context = depositor_secret
-            .map(|secret| DepositorContext::new(source_network, secret, n_of_n_public_keys));
-
-        let operator_context = operator_secret
-            .map(|secret| OperatorContext::new(source_network, secret, n_of_n_public_keys));
-
-        let verifier_context = verifier_secret
-            .map(|secret| VerifierContext::new(source_network, secret, n_of_n_public_keys));
-
-        let withdrawer_context = withdrawer_secret
-            .map(|secret| WithdrawerContext::new(source_network, secret, n_of_n_public_keys));
+        let depositor_context = depositor_signer.map(|signer| {
+            DepositorContext::new_with_signer(source_network, signer.signer(), n_of_n_public_keys)
+        });
+        let operator_context = operator_signer.as_ref().map(|signer| {
+            OperatorContext::new_with_signer(source_network, signer.signer(), n_of_n_public_keys)
+        });
+        let verifier_context = verifier_signer.as_ref().map(|signer| {
+            VerifierContext::new_with_signer(source_network, signer.signer(), n_of_n_public_keys)
+        });
+        let withdrawer_context = withdrawer_signer.map(|signer| {
+            WithdrawerContext::new_with_signer(source_network, signer.signer(), n_of_n_public_keys)
+        });
 
         let (n_of_n_public_key, _) = generate_n_of_n_public_key(n_of_n_public_keys);
 
@@ -174,7 +238,7 @@ impl BitVMClient {
             .join(source_network.to_string())
             .join(destination_network.to_string())
             .join(n_of_n_public_key.to_string());
-        println!("Using data file path: {}", local_file_path.display());
+        eprintln!("Using data file path: {}", local_file_path.display());
 
         let data = BitVMClientPublicData {
             version: 1,
@@ -187,6 +251,22 @@ impl BitVMClient {
         let private_data =
             get_private_data_from_file(&get_private_data_file_path(&local_file_path));
 
+        if let Some(signer) = operator_signer.as_ref().and_then(SignerConfig::local) {
+            if let Some(commitment_secrets) =
+                private_data.commitment_secrets.get(&signer.public_key())
+            {
+                for graph_commitment_secrets in commitment_secrets.values() {
+                    signer.add_winternitz_secrets(graph_commitment_secrets.values().cloned());
+                }
+            }
+        }
+        if let Some(signer) = verifier_signer.as_ref().and_then(SignerConfig::local) {
+            if let Some(secret_nonces) = private_data.musig2_secret_nonces.get(&signer.public_key())
+            {
+                signer.restore_musig2_nonces(secret_nonces.clone());
+            }
+        }
+
         Self {
             esplora: Builder::new(esplora_url.unwrap_or(get_esplora_url(source_network)))
                 .build_async()
@@ -198,6 +278,9 @@ impl BitVMClient {
             verifier_context,
             withdrawer_context,
 
+            operator_signer,
+            verifier_signer,
+
             data_store,
             data,
             latest_processed_file_name: None,
@@ -211,6 +294,8 @@ impl BitVMClient {
             ),
 
             zkproof_verifying_key,
+
+            broadcast_limits: None,
         }
     }
 
@@ -238,6 +323,26 @@ impl BitVMClient {
             .find(|graph| graph.id() == id)
     }
 
+    pub fn depositor_context(&self) -> Option<&DepositorContext> {
+        self.depositor_context.as_ref()
+    }
+
+    pub fn operator_context(&self) -> Option<&OperatorContext> {
+        self.operator_context.as_ref()
+    }
+
+    pub fn verifier_context(&self) -> Option<&VerifierContext> {
+        self.verifier_context.as_ref()
+    }
+
+    pub fn local_file_path(&self) -> &Path {
+        &self.local_file_path
+    }
+
+    pub fn set_broadcast_limits(&mut self, broadcast_limits: Option<BroadcastLimits>) {
+        self.broadcast_limits = broadcast_limits;
+    }
+
     // TODO: This should be private. Currently used in the fees test. See if it can be refactored.
     pub fn private_data(&self) -> &BitVMClientPrivateData {
         &self.private_data
@@ -302,15 +407,15 @@ impl BitVMClient {
                         Self::process_files_by_timestamp(self, latest_file_names, TEN_MINUTES)
                             .await;
                     match result {
-                        Ok(_) => (), // println!("Ok"),
-                        Err(err) => println!("Error: {}", err),
+                        Ok(_) => (), // eprintln!("Ok"),
+                        Err(err) => eprintln!("Error: {}", err),
                     }
 
                     self.merge_data(latest_file.unwrap()); // merge the latest data at the end
                 }
             }
         } else {
-            println!("Error: {}", latest_file_names_result.unwrap_err());
+            eprintln!("Error: {}", latest_file_names_result.unwrap_err());
         }
     }
 
@@ -323,14 +428,14 @@ impl BitVMClient {
                     match peg_out_graph.match_and_set_peg_out_event(&mut events).await {
                         Ok(_) => {
                             if peg_out_graph.peg_out_chain_event.is_some() {
-                                println!(
+                                eprintln!(
                                     "Peg-out graph ID: {} Event Matched, Event: {:?}",
                                     peg_out_graph.id(),
                                     peg_out_graph.peg_out_chain_event
                                 )
                             }
                         }
-                        Err(err) => println!("Error: {}", err),
+                        Err(err) => eprintln!("Error: {}", err),
                     }
                 }
             }
@@ -426,14 +531,14 @@ impl BitVMClient {
 
                 if latest_data.is_some() {
                     // merge the file if the data is valid
-                    println!("Merging {} data...", { file_name });
+                    eprintln!("Merging {} data...", { file_name });
                     self.merge_data(latest_data.unwrap());
                     if latest_valid_file_name.is_none() {
                         latest_valid_file_name = Some(file_name.clone());
                     }
                 } else {
                     // skip the file if the data is invalid
-                    println!("Invalid file {}, Skipping...", file_name);
+                    eprintln!("Invalid file {}, Skipping...", file_name);
                 }
             }
         }
@@ -456,7 +561,7 @@ impl BitVMClient {
                     self.validate_data_by_key(&file_name).await;
                 if latest_data.is_some() {
                     // data is valid
-                    println!(
+                    eprintln!(
                         "Fetched valid file: {} (size: {}, compressed: {})",
                         file_name,
                         human_bytes(latest_data_len as f64),
@@ -466,7 +571,7 @@ impl BitVMClient {
                     latest_valid_file_name = Some(file_name);
                     break;
                 } else {
-                    println!("Invalid file: {}", file_name); // TODO: can be removed
+                    eprintln!("Invalid file: {}", file_name); // TODO: can be removed
                 }
                 // for invalid data try another file
             }
@@ -501,7 +606,7 @@ impl BitVMClient {
             .await;
         match result {
             Ok((file_name, size)) => {
-                println!(
+                eprintln!(
                     "Pushed new file: {} (size: {}, compressed: {})",
                     file_name,
                     human_bytes(contents.len() as f64),
@@ -510,7 +615,7 @@ impl BitVMClient {
                 save_local_public_file(&self.local_file_path, &file_name, &contents);
                 self.latest_processed_file_name = Some(file_name);
             }
-            Err(err) => println!("Failed to push: {}", err),
+            Err(err) => eprintln!("Failed to push: {}", err),
         }
     }
 
@@ -539,7 +644,7 @@ impl BitVMClient {
     }
 
     pub async fn validate_data(client: &AsyncClient, data: &BitVMClientPublicData) -> bool {
-        println!(
+        eprintln!(
             "Validating {} PEG-IN graphs and {} PEG-OUT graphs...",
             data.peg_in_graphs.len(),
             data.peg_out_graphs.len()
@@ -669,7 +774,7 @@ impl BitVMClient {
         for peg_in_graph in self.data.peg_in_graphs.iter() {
             if peg_in_graph.depositor_public_key.eq(depositor_public_key) {
                 let status = peg_in_graph.depositor_status(&self.esplora).await;
-                println!(
+                eprintln!(
                     "[DEPOSITOR]: Peg-in graph ID: {} status: {}\n",
                     peg_in_graph.id(),
                     status
@@ -692,14 +797,14 @@ impl BitVMClient {
         for peg_in_graph in self.data.peg_in_graphs.iter() {
             let peg_out_graph_id = peg_out_generate_id(peg_in_graph, operator_public_key);
             if !peg_out_graphs_by_id.contains_key(&peg_out_graph_id) {
-                println!(
+                eprintln!(
                     "[OPERATOR]: Peg-in graph ID: {} status: Missing peg out graph.\n",
                     peg_in_graph.id() // TODO update this to ask the operator to create a new peg out graph
                 );
             } else {
                 let peg_out_graph = peg_out_graphs_by_id.get(&peg_out_graph_id).unwrap();
                 let status = peg_out_graph.operator_status(&self.esplora).await;
-                println!(
+                eprintln!(
                     "[OPERATOR]: Peg-out graph ID: {} status: {}\n",
                     peg_out_graph.id(),
                     status
@@ -721,7 +826,7 @@ impl BitVMClient {
                         let _ = self.broadcast_peg_in_confirm(peg_in_graph_id).await;
                     }
                     _ => {
-                        println!("Peg-in graph {} is in status: {}", peg_in_graph_id, status);
+                        eprintln!("Peg-in graph {} is in status: {}", peg_in_graph_id, status);
                     }
                 }
             }
@@ -743,19 +848,23 @@ impl BitVMClient {
                     .await;
                 match status {
                     PegInVerifierStatus::PendingOurNonces(graph_ids) => {
-                        println!("Pushing nonces for graphs {graph_ids:?}");
+                        eprintln!("Pushing nonces for graphs {graph_ids:?}");
                         for graph_id in graph_ids {
-                            self.push_verifier_nonces(&graph_id);
+                            if let Err(e) = self.push_verifier_nonces(&graph_id) {
+                                eprintln!("Failed to push nonces for graph {graph_id}: {e}");
+                            }
                         }
                     }
                     PegInVerifierStatus::PendingOurSignature(graph_ids) => {
-                        println!("Pushing signature for graphs {graph_ids:?}");
+                        eprintln!("Pushing signature for graphs {graph_ids:?}");
                         for graph_id in graph_ids {
-                            self.push_verifier_signature(&graph_id);
+                            if let Err(e) = self.push_verifier_signature(&graph_id) {
+                                eprintln!("Failed to push signature for graph {graph_id}: {e}");
+                            }
                         }
                     }
                     PegInVerifierStatus::ReadyToSubmit => {
-                        println!("Broadcasting peg-in confirm");
+                        eprintln!("Broadcasting peg-in confirm");
                         let _ = self.broadcast_peg_in_confirm(peg_in_graph_id).await;
                     }
                     _ => {
@@ -806,11 +915,11 @@ impl BitVMClient {
                             },
                         }
                     };
-                    self.create_peg_out_graph(
-                        peg_in_graph_id,
-                        input,
-                        CommitmentMessageId::generate_commitment_secrets(),
-                    );
+                    if let Err(e) =
+                        self.create_peg_out_graph_with_new_commitments(peg_in_graph_id, input)
+                    {
+                        eprintln!("Failed to create peg-out graph for peg-in graph {peg_in_graph_id}: {e}");
+                    }
                 }
             }
         }
@@ -847,12 +956,12 @@ impl BitVMClient {
                 }
                 PegOutOperatorStatus::PegOutAssertCommit1Available => {
                     let _ = self
-                        .broadcast_assert_commit_1(peg_out_graph.id(), &get_proof())
+                        .broadcast_assert_commit_1(peg_out_graph.id(), &get_dummy_proof())
                         .await;
                 }
                 PegOutOperatorStatus::PegOutAssertCommit2Available => {
                     let _ = self
-                        .broadcast_assert_commit_2(peg_out_graph.id(), &get_proof())
+                        .broadcast_assert_commit_2(peg_out_graph.id(), &get_dummy_proof())
                         .await;
                 }
                 PegOutOperatorStatus::PegOutAssertFinalAvailable => {
@@ -869,6 +978,50 @@ impl BitVMClient {
         }
     }
 
+    // TODO: handle internal errors
+    pub async fn process_peg_outs_as_verifier(&mut self, output_script_pubkey: &ScriptBuf) {
+        let mut statuses = Vec::new();
+        if let Some(ref context) = self.verifier_context {
+            for peg_out_graph in self.data.peg_out_graphs.iter() {
+                let status = peg_out_graph.verifier_status(&self.esplora, context).await;
+                statuses.push((peg_out_graph.id().clone(), status));
+            }
+        }
+
+        for (peg_out_graph_id, status) in statuses {
+            match status {
+                PegOutVerifierStatus::PegOutStartTimeTimeoutAvailable => {
+                    let _ = self
+                        .broadcast_start_time_timeout(
+                            &peg_out_graph_id,
+                            output_script_pubkey.clone(),
+                        )
+                        .await;
+                }
+                PegOutVerifierStatus::PegOutKickOffTimeoutAvailable => {
+                    let _ = self
+                        .broadcast_kick_off_timeout(&peg_out_graph_id, output_script_pubkey.clone())
+                        .await;
+                }
+                PegOutVerifierStatus::PegOutDisproveChainAvailable => {
+                    let _ = self
+                        .broadcast_disprove_chain(&peg_out_graph_id, output_script_pubkey.clone())
+                        .await;
+                }
+                PegOutVerifierStatus::PegOutDisproveAvailable => {
+                    let _ = self
+                        .broadcast_disprove(&peg_out_graph_id, output_script_pubkey.clone())
+                        .await;
+                }
+                PegOutVerifierStatus::PegOutChallengeAvailable => {
+                    // Challenging requires crowdfunding inputs, which cannot be picked automatically.
+                    eprintln!("Peg-out graph {peg_out_graph_id} is in status: {status}");
+                }
+                _ => {}
+            }
+        }
+    }
+
     async fn verifier_status(&self) {
         if self.verifier_context.is_none() {
             panic!("Verifier context must be initialized");
@@ -899,14 +1052,14 @@ impl BitVMClient {
                     let peg_out_status = peg_out_graph
                         .verifier_status(&self.esplora, self.verifier_context.as_ref().unwrap())
                         .await;
-                    println!(
+                    eprintln!(
                         "[VERIFIER]: Peg-out graph ID: {} status: {}\n",
                         peg_out_graph.id(),
                         peg_out_status
                     );
                 }
             }
-            println!(
+            eprintln!(
                 "[VERIFIER]: Peg-in graph ID: {} status: {}\n",
                 peg_in_graph.id(),
                 peg_in_status
@@ -914,13 +1067,17 @@ impl BitVMClient {
         }
     }
 
-    pub async fn create_peg_in_graph(&mut self, input: Input, evm_address: &str) -> String {
+    pub async fn create_peg_in_graph(
+        &mut self,
+        input: Input,
+        evm_address: &str,
+    ) -> Result<String, Error> {
         if self.depositor_context.is_none() {
             panic!("Depositor context must be initialized");
         }
 
         let peg_in_graph =
-            PegInGraph::new(self.depositor_context.as_ref().unwrap(), input, evm_address);
+            PegInGraph::new(self.depositor_context.as_ref().unwrap(), input, evm_address)?;
 
         let peg_in_graph_id = peg_in_generate_id(&peg_in_graph.peg_in_deposit_transaction);
 
@@ -935,7 +1092,7 @@ impl BitVMClient {
 
         self.data.peg_in_graphs.push(peg_in_graph);
 
-        peg_in_graph_id
+        Ok(peg_in_graph_id)
     }
 
     pub async fn broadcast_peg_in_deposit(
@@ -965,12 +1122,78 @@ impl BitVMClient {
         self.broadcast_tx(&tx).await
     }
 
+    /// Creates a peg-out graph committing to fresh Winternitz keys. A local operator signer
+    /// gets secrets generated here, a remote one generates them itself and only returns the
+    /// public keys.
+    pub fn create_peg_out_graph_with_new_commitments(
+        &mut self,
+        peg_in_graph_id: &str,
+        peg_out_confirm_input: Input,
+    ) -> Result<String, Error> {
+        match &self.operator_signer {
+            Some(SignerConfig::Local(_)) => self.create_peg_out_graph(
+                peg_in_graph_id,
+                peg_out_confirm_input,
+                CommitmentMessageId::generate_commitment_secrets(),
+            ),
+            Some(SignerConfig::Remote(signer)) => {
+                let commitment_public_keys = signer
+                    .generate_commitment_public_keys()
+                    .map_err(Error::Signer)?;
+                self.add_peg_out_graph(
+                    peg_in_graph_id,
+                    peg_out_confirm_input,
+                    &commitment_public_keys,
+                )
+            }
+            None => Err(Error::Client(ClientError::OperatorContextNotDefined)),
+        }
+    }
+
+    /// Creates a peg-out graph committing to the given Winternitz secrets, which are handed to
+    /// the local operator signer and persisted in the private data file.
     pub fn create_peg_out_graph(
         &mut self,
         peg_in_graph_id: &str,
         peg_out_confirm_input: Input,
         commitment_secrets: HashMap<CommitmentMessageId, WinternitzSecret>,
-    ) -> String {
+    ) -> Result<String, Error> {
+        let Some(signer) = self
+            .operator_signer
+            .as_ref()
+            .and_then(SignerConfig::local)
+            .cloned()
+        else {
+            return Err(Error::Client(ClientError::OperatorSignerNotLocal));
+        };
+
+        let commitment_public_keys = commitment_secrets
+            .iter()
+            .map(|(message_id, secret)| (message_id.clone(), WinternitzPublicKey::from(secret)))
+            .collect();
+        let peg_out_graph_id = self.add_peg_out_graph(
+            peg_in_graph_id,
+            peg_out_confirm_input,
+            &commitment_public_keys,
+        )?;
+
+        signer.add_winternitz_secrets(commitment_secrets.values().cloned());
+        self.private_data
+            .commitment_secrets
+            .entry(signer.public_key())
+            .or_default()
+            .insert(peg_out_graph_id.clone(), commitment_secrets);
+        self.save_private_data();
+
+        Ok(peg_out_graph_id)
+    }
+
+    fn add_peg_out_graph(
+        &mut self,
+        peg_in_graph_id: &str,
+        peg_out_confirm_input: Input,
+        commitment_public_keys: &HashMap<CommitmentMessageId, WinternitzPublicKey>,
+    ) -> Result<String, Error> {
         if self.operator_context.is_none() {
             panic!("Operator context must be initialized");
         }
@@ -993,23 +1216,17 @@ impl BitVMClient {
             panic!("Peg out graph already exists");
         }
 
-        let peg_out_graph = PegOutGraph::new(
+        let peg_out_graph = PegOutGraph::new_with_commitment_public_keys(
             self.operator_context.as_ref().unwrap(),
             peg_in_graph,
             peg_out_confirm_input,
-            &commitment_secrets,
-        );
+            commitment_public_keys,
+        )?;
 
         self.data.peg_out_graphs.push(peg_out_graph);
         peg_in_graph.peg_out_graphs.push(peg_out_graph_id.clone());
 
-        self.private_data.commitment_secrets = HashMap::from([(
-            *operator_public_key,
-            HashMap::from([(peg_out_graph_id.to_string(), commitment_secrets)]),
-        )]);
-        self.save_private_data();
-
-        peg_out_graph_id
+        Ok(peg_out_graph_id)
     }
 
     pub async fn broadcast_peg_out(
@@ -1046,16 +1263,7 @@ impl BitVMClient {
 
         if self.operator_context.is_some() {
             let tx = graph
-                .kick_off_1(
-                    &self.esplora,
-                    self.operator_context.as_ref().unwrap(),
-                    &self.private_data.commitment_secrets
-                        [&self.operator_context.as_ref().unwrap().operator_public_key]
-                        [peg_out_graph_id][&CommitmentMessageId::PegOutTxIdSourceNetwork],
-                    &self.private_data.commitment_secrets
-                        [&self.operator_context.as_ref().unwrap().operator_public_key]
-                        [peg_out_graph_id][&CommitmentMessageId::PegOutTxIdDestinationNetwork],
-                )
+                .kick_off_1(&self.esplora, self.operator_context.as_ref().unwrap())
                 .await?;
             self.broadcast_tx(&tx).await
         } else {
@@ -1068,13 +1276,7 @@ impl BitVMClient {
 
         if self.operator_context.is_some() {
             let tx = graph
-                .start_time(
-                    &self.esplora,
-                    self.operator_context.as_ref().unwrap(),
-                    &self.private_data.commitment_secrets
-                        [&self.operator_context.as_ref().unwrap().operator_public_key]
-                        [peg_out_graph_id][&CommitmentMessageId::StartTime],
-                )
+                .start_time(&self.esplora, self.operator_context.as_ref().unwrap())
                 .await?;
             self.broadcast_tx(&tx).await
         } else {
@@ -1097,16 +1299,7 @@ impl BitVMClient {
     pub async fn broadcast_kick_off_2(&mut self, peg_out_graph_id: &String) -> Result<Txid, Error> {
         let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
         let tx = graph
-            .kick_off_2(
-                &self.esplora,
-                self.operator_context.as_ref().unwrap(),
-                &self.private_data.commitment_secrets
-                    [&self.operator_context.as_ref().unwrap().operator_public_key]
-                    [peg_out_graph_id][&CommitmentMessageId::Superblock],
-                &self.private_data.commitment_secrets
-                    [&self.operator_context.as_ref().unwrap().operator_public_key]
-                    [peg_out_graph_id][&CommitmentMessageId::SuperblockHash],
-            )
+            .kick_off_2(&self.esplora, self.operator_context.as_ref().unwrap())
             .await?;
         self.broadcast_tx(&tx).await
     }
@@ -1129,32 +1322,56 @@ impl BitVMClient {
         crowdfundng_inputs: &Vec<InputWithScript<'_>>,
         output_script_pubkey: ScriptBuf,
     ) -> Result<Txid, Error> {
-        let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
-
-        let keypair = match (
+        let signer = Self::crowdfunding_signer(
             self.depositor_context.as_ref(),
             self.operator_context.as_ref(),
             self.verifier_context.as_ref(),
             self.withdrawer_context.as_ref(),
-        ) {
-            (Some(c), _, _, _) => &c.depositor_keypair,
-            (_, Some(c), _, _) => &c.operator_keypair,
-            (_, _, Some(c), _) => &c.verifier_keypair,
-            (_, _, _, Some(c)) => &c.withdrawer_keypair,
-            _ => Err(Error::Client(ClientError::NoUserContextDefined))?,
-        };
+        )?;
+        let graph = Self::find_peg_out_or_fail(&mut self.data, peg_out_graph_id)?;
 
         let tx = graph
             .challenge(
                 &self.esplora,
                 crowdfundng_inputs,
-                keypair,
+                signer,
                 output_script_pubkey,
             )
             .await?;
         self.broadcast_tx(&tx).await
     }
 
+    // Signs the crowdfunding inputs of a challenge with the first available user context.
+    fn crowdfunding_signer<'a>(
+        depositor_context: Option<&'a DepositorContext>,
+        operator_context: Option<&'a OperatorContext>,
+        verifier_context: Option<&'a VerifierContext>,
+        withdrawer_context: Option<&'a WithdrawerContext>,
+    ) -> Result<&'a dyn Signer, Error> {
+        match (
+            depositor_context,
+            operator_context,
+            verifier_context,
+            withdrawer_context,
+        ) {
+            (Some(c), _, _, _) => Ok(c.depositor_signer.as_ref()),
+            (_, Some(c), _, _) => Ok(c.operator_signer.as_ref()),
+            (_, _, Some(c), _) => Ok(c.verifier_signer.as_ref()),
+            (_, _, _, Some(c)) => Ok(c.withdrawer_signer.as_ref()),
+            _ => Err(Error::Client(ClientError::NoUserContextDefined)),
+        }
+    }
+
+    pub fn get_crowdfunding_public_key(&self) -> Result<PublicKey, Error> {
+        Self::crowdfunding_signer(
+            self.depositor_context.as_ref(),
+            self.operator_context.as_ref(),
+            self.verifier_context.as_ref(),
+            self.withdrawer_context.as_ref(),
+        )
+        .map(|signer| signer.public_key())
+    }
+
     pub async fn broadcast_assert_initial(
         &mut self,
         peg_out_graph_id: &String,
@@ -1173,9 +1390,9 @@ impl BitVMClient {
         let tx = graph
             .assert_commit_1(
                 &self.esplora,
-                &self.private_data.commitment_secrets
-                    [&self.operator_context.as_ref().unwrap().operator_public_key]
-                    [peg_out_graph_id],
+                self.operator_context
+                    .as_ref()
+                    .ok_or(Error::Client(ClientError::OperatorContextNotDefined))?,
                 proof,
             )
             .await?;
@@ -1191,9 +1408,9 @@ impl BitVMClient {
         let tx = graph
             .assert_commit_2(
                 &self.esplora,
-                &self.private_data.commitment_secrets
-                    [&self.operator_context.as_ref().unwrap().operator_public_key]
-                    [peg_out_graph_id],
+                self.operator_context
+                    .as_ref()
+                    .ok_or(Error::Client(ClientError::OperatorContextNotDefined))?,
                 proof,
             )
             .await?;
@@ -1211,9 +1428,9 @@ impl BitVMClient {
         let (commit1_tx, commit2_tx) = graph
             .assert_commits(
                 &self.esplora,
-                &self.private_data.commitment_secrets
-                    [&self.operator_context.as_ref().unwrap().operator_public_key]
-                    [peg_out_graph_id],
+                self.operator_context
+                    .as_ref()
+                    .ok_or(Error::Client(ClientError::OperatorContextNotDefined))?,
                 proof,
             )
             .await?;
@@ -1223,6 +1440,28 @@ impl BitVMClient {
         ))
     }
 
+    /// Same as `broadcast_assert_commits`, asserting the Groth16 proof `backend` wraps the
+    /// succinct `receipt` of the guest `image_id` into.
+    #[cfg(feature = "prover")]
+    pub async fn broadcast_assert_commits_with_receipt(
+        &mut self,
+        peg_out_graph_id: &String,
+        backend: &dyn ProverBackend,
+        receipt: &Receipt,
+        image_id: [u32; 8],
+    ) -> Result<(Txid, Txid), Error> {
+        let proof = get_proof(
+            backend,
+            receipt,
+            image_id,
+            self.zkproof_verifying_key
+                .as_ref()
+                .ok_or(Error::Client(ClientError::ZkProofVerifyingKeyNotDefined))?,
+        )?;
+        self.broadcast_assert_commits(peg_out_graph_id, &proof)
+            .await
+    }
+
     pub async fn broadcast_assert_final(
         &mut self,
         peg_out_graph_id: &String,
@@ -1250,6 +1489,63 @@ impl BitVMClient {
         self.broadcast_tx(&tx).await
     }
 
+    pub fn export_psbt(&self, graph_id: &GraphId, txid: &Txid) -> Result<Psbt, Error> {
+        if let Ok(peg_in_graph) = self.get_peg_in_graph(graph_id) {
+            return peg_in_graph.export_psbt(txid);
+        }
+
+        let peg_out_graph = self
+            .data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id().eq(graph_id))
+            .ok_or(Error::Client(ClientError::PegOutGraphNotFound(
+                graph_id.clone(),
+            )))?;
+        let peg_in_graph = self.get_peg_in_graph(&peg_out_graph.peg_in_graph_id)?;
+
+        peg_out_graph.export_psbt(txid, peg_in_graph.n_of_n_public_keys())
+    }
+
+    // Imports a PSBT signed with external tooling into the graph. Call `flush` to publish it.
+    pub fn import_psbt(&mut self, graph_id: &GraphId, psbt: &Psbt) -> Result<Txid, Error> {
+        if let Some(peg_in_graph) = self.get_peg_in_graph_mut(graph_id) {
+            return peg_in_graph.import_psbt(psbt);
+        }
+
+        let peg_in_graph_id = Self::find_peg_out_or_fail(&mut self.data, graph_id)?
+            .peg_in_graph_id
+            .clone();
+        let n_of_n_public_keys = self
+            .get_peg_in_graph(&peg_in_graph_id)?
+            .n_of_n_public_keys()
+            .to_vec();
+
+        Self::find_peg_out_or_fail(&mut self.data, graph_id)?.import_psbt(psbt, &n_of_n_public_keys)
+    }
+
+    pub async fn validate_peg_out_assertions(
+        &self,
+        peg_out_graph_id: &String,
+    ) -> Result<Option<usize>, Error> {
+        let graph = self
+            .data
+            .peg_out_graphs
+            .iter()
+            .find(|peg_out_graph| peg_out_graph.id().eq(peg_out_graph_id))
+            .ok_or(Error::Client(ClientError::PegOutGraphNotFound(
+                peg_out_graph_id.clone(),
+            )))?;
+        graph
+            .validate_assertions(
+                &self.esplora,
+                self.zkproof_verifying_key
+                    .as_ref()
+                    .ok_or(Error::Client(ClientError::ZkProofVerifyingKeyNotDefined))?,
+            )
+            .await
+    }
+
     pub async fn broadcast_disprove_chain(
         &mut self,
         peg_out_graph_id: &String,
@@ -1333,15 +1629,26 @@ impl BitVMClient {
             .unwrap()
     }
 
-    pub fn push_verifier_nonces(&mut self, graph_id: &GraphId) {
+    pub fn push_verifier_nonces(&mut self, graph_id: &GraphId) -> Result<(), Error> {
         if self.verifier_context.is_none() {
             panic!("Can only be called by a verifier!");
         }
 
         let graph = self.data.graph_mut(graph_id);
-        let secret_nonces = graph.push_verifier_nonces(self.verifier_context.as_ref().unwrap());
-        self.merge_secret_nonces(graph_id, secret_nonces);
+        let session_ids = graph.push_verifier_nonces(self.verifier_context.as_ref().unwrap())?;
+        self.merge_musig2_session_ids(graph_id, session_ids);
+        self.save_verifier_secret_nonces();
         self.save_private_data();
+
+        Ok(())
+    }
+
+    fn save_verifier_secret_nonces(&mut self) {
+        if let Some(signer) = self.verifier_signer.as_ref().and_then(SignerConfig::local) {
+            self.private_data
+                .musig2_secret_nonces
+                .insert(signer.public_key(), signer.musig2_nonces());
+        }
     }
 
     fn get_peg_in_graph(&self, peg_in_graph_id: &String) -> Result<&PegInGraph, Error> {
@@ -1389,42 +1696,46 @@ impl BitVMClient {
     }
 
     async fn broadcast_tx(&self, tx: &Transaction) -> Result<Txid, Error> {
+        if let Some(ref broadcast_limits) = self.broadcast_limits {
+            broadcast_limits.check(&self.esplora, tx).await?;
+        }
+
         let status_message = broadcast_and_verify(&self.esplora, tx).await?;
 
         let txid = tx.compute_txid();
-        println!("{} Txid: {}", status_message, txid.to_string().green());
+        eprintln!("{} Txid: {}", status_message, txid.to_string().green());
 
         Ok(txid)
     }
 
-    fn merge_secret_nonces(
+    fn merge_musig2_session_ids(
         &mut self,
         graph_id: &str,
-        secret_nonces: HashMap<Txid, HashMap<usize, SecNonce>>,
+        session_ids: HashMap<Txid, HashMap<usize, Musig2SessionId>>,
     ) {
         self.private_data
-            .secret_nonces
+            .musig2_session_ids
             .entry(self.verifier_context.as_ref().unwrap().verifier_public_key)
             .or_default();
 
-        if !self.private_data.secret_nonces
+        if !self.private_data.musig2_session_ids
             [&self.verifier_context.as_ref().unwrap().verifier_public_key]
             .contains_key(graph_id)
         {
             self.private_data
-                .secret_nonces
+                .musig2_session_ids
                 .get_mut(&self.verifier_context.as_ref().unwrap().verifier_public_key)
                 .unwrap()
                 .insert(graph_id.to_string(), HashMap::new());
         }
 
         self.private_data
-            .secret_nonces
+            .musig2_session_ids
             .get_mut(&self.verifier_context.as_ref().unwrap().verifier_public_key)
             .unwrap()
             .get_mut(graph_id)
             .unwrap()
-            .extend(secret_nonces);
+            .extend(session_ids);
     }
 
     pub fn generate_connector_z_taproot_address(
@@ -1453,7 +1764,7 @@ impl BitVMClient {
         amount: Amount,
         depositor_taproot_key: &XOnlyPublicKey,
         outpoint: OutPoint,
-    ) -> String {
+    ) -> Result<String, Error> {
         let connector_z = ConnectorZ::new(
             source_network,
             recipient_address,
@@ -1482,15 +1793,15 @@ impl BitVMClient {
                 .n_of_n_public_keys
                 .clone(),
         );
-        let secret_nonces_0 =
-            peg_in_confirm_tx.push_nonces(self.verifier_context.as_ref().unwrap());
+        let session_ids_0 =
+            peg_in_confirm_tx.push_nonces(self.verifier_context.as_ref().unwrap())?;
 
         peg_in_confirm_tx.pre_sign(
             self.verifier_context.as_ref().unwrap(),
             &connector_z,
-            &secret_nonces_0,
-        );
-        serialize_hex(&(peg_in_confirm_tx.tx_mut()))
+            &session_ids_0,
+        )?;
+        Ok(serialize_hex(&(peg_in_confirm_tx.tx_mut())))
     }
 
     pub fn generate_presign_pegin_deposit_tx(
@@ -1549,7 +1860,7 @@ impl BitVMClient {
         serialize_hex(&(peg_in_refund_tx.tx_mut()))
     }
 
-    pub fn push_verifier_signature(&mut self, graph_id: &GraphId) {
+    pub fn push_verifier_signature(&mut self, graph_id: &GraphId) -> Result<(), Error> {
         let verifier = self
             .verifier_context
             .as_ref()
@@ -1558,9 +1869,15 @@ impl BitVMClient {
         let graph = self.data.graph_mut(graph_id);
         graph.verifier_sign(
             verifier,
-            &self.private_data.secret_nonces
+            &self.private_data.musig2_session_ids
                 [&self.verifier_context.as_ref().unwrap().verifier_public_key][graph_id],
-        );
+        )?;
+
+        // The used secret nonces were consumed by the signer
+        self.save_verifier_secret_nonces();
+        self.save_private_data();
+
+        Ok(())
     }
 
     // pub async fn execute_possible_txs(
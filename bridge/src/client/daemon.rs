@@ -0,0 +1,483 @@
+use std::{
+    net::SocketAddr,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use bitcoin::ScriptBuf;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::RwLock,
+    time::sleep,
+};
+
+use crate::{
+    graphs::{
+        base::{BaseGraph, GraphId},
+        peg_out::generate_id as peg_out_generate_id,
+    },
+    serialization::{serialize, try_deserialize},
+};
+
+use super::{
+    client::BitVMClient,
+    files::{get_daemon_state_file_path, get_daemon_state_from_file, save_local_daemon_state_file},
+    policy::PolicyEngine,
+};
+
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(30);
+pub const DEFAULT_L2_POLL_INTERVAL: Duration = Duration::from_secs(60);
+pub const DEFAULT_STATUS_ADDRESS: &str = "127.0.0.1:8080";
+
+const MAX_HTTP_REQUEST_SIZE: usize = 8192;
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum DaemonRole {
+    Depositor,
+    Operator,
+    Verifier,
+}
+
+impl FromStr for DaemonRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "depositor" => Ok(DaemonRole::Depositor),
+            "operator" => Ok(DaemonRole::Operator),
+            "verifier" => Ok(DaemonRole::Verifier),
+            _ => Err(format!("Unknown daemon role: {s}")),
+        }
+    }
+}
+
+pub struct DaemonConfig {
+    pub roles: Vec<DaemonRole>,
+    pub poll_interval: Duration,
+    pub l2_poll_interval: Duration,
+    pub status_address: SocketAddr,
+    // Receives the outputs of timeout and disprove transactions broadcast as a verifier.
+    pub reward_script: Option<ScriptBuf>,
+    // Decides on peg-out actions instead of acting on every available status.
+    pub policy: Option<PolicyEngine>,
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct GraphStatusEntry {
+    pub graph_id: GraphId,
+    pub role: DaemonRole,
+    // Stable status variant name, e.g. `PegOutKickOff1Available`.
+    pub status: String,
+    pub description: String,
+}
+
+/// Progress of the daemon, persisted next to the client data after every poll
+/// and served by the status endpoint.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug, Default)]
+pub struct DaemonState {
+    pub roles: Vec<DaemonRole>,
+    pub iterations: u64,
+    pub last_poll_timestamp: Option<u64>,
+    pub last_l2_poll_timestamp: Option<u64>,
+    pub last_block_height: Option<u32>,
+    pub last_error: Option<String>,
+    pub graphs: Vec<GraphStatusEntry>,
+}
+
+pub struct Daemon {
+    config: DaemonConfig,
+    state: Arc<RwLock<DaemonState>>,
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before the UNIX epoch")
+        .as_secs()
+}
+
+impl Daemon {
+    pub fn new(config: DaemonConfig) -> Self {
+        Daemon {
+            config,
+            state: Arc::new(RwLock::new(DaemonState::default())),
+        }
+    }
+
+    pub fn state(&self) -> Arc<RwLock<DaemonState>> {
+        self.state.clone()
+    }
+
+    /// Runs the event loop until the process receives Ctrl-C.
+    pub async fn run(&self, client: &mut BitVMClient) -> Result<(), String> {
+        self.load_state(client).await;
+
+        let listener = TcpListener::bind(self.config.status_address)
+            .await
+            .map_err(|e| format!("Failed to bind status endpoint: {e}"))?;
+        eprintln!(
+            "Serving daemon status on http://{}/status",
+            self.config.status_address
+        );
+        tokio::spawn(serve_status(listener, self.state.clone()));
+
+        // Ctrl-C interrupts a running poll as well as the wait between polls. Whatever the
+        // interrupted poll already applied to the client data is flushed below.
+        let shutdown = tokio::signal::ctrl_c();
+        tokio::pin!(shutdown);
+        loop {
+            tokio::select! {
+                _ = async {
+                    self.poll(client).await;
+                    sleep(self.config.poll_interval).await;
+                } => {}
+                _ = &mut shutdown => {
+                    eprintln!("Shutting down daemon...");
+                    break;
+                }
+            }
+        }
+
+        client.flush().await;
+        self.save_state(client).await;
+
+        Ok(())
+    }
+
+    /// Runs a single iteration: sync, act according to the configured roles, persist.
+    pub async fn poll(&self, client: &mut BitVMClient) {
+        let poll_timestamp = now();
+        let l2_poll_due = match self.state.read().await.last_l2_poll_timestamp {
+            Some(timestamp) => poll_timestamp >= timestamp + self.config.l2_poll_interval.as_secs(),
+            None => true,
+        };
+
+        client.sync().await;
+        if l2_poll_due {
+            client.sync_l2().await;
+        }
+
+        let old_data = client.data().clone();
+
+        if !self.config.roles.is_empty() {
+            client.process_peg_ins().await;
+        }
+        if self.config.roles.contains(&DaemonRole::Operator) {
+            match &self.config.policy {
+                Some(policy_engine) => policy_engine.process_peg_outs_as_operator(client).await,
+                None => client.process_peg_outs().await,
+            }
+        }
+        if self.config.roles.contains(&DaemonRole::Verifier) {
+            match (&self.config.policy, &self.config.reward_script) {
+                (Some(policy_engine), reward_script) => {
+                    policy_engine
+                        .process_peg_outs_as_verifier(client, reward_script.as_ref())
+                        .await
+                }
+                (None, Some(reward_script)) => {
+                    client.process_peg_outs_as_verifier(reward_script).await
+                }
+                (None, None) => {
+                    eprintln!("No reward address configured, skipping verifier peg-out actions")
+                }
+            }
+        }
+
+        if client.data() != &old_data {
+            client.flush().await;
+        }
+
+        let last_block_height = client.esplora.get_height().await;
+        let graphs = collect_graph_statuses(client, &self.config.roles).await;
+        {
+            let mut state = self.state.write().await;
+            state.roles = self.config.roles.clone();
+            state.iterations += 1;
+            state.last_poll_timestamp = Some(poll_timestamp);
+            if l2_poll_due {
+                state.last_l2_poll_timestamp = Some(poll_timestamp);
+            }
+            match last_block_height {
+                Ok(height) => {
+                    state.last_block_height = Some(height);
+                    state.last_error = None;
+                }
+                Err(e) => state.last_error = Some(format!("Failed to get block height: {e}")),
+            }
+            state.graphs = graphs;
+        }
+
+        self.save_state(client).await;
+    }
+
+    async fn load_state(&self, client: &BitVMClient) {
+        let path = get_daemon_state_file_path(client.local_file_path());
+        if let Some(contents) = get_daemon_state_from_file(&path) {
+            match try_deserialize::<DaemonState>(&contents) {
+                Ok(state) => {
+                    eprintln!(
+                        "Resuming daemon after {} iterations (last block height: {:?})",
+                        state.iterations, state.last_block_height
+                    );
+                    *self.state.write().await = state;
+                }
+                Err(e) => eprintln!("Ignoring unreadable daemon state: {e}"),
+            }
+        }
+    }
+
+    async fn save_state(&self, client: &BitVMClient) {
+        let contents = serialize(&*self.state.read().await);
+        save_local_daemon_state_file(client.local_file_path(), &contents);
+    }
+}
+
+pub async fn collect_graph_statuses(
+    client: &BitVMClient,
+    roles: &[DaemonRole],
+) -> Vec<GraphStatusEntry> {
+    let data = client.data();
+    let mut entries = Vec::new();
+
+    if let (true, Some(context)) = (
+        roles.contains(&DaemonRole::Depositor),
+        client.depositor_context(),
+    ) {
+        for peg_in_graph in data.peg_in_graphs.iter() {
+            if peg_in_graph
+                .depositor_public_key
+                .eq(&context.depositor_public_key)
+            {
+                let status = peg_in_graph.depositor_status(&client.esplora).await;
+                entries.push(GraphStatusEntry {
+                    graph_id: peg_in_graph.id().clone(),
+                    role: DaemonRole::Depositor,
+                    status: format!("{status:?}"),
+                    description: status.to_string(),
+                });
+            }
+        }
+    }
+
+    if let (true, Some(context)) = (
+        roles.contains(&DaemonRole::Operator),
+        client.operator_context(),
+    ) {
+        for peg_in_graph in data.peg_in_graphs.iter() {
+            let peg_out_graph_id = peg_out_generate_id(peg_in_graph, &context.operator_public_key);
+            if let Some(peg_out_graph) = data
+                .peg_out_graphs
+                .iter()
+                .find(|peg_out_graph| peg_out_graph.id() == &peg_out_graph_id)
+            {
+                let status = peg_out_graph.operator_status(&client.esplora).await;
+                entries.push(GraphStatusEntry {
+                    graph_id: peg_out_graph_id,
+                    role: DaemonRole::Operator,
+                    status: format!("{status:?}"),
+                    description: status.to_string(),
+                });
+            }
+        }
+    }
+
+    if let (true, Some(context)) = (
+        roles.contains(&DaemonRole::Verifier),
+        client.verifier_context(),
+    ) {
+        for peg_in_graph in data.peg_in_graphs.iter() {
+            let peg_outs = data
+                .peg_out_graphs
+                .iter()
+                .filter(|peg_out_graph| peg_in_graph.peg_out_graphs.contains(peg_out_graph.id()))
+                .collect::<Vec<_>>();
+            let status = peg_in_graph
+                .verifier_status(&client.esplora, context, &peg_outs)
+                .await;
+            entries.push(GraphStatusEntry {
+                graph_id: peg_in_graph.id().clone(),
+                role: DaemonRole::Verifier,
+                status: format!("{status:?}"),
+                description: status.to_string(),
+            });
+
+            for peg_out_graph in peg_outs {
+                let status = peg_out_graph
+                    .verifier_status(&client.esplora, context)
+                    .await;
+                entries.push(GraphStatusEntry {
+                    graph_id: peg_out_graph.id().clone(),
+                    role: DaemonRole::Verifier,
+                    status: format!("{status:?}"),
+                    description: status.to_string(),
+                });
+            }
+        }
+    }
+
+    entries
+}
+
+async fn serve_status(listener: TcpListener, state: Arc<RwLock<DaemonState>>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(handle_status_request(stream, state.clone()));
+            }
+            Err(e) => eprintln!("Failed to accept status connection: {e}"),
+        }
+    }
+}
+
+async fn handle_status_request(mut stream: TcpStream, state: Arc<RwLock<DaemonState>>) {
+    let mut buffer = vec![0u8; MAX_HTTP_REQUEST_SIZE];
+    let size = match stream.read(&mut buffer).await {
+        Ok(size) => size,
+        Err(_) => return,
+    };
+    let request = String::from_utf8_lossy(&buffer[..size]);
+
+    let mut request_line = request.lines().next().unwrap_or("").split_whitespace();
+    let (status_line, body) = match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some("/status")) => ("200 OK", serialize(&*state.read().await)),
+        (Some("GET"), Some("/graphs")) => ("200 OK", serialize(&state.read().await.graphs)),
+        (Some("GET"), Some("/health")) => ("200 OK", json!({ "status": "ok" }).to_string()),
+        (Some("GET"), _) => ("404 Not Found", json!({ "error": "Not found" }).to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            json!({ "error": "Method not allowed" }).to_string(),
+        ),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {status_line}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, process, sync::Arc};
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+        sync::RwLock,
+    };
+
+    use crate::{
+        client::files::{
+            get_daemon_state_file_path, get_daemon_state_from_file, save_local_daemon_state_file,
+        },
+        serialization::{deserialize, serialize, try_deserialize},
+    };
+
+    use super::{serve_status, DaemonRole, DaemonState, GraphStatusEntry};
+
+    fn test_state() -> DaemonState {
+        DaemonState {
+            roles: vec![DaemonRole::Operator, DaemonRole::Verifier],
+            iterations: 42,
+            last_poll_timestamp: Some(1_700_000_000),
+            last_l2_poll_timestamp: Some(1_699_999_990),
+            last_block_height: Some(850_000),
+            last_error: Some("Failed to get block height: timeout".to_string()),
+            graphs: vec![GraphStatusEntry {
+                graph_id: "graph_id".to_string(),
+                role: DaemonRole::Operator,
+                status: "PegOutKickOff1Available".to_string(),
+                description: "Kick-off 1 available".to_string(),
+            }],
+        }
+    }
+
+    async fn request(state: DaemonState, request: &str) -> (String, String) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(serve_status(listener, Arc::new(RwLock::new(state))));
+
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        (head.lines().next().unwrap().to_string(), body.to_string())
+    }
+
+    #[test]
+    fn test_daemon_state_persistence_round_trip() {
+        let data_root_path =
+            std::env::temp_dir().join(format!("bitvm_daemon_state_test_{}", process::id()));
+        let state = test_state();
+
+        save_local_daemon_state_file(&data_root_path, &serialize(&state));
+        // Overwriting goes through the temporary file as well
+        save_local_daemon_state_file(&data_root_path, &serialize(&state));
+
+        let contents =
+            get_daemon_state_from_file(&get_daemon_state_file_path(&data_root_path)).unwrap();
+        let restored = try_deserialize::<DaemonState>(&contents).unwrap();
+        fs::remove_dir_all(&data_root_path).unwrap();
+
+        assert_eq!(restored, state);
+    }
+
+    #[test]
+    fn test_daemon_state_from_missing_file() {
+        let data_root_path =
+            std::env::temp_dir().join(format!("bitvm_daemon_state_missing_{}", process::id()));
+
+        assert!(get_daemon_state_from_file(&get_daemon_state_file_path(&data_root_path)).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_status_request() {
+        let (status_line, body) = request(test_state(), "GET /status HTTP/1.1\r\n\r\n").await;
+
+        assert_eq!(status_line, "HTTP/1.1 200 OK");
+        assert_eq!(deserialize::<DaemonState>(&body), test_state());
+    }
+
+    #[tokio::test]
+    async fn test_graphs_request() {
+        let (status_line, body) = request(test_state(), "GET /graphs HTTP/1.1\r\n\r\n").await;
+
+        assert_eq!(status_line, "HTTP/1.1 200 OK");
+        assert_eq!(
+            deserialize::<Vec<GraphStatusEntry>>(&body),
+            test_state().graphs
+        );
+    }
+
+    #[tokio::test]
+    async fn test_health_request() {
+        let (status_line, body) = request(test_state(), "GET /health HTTP/1.1\r\n\r\n").await;
+
+        assert_eq!(status_line, "HTTP/1.1 200 OK");
+        assert_eq!(body, r#"{"status":"ok"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_path_request() {
+        let (status_line, _) = request(test_state(), "GET /unknown HTTP/1.1\r\n\r\n").await;
+
+        assert_eq!(status_line, "HTTP/1.1 404 Not Found");
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_method_request() {
+        let (status_line, _) = request(test_state(), "POST /status HTTP/1.1\r\n\r\n").await;
+        assert_eq!(status_line, "HTTP/1.1 405 Method Not Allowed");
+
+        let (status_line, _) = request(test_state(), "\r\n").await;
+        assert_eq!(status_line, "HTTP/1.1 405 Method Not Allowed");
+    }
+}
@@ -99,7 +99,7 @@ impl DataStore {
                     return Ok(Some(data));
                 }
 
-                println!("No data file {} found", key);
+                eprintln!("No data file {} found", key);
                 Ok(None)
             }
             Err(err) => Err(err.to_string()),
@@ -142,7 +142,7 @@ impl DataStore {
                     return Ok((Some(data), size));
                 }
 
-                println!("No data file {} found", key);
+                eprintln!("No data file {} found", key);
                 Ok((None, 0))
             }
             Err(err) => Err(err.to_string()),
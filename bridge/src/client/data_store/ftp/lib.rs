@@ -99,7 +99,7 @@ pub async fn upload_object(
 ) -> Result<usize, String> {
     let size = contents.len();
 
-    println!("Writing data file to {} (size: {})", file_name, size);
+    eprintln!("Writing data file to {} (size: {})", file_name, size);
 
     match upload_file(credentials, file_name, contents.as_bytes(), file_path).await {
         Ok(_) => Ok(size),
@@ -131,7 +131,7 @@ pub async fn upload_compressed_object(
     let compressed_data = compress(contents, DEFAULT_COMPRESSION_LEVEL).map_err(err_to_string)?;
     let size = compressed_data.len();
 
-    println!("Writing data file to {} (size: {})", file_name, size);
+    eprintln!("Writing data file to {} (size: {})", file_name, size);
 
     match upload_file(
         credentials,
@@ -29,7 +29,7 @@ impl LocalFile {
         {
             return None;
         } else if !cfg!(debug_assertions) {
-            println!("Disabling local file data store in release mode, please remove BRIDGE_USE_LOCAL_FILE_DATA_STORE or set it to false in .env");
+            eprintln!("Disabling local file data store in release mode, please remove BRIDGE_USE_LOCAL_FILE_DATA_STORE or set it to false in .env");
             return None;
         }
 
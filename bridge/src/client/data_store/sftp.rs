@@ -218,7 +218,7 @@ impl DataStoreDriver for Sftp {
     ) -> Result<usize, String> {
         let size = contents.len();
 
-        println!("Writing data file to {} (size: {})", file_name, size);
+        eprintln!("Writing data file to {} (size: {})", file_name, size);
 
         match self
             .upload_object(file_name, contents.as_bytes(), file_path)
@@ -254,7 +254,7 @@ impl DataStoreDriver for Sftp {
             compress(contents, DEFAULT_COMPRESSION_LEVEL).map_err(err_to_string)?;
         let size = compressed_data.len();
 
-        println!("Writing data file to {} (size: {})", file_name, size);
+        eprintln!("Writing data file to {} (size: {})", file_name, size);
 
         match self
             .upload_object(file_name, compressed_data.as_slice(), file_path)
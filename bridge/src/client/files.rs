@@ -1,6 +1,6 @@
 use std::{
-    collections::HashMap,
-    fs,
+    fs::{self, OpenOptions},
+    io::Write,
     path::{Path, PathBuf},
 };
 
@@ -11,6 +11,8 @@ use super::client::BitVMClientPrivateData;
 pub const BRIDGE_DATA_DIRECTORY_NAME: &str = "bridge_data";
 pub const DEFAULT_PATH_PREFIX: &str = "default_user";
 pub const PRIVATE_DATA_FILE_NAME: &str = "secret_data.json";
+pub const DAEMON_STATE_FILE_NAME: &str = "daemon_state.json";
+pub const POLICY_AUDIT_LOG_FILE_NAME: &str = "policy_audit.jsonl";
 const PRIVATE_DATA_DIRECTORY_NAME: &str = "private";
 const PUBLIC_DATA_DIRECTORY_NAME: &str = "public";
 
@@ -22,6 +24,14 @@ pub fn get_private_data_file_path(data_root_path: &Path) -> PathBuf {
     get_private_data_directory_path(data_root_path).join(PRIVATE_DATA_FILE_NAME)
 }
 
+pub fn get_daemon_state_file_path(data_root_path: &Path) -> PathBuf {
+    data_root_path.join(DAEMON_STATE_FILE_NAME)
+}
+
+pub fn get_policy_audit_log_file_path(data_root_path: &Path) -> PathBuf {
+    data_root_path.join(POLICY_AUDIT_LOG_FILE_NAME)
+}
+
 fn get_public_data_directory_path(data_root_path: &Path) -> PathBuf {
     data_root_path.join(PUBLIC_DATA_DIRECTORY_NAME)
 }
@@ -44,15 +54,20 @@ pub fn create_directories_if_non_existent(data_root_path: &Path) {
 
 pub fn get_private_data_from_file(path: &Path) -> BitVMClientPrivateData {
     match read_file(path) {
-        Some(data) => try_deserialize::<BitVMClientPrivateData>(&data)
-            .expect("Could not deserialize private data"),
-        None => BitVMClientPrivateData {
-            secret_nonces: HashMap::new(),
-            commitment_secrets: HashMap::new(),
-        },
+        Some(data) => {
+            let mut private_data = try_deserialize::<BitVMClientPrivateData>(&data)
+                .expect("Could not deserialize private data");
+            private_data.migrate_secret_nonces();
+            private_data
+        }
+        None => BitVMClientPrivateData::default(),
     }
 }
 
+pub fn get_daemon_state_from_file(path: &Path) -> Option<String> {
+    read_file(path)
+}
+
 fn read_file(path: &Path) -> Option<String> {
     match fs::read_to_string(path) {
         Ok(content) => Some(content),
@@ -69,7 +84,7 @@ fn read_file(path: &Path) -> Option<String> {
 
 pub fn save_local_public_file(data_root_path: &Path, file_name: &String, contents: &String) {
     create_directories_if_non_existent(data_root_path);
-    println!("Saving public data in local file: {}...", file_name);
+    eprintln!("Saving public data in local file: {}...", file_name);
     fs::write(
         get_public_data_directory_path(data_root_path).join(file_name),
         contents,
@@ -79,7 +94,77 @@ pub fn save_local_public_file(data_root_path: &Path, file_name: &String, content
 
 pub fn save_local_private_file(data_root_path: &Path, contents: &String) {
     create_directories_if_non_existent(data_root_path);
-    println!("Saving private data in local file...");
+    eprintln!("Saving private data in local file...");
     fs::write(get_private_data_file_path(data_root_path), contents)
         .expect("Unable to write a file");
 }
+
+pub fn save_local_daemon_state_file(data_root_path: &Path, contents: &String) {
+    create_directories_if_non_existent(data_root_path);
+    // Write to a temporary file first, so a crash mid-write never leaves a truncated state file.
+    let file_path = get_daemon_state_file_path(data_root_path);
+    let temp_file_path = file_path.with_extension("json.tmp");
+    fs::write(&temp_file_path, contents).expect("Unable to write a file");
+    fs::rename(&temp_file_path, &file_path).expect("Unable to rename a file");
+}
+
+pub fn append_line_to_file(file_path: &Path, line: &str) {
+    if let Some(parent) = file_path.parent() {
+        if !parent.as_os_str().is_empty() && !parent.exists() {
+            fs::create_dir_all(parent).expect("Failed to create directories");
+        }
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file_path)
+        .expect("Unable to open a file");
+    writeln!(file, "{line}").expect("Unable to write a file");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, process};
+
+    use bitcoin::{Network, Txid};
+    use musig2::SecNonce;
+
+    use crate::{
+        contexts::base::generate_keys_from_secret, serialization::serialize,
+        transactions::signing_musig2::generate_musig2_session_id,
+    };
+
+    use super::{get_private_data_file_path, get_private_data_from_file, save_local_private_file};
+
+    #[test]
+    fn test_private_data_migrates_old_secret_nonces() {
+        let data_root_path =
+            std::env::temp_dir().join(format!("bitvm_private_data_test_{}", process::id()));
+        let (_, verifier_public_key) = generate_keys_from_secret(Network::Regtest, &"7".repeat(64));
+        let txid: Txid = "1".repeat(64).parse().unwrap();
+        let secret_nonce = SecNonce::build([1u8; 32]).build();
+
+        // Written before the MuSig2 session IDs, without any of the musig2_* fields
+        let old_private_data = format!(
+            r#"{{"secret_nonces":{{"{verifier_public_key}":{{"graph_id":{{"{txid}":{{"0":{nonce}}}}}}}}},"commitment_secrets":{{}}}}"#,
+            nonce = serde_json::to_string(&secret_nonce).unwrap()
+        );
+        save_local_private_file(&data_root_path, &old_private_data);
+
+        let private_data = get_private_data_from_file(&get_private_data_file_path(&data_root_path));
+        fs::remove_dir_all(&data_root_path).unwrap();
+
+        let session_id = generate_musig2_session_id(txid, 0);
+        assert!(private_data.secret_nonces.is_empty());
+        assert_eq!(
+            private_data.musig2_session_ids[&verifier_public_key]["graph_id"][&txid][&0],
+            session_id
+        );
+        assert_eq!(
+            private_data.musig2_secret_nonces[&verifier_public_key][&session_id],
+            secret_nonce
+        );
+        assert!(private_data.commitment_secrets.is_empty());
+        assert!(!serialize(&private_data).contains(r#""secret_nonces""#));
+    }
+}
@@ -2,8 +2,10 @@
 pub mod chain;
 pub mod cli;
 pub mod client;
+pub mod daemon;
 pub mod data_store;
 pub mod esplora;
 pub mod files;
 pub mod memory_cache;
+pub mod policy;
 pub mod sdk;
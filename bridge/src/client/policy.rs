@@ -0,0 +1,682 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Mutex,
+};
+
+use bitcoin::{Address, Amount, Network, OutPoint, ScriptBuf, Transaction, Txid};
+use colored::Colorize;
+use esplora_client::AsyncClient;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{Error, PolicyError},
+    graphs::{
+        base::{BaseGraph, GraphId},
+        peg_out::{PegOutOperatorStatus, PegOutVerifierStatus},
+    },
+    proof::get_dummy_proof,
+    scripts::{generate_pay_to_pubkey_script, generate_pay_to_pubkey_script_address},
+    serialization::serialize,
+    transactions::base::InputWithScript,
+};
+
+use super::{
+    client::BitVMClient,
+    daemon::{now, DaemonRole},
+    files::{append_line_to_file, get_policy_audit_log_file_path},
+};
+
+/// Rules for the automatic dispute actions of operators and verifiers, loaded from a TOML file:
+///
+/// ```toml
+/// dry_run = true
+///
+/// [graphs]
+/// watch = []   # peg-out graph ids to act on, all graphs if empty
+/// ignore = []  # peg-out graph ids to never act on
+///
+/// [fees]
+/// max_fee = 500000    # sats per transaction
+/// max_fee_rate = 50.0 # sat/vB
+///
+/// [operator]
+/// enabled = true
+///
+/// [verifier]
+/// reward_address = "tb1q..."
+/// timeouts = true
+/// challenge = true
+/// max_challenge_contribution = 2000000 # sats
+/// disprove_chain = true
+/// disprove = true
+/// disprove_requires_local_validation = true
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct Policy {
+    // Decide and log as usual, but never broadcast.
+    pub dry_run: bool,
+    // Defaults to `policy_audit.jsonl` in the local data directory.
+    pub audit_log: Option<PathBuf>,
+    pub graphs: GraphPolicy,
+    pub fees: FeePolicy,
+    pub operator: OperatorPolicy,
+    pub verifier: VerifierPolicy,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct GraphPolicy {
+    pub watch: Vec<GraphId>,
+    pub ignore: Vec<GraphId>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct FeePolicy {
+    pub max_fee: Option<u64>,
+    pub max_fee_rate: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct OperatorPolicy {
+    pub enabled: bool,
+}
+
+impl Default for OperatorPolicy {
+    fn default() -> Self {
+        OperatorPolicy { enabled: true }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct VerifierPolicy {
+    pub reward_address: Option<String>,
+    pub timeouts: bool,
+    // Challenging spends the verifier's own funds, so it must be enabled explicitly.
+    pub challenge: bool,
+    pub max_challenge_contribution: Option<u64>,
+    pub disprove_chain: bool,
+    pub disprove: bool,
+    pub disprove_requires_local_validation: bool,
+}
+
+impl Default for VerifierPolicy {
+    fn default() -> Self {
+        VerifierPolicy {
+            reward_address: None,
+            timeouts: true,
+            challenge: false,
+            max_challenge_contribution: None,
+            disprove_chain: true,
+            disprove: true,
+            disprove_requires_local_validation: true,
+        }
+    }
+}
+
+impl Policy {
+    pub fn from_toml(contents: &str) -> Result<Self, String> {
+        toml::from_str(contents).map_err(|e| format!("Invalid policy: {e}"))
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Could not read policy file {}: {e}", path.display()))?;
+        Self::from_toml(&contents)
+    }
+
+    pub fn watches(&self, graph_id: &GraphId) -> bool {
+        !self.graphs.ignore.contains(graph_id)
+            && (self.graphs.watch.is_empty() || self.graphs.watch.contains(graph_id))
+    }
+
+    pub fn broadcast_limits(&self) -> BroadcastLimits {
+        BroadcastLimits {
+            max_fee: self.fees.max_fee.map(Amount::from_sat),
+            max_fee_rate: self.fees.max_fee_rate,
+            dry_run: self.dry_run,
+        }
+    }
+
+    pub fn reward_script(&self, network: Network) -> Result<Option<ScriptBuf>, String> {
+        self.verifier
+            .reward_address
+            .as_ref()
+            .map(|address| {
+                Address::from_str(address)
+                    .map_err(|e| format!("Invalid reward address {address}: {e}"))?
+                    .require_network(network)
+                    .map_err(|e| format!("Invalid reward address {address}: {e}"))
+                    .map(|address| address.script_pubkey())
+            })
+            .transpose()
+    }
+
+    /// Why `action` must not be taken at all under this policy, if it is disabled.
+    pub fn disabled_reason(&self, action: PolicyAction) -> Option<&'static str> {
+        let verifier = &self.verifier;
+        match action {
+            PolicyAction::StartTimeTimeout | PolicyAction::KickOffTimeout if !verifier.timeouts => {
+                Some("Timeouts are disabled")
+            }
+            PolicyAction::Challenge if !verifier.challenge => Some("Challenges are disabled"),
+            PolicyAction::DisproveChain if !verifier.disprove_chain => {
+                Some("Disprove chain is disabled")
+            }
+            PolicyAction::Disprove if !verifier.disprove => Some("Disprove is disabled"),
+            PolicyAction::StartTimeTimeout
+            | PolicyAction::KickOffTimeout
+            | PolicyAction::Challenge
+            | PolicyAction::DisproveChain
+            | PolicyAction::Disprove => None,
+            _ if !self.operator.enabled => Some("Operator actions are disabled"),
+            _ => None,
+        }
+    }
+
+    /// Why a challenge requiring `crowdfunding_amount` must not be funded. An amount that
+    /// cannot be determined is never funded.
+    pub fn challenge_denied_reason(
+        &self,
+        crowdfunding_amount: &Result<Amount, Error>,
+    ) -> Option<String> {
+        match (crowdfunding_amount, self.verifier.max_challenge_contribution) {
+            (Err(e), _) => Some(format!("Could not determine the crowdfunding amount: {e}")),
+            (Ok(crowdfunding_amount), Some(max_contribution))
+                if *crowdfunding_amount > Amount::from_sat(max_contribution) =>
+            {
+                Some(format!(
+                    "Crowdfunding amount {crowdfunding_amount} exceeds the maximum challenge contribution of {}",
+                    Amount::from_sat(max_contribution)
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Checked by the client right before any transaction is broadcast.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct BroadcastLimits {
+    pub max_fee: Option<Amount>,
+    pub max_fee_rate: Option<f64>,
+    pub dry_run: bool,
+}
+
+impl BroadcastLimits {
+    pub async fn check(&self, client: &AsyncClient, tx: &Transaction) -> Result<(), Error> {
+        let txid = tx.compute_txid();
+        if self.max_fee.is_some() || self.max_fee_rate.is_some() {
+            let fee = get_transaction_fee(client, tx).await?;
+            self.check_fee(txid, fee, tx.vsize())?;
+        }
+
+        if self.dry_run {
+            return Err(Error::Policy(PolicyError::DryRun(txid)));
+        }
+
+        Ok(())
+    }
+
+    /// Checks the fee limits against the `fee` paid by the transaction `txid` of `vsize` vbytes.
+    pub fn check_fee(&self, txid: Txid, fee: Amount, vsize: usize) -> Result<(), Error> {
+        if let Some(max_fee) = self.max_fee {
+            if fee > max_fee {
+                return Err(Error::Policy(PolicyError::FeeLimitExceeded(
+                    txid, fee, max_fee,
+                )));
+            }
+        }
+        if let Some(max_fee_rate) = self.max_fee_rate {
+            let fee_rate = fee.to_sat() as f64 / vsize as f64;
+            if fee_rate > max_fee_rate {
+                return Err(Error::Policy(PolicyError::FeeRateLimitExceeded(
+                    txid,
+                    fee_rate,
+                    max_fee_rate,
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+async fn get_transaction_fee(client: &AsyncClient, tx: &Transaction) -> Result<Amount, Error> {
+    let mut input_amount = Amount::ZERO;
+    for input in tx.input.iter() {
+        let previous_output = input.previous_output;
+        let previous_tx = client
+            .get_tx(&previous_output.txid)
+            .await
+            .map_err(Error::Esplora)?
+            .ok_or(Error::Other(format!(
+                "Esplora failed to retrieve previous tx with id: {}",
+                previous_output.txid
+            )))?;
+        input_amount += previous_tx.output[previous_output.vout as usize].value;
+    }
+    let output_amount = tx.output.iter().map(|output| output.value).sum::<Amount>();
+
+    input_amount
+        .checked_sub(output_amount)
+        .ok_or(Error::Other(format!(
+            "Outputs of tx {} exceed its inputs",
+            tx.compute_txid()
+        )))
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyAction {
+    PegOutConfirm,
+    KickOff1,
+    StartTime,
+    KickOff2,
+    AssertInitial,
+    AssertCommit1,
+    AssertCommit2,
+    AssertFinal,
+    Take1,
+    Take2,
+    StartTimeTimeout,
+    KickOffTimeout,
+    Challenge,
+    DisproveChain,
+    Disprove,
+}
+
+impl PolicyAction {
+    pub fn for_operator_status(status: &PegOutOperatorStatus) -> Option<Self> {
+        match status {
+            PegOutOperatorStatus::PegOutPegOutConfirmAvailable => Some(PolicyAction::PegOutConfirm),
+            PegOutOperatorStatus::PegOutKickOff1Available => Some(PolicyAction::KickOff1),
+            PegOutOperatorStatus::PegOutStartTimeAvailable => Some(PolicyAction::StartTime),
+            PegOutOperatorStatus::PegOutKickOff2Available => Some(PolicyAction::KickOff2),
+            PegOutOperatorStatus::PegOutAssertInitialAvailable => Some(PolicyAction::AssertInitial),
+            PegOutOperatorStatus::PegOutAssertCommit1Available => Some(PolicyAction::AssertCommit1),
+            PegOutOperatorStatus::PegOutAssertCommit2Available => Some(PolicyAction::AssertCommit2),
+            PegOutOperatorStatus::PegOutAssertFinalAvailable => Some(PolicyAction::AssertFinal),
+            PegOutOperatorStatus::PegOutTake1Available => Some(PolicyAction::Take1),
+            PegOutOperatorStatus::PegOutTake2Available => Some(PolicyAction::Take2),
+            _ => None,
+        }
+    }
+
+    pub fn for_verifier_status(status: &PegOutVerifierStatus) -> Option<Self> {
+        match status {
+            PegOutVerifierStatus::PegOutStartTimeTimeoutAvailable => {
+                Some(PolicyAction::StartTimeTimeout)
+            }
+            PegOutVerifierStatus::PegOutKickOffTimeoutAvailable => {
+                Some(PolicyAction::KickOffTimeout)
+            }
+            PegOutVerifierStatus::PegOutChallengeAvailable => Some(PolicyAction::Challenge),
+            PegOutVerifierStatus::PegOutDisproveChainAvailable => Some(PolicyAction::DisproveChain),
+            PegOutVerifierStatus::PegOutDisproveAvailable => Some(PolicyAction::Disprove),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Eq, PartialEq, Hash, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyDecision {
+    Broadcast,
+    DryRun,
+    Skipped,
+    Failed,
+}
+
+/// One line of the audit log.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct AuditRecord {
+    pub timestamp: u64,
+    pub graph_id: GraphId,
+    pub role: DaemonRole,
+    pub status: String,
+    pub action: PolicyAction,
+    pub decision: PolicyDecision,
+    pub reason: Option<String>,
+    pub txid: Option<Txid>,
+}
+
+pub struct PolicyEngine {
+    policy: Policy,
+    audit_log_path: PathBuf,
+    // Statuses are re-evaluated on every poll, only the first occurrence of a decision is logged.
+    recorded_decisions: Mutex<HashSet<(GraphId, PolicyAction, PolicyDecision, Option<String>)>>,
+}
+
+impl PolicyEngine {
+    pub fn new(policy: Policy, data_root_path: &Path) -> Self {
+        let audit_log_path = policy
+            .audit_log
+            .clone()
+            .unwrap_or_else(|| get_policy_audit_log_file_path(data_root_path));
+
+        PolicyEngine {
+            policy,
+            audit_log_path,
+            recorded_decisions: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    pub fn audit_log_path(&self) -> &Path {
+        &self.audit_log_path
+    }
+
+    // TODO: handle internal errors
+    pub async fn process_peg_outs_as_operator(&self, client: &mut BitVMClient) {
+        let mut actions = Vec::new();
+        for peg_out_graph in client.data().peg_out_graphs.iter() {
+            let status = peg_out_graph.operator_status(&client.esplora).await;
+            if let Some(action) = PolicyAction::for_operator_status(&status) {
+                actions.push((peg_out_graph.id().clone(), format!("{status:?}"), action));
+            }
+        }
+
+        for (peg_out_graph_id, status, action) in actions {
+            self.act(
+                client,
+                &peg_out_graph_id,
+                DaemonRole::Operator,
+                status,
+                action,
+                None,
+            )
+            .await;
+        }
+    }
+
+    // TODO: handle internal errors
+    pub async fn process_peg_outs_as_verifier(
+        &self,
+        client: &mut BitVMClient,
+        output_script_pubkey: Option<&ScriptBuf>,
+    ) {
+        let mut actions = Vec::new();
+        if let Some(context) = client.verifier_context() {
+            for peg_out_graph in client.data().peg_out_graphs.iter() {
+                let status = peg_out_graph
+                    .verifier_status(&client.esplora, context)
+                    .await;
+                if let Some(action) = PolicyAction::for_verifier_status(&status) {
+                    actions.push((peg_out_graph.id().clone(), format!("{status:?}"), action));
+                }
+            }
+        }
+
+        for (peg_out_graph_id, status, action) in actions {
+            self.act(
+                client,
+                &peg_out_graph_id,
+                DaemonRole::Verifier,
+                status,
+                action,
+                output_script_pubkey,
+            )
+            .await;
+        }
+    }
+
+    async fn act(
+        &self,
+        client: &mut BitVMClient,
+        peg_out_graph_id: &GraphId,
+        role: DaemonRole,
+        status: String,
+        action: PolicyAction,
+        output_script_pubkey: Option<&ScriptBuf>,
+    ) {
+        let record = |decision: PolicyDecision, reason: Option<String>, txid: Option<Txid>| {
+            self.record(AuditRecord {
+                timestamp: now(),
+                graph_id: peg_out_graph_id.clone(),
+                role,
+                status: status.clone(),
+                action,
+                decision,
+                reason,
+                txid,
+            })
+        };
+
+        if !self.policy.watches(peg_out_graph_id) {
+            return record(
+                PolicyDecision::Skipped,
+                Some("Graph is not watched".to_string()),
+                None,
+            );
+        }
+        if let Some(reason) = self.policy.disabled_reason(action) {
+            return record(PolicyDecision::Skipped, Some(reason.to_string()), None);
+        }
+
+        if action == PolicyAction::Challenge {
+            if let Some(reason) = self
+                .policy
+                .challenge_denied_reason(&get_crowdfunding_amount(client, peg_out_graph_id))
+            {
+                return record(PolicyDecision::Skipped, Some(reason), None);
+            }
+        }
+
+        let mut reason = None;
+        if action == PolicyAction::Disprove
+            && self.policy.verifier.disprove_requires_local_validation
+        {
+            match client.validate_peg_out_assertions(peg_out_graph_id).await {
+                Ok(Some(script_index)) => {
+                    reason = Some(format!(
+                    "Local validation found invalid assertion for disprove script {script_index}"
+                ))
+                }
+                Ok(None) => {
+                    return record(
+                        PolicyDecision::Skipped,
+                        Some("Local validation found all assertions valid".to_string()),
+                        None,
+                    )
+                }
+                Err(e) => {
+                    return record(
+                        PolicyDecision::Failed,
+                        Some(format!("Local validation failed: {e}")),
+                        None,
+                    )
+                }
+            }
+        }
+
+        // Building a transaction fills in inputs and outputs of the graph, which must not persist in dry-run mode.
+        let peg_out_graph_snapshot = match self.policy.dry_run {
+            true => client.get_peg_out_graph_mut(peg_out_graph_id).cloned(),
+            false => None,
+        };
+
+        client.set_broadcast_limits(Some(self.policy.broadcast_limits()));
+        let result = self
+            .execute(client, peg_out_graph_id, action, output_script_pubkey)
+            .await;
+        client.set_broadcast_limits(None);
+
+        if let Some(peg_out_graph) = peg_out_graph_snapshot {
+            *client.get_peg_out_graph_mut(peg_out_graph_id).unwrap() = peg_out_graph;
+        }
+
+        match result {
+            Ok(txid) => record(PolicyDecision::Broadcast, reason, Some(txid)),
+            Err(Error::Policy(PolicyError::DryRun(txid))) => {
+                record(PolicyDecision::DryRun, reason, Some(txid))
+            }
+            Err(Error::Policy(e)) => record(PolicyDecision::Skipped, Some(format!("{e:?}")), None),
+            Err(e) => record(PolicyDecision::Failed, Some(e.to_string()), None),
+        }
+    }
+
+    async fn execute(
+        &self,
+        client: &mut BitVMClient,
+        peg_out_graph_id: &GraphId,
+        action: PolicyAction,
+        output_script_pubkey: Option<&ScriptBuf>,
+    ) -> Result<Txid, Error> {
+        let reward_script = || {
+            output_script_pubkey
+                .cloned()
+                .ok_or(Error::Other("No reward address configured".to_string()))
+        };
+
+        match action {
+            PolicyAction::PegOutConfirm => client.broadcast_peg_out_confirm(peg_out_graph_id).await,
+            PolicyAction::KickOff1 => client.broadcast_kick_off_1(peg_out_graph_id).await,
+            PolicyAction::StartTime => client.broadcast_start_time(peg_out_graph_id).await,
+            PolicyAction::KickOff2 => client.broadcast_kick_off_2(peg_out_graph_id).await,
+            PolicyAction::AssertInitial => client.broadcast_assert_initial(peg_out_graph_id).await,
+            PolicyAction::AssertCommit1 => {
+                client
+                    .broadcast_assert_commit_1(peg_out_graph_id, &get_dummy_proof())
+                    .await
+            }
+            PolicyAction::AssertCommit2 => {
+                client
+                    .broadcast_assert_commit_2(peg_out_graph_id, &get_dummy_proof())
+                    .await
+            }
+            PolicyAction::AssertFinal => client.broadcast_assert_final(peg_out_graph_id).await,
+            PolicyAction::Take1 => client.broadcast_take_1(peg_out_graph_id).await,
+            PolicyAction::Take2 => client.broadcast_take_2(peg_out_graph_id).await,
+            PolicyAction::StartTimeTimeout => {
+                client
+                    .broadcast_start_time_timeout(peg_out_graph_id, reward_script()?)
+                    .await
+            }
+            PolicyAction::KickOffTimeout => {
+                client
+                    .broadcast_kick_off_timeout(peg_out_graph_id, reward_script()?)
+                    .await
+            }
+            PolicyAction::Challenge => self.challenge(client, peg_out_graph_id).await,
+            PolicyAction::DisproveChain => {
+                client
+                    .broadcast_disprove_chain(peg_out_graph_id, reward_script()?)
+                    .await
+            }
+            PolicyAction::Disprove => {
+                client
+                    .broadcast_disprove(peg_out_graph_id, reward_script()?)
+                    .await
+            }
+        }
+    }
+
+    // Funds the whole crowdfunding amount from the confirmed UTXOs of the client's funding key.
+    async fn challenge(
+        &self,
+        client: &mut BitVMClient,
+        peg_out_graph_id: &GraphId,
+    ) -> Result<Txid, Error> {
+        let crowdfunding_amount = get_crowdfunding_amount(client, peg_out_graph_id)?;
+        let public_key = client.get_crowdfunding_public_key()?;
+        let address = generate_pay_to_pubkey_script_address(client.source_network, &public_key);
+        let mut utxos = client
+            .esplora
+            .get_address_utxo(address.clone())
+            .await
+            .map_err(Error::Esplora)?
+            .into_iter()
+            .filter(|utxo| utxo.status.confirmed)
+            .collect::<Vec<_>>();
+        utxos.sort_by(|a, b| b.value.cmp(&a.value));
+
+        let mut outpoints = Vec::new();
+        let mut total_amount = Amount::ZERO;
+        for utxo in utxos {
+            if total_amount >= crowdfunding_amount {
+                break;
+            }
+            total_amount += utxo.value;
+            outpoints.push((OutPoint::new(utxo.txid, utxo.vout), utxo.value));
+        }
+        if total_amount < crowdfunding_amount {
+            return Err(Error::Other(format!(
+                "Insufficient funds at {address} to crowdfund the challenge: {total_amount} available, {crowdfunding_amount} required"
+            )));
+        }
+
+        let script = generate_pay_to_pubkey_script(&public_key);
+        let crowdfunding_inputs = outpoints
+            .into_iter()
+            .map(|(outpoint, amount)| InputWithScript {
+                outpoint,
+                amount,
+                script: &script,
+            })
+            .collect::<Vec<_>>();
+
+        client
+            .broadcast_challenge(
+                peg_out_graph_id,
+                &crowdfunding_inputs,
+                address.script_pubkey(),
+            )
+            .await
+    }
+
+    fn record(&self, record: AuditRecord) {
+        let key = (
+            record.graph_id.clone(),
+            record.action,
+            record.decision,
+            record.reason.clone(),
+        );
+        if !self.recorded_decisions.lock().unwrap().insert(key) {
+            return;
+        }
+
+        let message = format!(
+            "Policy decision for {:?} on graph {}: {:?}{}",
+            record.action,
+            record.graph_id,
+            record.decision,
+            record
+                .reason
+                .as_ref()
+                .map(|reason| format!(" ({reason})"))
+                .unwrap_or_default()
+        );
+        match record.decision {
+            PolicyDecision::Broadcast | PolicyDecision::DryRun => eprintln!("{}", message.green()),
+            PolicyDecision::Skipped => eprintln!("{}", message.yellow()),
+            PolicyDecision::Failed => eprintln!("{}", message.red()),
+        }
+
+        append_line_to_file(&self.audit_log_path, &serialize(&record));
+    }
+}
+
+fn get_crowdfunding_amount(
+    client: &BitVMClient,
+    peg_out_graph_id: &GraphId,
+) -> Result<Amount, Error> {
+    client
+        .data()
+        .peg_out_graphs
+        .iter()
+        .find(|peg_out_graph| peg_out_graph.id() == peg_out_graph_id)
+        .map(|peg_out_graph| Amount::from_sat(peg_out_graph.min_crowdfunding_amount()))
+        .ok_or(Error::Other(format!(
+            "Peg-out graph {peg_out_graph_id} not found"
+        )))
+}
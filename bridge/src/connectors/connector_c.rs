@@ -99,7 +99,7 @@ impl ConnectorC {
         commit_2_witness: Vec<RawWitness>,
         vk: &ZkProofVerifyingKey,
     ) -> Result<(usize, RawWitness), Error> {
-        println!("Generating disprove witness ...");
+        eprintln!("Generating disprove witness ...");
         let mut sorted_pks: Vec<(u32, WinternitzPublicKey)> = vec![];
         self.commitment_public_keys
             .clone()
@@ -296,7 +296,7 @@ fn generate_taproot_spend_info(
     operator_taproot_public_key: XOnlyPublicKey,
     lock_scripts_bytes: &Vec<Vec<u8>>,
 ) -> TaprootSpendInfo {
-    println!("Generating new taproot spend info for connector C...");
+    eprintln!("Generating new taproot spend info for connector C...");
     let script_weights = lock_scripts_bytes
         .iter()
         .map(|b| (1, ScriptBuf::from_bytes(b.clone())));
@@ -309,7 +309,7 @@ fn generate_taproot_spend_info(
 fn generate_assert_leaves(
     commits_public_keys: &BTreeMap<CommitmentMessageId, WinternitzPublicKey>,
 ) -> Vec<Vec<u8>> {
-    println!("Generating new lock scripts...");
+    eprintln!("Generating new lock scripts...");
     // hash map to btree map
     let mut sorted_pks: Vec<(u32, WinternitzPublicKey)> = vec![];
     commits_public_keys.clone().into_iter().for_each(|(k, v)| {
@@ -1,11 +1,16 @@
-use bitcoin::{key::Keypair, Network, PublicKey, XOnlyPublicKey};
+use std::sync::Arc;
 
-use super::base::{generate_keys_from_secret, generate_n_of_n_public_key, BaseContext};
+use bitcoin::{Network, PublicKey, XOnlyPublicKey};
+
+use super::{
+    super::signers::{base::Signer, local::LocalSigner},
+    base::{generate_n_of_n_public_key, BaseContext},
+};
 
 pub struct DepositorContext {
     pub network: Network,
 
-    pub depositor_keypair: Keypair,
+    pub depositor_signer: Arc<dyn Signer>,
     pub depositor_public_key: PublicKey,
     pub depositor_taproot_public_key: XOnlyPublicKey,
 
@@ -31,14 +36,26 @@ impl BaseContext for DepositorContext {
 
 impl DepositorContext {
     pub fn new(network: Network, depositor_secret: &str, n_of_n_public_keys: &[PublicKey]) -> Self {
-        let (keypair, public_key) = generate_keys_from_secret(network, depositor_secret);
+        Self::new_with_signer(
+            network,
+            Arc::new(LocalSigner::from_secret(network, depositor_secret)),
+            n_of_n_public_keys,
+        )
+    }
+
+    pub fn new_with_signer(
+        network: Network,
+        depositor_signer: Arc<dyn Signer>,
+        n_of_n_public_keys: &[PublicKey],
+    ) -> Self {
+        let public_key = depositor_signer.public_key();
         let (n_of_n_public_key, n_of_n_taproot_public_key) =
             generate_n_of_n_public_key(n_of_n_public_keys);
 
         DepositorContext {
             network,
 
-            depositor_keypair: keypair,
+            depositor_signer,
             depositor_public_key: public_key,
             depositor_taproot_public_key: XOnlyPublicKey::from(public_key),
 
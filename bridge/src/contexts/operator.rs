@@ -1,11 +1,16 @@
-use bitcoin::{key::Keypair, Network, PublicKey, XOnlyPublicKey};
+use std::sync::Arc;
 
-use super::base::{generate_keys_from_secret, generate_n_of_n_public_key, BaseContext};
+use bitcoin::{Network, PublicKey, XOnlyPublicKey};
+
+use super::{
+    super::signers::{base::Signer, local::LocalSigner},
+    base::{generate_n_of_n_public_key, BaseContext},
+};
 
 pub struct OperatorContext {
     pub network: Network,
 
-    pub operator_keypair: Keypair,
+    pub operator_signer: Arc<dyn Signer>,
     pub operator_public_key: PublicKey,
     pub operator_taproot_public_key: XOnlyPublicKey,
 
@@ -31,14 +36,26 @@ impl BaseContext for OperatorContext {
 
 impl OperatorContext {
     pub fn new(network: Network, operator_secret: &str, n_of_n_public_keys: &[PublicKey]) -> Self {
-        let (keypair, public_key) = generate_keys_from_secret(network, operator_secret);
+        Self::new_with_signer(
+            network,
+            Arc::new(LocalSigner::from_secret(network, operator_secret)),
+            n_of_n_public_keys,
+        )
+    }
+
+    pub fn new_with_signer(
+        network: Network,
+        operator_signer: Arc<dyn Signer>,
+        n_of_n_public_keys: &[PublicKey],
+    ) -> Self {
+        let public_key = operator_signer.public_key();
         let (n_of_n_public_key, n_of_n_taproot_public_key) =
             generate_n_of_n_public_key(n_of_n_public_keys);
 
         OperatorContext {
             network,
 
-            operator_keypair: keypair,
+            operator_signer,
             operator_public_key: public_key,
             operator_taproot_public_key: XOnlyPublicKey::from(public_key),
 
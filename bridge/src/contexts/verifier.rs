@@ -1,11 +1,16 @@
-use bitcoin::{key::Keypair, Network, PublicKey, XOnlyPublicKey};
+use std::sync::Arc;
 
-use super::base::{generate_keys_from_secret, generate_n_of_n_public_key, BaseContext};
+use bitcoin::{Network, PublicKey, XOnlyPublicKey};
+
+use super::{
+    super::signers::{base::Signer, local::LocalSigner},
+    base::{generate_n_of_n_public_key, BaseContext},
+};
 
 pub struct VerifierContext {
     pub network: Network,
 
-    pub verifier_keypair: Keypair,
+    pub verifier_signer: Arc<dyn Signer>,
     pub verifier_public_key: PublicKey,
 
     pub n_of_n_public_keys: Vec<PublicKey>,
@@ -30,14 +35,26 @@ impl BaseContext for VerifierContext {
 
 impl VerifierContext {
     pub fn new(network: Network, verifier_secret: &str, n_of_n_public_keys: &[PublicKey]) -> Self {
-        let (keypair, public_key) = generate_keys_from_secret(network, verifier_secret);
+        Self::new_with_signer(
+            network,
+            Arc::new(LocalSigner::from_secret(network, verifier_secret)),
+            n_of_n_public_keys,
+        )
+    }
+
+    pub fn new_with_signer(
+        network: Network,
+        verifier_signer: Arc<dyn Signer>,
+        n_of_n_public_keys: &[PublicKey],
+    ) -> Self {
+        let public_key = verifier_signer.public_key();
         let (n_of_n_public_key, n_of_n_taproot_public_key) =
             generate_n_of_n_public_key(n_of_n_public_keys);
 
         VerifierContext {
             network,
 
-            verifier_keypair: keypair,
+            verifier_signer,
             verifier_public_key: public_key,
 
             n_of_n_public_keys: n_of_n_public_keys.to_owned(),
@@ -1,11 +1,16 @@
-use bitcoin::{key::Keypair, Network, PublicKey, XOnlyPublicKey};
+use std::sync::Arc;
 
-use super::base::{generate_keys_from_secret, generate_n_of_n_public_key, BaseContext};
+use bitcoin::{Network, PublicKey, XOnlyPublicKey};
+
+use super::{
+    super::signers::{base::Signer, local::LocalSigner},
+    base::{generate_n_of_n_public_key, BaseContext},
+};
 
 pub struct WithdrawerContext {
     pub network: Network,
 
-    pub withdrawer_keypair: Keypair,
+    pub withdrawer_signer: Arc<dyn Signer>,
     pub withdrawer_public_key: PublicKey,
     pub withdrawer_taproot_public_key: XOnlyPublicKey,
 
@@ -35,14 +40,26 @@ impl WithdrawerContext {
         withdrawer_secret: &str,
         n_of_n_public_keys: &[PublicKey],
     ) -> Self {
-        let (keypair, public_key) = generate_keys_from_secret(network, withdrawer_secret);
+        Self::new_with_signer(
+            network,
+            Arc::new(LocalSigner::from_secret(network, withdrawer_secret)),
+            n_of_n_public_keys,
+        )
+    }
+
+    pub fn new_with_signer(
+        network: Network,
+        withdrawer_signer: Arc<dyn Signer>,
+        n_of_n_public_keys: &[PublicKey],
+    ) -> Self {
+        let public_key = withdrawer_signer.public_key();
         let (n_of_n_public_key, n_of_n_taproot_public_key) =
             generate_n_of_n_public_key(n_of_n_public_keys);
 
         WithdrawerContext {
             network,
 
-            withdrawer_keypair: keypair,
+            withdrawer_signer,
             withdrawer_public_key: public_key,
             withdrawer_taproot_public_key: XOnlyPublicKey::from(public_key),
 
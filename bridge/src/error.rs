@@ -1,13 +1,14 @@
 use super::commitments::CommitmentMessageId;
 use super::graphs::base::GraphId;
 use super::transactions::{base::BaseTransaction, pre_signed::PreSignedTransaction};
-use bitcoin::{PublicKey, Txid};
+use bitcoin::{psbt, Amount, PublicKey, Txid};
 use std::fmt::{self, Display};
 
 #[derive(Debug)]
 pub enum ClientError {
     NoUserContextDefined,
     OperatorContextNotDefined,
+    OperatorSignerNotLocal,
     ZkProofVerifyingKeyNotDefined,
     PegInGraphNotFound(GraphId),
     PegOutGraphNotFound(GraphId),
@@ -36,6 +37,7 @@ pub enum GraphError {
     PrecedingTxNotConfirmed(Vec<NamedTx>),
     PrecedingTxTimelockNotMet(NamedTx),
     WitnessNotGenerated(CommitmentMessageId),
+    CommitmentPublicKeyNotFound(CommitmentMessageId),
 }
 
 #[derive(Debug)]
@@ -60,6 +62,27 @@ pub enum ChunkerError {
     ValidProof,
 }
 
+#[derive(Debug)]
+pub enum PolicyError {
+    DryRun(Txid),
+    FeeLimitExceeded(Txid, Amount, Amount), // txid: the transaction id, amounts: the fee and the configured limit
+    FeeRateLimitExceeded(Txid, f64, f64), // txid: the transaction id, f64s: the fee rate and the configured limit in sat/vB
+}
+
+#[derive(Debug)]
+pub enum PsbtError {
+    Invalid(psbt::Error),
+    TxNotFound(Txid),
+    TxMismatch(&'static str, Txid), // str: tx name, txid: the unsigned transaction id of the PSBT
+    MissingPrevScript(&'static str, usize), // str: tx name, usize: tx input index
+    LeafNotFound(&'static str, usize), // str: tx name, usize: tx input index
+    InvalidMusig2Field(&'static str, usize), // str: tx name, usize: tx input index
+    MissingNonceSignature(&'static str, PublicKey, usize), // str: tx name, pubkey: the participant public key, usize: tx input index
+    InvalidSignature(&'static str, usize),                 // str: tx name, usize: tx input index
+    CannotFinalize(&'static str, usize),                   // str: tx name, usize: tx input index
+    InvalidWitness(&'static str, usize),                   // str: tx name, usize: tx input index
+}
+
 #[derive(Debug)]
 pub enum Error {
     Esplora(esplora_client::Error),
@@ -69,6 +92,11 @@ pub enum Error {
     L2(L2Error),
     Chunker(ChunkerError),
     Validation(ValidationError),
+    Policy(PolicyError),
+    Psbt(PsbtError),
+    #[cfg(feature = "prover")]
+    Prover(prover::backend::BackendError),
+    Signer(String),
     Other(String),
 }
 
@@ -6,11 +6,11 @@ use bitcoin::{
 };
 use esplora_client::{AsyncClient, TxStatus};
 use futures::future::join_all;
-use musig2::SecNonce;
 
 use crate::{
     contexts::verifier::VerifierContext,
     error::{Error, TransactionError},
+    signers::base::Musig2SessionId,
     transactions::base::{
         MIN_RELAY_FEE_ASSERT_COMMIT1, MIN_RELAY_FEE_ASSERT_COMMIT2, MIN_RELAY_FEE_ASSERT_FINAL,
         MIN_RELAY_FEE_ASSERT_INITIAL, MIN_RELAY_FEE_DISPROVE, MIN_RELAY_FEE_KICK_OFF_1,
@@ -60,12 +60,12 @@ pub trait BaseGraph {
     fn push_verifier_nonces(
         &mut self,
         verifier_context: &VerifierContext,
-    ) -> HashMap<Txid, HashMap<usize, SecNonce>>;
+    ) -> Result<HashMap<Txid, HashMap<usize, Musig2SessionId>>, Error>;
     fn verifier_sign(
         &mut self,
         verifier_context: &VerifierContext,
-        secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
-    );
+        session_ids: &HashMap<Txid, HashMap<usize, Musig2SessionId>>,
+    ) -> Result<(), Error>;
 }
 
 pub const fn max(a: u64, b: u64) -> u64 {
@@ -1,10 +1,10 @@
 use bitcoin::{
     hex::{Case::Upper, DisplayHex},
-    Network, OutPoint, PublicKey, Transaction, Txid, XOnlyPublicKey,
+    EcdsaSighashType, Network, OutPoint, Psbt, PublicKey, TapSighashType, Transaction, Txid,
+    XOnlyPublicKey,
 };
 use esplora_client::{AsyncClient, TxStatus};
 use itertools::Itertools;
-use musig2::SecNonce;
 use num_traits::ToPrimitive;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -14,13 +14,17 @@ use crate::{
     client::sdk::{
         query::GraphCliQuery, query_contexts::depositor_signatures::DepositorSignatures,
     },
-    error::{Error, GraphError, NamedTx},
-    transactions::pre_signed_musig2::PreSignedMusig2Transaction,
+    error::{Error, GraphError, NamedTx, PsbtError},
+    signers::base::Musig2SessionId,
+    transactions::{
+        pre_signed_musig2::PreSignedMusig2Transaction,
+        psbt::{export_musig2_psbt, export_psbt, import_musig2_psbt, import_psbt, PsbtInput},
+    },
 };
 
 use super::{
     super::{
-        connectors::{connector_0::Connector0, connector_z::ConnectorZ},
+        connectors::{base::TaprootConnector, connector_0::Connector0, connector_z::ConnectorZ},
         contexts::{depositor::DepositorContext, verifier::VerifierContext},
         transactions::{
             base::{validate_transaction, verify_public_nonces_for_tx, BaseTransaction, Input},
@@ -37,7 +41,7 @@ use super::{
     peg_out::{PegOutGraph, PegOutId},
 };
 
-#[derive(derive_more::Display)]
+#[derive(Debug, derive_more::Display)]
 pub enum PegInDepositorStatus {
     #[display("Peg-in deposit transaction not confirmed yet. Wait...")]
     PegInDepositWait, // peg-in deposit not yet confirmed
@@ -122,30 +126,34 @@ impl BaseGraph for PegInGraph {
     fn verifier_sign(
         &mut self,
         verifier_context: &VerifierContext,
-        secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
-    ) {
+        session_ids: &HashMap<Txid, HashMap<usize, Musig2SessionId>>,
+    ) -> Result<(), Error> {
         self.peg_in_confirm_transaction.pre_sign(
             verifier_context,
             &self.connector_z,
-            &secret_nonces[&self.peg_in_confirm_transaction.tx().compute_txid()],
-        );
+            &session_ids[&self.peg_in_confirm_transaction.tx().compute_txid()],
+        )
     }
 
     fn push_verifier_nonces(
         &mut self,
         verifier_context: &VerifierContext,
-    ) -> HashMap<Txid, HashMap<usize, SecNonce>> {
-        [(
+    ) -> Result<HashMap<Txid, HashMap<usize, Musig2SessionId>>, Error> {
+        Ok([(
             self.peg_in_confirm_transaction.tx().compute_txid(),
             self.peg_in_confirm_transaction
-                .push_nonces(verifier_context),
+                .push_nonces(verifier_context)?,
         )]
-        .into()
+        .into())
     }
 }
 
 impl PegInGraph {
-    pub fn new(context: &DepositorContext, deposit_input: Input, evm_address: &str) -> Self {
+    pub fn new(
+        context: &DepositorContext,
+        deposit_input: Input,
+        evm_address: &str,
+    ) -> Result<Self, Error> {
         let connectors = create_new_connectors(
             context.network,
             &context.n_of_n_taproot_public_key,
@@ -154,14 +162,14 @@ impl PegInGraph {
         );
 
         let peg_in_deposit_transaction =
-            PegInDepositTransaction::new(context, &connectors.connector_z, deposit_input);
+            PegInDepositTransaction::new(context, &connectors.connector_z, deposit_input)?;
 
         let peg_in_refund_vout_0: usize = 0;
         let peg_in_refund_transaction = PegInRefundTransaction::new(
             context,
             &connectors.connector_z,
             generate_input(peg_in_deposit_transaction.tx(), peg_in_refund_vout_0),
-        );
+        )?;
 
         let peg_in_confirm_vout_0: usize = 0;
         let peg_in_confirm_transaction = PegInConfirmTransaction::new(
@@ -169,9 +177,9 @@ impl PegInGraph {
             &connectors.connector_0,
             &connectors.connector_z,
             generate_input(peg_in_deposit_transaction.tx(), peg_in_confirm_vout_0),
-        );
+        )?;
 
-        PegInGraph {
+        Ok(PegInGraph {
             version: GRAPH_VERSION.to_string(),
             network: context.network,
             id: generate_id(&peg_in_deposit_transaction),
@@ -187,7 +195,7 @@ impl PegInGraph {
             connector_0: connectors.connector_0,
             connector_z: connectors.connector_z,
             peg_out_graphs: Vec::new(),
-        }
+        })
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -297,6 +305,10 @@ impl PegInGraph {
         &self.peg_in_confirm_transaction
     }
 
+    pub fn n_of_n_public_keys(&self) -> &[PublicKey] {
+        &self.n_of_n_public_keys
+    }
+
     pub async fn verifier_status(
         &self,
         client: &AsyncClient,
@@ -549,6 +561,56 @@ impl PegInGraph {
         self.peg_out_graphs.sort();
         self.peg_out_graphs.dedup();
     }
+
+    pub fn export_psbt(&self, txid: &Txid) -> Result<Psbt, Error> {
+        if *txid == self.peg_in_deposit_transaction.tx().compute_txid() {
+            export_psbt(
+                &self.peg_in_deposit_transaction,
+                vec![PsbtInput::p2wsh(EcdsaSighashType::All)],
+            )
+        } else if *txid == self.peg_in_refund_transaction.tx().compute_txid() {
+            export_psbt(
+                &self.peg_in_refund_transaction,
+                vec![PsbtInput::taproot_script(
+                    self.connector_z.generate_taproot_spend_info(),
+                    TapSighashType::All,
+                )],
+            )
+        } else if *txid == self.peg_in_confirm_transaction.tx().compute_txid() {
+            export_musig2_psbt(
+                &self.peg_in_confirm_transaction,
+                vec![PsbtInput::taproot_script(
+                    self.connector_z.generate_taproot_spend_info(),
+                    TapSighashType::All,
+                )],
+                &self.n_of_n_public_keys,
+            )
+        } else {
+            Err(Error::Psbt(PsbtError::TxNotFound(*txid)))
+        }
+    }
+
+    // Returns the txid of the updated transaction.
+    pub fn import_psbt(&mut self, psbt: &Psbt) -> Result<Txid, Error> {
+        let txid = psbt.unsigned_tx.compute_txid();
+        let exported = self.export_psbt(&txid)?;
+        if txid == self.peg_in_deposit_transaction.tx().compute_txid() {
+            import_psbt(&mut self.peg_in_deposit_transaction, psbt, &exported)?;
+        } else if txid == self.peg_in_refund_transaction.tx().compute_txid() {
+            import_psbt(&mut self.peg_in_refund_transaction, psbt, &exported)?;
+        } else if txid == self.peg_in_confirm_transaction.tx().compute_txid() {
+            import_musig2_psbt(
+                &mut self.peg_in_confirm_transaction,
+                psbt,
+                &exported,
+                &self.n_of_n_public_keys,
+            )?;
+        } else {
+            return Err(Error::Psbt(PsbtError::TxNotFound(txid)));
+        }
+
+        Ok(txid)
+    }
 }
 
 impl GraphCliQuery for PegInGraph {
@@ -1,11 +1,10 @@
 use bitcoin::{
     hashes::Hash,
     hex::{Case::Upper, DisplayHex},
-    key::Keypair,
-    Amount, Network, OutPoint, PublicKey, ScriptBuf, Transaction, Txid, XOnlyPublicKey,
+    Amount, EcdsaSighashType, Network, OutPoint, Psbt, PublicKey, ScriptBuf, TapSighashType,
+    Transaction, Txid, XOnlyPublicKey,
 };
 use esplora_client::{AsyncClient, TxStatus};
-use musig2::SecNonce;
 use num_traits::ToPrimitive;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -18,7 +17,8 @@ use crate::{
         connector_c::get_commit_from_assert_commit_tx, connector_d::ConnectorD,
         connector_e::ConnectorE, connector_f_1::ConnectorF1, connector_f_2::ConnectorF2,
     },
-    error::{Error, GraphError, L2Error, NamedTx},
+    error::{ChunkerError, Error, GraphError, L2Error, NamedTx, PsbtError},
+    signers::base::{Musig2SessionId, Signer},
     superblock::{
         find_superblock, get_start_time_block_number, get_superblock_hash_message,
         get_superblock_message,
@@ -30,32 +30,30 @@ use crate::{
             assert_final::AssertFinalTransaction,
             assert_initial::AssertInitialTransaction,
             utils::{
-                groth16_commitment_secrets_to_public_keys, merge_to_connector_c_commits_public_key,
-                sign_assert_tx_with_groth16_proof, AssertCommit1ConnectorsE,
-                AssertCommit2ConnectorsE, AssertCommitConnectorsF,
+                groth16_commitment_public_keys, merge_to_connector_c_commits_public_key,
+                AssertCommit1ConnectorsE, AssertCommit2ConnectorsE, AssertCommitConnectorsF,
             },
         },
         base::validate_witness,
         peg_in_confirm::PEG_IN_CONFIRM_TX_NAME,
         pre_signed_musig2::PreSignedMusig2Transaction,
+        psbt::{export_musig2_psbt, export_psbt, import_musig2_psbt, import_psbt, PsbtInput},
     },
 };
 
 use bitvm::{
-    chunk::api::type_conversion_utils::RawProof,
-    signatures::signing_winternitz::{
-        WinternitzPublicKey, WinternitzSecret, WinternitzSigningInputs,
-    },
+    chunk::api::type_conversion_utils::{RawProof, RawWitness},
+    signatures::signing_winternitz::{WinternitzPublicKey, WinternitzSecret},
 };
 
 use super::{
     super::{
         client::chain::chain::PegOutEvent,
         connectors::{
-            connector_0::Connector0, connector_1::Connector1, connector_2::Connector2,
-            connector_3::Connector3, connector_4::Connector4, connector_5::Connector5,
-            connector_6::Connector6, connector_a::ConnectorA, connector_b::ConnectorB,
-            connector_c::ConnectorC,
+            base::TaprootConnector, connector_0::Connector0, connector_1::Connector1,
+            connector_2::Connector2, connector_3::Connector3, connector_4::Connector4,
+            connector_5::Connector5, connector_6::Connector6, connector_a::ConnectorA,
+            connector_b::ConnectorB, connector_c::ConnectorC,
         },
         contexts::{operator::OperatorContext, verifier::VerifierContext},
         transactions::{
@@ -97,7 +95,7 @@ pub enum PegOutWithdrawerStatus {
     PegOutComplete, // peg-out complete
 }
 
-#[derive(derive_more::Display)]
+#[derive(Debug, derive_more::Display)]
 pub enum PegOutVerifierStatus {
     #[display("Nonces required. Push nonces for peg-out transactions?")]
     PegOutPendingNonces, // should push nonces
@@ -127,7 +125,7 @@ pub enum PegOutVerifierStatus {
     PegOutFailed, // timeouts or disproves executed
 }
 
-#[derive(derive_more::Display)]
+#[derive(Debug, derive_more::Display)]
 pub enum PegOutOperatorStatus {
     // TODO: add assert initial and assert final
     #[display("No action available. Wait...")]
@@ -247,65 +245,67 @@ impl BaseGraph for PegOutGraph {
     fn verifier_sign(
         &mut self,
         verifier_context: &VerifierContext,
-        secret_nonces: &HashMap<Txid, HashMap<usize, SecNonce>>,
-    ) {
+        session_ids: &HashMap<Txid, HashMap<usize, Musig2SessionId>>,
+    ) -> Result<(), Error> {
         self.assert_initial_transaction.pre_sign(
             verifier_context,
             &self.connector_b,
-            &secret_nonces[&self.assert_initial_transaction.tx().compute_txid()],
-        );
+            &session_ids[&self.assert_initial_transaction.tx().compute_txid()],
+        )?;
         self.assert_final_transaction.pre_sign(
             verifier_context,
             &self.connector_d,
-            &secret_nonces[&self.assert_final_transaction.tx().compute_txid()],
-        );
+            &session_ids[&self.assert_final_transaction.tx().compute_txid()],
+        )?;
         self.disprove_chain_transaction.pre_sign(
             verifier_context,
             &self.connector_b,
-            &secret_nonces[&self.disprove_chain_transaction.tx().compute_txid()],
-        );
+            &session_ids[&self.disprove_chain_transaction.tx().compute_txid()],
+        )?;
         self.disprove_transaction.pre_sign(
             verifier_context,
             &self.connector_5,
-            &secret_nonces[&self.disprove_transaction.tx().compute_txid()],
-        );
+            &session_ids[&self.disprove_transaction.tx().compute_txid()],
+        )?;
         self.kick_off_timeout_transaction.pre_sign(
             verifier_context,
             &self.connector_1,
-            &secret_nonces[&self.kick_off_timeout_transaction.tx().compute_txid()],
-        );
+            &session_ids[&self.kick_off_timeout_transaction.tx().compute_txid()],
+        )?;
         self.start_time_timeout_transaction.pre_sign(
             verifier_context,
             &self.connector_1,
             &self.connector_2,
-            &secret_nonces[&self.start_time_timeout_transaction.tx().compute_txid()],
-        );
+            &session_ids[&self.start_time_timeout_transaction.tx().compute_txid()],
+        )?;
         self.take_1_transaction.pre_sign(
             verifier_context,
             &self.connector_0,
             &self.connector_b,
-            &secret_nonces[&self.take_1_transaction.tx().compute_txid()],
-        );
+            &session_ids[&self.take_1_transaction.tx().compute_txid()],
+        )?;
         self.take_2_transaction.pre_sign(
             verifier_context,
             &self.connector_0,
             &self.connector_5,
-            &secret_nonces[&self.take_2_transaction.tx().compute_txid()],
-        );
+            &session_ids[&self.take_2_transaction.tx().compute_txid()],
+        )?;
 
         self.n_of_n_presigned = true; // TODO: set to true after collecting all n of n signatures
+
+        Ok(())
     }
 
     fn push_verifier_nonces(
         &mut self,
         verifier_context: &VerifierContext,
-    ) -> HashMap<Txid, HashMap<usize, SecNonce>> {
+    ) -> Result<HashMap<Txid, HashMap<usize, Musig2SessionId>>, Error> {
         self.all_presigned_txs_mut()
             .map(|tx_wrapper| {
-                (
+                Ok((
                     tx_wrapper.tx().compute_txid(),
-                    tx_wrapper.push_nonces(verifier_context),
-                )
+                    tx_wrapper.push_nonces(verifier_context)?,
+                ))
             })
             .collect()
     }
@@ -317,55 +317,75 @@ impl PegOutGraph {
         peg_in_graph: &PegInGraph,
         peg_out_confirm_input: Input,
         commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
-    ) -> Self {
+    ) -> Result<Self, Error> {
+        Self::new_with_commitment_public_keys(
+            context,
+            peg_in_graph,
+            peg_out_confirm_input,
+            &commitment_secrets
+                .iter()
+                .map(|(message_id, secret)| (message_id.clone(), WinternitzPublicKey::from(secret)))
+                .collect(),
+        )
+    }
+
+    /// Creates the graph from the public keys of the operator's Winternitz commitments, so the
+    /// secrets can stay in the operator's signer.
+    pub fn new_with_commitment_public_keys(
+        context: &OperatorContext,
+        peg_in_graph: &PegInGraph,
+        peg_out_confirm_input: Input,
+        commitment_public_keys: &HashMap<CommitmentMessageId, WinternitzPublicKey>,
+    ) -> Result<Self, Error> {
         let peg_in_confirm_transaction = peg_in_graph.peg_in_confirm_transaction_ref();
         let peg_in_confirm_txid = peg_in_confirm_transaction.tx().compute_txid();
 
+        let public_key = |message_id: CommitmentMessageId| {
+            commitment_public_keys
+                .get(&message_id)
+                .cloned()
+                .ok_or(Error::Graph(GraphError::CommitmentPublicKeyNotFound(
+                    message_id,
+                )))
+        };
+
         let connector_1_commitment_public_keys = HashMap::from([
             (
                 CommitmentMessageId::Superblock,
-                WinternitzPublicKey::from(&commitment_secrets[&CommitmentMessageId::Superblock]),
+                public_key(CommitmentMessageId::Superblock)?,
             ),
             (
                 CommitmentMessageId::SuperblockHash,
-                WinternitzPublicKey::from(
-                    &commitment_secrets[&CommitmentMessageId::SuperblockHash],
-                ),
+                public_key(CommitmentMessageId::SuperblockHash)?,
             ),
         ]);
         let connector_2_commitment_public_keys = HashMap::from([(
             CommitmentMessageId::StartTime,
-            WinternitzPublicKey::from(&commitment_secrets[&CommitmentMessageId::StartTime]),
+            public_key(CommitmentMessageId::StartTime)?,
         )]);
         let connector_6_commitment_public_keys = HashMap::from([
             (
                 CommitmentMessageId::PegOutTxIdSourceNetwork,
-                WinternitzPublicKey::from(
-                    &commitment_secrets[&CommitmentMessageId::PegOutTxIdSourceNetwork],
-                ),
+                public_key(CommitmentMessageId::PegOutTxIdSourceNetwork)?,
             ),
             (
                 CommitmentMessageId::PegOutTxIdDestinationNetwork,
-                WinternitzPublicKey::from(
-                    &commitment_secrets[&CommitmentMessageId::PegOutTxIdDestinationNetwork],
-                ),
+                public_key(CommitmentMessageId::PegOutTxIdDestinationNetwork)?,
             ),
         ]);
         let connector_b_commitment_public_keys = HashMap::from([
             (
                 CommitmentMessageId::StartTime,
-                WinternitzPublicKey::from(&commitment_secrets[&CommitmentMessageId::StartTime]),
+                public_key(CommitmentMessageId::StartTime)?,
             ),
             (
                 CommitmentMessageId::SuperblockHash,
-                WinternitzPublicKey::from(
-                    &commitment_secrets[&CommitmentMessageId::SuperblockHash],
-                ),
+                public_key(CommitmentMessageId::SuperblockHash)?,
             ),
         ]);
 
         let (connector_e1_commitment_public_keys, connector_e2_commitment_public_keys) =
-            groth16_commitment_secrets_to_public_keys(commitment_secrets);
+            groth16_commitment_public_keys(commitment_public_keys);
 
         let connectors = Self::create_new_connectors(
             context.network,
@@ -381,7 +401,7 @@ impl PegOutGraph {
         );
 
         let peg_out_confirm_transaction =
-            PegOutConfirmTransaction::new(context, &connectors.connector_6, peg_out_confirm_input);
+            PegOutConfirmTransaction::new(context, &connectors.connector_6, peg_out_confirm_input)?;
         let peg_out_confirm_txid = peg_out_confirm_transaction.tx().compute_txid();
 
         let kick_off_1_vout_0 = 0;
@@ -476,7 +496,7 @@ impl PegOutGraph {
                 amount: kick_off_1_transaction.tx().output[challenge_vout_0].value,
             },
             input_amount_crowdfunding,
-        );
+        )?;
 
         let take_1_vout_0 = 0;
         let take_1_vout_1 = 0;
@@ -516,7 +536,7 @@ impl PegOutGraph {
                 },
                 amount: kick_off_2_transaction.tx().output[take_1_vout_3].value,
             },
-        );
+        )?;
 
         // assert initial
         let assert_initial_vout_0 = 1;
@@ -599,7 +619,7 @@ impl PegOutGraph {
                 },
                 amount: assert_commit_2_transaction.tx().output[assert_final_vout_2].value,
             },
-        );
+        )?;
         let assert_final_txid = assert_final_transaction.tx().compute_txid();
 
         let take_2_vout_0 = 0;
@@ -640,7 +660,7 @@ impl PegOutGraph {
                 },
                 amount: assert_final_transaction.tx().output[take_2_vout_3].value,
             },
-        );
+        )?;
 
         let disprove_vout_0 = 1;
         let disprove_vout_1 = 2;
@@ -677,7 +697,7 @@ impl PegOutGraph {
             },
         );
 
-        PegOutGraph {
+        Ok(PegOutGraph {
             version: GRAPH_VERSION.to_string(),
             network: context.network,
             id: generate_id(peg_in_graph, &context.operator_public_key),
@@ -720,7 +740,7 @@ impl PegOutGraph {
             operator_taproot_public_key: context.operator_taproot_public_key,
             peg_out_chain_event: None,
             peg_out_transaction: None,
-        }
+        })
     }
 
     pub fn new_for_validation(&self) -> Self {
@@ -1425,7 +1445,7 @@ impl PegOutGraph {
             verify_if_not_mined(client, txid).await?;
         } else {
             let event = self.peg_out_chain_event.as_ref().unwrap();
-            let tx = PegOutTransaction::new(context, event, input);
+            let tx = PegOutTransaction::new(context, event, input)?;
             self.peg_out_transaction = Some(tx);
         }
 
@@ -1465,8 +1485,6 @@ impl PegOutGraph {
         &mut self,
         client: &AsyncClient,
         context: &OperatorContext,
-        source_network_txid_commitment_secret: &WinternitzSecret,
-        destination_network_txid_commitment_secret: &WinternitzSecret,
     ) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.kick_off_1_transaction.tx().compute_txid()).await?;
 
@@ -1484,25 +1502,16 @@ impl PegOutGraph {
                         .compute_txid()
                         .as_byte_array()
                         .to_owned();
-                    let source_network_txid_inputs = WinternitzSigningInputs {
-                        message: &pegout_txid,
-                        signing_key: source_network_txid_commitment_secret,
-                    };
-                    let destination_network_txid_inputs = WinternitzSigningInputs {
-                        message: self
-                            .peg_out_chain_event
+                    self.kick_off_1_transaction.sign(
+                        context,
+                        &self.connector_6,
+                        &pegout_txid,
+                        self.peg_out_chain_event
                             .as_ref()
                             .unwrap()
                             .tx_hash
                             .as_slice(),
-                        signing_key: destination_network_txid_commitment_secret,
-                    };
-                    self.kick_off_1_transaction.sign(
-                        context,
-                        &self.connector_6,
-                        &source_network_txid_inputs,
-                        &destination_network_txid_inputs,
-                    );
+                    )?;
                     Ok(self.kick_off_1_transaction.finalize())
                 }
                 false => Err(Error::Graph(GraphError::PrecedingTxNotConfirmed(vec![
@@ -1517,7 +1526,7 @@ impl PegOutGraph {
         &mut self,
         client: &AsyncClient,
         crowdfundng_inputs: &Vec<InputWithScript<'_>>,
-        keypair: &Keypair,
+        signer: &dyn Signer,
         output_script_pubkey: ScriptBuf,
     ) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.challenge_transaction.tx().compute_txid()).await?;
@@ -1530,9 +1539,9 @@ impl PegOutGraph {
                 true => {
                     self.challenge_transaction.add_inputs_and_output(
                         crowdfundng_inputs,
-                        keypair,
+                        signer,
                         output_script_pubkey,
-                    );
+                    )?;
                     Ok(self.challenge_transaction.finalize())
                 }
                 false => Err(Error::Graph(GraphError::PrecedingTxNotConfirmed(vec![
@@ -1547,7 +1556,6 @@ impl PegOutGraph {
         &mut self,
         client: &AsyncClient,
         context: &OperatorContext,
-        start_time_commitment_secret: &WinternitzSecret,
     ) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.start_time_transaction.tx().compute_txid()).await?;
 
@@ -1561,8 +1569,7 @@ impl PegOutGraph {
                         context,
                         &self.connector_2,
                         get_start_time_block_number(context.network),
-                        start_time_commitment_secret,
-                    );
+                    )?;
                     Ok(self.start_time_transaction.finalize())
                 }
                 false => Err(Error::Graph(GraphError::PrecedingTxNotConfirmed(vec![
@@ -1617,8 +1624,6 @@ impl PegOutGraph {
         &mut self,
         client: &AsyncClient,
         context: &OperatorContext,
-        superblock_commitment_secret: &WinternitzSecret,
-        superblock_hash_commitment_secret: &WinternitzSecret,
     ) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.kick_off_2_transaction.tx().compute_txid()).await?;
 
@@ -1639,15 +1644,9 @@ impl PegOutGraph {
                         self.kick_off_2_transaction.sign(
                             context,
                             &self.connector_1,
-                            &WinternitzSigningInputs {
-                                message: &get_superblock_message(&superblock_header),
-                                signing_key: superblock_commitment_secret,
-                            },
-                            &WinternitzSigningInputs {
-                                message: &get_superblock_hash_message(&superblock_header),
-                                signing_key: superblock_hash_commitment_secret,
-                            },
-                        );
+                            &get_superblock_message(&superblock_header),
+                            &get_superblock_hash_message(&superblock_header),
+                        )?;
                         Ok(self.kick_off_2_transaction.finalize())
                     }
                     _ => Err(Error::Graph(GraphError::PrecedingTxTimelockNotMet(
@@ -1735,7 +1734,7 @@ impl PegOutGraph {
     pub async fn assert_commit_1(
         &mut self,
         client: &AsyncClient,
-        commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
+        context: &OperatorContext,
         proof: &RawProof,
     ) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.assert_commit_1_transaction.tx().compute_txid()).await?;
@@ -1746,8 +1745,7 @@ impl PegOutGraph {
         match assert_initial_status {
             Ok(status) => match status.confirmed {
                 true => {
-                    let (witness_for_commit1, _) =
-                        sign_assert_tx_with_groth16_proof(commitment_secrets, proof);
+                    let (witness_for_commit1, _) = self.sign_groth16_proof(context, proof)?;
                     self.assert_commit_1_transaction
                         .sign(&self.connector_e_1, witness_for_commit1);
                     Ok(self.assert_commit_1_transaction.finalize())
@@ -1763,7 +1761,7 @@ impl PegOutGraph {
     pub async fn assert_commit_2(
         &mut self,
         client: &AsyncClient,
-        commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
+        context: &OperatorContext,
         proof: &RawProof,
     ) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.assert_commit_2_transaction.tx().compute_txid()).await?;
@@ -1774,8 +1772,7 @@ impl PegOutGraph {
         match assert_initial_status {
             Ok(status) => match status.confirmed {
                 true => {
-                    let (_, witness_for_commit2) =
-                        sign_assert_tx_with_groth16_proof(commitment_secrets, proof);
+                    let (_, witness_for_commit2) = self.sign_groth16_proof(context, proof)?;
                     self.assert_commit_2_transaction
                         .sign(&self.connector_e_2, witness_for_commit2);
                     Ok(self.assert_commit_2_transaction.finalize())
@@ -1793,7 +1790,7 @@ impl PegOutGraph {
     pub async fn assert_commits(
         &mut self,
         client: &AsyncClient,
-        commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
+        context: &OperatorContext,
         proof: &RawProof,
     ) -> Result<(Transaction, Transaction), Error> {
         verify_if_not_mined(client, self.assert_commit_1_transaction.tx().compute_txid()).await?;
@@ -1806,7 +1803,7 @@ impl PegOutGraph {
             Ok(status) => match status.confirmed {
                 true => {
                     let (witness_for_commit1, witness_for_commit2) =
-                        sign_assert_tx_with_groth16_proof(commitment_secrets, proof);
+                        self.sign_groth16_proof(context, proof)?;
                     self.assert_commit_1_transaction
                         .sign(&self.connector_e_1, witness_for_commit1);
                     self.assert_commit_2_transaction
@@ -1824,6 +1821,30 @@ impl PegOutGraph {
         }
     }
 
+    // Signs the intermediate values of the proof with the Winternitz keys committed to in the
+    // connectors e of both assert commit transactions and splits the witnesses between them.
+    fn sign_groth16_proof(
+        &self,
+        context: &OperatorContext,
+        proof: &RawProof,
+    ) -> Result<(Vec<RawWitness>, Vec<RawWitness>), Error> {
+        let public_keys: Vec<WinternitzPublicKey> = self
+            .connector_e_1
+            .commitment_public_keys()
+            .into_iter()
+            .chain(self.connector_e_2.commitment_public_keys())
+            .flat_map(|public_keys| public_keys.into_values())
+            .collect();
+        let mut witness_for_commit1 = context
+            .operator_signer
+            .sign_groth16_proof(&public_keys, proof)
+            .map_err(Error::Signer)?;
+        let witness_for_commit2 =
+            witness_for_commit1.split_off(self.connector_e_1.connectors_num());
+
+        Ok((witness_for_commit1, witness_for_commit2))
+    }
+
     pub async fn assert_final(&mut self, client: &AsyncClient) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.assert_final_transaction.tx().compute_txid()).await?;
 
@@ -1849,6 +1870,46 @@ impl PegOutGraph {
     ) -> Result<Transaction, Error> {
         verify_if_not_mined(client, self.disprove_transaction.tx().compute_txid()).await?;
 
+        let (assert_commit_1_witness, assert_commit_2_witness) =
+            self.get_assert_commit_witnesses(client).await?;
+        let (input_script_index, disprove_witness) = self.connector_c.generate_disprove_witness(
+            assert_commit_1_witness,
+            assert_commit_2_witness,
+            verifying_key,
+        )?;
+        self.disprove_transaction.add_input_output(
+            &self.connector_c,
+            input_script_index,
+            disprove_witness,
+            output_script_pubkey,
+        );
+        Ok(self.disprove_transaction.finalize())
+    }
+
+    // Re-runs `validate_assertions` on the commitments of the confirmed assert txs.
+    // Returns the index of the first invalid chunk, or `None` if all assertions are valid.
+    pub async fn validate_assertions(
+        &self,
+        client: &AsyncClient,
+        verifying_key: &ZkProofVerifyingKey,
+    ) -> Result<Option<usize>, Error> {
+        let (assert_commit_1_witness, assert_commit_2_witness) =
+            self.get_assert_commit_witnesses(client).await?;
+        match self.connector_c.generate_disprove_witness(
+            assert_commit_1_witness,
+            assert_commit_2_witness,
+            verifying_key,
+        ) {
+            Ok((input_script_index, _)) => Ok(Some(input_script_index)),
+            Err(Error::Chunker(ChunkerError::ValidProof)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn get_assert_commit_witnesses(
+        &self,
+        client: &AsyncClient,
+    ) -> Result<(Vec<RawWitness>, Vec<RawWitness>), Error> {
         let assert_commit_1_txid = self.assert_commit_1_transaction.tx().compute_txid();
         let assert_commit_2_txid = self.assert_commit_2_transaction.tx().compute_txid();
         let Some(onchain_assert_commit_1_tx) = client
@@ -1885,20 +1946,7 @@ impl PegOutGraph {
                         get_commit_from_assert_commit_tx(&onchain_assert_commit_1_tx);
                     let assert_commit_2_witness =
                         get_commit_from_assert_commit_tx(&onchain_assert_commit_2_tx);
-
-                    let (input_script_index, disprove_witness) =
-                        self.connector_c.generate_disprove_witness(
-                            assert_commit_1_witness,
-                            assert_commit_2_witness,
-                            verifying_key,
-                        )?;
-                    self.disprove_transaction.add_input_output(
-                        &self.connector_c,
-                        input_script_index,
-                        disprove_witness,
-                        output_script_pubkey,
-                    );
-                    Ok(self.disprove_transaction.finalize())
+                    Ok((assert_commit_1_witness, assert_commit_2_witness))
                 }
                 false => Err(Error::Graph(GraphError::PrecedingTxNotConfirmed(vec![
                     NamedTx::for_tx(&self.assert_final_transaction, status.confirmed),
@@ -2023,7 +2071,7 @@ impl PegOutGraph {
                             block_height + self.connector_4.num_blocks_timelock <= height
                         }) =>
                     {
-                        self.take_2_transaction.sign(context, &self.connector_c);
+                        self.take_2_transaction.sign(context, &self.connector_c)?;
                         Ok(self.take_2_transaction.finalize())
                     }
                     _ => Err(Error::Graph(GraphError::PrecedingTxTimelockNotMet(
@@ -2363,6 +2411,295 @@ impl PegOutGraph {
             .merge(&source_peg_out_graph.take_2_transaction);
     }
 
+    // The verifier public keys are those of the peg-in graph, in key aggregation order.
+    pub fn export_psbt(
+        &self,
+        txid: &Txid,
+        n_of_n_public_keys: &[PublicKey],
+    ) -> Result<Psbt, Error> {
+        let is_tx = |tx: &dyn PreSignedTransaction| tx.tx().compute_txid() == *txid;
+
+        if is_tx(&self.peg_out_confirm_transaction) {
+            export_psbt(
+                &self.peg_out_confirm_transaction,
+                vec![PsbtInput::p2wsh(EcdsaSighashType::All)],
+            )
+        } else if is_tx(&self.kick_off_1_transaction) {
+            export_psbt(
+                &self.kick_off_1_transaction,
+                vec![PsbtInput::taproot_script(
+                    self.connector_6.generate_taproot_spend_info(),
+                    TapSighashType::All,
+                )],
+            )
+        } else if is_tx(&self.start_time_transaction) {
+            export_musig2_psbt(
+                &self.start_time_transaction,
+                vec![PsbtInput::taproot_script(
+                    self.connector_2.generate_taproot_spend_info(),
+                    TapSighashType::All,
+                )],
+                n_of_n_public_keys,
+            )
+        } else if is_tx(&self.start_time_timeout_transaction) {
+            export_musig2_psbt(
+                &self.start_time_timeout_transaction,
+                vec![
+                    PsbtInput::taproot_script(
+                        self.connector_2.generate_taproot_spend_info(),
+                        TapSighashType::Single,
+                    ),
+                    PsbtInput::taproot_script(
+                        self.connector_1.generate_taproot_spend_info(),
+                        TapSighashType::None,
+                    ),
+                ],
+                n_of_n_public_keys,
+            )
+        } else if is_tx(&self.kick_off_2_transaction) {
+            export_psbt(
+                &self.kick_off_2_transaction,
+                vec![PsbtInput::taproot_script(
+                    self.connector_1.generate_taproot_spend_info(),
+                    TapSighashType::All,
+                )],
+            )
+        } else if is_tx(&self.kick_off_timeout_transaction) {
+            export_musig2_psbt(
+                &self.kick_off_timeout_transaction,
+                vec![PsbtInput::taproot_script(
+                    self.connector_1.generate_taproot_spend_info(),
+                    TapSighashType::Single,
+                )],
+                n_of_n_public_keys,
+            )
+        } else if is_tx(&self.challenge_transaction) {
+            // Crowdfunding inputs are added and signed by the challengers.
+            export_psbt(
+                &self.challenge_transaction,
+                vec![PsbtInput::taproot_script(
+                    self.connector_a.generate_taproot_spend_info(),
+                    TapSighashType::SinglePlusAnyoneCanPay,
+                )],
+            )
+        } else if is_tx(&self.assert_initial_transaction) {
+            export_musig2_psbt(
+                &self.assert_initial_transaction,
+                vec![PsbtInput::taproot_script(
+                    self.connector_b.generate_taproot_spend_info(),
+                    TapSighashType::All,
+                )],
+                n_of_n_public_keys,
+            )
+        } else if is_tx(&self.assert_commit_1_transaction) {
+            export_psbt(
+                &self.assert_commit_1_transaction,
+                self.connector_e_1
+                    .connectors_e
+                    .iter()
+                    .map(|connector_e| {
+                        PsbtInput::taproot_script_unsigned(
+                            connector_e.generate_taproot_spend_info(),
+                        )
+                    })
+                    .collect(),
+            )
+        } else if is_tx(&self.assert_commit_2_transaction) {
+            export_psbt(
+                &self.assert_commit_2_transaction,
+                self.connector_e_2
+                    .connectors_e
+                    .iter()
+                    .map(|connector_e| {
+                        PsbtInput::taproot_script_unsigned(
+                            connector_e.generate_taproot_spend_info(),
+                        )
+                    })
+                    .collect(),
+            )
+        } else if is_tx(&self.assert_final_transaction) {
+            export_musig2_psbt(
+                &self.assert_final_transaction,
+                vec![
+                    PsbtInput::taproot_script(
+                        self.connector_d.generate_taproot_spend_info(),
+                        TapSighashType::All,
+                    ),
+                    PsbtInput::p2wsh(EcdsaSighashType::All),
+                    PsbtInput::p2wsh(EcdsaSighashType::All),
+                ],
+                n_of_n_public_keys,
+            )
+        } else if is_tx(&self.disprove_chain_transaction) {
+            export_musig2_psbt(
+                &self.disprove_chain_transaction,
+                vec![PsbtInput::taproot_script(
+                    self.connector_b.generate_taproot_spend_info(),
+                    TapSighashType::Single,
+                )],
+                n_of_n_public_keys,
+            )
+        } else if is_tx(&self.disprove_transaction) {
+            // The reward input is added by the disprover.
+            export_musig2_psbt(
+                &self.disprove_transaction,
+                vec![
+                    PsbtInput::taproot_script(
+                        self.connector_5.generate_taproot_spend_info(),
+                        TapSighashType::Single,
+                    ),
+                    PsbtInput::unknown(),
+                ],
+                n_of_n_public_keys,
+            )
+        } else if is_tx(&self.take_1_transaction) {
+            export_musig2_psbt(
+                &self.take_1_transaction,
+                vec![
+                    PsbtInput::taproot_script(
+                        self.connector_0.generate_taproot_spend_info(),
+                        TapSighashType::All,
+                    ),
+                    PsbtInput::taproot_script(
+                        self.connector_a.generate_taproot_spend_info(),
+                        TapSighashType::All,
+                    ),
+                    PsbtInput::p2wsh(EcdsaSighashType::All),
+                    PsbtInput::taproot_script(
+                        self.connector_b.generate_taproot_spend_info(),
+                        TapSighashType::All,
+                    ),
+                ],
+                n_of_n_public_keys,
+            )
+        } else if is_tx(&self.take_2_transaction) {
+            export_musig2_psbt(
+                &self.take_2_transaction,
+                vec![
+                    PsbtInput::taproot_script(
+                        self.connector_0.generate_taproot_spend_info(),
+                        TapSighashType::All,
+                    ),
+                    PsbtInput::p2wsh(EcdsaSighashType::All),
+                    PsbtInput::taproot_script(
+                        self.connector_5.generate_taproot_spend_info(),
+                        TapSighashType::All,
+                    ),
+                    PsbtInput::taproot_key(
+                        self.connector_c.generate_taproot_spend_info(),
+                        TapSighashType::All,
+                    ),
+                ],
+                n_of_n_public_keys,
+            )
+        } else if let Some(peg_out_transaction) =
+            self.peg_out_transaction.as_ref().filter(|tx| is_tx(*tx))
+        {
+            export_psbt(
+                peg_out_transaction,
+                vec![PsbtInput::p2wsh(EcdsaSighashType::All)],
+            )
+        } else {
+            Err(Error::Psbt(PsbtError::TxNotFound(*txid)))
+        }
+    }
+
+    // Returns the txid of the updated transaction.
+    pub fn import_psbt(
+        &mut self,
+        psbt: &Psbt,
+        n_of_n_public_keys: &[PublicKey],
+    ) -> Result<Txid, Error> {
+        let txid = psbt.unsigned_tx.compute_txid();
+        let exported = &self.export_psbt(&txid, n_of_n_public_keys)?;
+        let is_tx = |tx: &dyn PreSignedTransaction| tx.tx().compute_txid() == txid;
+
+        if is_tx(&self.peg_out_confirm_transaction) {
+            import_psbt(&mut self.peg_out_confirm_transaction, psbt, exported)?;
+        } else if is_tx(&self.kick_off_1_transaction) {
+            import_psbt(&mut self.kick_off_1_transaction, psbt, exported)?;
+        } else if is_tx(&self.start_time_transaction) {
+            import_musig2_psbt(
+                &mut self.start_time_transaction,
+                psbt,
+                exported,
+                n_of_n_public_keys,
+            )?;
+        } else if is_tx(&self.start_time_timeout_transaction) {
+            import_musig2_psbt(
+                &mut self.start_time_timeout_transaction,
+                psbt,
+                exported,
+                n_of_n_public_keys,
+            )?;
+        } else if is_tx(&self.kick_off_2_transaction) {
+            import_psbt(&mut self.kick_off_2_transaction, psbt, exported)?;
+        } else if is_tx(&self.kick_off_timeout_transaction) {
+            import_musig2_psbt(
+                &mut self.kick_off_timeout_transaction,
+                psbt,
+                exported,
+                n_of_n_public_keys,
+            )?;
+        } else if is_tx(&self.challenge_transaction) {
+            import_psbt(&mut self.challenge_transaction, psbt, exported)?;
+        } else if is_tx(&self.assert_initial_transaction) {
+            import_musig2_psbt(
+                &mut self.assert_initial_transaction,
+                psbt,
+                exported,
+                n_of_n_public_keys,
+            )?;
+        } else if is_tx(&self.assert_commit_1_transaction) {
+            import_psbt(&mut self.assert_commit_1_transaction, psbt, exported)?;
+        } else if is_tx(&self.assert_commit_2_transaction) {
+            import_psbt(&mut self.assert_commit_2_transaction, psbt, exported)?;
+        } else if is_tx(&self.assert_final_transaction) {
+            import_musig2_psbt(
+                &mut self.assert_final_transaction,
+                psbt,
+                exported,
+                n_of_n_public_keys,
+            )?;
+        } else if is_tx(&self.disprove_chain_transaction) {
+            import_musig2_psbt(
+                &mut self.disprove_chain_transaction,
+                psbt,
+                exported,
+                n_of_n_public_keys,
+            )?;
+        } else if is_tx(&self.disprove_transaction) {
+            import_musig2_psbt(
+                &mut self.disprove_transaction,
+                psbt,
+                exported,
+                n_of_n_public_keys,
+            )?;
+        } else if is_tx(&self.take_1_transaction) {
+            import_musig2_psbt(
+                &mut self.take_1_transaction,
+                psbt,
+                exported,
+                n_of_n_public_keys,
+            )?;
+        } else if is_tx(&self.take_2_transaction) {
+            import_musig2_psbt(
+                &mut self.take_2_transaction,
+                psbt,
+                exported,
+                n_of_n_public_keys,
+            )?;
+        } else if let Some(peg_out_transaction) =
+            self.peg_out_transaction.as_mut().filter(|tx| is_tx(&**tx))
+        {
+            import_psbt(peg_out_transaction, psbt, exported)?;
+        } else {
+            return Err(Error::Psbt(PsbtError::TxNotFound(txid)));
+        }
+
+        Ok(txid)
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn create_new_connectors(
         network: Network,
@@ -7,8 +7,11 @@ pub mod contexts;
 pub mod error;
 pub mod graphs;
 pub mod proof;
+#[cfg(feature = "prover")]
+pub mod receipt;
 pub mod scripts;
 pub mod serialization;
+pub mod signers;
 pub mod superblock;
 pub mod transactions;
 pub mod utils;
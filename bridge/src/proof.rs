@@ -10,8 +10,8 @@ use ark_std::{test_rng, UniformRand};
 use bitvm::chunk::api::type_conversion_utils::RawProof;
 use rand::{RngCore, SeedableRng};
 
-// TODO: replace with actual implementation
-pub fn get_proof() -> RawProof {
+// TODO: replace with the proof wrapped by `receipt::get_proof` in the remaining flows
+pub fn get_dummy_proof() -> RawProof {
     type E = Bn254;
     let k = 6;
     let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
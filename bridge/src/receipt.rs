@@ -0,0 +1,92 @@
+use ark_bn254::{g1::G1Affine, g2::G2Affine, Bn254, Fq, Fq2, Fr};
+use ark_ff::PrimeField;
+use ark_groth16::{Proof, VerifyingKey};
+use bitvm::chunk::api::type_conversion_utils::RawProof;
+use prover::backend::ProverBackend;
+use risc0_groth16::Seal;
+use risc0_zkvm::Receipt;
+
+use super::error::Error;
+
+/// Wraps the succinct `receipt` of the guest `image_id` into the Groth16 proof asserted by the
+/// operator. `vk` is the verifying key of the circuit `backend` wraps receipts with.
+pub fn get_proof(
+    backend: &dyn ProverBackend,
+    receipt: &Receipt,
+    image_id: [u32; 8],
+    vk: &VerifyingKey<Bn254>,
+) -> Result<RawProof, Error> {
+    let (seal, output) = backend.wrap(receipt, image_id).map_err(Error::Prover)?;
+
+    Ok(RawProof {
+        proof: seal_to_proof(&seal),
+        public: vec![Fr::from_be_bytes_mod_order(&output)],
+        vk: vk.clone(),
+    })
+}
+
+/// Decodes the big-endian coordinates of the seal. The coordinates of `b` are `(c1, c0)` pairs.
+fn seal_to_proof(seal: &Seal) -> Proof<Bn254> {
+    let fq = |bytes: &Vec<u8>| Fq::from_be_bytes_mod_order(bytes);
+    let g1 = |point: &Vec<Vec<u8>>| G1Affine::new_unchecked(fq(&point[0]), fq(&point[1]));
+    let fq2 = |element: &Vec<Vec<u8>>| Fq2::new(fq(&element[1]), fq(&element[0]));
+
+    Proof {
+        a: g1(&seal.a),
+        b: G2Affine::new_unchecked(fq2(&seal.b[0]), fq2(&seal.b[1])),
+        c: g1(&seal.c),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ff::BigInteger;
+    use prover::{
+        backend::{BackendError, MockBackend},
+        calculate_groth16_output,
+    };
+    use risc0_zkvm::{FakeReceipt, InnerReceipt, ReceiptClaim};
+
+    use super::{super::proof::get_dummy_proof, *};
+
+    fn fq_to_bytes(fq: Fq) -> Vec<u8> {
+        fq.into_bigint().to_bytes_be()
+    }
+
+    #[test]
+    fn test_seal_to_proof() {
+        let proof = get_dummy_proof().proof;
+        let seal = Seal {
+            a: vec![fq_to_bytes(proof.a.x), fq_to_bytes(proof.a.y)],
+            b: vec![
+                vec![fq_to_bytes(proof.b.x.c1), fq_to_bytes(proof.b.x.c0)],
+                vec![fq_to_bytes(proof.b.y.c1), fq_to_bytes(proof.b.y.c0)],
+            ],
+            c: vec![fq_to_bytes(proof.c.x), fq_to_bytes(proof.c.y)],
+        };
+        assert_eq!(seal_to_proof(&seal), proof);
+    }
+
+    #[test]
+    fn test_get_proof_with_mock_backend() {
+        let image_id = [1; 8];
+        let journal = vec![7; 32];
+        let claim = ReceiptClaim::ok(image_id, journal.clone());
+        let receipt = Receipt::new(InnerReceipt::Fake(FakeReceipt::new(claim)), journal.clone());
+        let vk = get_dummy_proof().vk;
+
+        let raw_proof = get_proof(&MockBackend::new(), &receipt, image_id, &vk).unwrap();
+        assert_eq!(
+            raw_proof.public,
+            vec![Fr::from_be_bytes_mod_order(&calculate_groth16_output(
+                image_id, &journal
+            ))]
+        );
+        assert_eq!(raw_proof.vk, vk);
+
+        assert!(matches!(
+            get_proof(&MockBackend::new(), &receipt, [2; 8], &vk),
+            Err(Error::Prover(BackendError::ImageIdMismatch { .. }))
+        ));
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use bitcoin::{
+    secp256k1::{ecdsa, schnorr, Message},
+    PublicKey, TapNodeHash, Witness,
+};
+use bitvm::{
+    chunk::api::type_conversion_utils::{RawProof, RawWitness},
+    signatures::signing_winternitz::WinternitzPublicKey,
+};
+use musig2::{AggNonce, PartialSignature, PubNonce};
+
+use super::super::commitments::CommitmentMessageId;
+
+/// Identifies a MuSig2 signing session, i.e. one input of one pre-signed transaction.
+pub type Musig2SessionId = String;
+
+/// Signs on behalf of a single participant (depositor, operator, verifier or withdrawer).
+///
+/// Contexts and the pre-signing code only talk to this trait, so the secret keys may live
+/// in this process ([`super::local::LocalSigner`]) or in a separate signer process
+/// ([`super::remote::RemoteSigner`]).
+pub trait Signer: Send + Sync {
+    /// The public key of the participant's ECDSA/Schnorr key.
+    fn public_key(&self) -> PublicKey;
+
+    /// Signs a sighash with ECDSA, used for p2wsh and p2wpkh inputs.
+    fn sign_ecdsa(&self, message: &Message) -> Result<ecdsa::Signature, String>;
+
+    /// Signs a tapscript sighash with BIP-340 Schnorr, without aux randomness.
+    fn sign_schnorr(&self, message: &Message) -> Result<schnorr::Signature, String>;
+
+    /// Signs a taproot key-spend sighash with the key tweaked by `merkle_root`.
+    fn sign_schnorr_key_spend(
+        &self,
+        message: &Message,
+        merkle_root: Option<TapNodeHash>,
+    ) -> Result<schnorr::Signature, String>;
+
+    /// Generates a fresh MuSig2 nonce for `session_id` and returns its public part. The secret
+    /// nonce never leaves the signer. Generating again for the same session replaces it.
+    fn generate_musig2_nonce(&self, session_id: &str) -> Result<PubNonce, String>;
+
+    /// Produces this participant's MuSig2 partial signature for the n-of-n key
+    /// aggregated from `n_of_n_public_keys`, using the secret nonce generated for
+    /// `session_id`. The secret nonce is consumed, so a session can only be signed once.
+    fn sign_musig2_partial(
+        &self,
+        n_of_n_public_keys: &[PublicKey],
+        session_id: &str,
+        aggregated_nonce: &AggNonce,
+        message: &Message,
+    ) -> Result<PartialSignature, String>;
+
+    /// Signs `message` with the Winternitz secret matching `public_key` and returns the witness.
+    fn sign_winternitz(
+        &self,
+        public_key: &WinternitzPublicKey,
+        message: &[u8],
+    ) -> Result<Witness, String>;
+
+    /// Generates fresh Winternitz secrets for every operator commitment of a peg-out graph,
+    /// keeps them in the signer and returns their public keys.
+    fn generate_commitment_public_keys(
+        &self,
+    ) -> Result<HashMap<CommitmentMessageId, WinternitzPublicKey>, String>;
+
+    /// Signs the intermediate values of the Groth16 verification of `proof` with the
+    /// Winternitz secrets matching `public_keys` and returns one witness per key, in order.
+    fn sign_groth16_proof(
+        &self,
+        public_keys: &[WinternitzPublicKey],
+        proof: &RawProof,
+    ) -> Result<Vec<RawWitness>, String>;
+}
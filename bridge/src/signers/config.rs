@@ -0,0 +1,42 @@
+use std::{path::Path, sync::Arc};
+
+use bitcoin::Network;
+
+use super::{base::Signer, local::LocalSigner, remote::RemoteSigner};
+
+/// Where the keys of one participant are held.
+#[derive(Clone)]
+pub enum SignerConfig {
+    /// Keys derived from a secret and held by this process.
+    Local(Arc<LocalSigner>),
+    /// Keys held by a separate signer process, see [`RemoteSigner`].
+    Remote(Arc<RemoteSigner>),
+}
+
+impl SignerConfig {
+    pub fn from_secret(network: Network, secret: &str) -> Self {
+        SignerConfig::Local(Arc::new(LocalSigner::from_secret(network, secret)))
+    }
+
+    pub fn connect(socket_path: impl AsRef<Path>) -> Result<Self, String> {
+        Ok(SignerConfig::Remote(Arc::new(RemoteSigner::connect(
+            socket_path,
+        )?)))
+    }
+
+    pub fn signer(&self) -> Arc<dyn Signer> {
+        match self {
+            SignerConfig::Local(signer) => signer.clone(),
+            SignerConfig::Remote(signer) => signer.clone(),
+        }
+    }
+
+    /// The in-process signer, whose Winternitz secrets and MuSig2 secret nonces the client
+    /// has to persist itself. A remote signer keeps its own.
+    pub fn local(&self) -> Option<&Arc<LocalSigner>> {
+        match self {
+            SignerConfig::Local(signer) => Some(signer),
+            SignerConfig::Remote(_) => None,
+        }
+    }
+}
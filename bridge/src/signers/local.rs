@@ -0,0 +1,203 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, RwLock},
+};
+
+use bitcoin::{
+    hex::DisplayHex,
+    key::{Keypair, TapTweak},
+    secp256k1::{ecdsa, schnorr, Message},
+    Network, PublicKey, TapNodeHash, Witness,
+};
+use bitvm::{
+    chunk::api::{
+        generate_signatures_for_any_proof,
+        type_conversion_utils::{utils_raw_witnesses_from_signatures, RawProof, RawWitness},
+    },
+    signatures::signing_winternitz::{
+        generate_winternitz_witness, WinternitzPublicKey, WinternitzSecret, WinternitzSigningInputs,
+    },
+};
+use musig2::{
+    secp::Point, sign_partial, AggNonce, KeyAggContext, PartialSignature, PubNonce, SecNonce,
+};
+use secp256k1::SECP256K1;
+
+use super::{
+    super::{
+        commitments::CommitmentMessageId, contexts::base::generate_keys_from_secret,
+        error::err_to_string,
+    },
+    base::{Musig2SessionId, Signer},
+};
+
+/// In-process signer holding the secret keys in memory.
+pub struct LocalSigner {
+    keypair: Keypair,
+    public_key: PublicKey,
+    winternitz_secrets: RwLock<HashMap<WinternitzPublicKey, WinternitzSecret>>,
+    musig2_nonces: Mutex<HashMap<Musig2SessionId, SecNonce>>,
+}
+
+impl LocalSigner {
+    pub fn new(keypair: Keypair) -> Self {
+        LocalSigner {
+            keypair,
+            public_key: PublicKey::new(keypair.public_key()),
+            winternitz_secrets: RwLock::new(HashMap::new()),
+            musig2_nonces: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn from_secret(network: Network, secret: &str) -> Self {
+        let (keypair, _) = generate_keys_from_secret(network, secret);
+        Self::new(keypair)
+    }
+
+    pub fn add_winternitz_secret(&self, secret: WinternitzSecret) {
+        self.winternitz_secrets
+            .write()
+            .unwrap()
+            .insert(WinternitzPublicKey::from(&secret), secret);
+    }
+
+    pub fn add_winternitz_secrets(&self, secrets: impl IntoIterator<Item = WinternitzSecret>) {
+        for secret in secrets {
+            self.add_winternitz_secret(secret);
+        }
+    }
+
+    /// The secret nonces of the MuSig2 sessions that have not been signed yet, so a client
+    /// holding the secret key can persist them between runs.
+    pub fn musig2_nonces(&self) -> HashMap<Musig2SessionId, SecNonce> {
+        self.musig2_nonces.lock().unwrap().clone()
+    }
+
+    /// Restores secret nonces previously returned by [`Self::musig2_nonces`].
+    pub fn restore_musig2_nonces(&self, nonces: HashMap<Musig2SessionId, SecNonce>) {
+        self.musig2_nonces.lock().unwrap().extend(nonces);
+    }
+}
+
+impl Signer for LocalSigner {
+    fn public_key(&self) -> PublicKey {
+        self.public_key
+    }
+
+    fn sign_ecdsa(&self, message: &Message) -> Result<ecdsa::Signature, String> {
+        Ok(self.keypair.secret_key().sign_ecdsa(*message))
+    }
+
+    fn sign_schnorr(&self, message: &Message) -> Result<schnorr::Signature, String> {
+        // If secp256k1 is updated to 0.30.0, the following line can be replaced with
+        // let signature = keypair.sign_schnorr_no_aux_rand(message);
+        Ok(SECP256K1.sign_schnorr_no_aux_rand(message, &self.keypair))
+    }
+
+    fn sign_schnorr_key_spend(
+        &self,
+        message: &Message,
+        merkle_root: Option<TapNodeHash>,
+    ) -> Result<schnorr::Signature, String> {
+        let tweak_keypair = self.keypair.tap_tweak(SECP256K1, merkle_root);
+        Ok(SECP256K1.sign_schnorr_no_aux_rand(message, &tweak_keypair.to_inner()))
+    }
+
+    fn generate_musig2_nonce(&self, session_id: &str) -> Result<PubNonce, String> {
+        let secret_nonce = SecNonce::build(&mut rand::rngs::OsRng)
+            .with_seckey(self.keypair.secret_key())
+            .build();
+        let public_nonce = secret_nonce.public_nonce();
+        self.musig2_nonces
+            .lock()
+            .unwrap()
+            .insert(session_id.to_string(), secret_nonce);
+
+        Ok(public_nonce)
+    }
+
+    fn sign_musig2_partial(
+        &self,
+        n_of_n_public_keys: &[PublicKey],
+        session_id: &str,
+        aggregated_nonce: &AggNonce,
+        message: &Message,
+    ) -> Result<PartialSignature, String> {
+        let pubkeys: Vec<Point> = n_of_n_public_keys
+            .iter()
+            .map(|&public_key| public_key.inner.into())
+            .collect();
+        let key_agg_ctx = KeyAggContext::new(pubkeys).map_err(err_to_string)?;
+        let secret_nonce = self
+            .musig2_nonces
+            .lock()
+            .unwrap()
+            .remove(session_id)
+            .ok_or_else(|| format!("No MuSig2 nonce generated for session {session_id}"))?;
+
+        sign_partial(
+            &key_agg_ctx,
+            self.keypair.secret_key(),
+            secret_nonce,
+            aggregated_nonce,
+            &message[..],
+        )
+        .map_err(err_to_string)
+    }
+
+    fn sign_winternitz(
+        &self,
+        public_key: &WinternitzPublicKey,
+        message: &[u8],
+    ) -> Result<Witness, String> {
+        let winternitz_secrets = self.winternitz_secrets.read().unwrap();
+        let signing_key = winternitz_secrets
+            .get(public_key)
+            .ok_or("No Winternitz secret registered for the given public key")?;
+
+        Ok(generate_winternitz_witness(&WinternitzSigningInputs {
+            message,
+            signing_key,
+        }))
+    }
+    fn generate_commitment_public_keys(
+        &self,
+    ) -> Result<HashMap<CommitmentMessageId, WinternitzPublicKey>, String> {
+        let commitment_secrets = CommitmentMessageId::generate_commitment_secrets();
+        let public_keys = commitment_secrets
+            .iter()
+            .map(|(message_id, secret)| (message_id.clone(), WinternitzPublicKey::from(secret)))
+            .collect();
+        self.add_winternitz_secrets(commitment_secrets.into_values());
+
+        Ok(public_keys)
+    }
+
+    fn sign_groth16_proof(
+        &self,
+        public_keys: &[WinternitzPublicKey],
+        proof: &RawProof,
+    ) -> Result<Vec<RawWitness>, String> {
+        let secrets = {
+            let winternitz_secrets = self.winternitz_secrets.read().unwrap();
+            public_keys
+                .iter()
+                .map(|public_key| {
+                    winternitz_secrets
+                        .get(public_key)
+                        .map(|secret| secret.secret_key.to_lower_hex_string())
+                        .ok_or("No Winternitz secret registered for a Groth16 public key")
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        let signatures = generate_signatures_for_any_proof(
+            proof.proof.clone(),
+            proof.public.clone(),
+            &proof.vk,
+            secrets,
+        );
+
+        Ok(utils_raw_witnesses_from_signatures(&signatures))
+    }
+}
@@ -0,0 +1,4 @@
+pub mod base;
+pub mod config;
+pub mod local;
+pub mod remote;
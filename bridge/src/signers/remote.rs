@@ -0,0 +1,532 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use bitcoin::{
+    hex::{DisplayHex, FromHex},
+    secp256k1::{ecdsa, schnorr, Message},
+    PublicKey, TapNodeHash, Witness,
+};
+use bitvm::{
+    chunk::api::type_conversion_utils::{RawProof, RawWitness},
+    signatures::signing_winternitz::WinternitzPublicKey,
+};
+use musig2::{AggNonce, PartialSignature, PubNonce};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{
+    super::{commitments::CommitmentMessageId, error::err_to_string},
+    base::Signer,
+};
+
+const JSON_RPC_VERSION: &str = "2.0";
+const JSON_RPC_SIGNER_ERROR: i64 = -32000;
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Requests understood by a signer process. Byte strings (sighashes, signatures, witness
+/// elements, merkle roots, compressed Groth16 proofs) are lower-case hex.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "method", content = "params", rename_all = "snake_case")]
+pub enum SignerRequest {
+    PublicKey,
+    SignEcdsa {
+        message: String,
+    },
+    SignSchnorr {
+        message: String,
+    },
+    SignSchnorrKeySpend {
+        message: String,
+        merkle_root: Option<String>,
+    },
+    GenerateMusig2Nonce {
+        session_id: String,
+    },
+    SignMusig2Partial {
+        n_of_n_public_keys: Vec<PublicKey>,
+        session_id: String,
+        aggregated_nonce: AggNonce,
+        message: String,
+    },
+    SignWinternitz {
+        public_key: WinternitzPublicKey,
+        message: String,
+    },
+    GenerateCommitmentPublicKeys,
+    SignGroth16Proof {
+        public_keys: Vec<WinternitzPublicKey>,
+        proof: String,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonRpcRequest {
+    jsonrpc: String,
+    id: u64,
+    #[serde(flatten)]
+    request: SignerRequest,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonRpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonRpcResponse {
+    jsonrpc: String,
+    id: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<JsonRpcError>,
+}
+
+/// Signer that forwards every request to a separate signer process over a Unix socket,
+/// using newline-delimited JSON-RPC 2.0.
+pub struct RemoteSigner {
+    socket_path: PathBuf,
+    public_key: PublicKey,
+    timeout: Duration,
+    next_id: AtomicU64,
+}
+
+impl RemoteSigner {
+    /// Connects to the signer listening on `socket_path` and fetches its public key.
+    pub fn connect(socket_path: impl AsRef<Path>) -> Result<Self, String> {
+        let socket_path = socket_path.as_ref().to_path_buf();
+        let public_key = serde_json::from_value(call_signer(
+            &socket_path,
+            DEFAULT_TIMEOUT,
+            0,
+            SignerRequest::PublicKey,
+        )?)
+        .map_err(err_to_string)?;
+
+        Ok(RemoteSigner {
+            socket_path,
+            public_key,
+            timeout: DEFAULT_TIMEOUT,
+            next_id: AtomicU64::new(1),
+        })
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn call(&self, request: SignerRequest) -> Result<Value, String> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        call_signer(&self.socket_path, self.timeout, id, request)
+    }
+
+    fn call_for_bytes(&self, request: SignerRequest) -> Result<Vec<u8>, String> {
+        let hex: String = serde_json::from_value(self.call(request)?).map_err(err_to_string)?;
+        Vec::from_hex(&hex).map_err(err_to_string)
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn public_key(&self) -> PublicKey {
+        self.public_key
+    }
+
+    fn sign_ecdsa(&self, message: &Message) -> Result<ecdsa::Signature, String> {
+        let bytes = self.call_for_bytes(SignerRequest::SignEcdsa {
+            message: message[..].to_lower_hex_string(),
+        })?;
+        ecdsa::Signature::from_compact(&bytes).map_err(err_to_string)
+    }
+
+    fn sign_schnorr(&self, message: &Message) -> Result<schnorr::Signature, String> {
+        let bytes = self.call_for_bytes(SignerRequest::SignSchnorr {
+            message: message[..].to_lower_hex_string(),
+        })?;
+        schnorr::Signature::from_slice(&bytes).map_err(err_to_string)
+    }
+
+    fn sign_schnorr_key_spend(
+        &self,
+        message: &Message,
+        merkle_root: Option<TapNodeHash>,
+    ) -> Result<schnorr::Signature, String> {
+        let bytes = self.call_for_bytes(SignerRequest::SignSchnorrKeySpend {
+            message: message[..].to_lower_hex_string(),
+            merkle_root: merkle_root.map(|root| root.to_string()),
+        })?;
+        schnorr::Signature::from_slice(&bytes).map_err(err_to_string)
+    }
+
+    fn generate_musig2_nonce(&self, session_id: &str) -> Result<PubNonce, String> {
+        let result = self.call(SignerRequest::GenerateMusig2Nonce {
+            session_id: session_id.to_string(),
+        })?;
+        serde_json::from_value(result).map_err(err_to_string)
+    }
+
+    fn sign_musig2_partial(
+        &self,
+        n_of_n_public_keys: &[PublicKey],
+        session_id: &str,
+        aggregated_nonce: &AggNonce,
+        message: &Message,
+    ) -> Result<PartialSignature, String> {
+        let result = self.call(SignerRequest::SignMusig2Partial {
+            n_of_n_public_keys: n_of_n_public_keys.to_vec(),
+            session_id: session_id.to_string(),
+            aggregated_nonce: aggregated_nonce.clone(),
+            message: message[..].to_lower_hex_string(),
+        })?;
+        serde_json::from_value(result).map_err(err_to_string)
+    }
+
+    fn sign_winternitz(
+        &self,
+        public_key: &WinternitzPublicKey,
+        message: &[u8],
+    ) -> Result<Witness, String> {
+        let result = self.call(SignerRequest::SignWinternitz {
+            public_key: public_key.clone(),
+            message: message.to_lower_hex_string(),
+        })?;
+        let elements: Vec<String> = serde_json::from_value(result).map_err(err_to_string)?;
+        let elements = elements
+            .iter()
+            .map(|element| Vec::from_hex(element).map_err(err_to_string))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Witness::from_slice(&elements))
+    }
+
+    fn generate_commitment_public_keys(
+        &self,
+    ) -> Result<HashMap<CommitmentMessageId, WinternitzPublicKey>, String> {
+        let result = self.call(SignerRequest::GenerateCommitmentPublicKeys)?;
+        serde_json::from_value(result).map_err(err_to_string)
+    }
+
+    fn sign_groth16_proof(
+        &self,
+        public_keys: &[WinternitzPublicKey],
+        proof: &RawProof,
+    ) -> Result<Vec<RawWitness>, String> {
+        let mut proof_bytes = Vec::new();
+        proof
+            .serialize_compressed(&mut proof_bytes)
+            .map_err(err_to_string)?;
+        let result = self.call(SignerRequest::SignGroth16Proof {
+            public_keys: public_keys.to_vec(),
+            proof: proof_bytes.to_lower_hex_string(),
+        })?;
+        let witnesses: Vec<Vec<String>> = serde_json::from_value(result).map_err(err_to_string)?;
+
+        witnesses
+            .iter()
+            .map(|witness| {
+                witness
+                    .iter()
+                    .map(|element| Vec::from_hex(element).map_err(err_to_string))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn call_signer(
+    socket_path: &Path,
+    timeout: Duration,
+    id: u64,
+    request: SignerRequest,
+) -> Result<Value, String> {
+    let mut line = serde_json::to_string(&JsonRpcRequest {
+        jsonrpc: JSON_RPC_VERSION.to_string(),
+        id,
+        request,
+    })
+    .map_err(err_to_string)?;
+    line.push('\n');
+
+    let mut stream = UnixStream::connect(socket_path).map_err(|e| {
+        format!(
+            "Unable to connect to signer at {}: {e}",
+            socket_path.display()
+        )
+    })?;
+    stream
+        .set_read_timeout(Some(timeout))
+        .map_err(err_to_string)?;
+    stream
+        .set_write_timeout(Some(timeout))
+        .map_err(err_to_string)?;
+    stream.write_all(line.as_bytes()).map_err(err_to_string)?;
+
+    let mut response_line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut response_line)
+        .map_err(err_to_string)?;
+    let response: JsonRpcResponse = serde_json::from_str(&response_line).map_err(err_to_string)?;
+
+    if response.id != id {
+        return Err(format!(
+            "Signer response id {} does not match request id {id}",
+            response.id
+        ));
+    }
+    match (response.result, response.error) {
+        (_, Some(error)) => Err(format!("Signer error {}: {}", error.code, error.message)),
+        (Some(result), None) => Ok(result),
+        (None, None) => Err("Signer returned neither result nor error".to_string()),
+    }
+}
+
+fn parse_message(hex: &str) -> Result<Message, String> {
+    Message::from_digest_slice(&Vec::from_hex(hex).map_err(err_to_string)?).map_err(err_to_string)
+}
+
+/// Executes one request against `signer`. Signer processes can use this to implement
+/// the server side of the protocol spoken by [`RemoteSigner`].
+pub fn handle_signer_request(signer: &dyn Signer, request: SignerRequest) -> Result<Value, String> {
+    let result = match request {
+        SignerRequest::PublicKey => serde_json::to_value(signer.public_key()),
+        SignerRequest::SignEcdsa { message } => {
+            let signature = signer.sign_ecdsa(&parse_message(&message)?)?;
+            serde_json::to_value(signature.serialize_compact().to_lower_hex_string())
+        }
+        SignerRequest::SignSchnorr { message } => {
+            let signature = signer.sign_schnorr(&parse_message(&message)?)?;
+            serde_json::to_value(signature.serialize().to_lower_hex_string())
+        }
+        SignerRequest::SignSchnorrKeySpend {
+            message,
+            merkle_root,
+        } => {
+            let merkle_root = merkle_root
+                .map(|root| root.parse::<TapNodeHash>().map_err(err_to_string))
+                .transpose()?;
+            let signature =
+                signer.sign_schnorr_key_spend(&parse_message(&message)?, merkle_root)?;
+            serde_json::to_value(signature.serialize().to_lower_hex_string())
+        }
+        SignerRequest::GenerateMusig2Nonce { session_id } => {
+            serde_json::to_value(signer.generate_musig2_nonce(&session_id)?)
+        }
+        SignerRequest::SignMusig2Partial {
+            n_of_n_public_keys,
+            session_id,
+            aggregated_nonce,
+            message,
+        } => {
+            let partial_signature = signer.sign_musig2_partial(
+                &n_of_n_public_keys,
+                &session_id,
+                &aggregated_nonce,
+                &parse_message(&message)?,
+            )?;
+            serde_json::to_value(partial_signature)
+        }
+        SignerRequest::SignWinternitz {
+            public_key,
+            message,
+        } => {
+            let message = Vec::from_hex(&message).map_err(err_to_string)?;
+            let witness = signer.sign_winternitz(&public_key, &message)?;
+            serde_json::to_value(
+                witness
+                    .iter()
+                    .map(|element| element.to_lower_hex_string())
+                    .collect::<Vec<_>>(),
+            )
+        }
+        SignerRequest::GenerateCommitmentPublicKeys => {
+            serde_json::to_value(signer.generate_commitment_public_keys()?)
+        }
+        SignerRequest::SignGroth16Proof { public_keys, proof } => {
+            let proof =
+                RawProof::deserialize_compressed(&*Vec::from_hex(&proof).map_err(err_to_string)?)
+                    .map_err(err_to_string)?;
+            let witnesses = signer.sign_groth16_proof(&public_keys, &proof)?;
+            serde_json::to_value(
+                witnesses
+                    .iter()
+                    .map(|witness| {
+                        witness
+                            .iter()
+                            .map(|element| element.to_lower_hex_string())
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        }
+    };
+
+    result.map_err(err_to_string)
+}
+
+/// Serves newline-delimited JSON-RPC requests from one connection until it is closed.
+pub fn serve_signer_connection(signer: &dyn Signer, stream: UnixStream) -> std::io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<JsonRpcRequest>(&line) {
+            Ok(request) => match handle_signer_request(signer, request.request) {
+                Ok(result) => JsonRpcResponse {
+                    jsonrpc: JSON_RPC_VERSION.to_string(),
+                    id: request.id,
+                    result: Some(result),
+                    error: None,
+                },
+                Err(message) => JsonRpcResponse {
+                    jsonrpc: JSON_RPC_VERSION.to_string(),
+                    id: request.id,
+                    result: None,
+                    error: Some(JsonRpcError {
+                        code: JSON_RPC_SIGNER_ERROR,
+                        message,
+                    }),
+                },
+            },
+            Err(e) => JsonRpcResponse {
+                jsonrpc: JSON_RPC_VERSION.to_string(),
+                id: 0,
+                result: None,
+                error: Some(JsonRpcError {
+                    code: -32700,
+                    message: e.to_string(),
+                }),
+            },
+        };
+
+        let mut response_line = serde_json::to_string(&response).map_err(std::io::Error::other)?;
+        response_line.push('\n');
+        writer.write_all(response_line.as_bytes())?;
+    }
+
+    Ok(())
+}
+
+/// Serves every connection accepted by `listener`, one at a time.
+pub fn serve_signer(signer: &dyn Signer, listener: &UnixListener) -> std::io::Result<()> {
+    for stream in listener.incoming() {
+        if let Err(e) = serve_signer_connection(signer, stream?) {
+            eprintln!("Signer connection failed with: {e}");
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{os::unix::net::UnixListener, thread};
+
+    use bitcoin::{hex::DisplayHex, key::constants::SECRET_KEY_SIZE, secp256k1::Message, Network};
+    use bitvm::signatures::signing_winternitz::{WinternitzPublicKey, WinternitzSecret};
+    use musig2::{secp::Point, verify_partial, KeyAggContext};
+
+    use crate::{
+        commitments::CommitmentMessageId,
+        constants::START_TIME_MESSAGE_LENGTH,
+        signers::{base::Signer, local::LocalSigner},
+        transactions::signing_musig2::generate_aggregated_nonce,
+    };
+
+    use super::{serve_signer, RemoteSigner};
+
+    #[test]
+    fn test_remote_signer_matches_local_signer() {
+        let secret = [7u8; SECRET_KEY_SIZE].to_lower_hex_string();
+        let winternitz_secret = WinternitzSecret::new(4);
+        let winternitz_public_key = WinternitzPublicKey::from(&winternitz_secret);
+
+        let local_signer = LocalSigner::from_secret(Network::Regtest, &secret);
+        let served_signer = LocalSigner::from_secret(Network::Regtest, &secret);
+        served_signer.add_winternitz_secret(winternitz_secret);
+
+        let socket_path = std::env::temp_dir().join(format!(
+            "bridge-remote-signer-test-{}.sock",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = UnixListener::bind(&socket_path).unwrap();
+        thread::spawn(move || serve_signer(&served_signer, &listener));
+
+        let remote_signer = RemoteSigner::connect(&socket_path).unwrap();
+        assert_eq!(remote_signer.public_key(), local_signer.public_key());
+
+        let message = Message::from_digest([42u8; 32]);
+        assert_eq!(
+            remote_signer.sign_ecdsa(&message).unwrap(),
+            local_signer.sign_ecdsa(&message).unwrap()
+        );
+        assert_eq!(
+            remote_signer.sign_schnorr(&message).unwrap(),
+            local_signer.sign_schnorr(&message).unwrap()
+        );
+        assert_eq!(
+            remote_signer
+                .sign_schnorr_key_spend(&message, None)
+                .unwrap(),
+            local_signer.sign_schnorr_key_spend(&message, None).unwrap()
+        );
+
+        // The secret nonce stays in the signer process, so the partial signature is checked
+        // against the public nonce instead of a local signer.
+        let n_of_n_public_keys = vec![local_signer.public_key()];
+        let session_id = "test-session";
+        let public_nonce = remote_signer.generate_musig2_nonce(session_id).unwrap();
+        let aggregated_nonce = generate_aggregated_nonce(&vec![public_nonce.clone()]);
+        let partial_signature = remote_signer
+            .sign_musig2_partial(&n_of_n_public_keys, session_id, &aggregated_nonce, &message)
+            .unwrap();
+        let key_agg_ctx =
+            KeyAggContext::new(vec![Point::from(local_signer.public_key().inner)]).unwrap();
+        verify_partial(
+            &key_agg_ctx,
+            partial_signature,
+            &aggregated_nonce,
+            Point::from(local_signer.public_key().inner),
+            &public_nonce,
+            &message[..],
+        )
+        .unwrap();
+
+        // A nonce is consumed by signing and can't be reused
+        assert!(remote_signer
+            .sign_musig2_partial(&n_of_n_public_keys, session_id, &aggregated_nonce, &message)
+            .is_err());
+
+        let witness = remote_signer
+            .sign_winternitz(&winternitz_public_key, &[1, 2, 3, 4])
+            .unwrap();
+        assert!(!witness.is_empty());
+        assert!(local_signer
+            .sign_winternitz(&winternitz_public_key, &[1, 2, 3, 4])
+            .is_err());
+
+        // Commitment secrets are generated in the signer process and only their public keys
+        // are returned
+        let commitment_public_keys = remote_signer.generate_commitment_public_keys().unwrap();
+        let start_time_public_key = &commitment_public_keys[&CommitmentMessageId::StartTime];
+        assert!(remote_signer
+            .sign_winternitz(start_time_public_key, &[0u8; START_TIME_MESSAGE_LENGTH])
+            .is_ok());
+        assert!(local_signer
+            .sign_winternitz(start_time_public_key, &[0u8; START_TIME_MESSAGE_LENGTH])
+            .is_err());
+
+        let _ = std::fs::remove_file(&socket_path);
+    }
+}
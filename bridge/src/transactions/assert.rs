@@ -1,7 +1,7 @@
 use bitcoin::{
     absolute, consensus, Amount, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
 };
-use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
+use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -12,7 +12,9 @@ use super::{
             connector_c::ConnectorC,
         },
         contexts::{base::BaseContext, verifier::VerifierContext},
+        error::Error,
         graphs::base::DUST_AMOUNT,
+        signers::base::Musig2SessionId,
     },
     base::*,
     pre_signed::*,
@@ -138,24 +140,24 @@ impl AssertTransaction {
         &mut self,
         context: &VerifierContext,
         connector_b: &ConnectorB,
-        secret_nonce: &SecNonce,
-    ) {
+        session_id: &str,
+    ) -> Result<(), Error> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, TapSighashType::All, session_id)?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context, connector_b);
+            self.finalize_input_0(context, connector_b)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_b: &ConnectorB) {
+    fn finalize_input_0(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_b: &ConnectorB,
+    ) -> Result<(), Error> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
@@ -163,17 +165,17 @@ impl AssertTransaction {
             input_index,
             TapSighashType::All,
             connector_b.generate_taproot_spend_info(),
-        );
+        )
     }
 
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
         connector_b: &ConnectorB,
-        secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+        session_ids: &HashMap<usize, Musig2SessionId>,
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_b, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_b, &session_ids[&input_index])
     }
 
     pub fn merge(&mut self, assert: &AssertTransaction) {
@@ -2,7 +2,7 @@ use bitcoin::{
     absolute, consensus, Amount, EcdsaSighashType, PublicKey, ScriptBuf, TapSighashType,
     Transaction, TxOut,
 };
-use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
+use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -15,7 +15,9 @@ use super::{
                 base::*, connector_4::Connector4, connector_5::Connector5, connector_c::ConnectorC,
             },
             contexts::{base::BaseContext, verifier::VerifierContext},
+            error::Error,
             graphs::base::DUST_AMOUNT,
+            signers::base::Musig2SessionId,
         },
         base::*,
         pre_signed::*,
@@ -95,7 +97,7 @@ impl AssertFinalTransaction {
         input_0: Input,
         input_1: Input,
         input_2: Input,
-    ) -> Self {
+    ) -> Result<Self, Error> {
         let mut this = Self::new_for_validation(
             connector_4,
             connector_5,
@@ -107,9 +109,9 @@ impl AssertFinalTransaction {
             input_2,
         );
 
-        this.sign_commit_inputs(context);
+        this.sign_commit_inputs(context)?;
 
-        this
+        Ok(this)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -197,36 +199,38 @@ impl AssertFinalTransaction {
         &mut self,
         context: &VerifierContext,
         connector_d: &ConnectorD,
-        secret_nonce: &SecNonce,
-    ) {
+        session_id: &str,
+    ) -> Result<(), Error> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, TapSighashType::All, session_id)?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context, connector_d);
+            self.finalize_input_0(context, connector_d)?;
         }
+
+        Ok(())
     }
 
-    fn sign_commit_inputs(&mut self, context: &OperatorContext) {
+    fn sign_commit_inputs(&mut self, context: &OperatorContext) -> Result<(), Error> {
         let input_indexes = [1, 2];
         for input_index in input_indexes {
             pre_sign_p2wsh_input(
                 self,
                 input_index,
                 EcdsaSighashType::All,
-                &vec![&context.operator_keypair],
-            );
+                &vec![context.operator_signer.as_ref()],
+            )?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_d: &ConnectorD) {
+    fn finalize_input_0(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_d: &ConnectorD,
+    ) -> Result<(), Error> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
@@ -234,17 +238,17 @@ impl AssertFinalTransaction {
             input_index,
             TapSighashType::All,
             connector_d.generate_taproot_spend_info(),
-        );
+        )
     }
 
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
         connector_d: &ConnectorD,
-        secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+        session_ids: &HashMap<usize, Musig2SessionId>,
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_d, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_d, &session_ids[&input_index])
     }
 
     pub fn merge(&mut self, assert: &AssertFinalTransaction) {
@@ -69,7 +69,7 @@ pub fn sign_assert_tx_with_groth16_proof(
     commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
     proof: &RawProof,
 ) -> (Vec<RawWitness>, Vec<RawWitness>) {
-    println!("Signing assert tx with groth16 proof ...");
+    eprintln!("Signing assert tx with groth16 proof ...");
     let mut sorted_secrets: Vec<(u32, String)> = vec![];
     commitment_secrets.clone().into_iter().for_each(|(k, v)| {
         if let CommitmentMessageId::Groth16IntermediateValues((name, _)) = k {
@@ -102,25 +102,35 @@ pub fn groth16_commitment_secrets_to_public_keys(
     Vec<BTreeMap<CommitmentMessageId, WinternitzPublicKey>>,
     Vec<BTreeMap<CommitmentMessageId, WinternitzPublicKey>>,
 ) {
-    // hash map to btree map
-    let commitment_secrets: BTreeMap<CommitmentMessageId, WinternitzSecret> =
-        commitment_secrets.clone().into_iter().collect();
+    groth16_commitment_public_keys(
+        &commitment_secrets
+            .iter()
+            .map(|(message_id, secret)| (message_id.clone(), WinternitzPublicKey::from(secret)))
+            .collect(),
+    )
+}
 
+pub fn groth16_commitment_public_keys(
+    commitment_public_keys: &HashMap<CommitmentMessageId, WinternitzPublicKey>,
+) -> (
+    Vec<BTreeMap<CommitmentMessageId, WinternitzPublicKey>>,
+    Vec<BTreeMap<CommitmentMessageId, WinternitzPublicKey>>,
+) {
     // see the unit test: assigner.rs/test_commitment_size
     let connectors_e_of_transaction = 300;
     let mut connector_e1_commitment_public_keys = vec![];
     let mut connector_e2_commitment_public_keys = vec![];
 
-    let mut secrets_vec = vec![];
-    for (message_id, secret) in commitment_secrets.iter() {
+    let mut public_keys_vec = vec![];
+    for (message_id, public_key) in commitment_public_keys.iter() {
         if let CommitmentMessageId::Groth16IntermediateValues((name, _)) = message_id {
             let index = u32::from_str_radix(name, 10).unwrap();
-            secrets_vec.push((index, (message_id, secret)));
+            public_keys_vec.push((index, (message_id, public_key)));
         }
     }
 
-    secrets_vec.sort_by(|a, b| a.0.cmp(&b.0));
-    for (_, (message_id, secret)) in secrets_vec {
+    public_keys_vec.sort_by(|a, b| a.0.cmp(&b.0));
+    for (_, (message_id, public_key)) in public_keys_vec {
         let pushing_keys =
             if connector_e1_commitment_public_keys.len() < connectors_e_of_transaction {
                 &mut connector_e1_commitment_public_keys
@@ -128,10 +138,7 @@ pub fn groth16_commitment_secrets_to_public_keys(
                 &mut connector_e2_commitment_public_keys
             };
 
-        pushing_keys.push(BTreeMap::from([(
-            message_id.clone(),
-            WinternitzPublicKey::from(secret),
-        )]));
+        pushing_keys.push(BTreeMap::from([(message_id.clone(), public_key.clone())]));
     }
 
     assert!(connector_e1_commitment_public_keys.len() <= connectors_e_of_transaction);
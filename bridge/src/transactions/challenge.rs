@@ -1,6 +1,6 @@
 use bitcoin::{
-    absolute, consensus, key::Keypair, Amount, Network, PublicKey, ScriptBuf, Sequence,
-    TapSighashType, Transaction, TxIn, TxOut, Witness,
+    absolute, consensus, Amount, Network, PublicKey, ScriptBuf, Sequence, TapSighashType,
+    Transaction, TxIn, TxOut, Witness,
 };
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
@@ -8,7 +8,10 @@ use std::cmp::Ordering;
 use crate::{connectors::base::TaprootConnector, graphs::base::DUST_AMOUNT};
 
 use super::{
-    super::{connectors::connector_a::ConnectorA, contexts::operator::OperatorContext, scripts::*},
+    super::{
+        connectors::connector_a::ConnectorA, contexts::operator::OperatorContext, error::Error,
+        scripts::*, signers::base::Signer,
+    },
     base::*,
     pre_signed::*,
     signing::populate_p2wsh_witness,
@@ -48,7 +51,7 @@ impl ChallengeTransaction {
         connector_a: &ConnectorA,
         input_0: Input,
         input_amount_crowdfunding: Amount,
-    ) -> Self {
+    ) -> Result<Self, Error> {
         let mut this = Self::new_for_validation(
             context.network,
             &context.operator_public_key,
@@ -57,9 +60,9 @@ impl ChallengeTransaction {
             input_amount_crowdfunding,
         );
 
-        this.sign_input_0(context, connector_a);
+        this.sign_input_0(context, connector_a)?;
 
-        this
+        Ok(this)
     }
 
     pub fn new_for_validation(
@@ -103,23 +106,27 @@ impl ChallengeTransaction {
         }
     }
 
-    fn sign_input_0(&mut self, context: &OperatorContext, connector_a: &ConnectorA) {
+    fn sign_input_0(
+        &mut self,
+        context: &OperatorContext,
+        connector_a: &ConnectorA,
+    ) -> Result<(), Error> {
         pre_sign_taproot_input_default(
             self,
             0,
             TapSighashType::SinglePlusAnyoneCanPay,
             connector_a.generate_taproot_spend_info(),
-            &vec![&context.operator_keypair],
-        );
+            &vec![context.operator_signer.as_ref()],
+        )
     }
 
     // allows for aggregating multiple inputs and one refund output
     pub fn add_inputs_and_output(
         &mut self,
         inputs: &Vec<InputWithScript>,
-        keypair: &Keypair,
+        signer: &dyn Signer,
         output_script_pubkey: ScriptBuf,
-    ) {
+    ) -> Result<(), Error> {
         if self.tx.input.len() > 1 {
             panic!("Cannot add any more inputs or outputs.");
         }
@@ -165,11 +172,13 @@ impl ChallengeTransaction {
                 sighash_type,
                 input.script,
                 input.amount,
-                &vec![&keypair],
-            );
+                &vec![signer],
+            )?;
 
             input_index += 1;
         }
+
+        Ok(())
     }
 
     pub fn merge(&mut self, challenge: &ChallengeTransaction) {
@@ -2,7 +2,7 @@ use bitcoin::{
     absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
 };
 use bitvm::chunk::api::type_conversion_utils::RawWitness;
-use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
+use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -10,7 +10,9 @@ use super::{
     super::{
         connectors::{base::*, connector_5::Connector5, connector_c::ConnectorC},
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
+        error::Error,
         scripts::*,
+        signers::base::Musig2SessionId,
     },
     base::*,
     pre_signed::*,
@@ -148,24 +150,30 @@ impl DisproveTransaction {
         &mut self,
         context: &VerifierContext,
         connector_5: &Connector5,
-        secret_nonce: &SecNonce,
-    ) {
+        session_id: &str,
+    ) -> Result<(), Error> {
         let input_index = 0;
         pre_sign_musig2_taproot_input(
             self,
             context,
             input_index,
             TapSighashType::Single,
-            secret_nonce,
-        );
+            session_id,
+        )?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context, connector_5);
+            self.finalize_input_0(context, connector_5)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_5: &Connector5) {
+    fn finalize_input_0(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_5: &Connector5,
+    ) -> Result<(), Error> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
@@ -173,17 +181,17 @@ impl DisproveTransaction {
             input_index,
             TapSighashType::Single,
             connector_5.generate_taproot_spend_info(),
-        );
+        )
     }
 
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
         connector_5: &Connector5,
-        secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+        session_ids: &HashMap<usize, Musig2SessionId>,
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_5, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_5, &session_ids[&input_index])
     }
 
     pub fn add_input_output(
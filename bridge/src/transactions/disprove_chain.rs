@@ -2,7 +2,7 @@ use bitcoin::{
     absolute, block::Header, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType,
     Transaction, TxOut, Witness,
 };
-use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
+use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -12,7 +12,9 @@ use super::{
     super::{
         connectors::connector_b::ConnectorB,
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
+        error::Error,
         scripts::*,
+        signers::base::Musig2SessionId,
     },
     base::*,
     pre_signed::*,
@@ -126,24 +128,30 @@ impl DisproveChainTransaction {
         &mut self,
         context: &VerifierContext,
         connector_b: &ConnectorB,
-        secret_nonce: &SecNonce,
-    ) {
+        session_id: &str,
+    ) -> Result<(), Error> {
         let input_index = 0;
         pre_sign_musig2_taproot_input(
             self,
             context,
             input_index,
             TapSighashType::Single,
-            secret_nonce,
-        );
+            session_id,
+        )?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context, connector_b);
+            self.finalize_input_0(context, connector_b)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_b: &ConnectorB) {
+    fn finalize_input_0(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_b: &ConnectorB,
+    ) -> Result<(), Error> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
@@ -151,20 +159,22 @@ impl DisproveChainTransaction {
             input_index,
             TapSighashType::Single,
             connector_b.generate_taproot_spend_info(),
-        );
+        )
     }
 
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
         connector_b: &ConnectorB,
-        secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+        session_ids: &HashMap<usize, Musig2SessionId>,
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_b, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_b, &session_ids[&input_index])?;
         // TODO: We probably shouldn't finalize the witness when pre-signing (sign_input_0 calls finalize_input_0,
         // which adds a control block to the witness). Please double-check that the control block should be only added
         // after the tx is signed (see `sign()`) and ready to be broadcast.
+
+        Ok(())
     }
 
     pub fn sign(
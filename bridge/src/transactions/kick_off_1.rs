@@ -6,18 +6,19 @@ use serde::{Deserialize, Serialize};
 
 use super::{
     super::{
+        commitments::CommitmentMessageId,
         connectors::{
             base::*, connector_1::Connector1, connector_2::Connector2, connector_6::Connector6,
             connector_a::ConnectorA,
         },
         contexts::operator::OperatorContext,
+        error::Error,
         graphs::base::DUST_AMOUNT,
     },
     base::*,
     pre_signed::*,
     signing::{generate_taproot_leaf_schnorr_signature, populate_taproot_input_witness},
 };
-use bitvm::signatures::signing_winternitz::{generate_winternitz_witness, WinternitzSigningInputs};
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct KickOff1Transaction {
@@ -120,9 +121,9 @@ impl KickOff1Transaction {
         &mut self,
         context: &OperatorContext,
         connector_6: &Connector6,
-        source_network_txid_inputs: &WinternitzSigningInputs,
-        destination_network_txid_inputs: &WinternitzSigningInputs,
-    ) {
+        source_network_txid: &[u8],
+        destination_network_txid: &[u8],
+    ) -> Result<(), Error> {
         let input_index = 0;
         let script = &self.prev_scripts()[input_index].clone();
         let prev_outs = &self.prev_outs().clone();
@@ -136,15 +137,30 @@ impl KickOff1Transaction {
             input_index,
             TapSighashType::All,
             script,
-            &context.operator_keypair,
-        );
+            context.operator_signer.as_ref(),
+        )?;
         unlock_data.push(schnorr_signature.to_vec());
 
         // get winternitz signature for source network txid
-        unlock_data.extend(generate_winternitz_witness(source_network_txid_inputs).to_vec());
+        let source_network_txid_witness = context
+            .operator_signer
+            .sign_winternitz(
+                &connector_6.commitment_public_keys[&CommitmentMessageId::PegOutTxIdSourceNetwork],
+                source_network_txid,
+            )
+            .map_err(Error::Signer)?;
+        unlock_data.extend(source_network_txid_witness.to_vec());
 
         // get winternitz signature for destination network txid
-        unlock_data.extend(generate_winternitz_witness(destination_network_txid_inputs).to_vec());
+        let destination_network_txid_witness = context
+            .operator_signer
+            .sign_winternitz(
+                &connector_6.commitment_public_keys
+                    [&CommitmentMessageId::PegOutTxIdDestinationNetwork],
+                destination_network_txid,
+            )
+            .map_err(Error::Signer)?;
+        unlock_data.extend(destination_network_txid_witness.to_vec());
 
         populate_taproot_input_witness(
             self.tx_mut(),
@@ -153,21 +169,23 @@ impl KickOff1Transaction {
             script,
             unlock_data,
         );
+
+        Ok(())
     }
 
     pub fn sign(
         &mut self,
         context: &OperatorContext,
         connector_6: &Connector6,
-        source_network_txid_inputs: &WinternitzSigningInputs,
-        destination_network_txid_inputs: &WinternitzSigningInputs,
-    ) {
+        source_network_txid: &[u8],
+        destination_network_txid: &[u8],
+    ) -> Result<(), Error> {
         self.sign_input_0(
             context,
             connector_6,
-            source_network_txid_inputs,
-            destination_network_txid_inputs,
-        );
+            source_network_txid,
+            destination_network_txid,
+        )
     }
 }
 
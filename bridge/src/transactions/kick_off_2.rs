@@ -8,8 +8,10 @@ use crate::connectors::base::{P2wshConnector, TaprootConnector};
 
 use super::{
     super::{
+        commitments::CommitmentMessageId,
         connectors::{connector_1::Connector1, connector_3::Connector3, connector_b::ConnectorB},
         contexts::operator::OperatorContext,
+        error::Error,
         graphs::base::DUST_AMOUNT,
     },
     base::*,
@@ -17,8 +19,6 @@ use super::{
     signing::{generate_taproot_leaf_schnorr_signature, populate_taproot_input_witness},
 };
 
-use bitvm::signatures::signing_winternitz::{generate_winternitz_witness, WinternitzSigningInputs};
-
 pub const MIN_RELAY_FEE_AMOUNT: u64 = 105_771;
 
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
@@ -110,9 +110,9 @@ impl KickOff2Transaction {
         &mut self,
         context: &OperatorContext,
         connector_1: &Connector1,
-        superblock_signing_inputs: &WinternitzSigningInputs,
-        superblock_hash_signing_inputs: &WinternitzSigningInputs,
-    ) {
+        superblock_message: &[u8],
+        superblock_hash_message: &[u8],
+    ) -> Result<(), Error> {
         let input_index = 0;
         let prev_outs = &self.prev_outs().clone();
         let script = &self.prev_scripts()[input_index].clone();
@@ -125,14 +125,28 @@ impl KickOff2Transaction {
             input_index,
             TapSighashType::All,
             script,
-            &context.operator_keypair,
-        );
+            context.operator_signer.as_ref(),
+        )?;
         unlock_data.push(schnorr_signature.to_vec());
 
-        unlock_data.extend(generate_winternitz_witness(superblock_signing_inputs).to_vec());
-
-        self.superblock_hash_witness =
-            Some(generate_winternitz_witness(superblock_hash_signing_inputs));
+        let superblock_witness = context
+            .operator_signer
+            .sign_winternitz(
+                &connector_1.commitment_public_keys[&CommitmentMessageId::Superblock],
+                superblock_message,
+            )
+            .map_err(Error::Signer)?;
+        unlock_data.extend(superblock_witness.to_vec());
+
+        self.superblock_hash_witness = Some(
+            context
+                .operator_signer
+                .sign_winternitz(
+                    &connector_1.commitment_public_keys[&CommitmentMessageId::SuperblockHash],
+                    superblock_hash_message,
+                )
+                .map_err(Error::Signer)?,
+        );
         unlock_data.extend(self.superblock_hash_witness.as_ref().unwrap().to_vec());
 
         populate_taproot_input_witness(
@@ -142,21 +156,23 @@ impl KickOff2Transaction {
             script,
             unlock_data,
         );
+
+        Ok(())
     }
 
     pub fn sign(
         &mut self,
         context: &OperatorContext,
         connector_1: &Connector1,
-        superblock_signing_inputs: &WinternitzSigningInputs,
-        superblock_hash_signing_inputs: &WinternitzSigningInputs,
-    ) {
+        superblock_message: &[u8],
+        superblock_hash_message: &[u8],
+    ) -> Result<(), Error> {
         self.sign_input_0(
             context,
             connector_1,
-            superblock_signing_inputs,
-            superblock_hash_signing_inputs,
-        );
+            superblock_message,
+            superblock_hash_message,
+        )
     }
 }
 
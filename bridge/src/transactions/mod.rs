@@ -14,6 +14,7 @@ pub mod peg_out;
 pub mod peg_out_confirm;
 pub mod pre_signed;
 pub mod pre_signed_musig2;
+pub mod psbt;
 pub mod signing;
 pub mod signing_musig2;
 pub mod start_time;
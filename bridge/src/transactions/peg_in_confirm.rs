@@ -1,7 +1,7 @@
 use bitcoin::{
     absolute, consensus, Amount, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
 };
-use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
+use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -9,6 +9,8 @@ use super::{
     super::{
         connectors::{base::*, connector_0::Connector0, connector_z::ConnectorZ},
         contexts::{base::BaseContext, depositor::DepositorContext, verifier::VerifierContext},
+        error::Error,
+        signers::base::Musig2SessionId,
     },
     base::*,
     pre_signed::*,
@@ -85,7 +87,7 @@ impl PegInConfirmTransaction {
         connector_0: &Connector0,
         connector_z: &ConnectorZ,
         input_0: Input,
-    ) -> Self {
+    ) -> Result<Self, Error> {
         let mut this = Self::new_for_validation(
             connector_0,
             connector_z,
@@ -93,9 +95,9 @@ impl PegInConfirmTransaction {
             context.n_of_n_public_keys.clone(),
         );
 
-        this.generate_and_push_depositor_signature_input_0(context);
+        this.generate_and_push_depositor_signature_input_0(context)?;
 
-        this
+        Ok(this)
     }
 
     pub fn new_with_depositor_signature(
@@ -152,7 +154,10 @@ impl PegInConfirmTransaction {
         }
     }
 
-    fn generate_and_push_depositor_signature_input_0(&mut self, context: &DepositorContext) {
+    fn generate_and_push_depositor_signature_input_0(
+        &mut self,
+        context: &DepositorContext,
+    ) -> Result<(), Error> {
         let input_index = 0;
         let schnorr_signature = generate_taproot_leaf_schnorr_signature(
             &mut self.tx,
@@ -160,10 +165,12 @@ impl PegInConfirmTransaction {
             input_index,
             TapSighashType::All,
             &self.prev_scripts[input_index],
-            &context.depositor_keypair,
-        );
+            context.depositor_signer.as_ref(),
+        )?;
 
         self.push_depositor_signature_input(input_index, schnorr_signature);
+
+        Ok(())
     }
 
     fn push_depositor_signature_input(
@@ -180,24 +187,24 @@ impl PegInConfirmTransaction {
         &mut self,
         context: &VerifierContext,
         connector_z: &ConnectorZ,
-        secret_nonce: &SecNonce,
-    ) {
+        session_id: &str,
+    ) -> Result<(), Error> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, TapSighashType::All, session_id)?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context, connector_z);
+            self.finalize_input_0(context, connector_z)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_z: &ConnectorZ) {
+    fn finalize_input_0(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_z: &ConnectorZ,
+    ) -> Result<(), Error> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
@@ -205,17 +212,17 @@ impl PegInConfirmTransaction {
             input_index,
             TapSighashType::All,
             connector_z.generate_taproot_spend_info(),
-        );
+        )
     }
 
     pub fn pre_sign(
         &mut self,
         context: &VerifierContext,
         connector_z: &ConnectorZ,
-        secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+        session_ids: &HashMap<usize, Musig2SessionId>,
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.push_verifier_signature_input_0(context, connector_z, &secret_nonces[&input_index]);
+        self.push_verifier_signature_input_0(context, connector_z, &session_ids[&input_index])
     }
 
     pub fn merge(&mut self, peg_in_confirm: &PegInConfirmTransaction) {
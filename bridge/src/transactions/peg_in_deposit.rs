@@ -9,6 +9,7 @@ use super::{
     super::{
         connectors::{base::*, connector_z::ConnectorZ},
         contexts::depositor::DepositorContext,
+        error::Error,
         scripts::*,
     },
     base::*,
@@ -44,7 +45,11 @@ impl PreSignedTransaction for PegInDepositTransaction {
 }
 
 impl PegInDepositTransaction {
-    pub fn new(context: &DepositorContext, connector_z: &ConnectorZ, input_0: Input) -> Self {
+    pub fn new(
+        context: &DepositorContext,
+        connector_z: &ConnectorZ,
+        input_0: Input,
+    ) -> Result<Self, Error> {
         let mut this = Self::new_for_validation(
             context.network,
             &context.depositor_public_key,
@@ -52,9 +57,9 @@ impl PegInDepositTransaction {
             input_0,
         );
 
-        this.sign_input_0(context);
+        this.sign_input_0(context)?;
 
-        this
+        Ok(this)
     }
 
     pub fn new_with_signature(
@@ -103,14 +108,14 @@ impl PegInDepositTransaction {
         }
     }
 
-    fn sign_input_0(&mut self, context: &DepositorContext) {
+    fn sign_input_0(&mut self, context: &DepositorContext) -> Result<(), Error> {
         let input_index = 0;
         pre_sign_p2wsh_input(
             self,
             input_index,
             EcdsaSighashType::All,
-            &vec![&context.depositor_keypair],
-        );
+            &vec![context.depositor_signer.as_ref()],
+        )
     }
 
     fn sign_input_0_with_signature(&mut self, signature: bitcoin::ecdsa::Signature) {
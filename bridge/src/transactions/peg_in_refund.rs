@@ -7,6 +7,7 @@ use super::{
     super::{
         connectors::{base::*, connector_z::ConnectorZ},
         contexts::depositor::DepositorContext,
+        error::Error,
         scripts::*,
     },
     base::*,
@@ -42,7 +43,11 @@ impl PreSignedTransaction for PegInRefundTransaction {
 }
 
 impl PegInRefundTransaction {
-    pub fn new(context: &DepositorContext, connector_z: &ConnectorZ, input_0: Input) -> Self {
+    pub fn new(
+        context: &DepositorContext,
+        connector_z: &ConnectorZ,
+        input_0: Input,
+    ) -> Result<Self, Error> {
         let mut this = Self::new_for_validation(
             context.network,
             &context.depositor_public_key,
@@ -50,9 +55,9 @@ impl PegInRefundTransaction {
             input_0,
         );
 
-        this.sign_input_0(context, connector_z);
+        this.sign_input_0(context, connector_z)?;
 
-        this
+        Ok(this)
     }
 
     pub fn new_with_signature(
@@ -102,14 +107,18 @@ impl PegInRefundTransaction {
         }
     }
 
-    fn sign_input_0(&mut self, context: &DepositorContext, connector_z: &ConnectorZ) {
+    fn sign_input_0(
+        &mut self,
+        context: &DepositorContext,
+        connector_z: &ConnectorZ,
+    ) -> Result<(), Error> {
         pre_sign_taproot_input_default(
             self,
             0,
             TapSighashType::All,
             connector_z.generate_taproot_spend_info(),
-            &vec![&context.depositor_keypair],
-        );
+            &vec![context.depositor_signer.as_ref()],
+        )
     }
 
     fn sign_input_0_with_signature(
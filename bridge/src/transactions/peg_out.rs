@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use crate::client::chain::chain::PegOutEvent;
 
 use super::{
-    super::{contexts::operator::OperatorContext, scripts::*},
+    super::{contexts::operator::OperatorContext, error::Error, scripts::*},
     base::*,
     pre_signed::*,
 };
@@ -40,7 +40,11 @@ impl PreSignedTransaction for PegOutTransaction {
 }
 
 impl PegOutTransaction {
-    pub fn new(context: &OperatorContext, peg_out_event: &PegOutEvent, input_0: Input) -> Self {
+    pub fn new(
+        context: &OperatorContext,
+        peg_out_event: &PegOutEvent,
+        input_0: Input,
+    ) -> Result<Self, Error> {
         let mut this = Self::new_for_validation(
             context.network,
             &context.operator_public_key,
@@ -48,9 +52,9 @@ impl PegOutTransaction {
             input_0,
         );
 
-        this.sign_input_0(context);
+        this.sign_input_0(context)?;
 
-        this
+        Ok(this)
     }
 
     pub fn new_for_validation(
@@ -95,14 +99,14 @@ impl PegOutTransaction {
         }
     }
 
-    fn sign_input_0(&mut self, context: &OperatorContext) {
+    fn sign_input_0(&mut self, context: &OperatorContext) -> Result<(), Error> {
         let input_index = 0;
         pre_sign_p2wsh_input(
             self,
             input_index,
             EcdsaSighashType::All,
-            &vec![&context.operator_keypair],
-        );
+            &vec![context.operator_signer.as_ref()],
+        )
     }
 }
 
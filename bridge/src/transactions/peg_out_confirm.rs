@@ -8,6 +8,7 @@ use super::{
     super::{
         connectors::{base::*, connector_6::Connector6},
         contexts::operator::OperatorContext,
+        error::Error,
         scripts::*,
     },
     base::*,
@@ -42,7 +43,11 @@ impl PreSignedTransaction for PegOutConfirmTransaction {
 }
 
 impl PegOutConfirmTransaction {
-    pub fn new(context: &OperatorContext, connector_6: &Connector6, input_0: Input) -> Self {
+    pub fn new(
+        context: &OperatorContext,
+        connector_6: &Connector6,
+        input_0: Input,
+    ) -> Result<Self, Error> {
         let mut this = Self::new_for_validation(
             context.network,
             &context.operator_public_key,
@@ -50,9 +55,9 @@ impl PegOutConfirmTransaction {
             input_0,
         );
 
-        this.sign_input_0(context);
+        this.sign_input_0(context)?;
 
-        this
+        Ok(this)
     }
 
     pub fn new_for_validation(
@@ -86,14 +91,14 @@ impl PegOutConfirmTransaction {
         }
     }
 
-    fn sign_input_0(&mut self, context: &OperatorContext) {
+    fn sign_input_0(&mut self, context: &OperatorContext) -> Result<(), Error> {
         let input_index = 0;
         pre_sign_p2wsh_input(
             self,
             input_index,
             EcdsaSighashType::All,
-            &vec![&context.operator_keypair],
-        );
+            &vec![context.operator_signer.as_ref()],
+        )
     }
 }
 
@@ -1,10 +1,10 @@
 use bitcoin::{
-    key::Keypair, taproot::TaprootSpendInfo, EcdsaSighashType, PublicKey, ScriptBuf,
-    TapSighashType, Transaction, TxOut,
+    taproot::TaprootSpendInfo, EcdsaSighashType, PublicKey, ScriptBuf, TapSighashType, Transaction,
+    TxOut,
 };
 
 use super::{
-    super::contexts::base::BaseContext,
+    super::{contexts::base::BaseContext, error::Error, signers::base::Signer},
     signing::{
         populate_p2wpkh_witness, populate_p2wsh_witness, populate_taproot_input_witness_default,
     },
@@ -21,8 +21,8 @@ pub fn pre_sign_p2wsh_input<T: PreSignedTransaction>(
     tx: &mut T,
     input_index: usize,
     sighash_type: EcdsaSighashType,
-    keypairs: &Vec<&Keypair>,
-) {
+    signers: &Vec<&dyn Signer>,
+) -> Result<(), Error> {
     let script = &tx.prev_scripts()[input_index].clone();
     let value = tx.prev_outs()[input_index].value;
 
@@ -32,8 +32,8 @@ pub fn pre_sign_p2wsh_input<T: PreSignedTransaction>(
         sighash_type,
         script,
         value,
-        keypairs,
-    );
+        signers,
+    )
 }
 
 pub fn pre_sign_p2wpkh_input<T: PreSignedTransaction>(
@@ -42,8 +42,8 @@ pub fn pre_sign_p2wpkh_input<T: PreSignedTransaction>(
     input_index: usize,
     sighash_type: EcdsaSighashType,
     public_key: &PublicKey,
-    keypair: &Keypair,
-) {
+    signer: &dyn Signer,
+) -> Result<(), Error> {
     let value = tx.prev_outs()[input_index].value;
 
     populate_p2wpkh_witness(
@@ -53,8 +53,8 @@ pub fn pre_sign_p2wpkh_input<T: PreSignedTransaction>(
         sighash_type,
         value,
         public_key,
-        keypair,
-    );
+        signer,
+    )
 }
 
 pub fn pre_sign_taproot_input_default<T: PreSignedTransaction>(
@@ -62,8 +62,8 @@ pub fn pre_sign_taproot_input_default<T: PreSignedTransaction>(
     input_index: usize,
     sighash_type: TapSighashType,
     taproot_spend_info: TaprootSpendInfo,
-    keypairs: &Vec<&Keypair>,
-) {
+    signers: &Vec<&dyn Signer>,
+) -> Result<(), Error> {
     let prev_outs = &tx.prev_outs().clone();
     let script = &tx.prev_scripts()[input_index].clone();
 
@@ -74,6 +74,6 @@ pub fn pre_sign_taproot_input_default<T: PreSignedTransaction>(
         sighash_type,
         &taproot_spend_info,
         script,
-        keypairs,
-    );
+        signers,
+    )
 }
@@ -6,17 +6,21 @@ use bitcoin::{
 use musig2::{
     secp::MaybeScalar,
     secp256k1::{schnorr::Signature, Message},
-    BinaryEncoding, PartialSignature, PubNonce, SecNonce,
+    BinaryEncoding, PartialSignature, PubNonce,
 };
 use std::collections::HashMap;
 
 use super::{
-    super::contexts::{base::BaseContext, verifier::VerifierContext},
+    super::{
+        contexts::{base::BaseContext, verifier::VerifierContext},
+        error::Error,
+        signers::base::Musig2SessionId,
+    },
     pre_signed::PreSignedTransaction,
     signing::push_taproot_leaf_script_and_control_block_to_witness,
     signing_musig2::{
-        generate_aggregated_nonce, generate_nonce, generate_taproot_aggregated_signature,
-        generate_taproot_partial_signature,
+        generate_aggregated_nonce, generate_musig2_session_id,
+        generate_taproot_aggregated_signature, generate_taproot_partial_signature,
     },
 };
 
@@ -53,25 +57,38 @@ pub trait PreSignedMusig2Transaction: PreSignedTransaction {
             })
         })
     }
-    fn push_nonces(&mut self, context: &VerifierContext) -> HashMap<usize, SecNonce> {
+    fn push_nonces(
+        &mut self,
+        context: &VerifierContext,
+    ) -> Result<HashMap<usize, Musig2SessionId>, Error> {
         self.verifier_inputs()
             .iter()
-            .map(|input_index| (*input_index, self.push_nonce(context, *input_index)))
+            .map(|input_index| Ok((*input_index, self.push_nonce(context, *input_index)?)))
             .collect()
     }
 
-    fn push_nonce(&mut self, context: &VerifierContext, input_index: usize) -> SecNonce {
+    fn push_nonce(
+        &mut self,
+        context: &VerifierContext,
+        input_index: usize,
+    ) -> Result<Musig2SessionId, Error> {
+        // The secret nonce stays in the verifier's signer, only the public nonce is pushed
+        let session_id = generate_musig2_session_id(self.tx().compute_txid(), input_index);
+        let public_nonce = context
+            .verifier_signer
+            .generate_musig2_nonce(&session_id)
+            .map_err(Error::Signer)?;
+
         // Push nonce
         let musig2_nonces = self.musig2_nonces_mut();
         if musig2_nonces.get(&input_index).is_none() {
             musig2_nonces.insert(input_index, HashMap::new());
         }
 
-        let secret_nonce = generate_nonce();
         musig2_nonces
             .get_mut(&input_index)
             .unwrap()
-            .insert(context.verifier_public_key, secret_nonce.public_nonce());
+            .insert(context.verifier_public_key, public_nonce.clone());
 
         // Sign the nonce and push the signature
         let musig2_nonce_signatures = self.musig2_nonce_signatures_mut();
@@ -80,15 +97,16 @@ pub trait PreSignedMusig2Transaction: PreSignedTransaction {
         }
 
         let nonce_signature = context
-            .verifier_keypair
-            .sign_schnorr(get_nonce_message(&secret_nonce.public_nonce()));
+            .verifier_signer
+            .sign_schnorr(&get_nonce_message(&public_nonce))
+            .map_err(Error::Signer)?;
 
         musig2_nonce_signatures
             .get_mut(&input_index)
             .unwrap()
             .insert(context.verifier_public_key, nonce_signature);
 
-        secret_nonce
+        Ok(session_id)
     }
 }
 
@@ -116,8 +134,8 @@ pub fn pre_sign_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Tr
     context: &VerifierContext,
     input_index: usize,
     sighash_type: TapSighashType,
-    secret_nonce: &SecNonce,
-) {
+    session_id: &str,
+) -> Result<(), Error> {
     // TODO validate nonces first
 
     let prev_outs = &tx.prev_outs().clone();
@@ -127,14 +145,14 @@ pub fn pre_sign_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Tr
     let partial_signature = generate_taproot_partial_signature(
         context,
         tx.tx_mut(),
-        secret_nonce,
+        session_id,
         &generate_aggregated_nonce(musig2_nonces),
         input_index,
         prev_outs,
         script,
         sighash_type,
     )
-    .unwrap(); // TODO: Add error handling.
+    .map_err(Error::Signer)?;
 
     let musig2_signatures = tx.musig2_signatures_mut();
     if musig2_signatures.get(&input_index).is_none() {
@@ -144,6 +162,8 @@ pub fn pre_sign_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Tr
         .get_mut(&input_index)
         .unwrap()
         .insert(context.verifier_public_key, partial_signature);
+
+    Ok(())
 }
 
 pub fn finalize_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Transaction>(
@@ -152,7 +172,7 @@ pub fn finalize_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Tr
     input_index: usize,
     sighash_type: TapSighashType,
     taproot_spend_info: TaprootSpendInfo,
-) {
+) -> Result<(), Error> {
     // TODO: Verify we have partial signatures from all verifiers.
     // TODO: Verify each signature against the signers public key.
     // See example here: https://github.com/conduition/musig2/blob/c39bfce58098d337a3ec38b54d93def8306d9953/src/signing.rs#L358C1-L366C65
@@ -178,7 +198,11 @@ pub fn finalize_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Tr
         sighash_type,
         musig2_signatures, // TODO: Is there a more elegant way of doing this?
     )
-    .unwrap(); // TODO: Add error handling.
+    .map_err(|e| {
+        Error::Other(format!(
+            "Failed to aggregate MuSig2 signatures for input {input_index}: {e}"
+        ))
+    })?;
 
     let final_signature = bitcoin::taproot::Signature {
         signature: signature.into(),
@@ -197,4 +221,6 @@ pub fn finalize_musig2_taproot_input<T: PreSignedTransaction + PreSignedMusig2Tr
         &taproot_spend_info,
         script,
     );
+
+    Ok(())
 }
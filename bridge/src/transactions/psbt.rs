@@ -0,0 +1,729 @@
+use bitcoin::{
+    ecdsa,
+    hashes::Hash,
+    opcodes::all::{OP_CHECKSIG, OP_CSV, OP_DROP},
+    psbt::{raw, Input as PsbtInputFields, Psbt, PsbtSighashType},
+    script::Instruction,
+    secp256k1::Message,
+    sighash::{Prevouts, SighashCache},
+    taproot::{
+        self, ControlBlock, LeafVersion, TapLeafHash, TaprootSpendInfo, TAPROOT_ANNEX_PREFIX,
+    },
+    EcdsaSighashType, PublicKey, Script, ScriptBuf, TapSighashType, Transaction, TxOut, Witness,
+    XOnlyPublicKey,
+};
+use bitvm::dry_run_taproot_input;
+use musig2::{secp256k1::schnorr::Signature, BinaryEncoding, PartialSignature, PubNonce};
+use secp256k1::SECP256K1;
+
+use super::{
+    super::{
+        contexts::base::generate_n_of_n_public_key,
+        error::{Error, PsbtError},
+        scripts::{generate_pay_to_pubkey_script, generate_pay_to_pubkey_taproot_script},
+    },
+    base::{verify_public_nonces_for_tx, BaseTransaction},
+    pre_signed::PreSignedTransaction,
+    pre_signed_musig2::PreSignedMusig2Transaction,
+    signing_musig2::{generate_aggregated_nonce, verify_taproot_partial_signature},
+};
+
+// BIP-373 input fields. The key data of the nonce and partial signature fields is
+// <participant public key> <aggregate public key> <tap leaf hash>.
+pub const PSBT_IN_MUSIG2_PARTICIPANT_PUBKEYS: u8 = 0x1a;
+pub const PSBT_IN_MUSIG2_PUB_NONCE: u8 = 0x1b;
+pub const PSBT_IN_MUSIG2_PARTIAL_SIG: u8 = 0x1c;
+
+// Nonces are only accepted together with the participant's signature over them (see
+// `verify_public_nonces_for_tx`), which BIP-373 has no field for.
+pub const PSBT_PROPRIETARY_PREFIX: &[u8] = b"bitvm";
+pub const PSBT_IN_BITVM_MUSIG2_NONCE_SIGNATURE: u8 = 0x00;
+
+const PUBLIC_KEY_SIZE: usize = 33;
+const TAP_LEAF_HASH_SIZE: usize = 32;
+
+pub enum PsbtSpend {
+    TaprootScript(TaprootSpendInfo),
+    TaprootKey(TaprootSpendInfo),
+    P2wsh,
+    Unknown,
+}
+
+/// How an input of a pre-signed transaction is spent, which determines the PSBT fields
+/// an external signer needs. The leaf and witness scripts are taken from `prev_scripts`.
+pub struct PsbtInput {
+    pub spend: PsbtSpend,
+    pub sighash_type: Option<PsbtSighashType>,
+}
+
+impl PsbtInput {
+    pub fn taproot_script(spend_info: TaprootSpendInfo, sighash_type: TapSighashType) -> Self {
+        PsbtInput {
+            spend: PsbtSpend::TaprootScript(spend_info),
+            sighash_type: Some(sighash_type.into()),
+        }
+    }
+
+    // Leaves that only require Winternitz commitments, e.g. the assert commit transactions.
+    pub fn taproot_script_unsigned(spend_info: TaprootSpendInfo) -> Self {
+        PsbtInput {
+            spend: PsbtSpend::TaprootScript(spend_info),
+            sighash_type: None,
+        }
+    }
+
+    pub fn taproot_key(spend_info: TaprootSpendInfo, sighash_type: TapSighashType) -> Self {
+        PsbtInput {
+            spend: PsbtSpend::TaprootKey(spend_info),
+            sighash_type: Some(sighash_type.into()),
+        }
+    }
+
+    pub fn p2wsh(sighash_type: EcdsaSighashType) -> Self {
+        PsbtInput {
+            spend: PsbtSpend::P2wsh,
+            sighash_type: Some(sighash_type.into()),
+        }
+    }
+
+    pub fn unknown() -> Self {
+        PsbtInput {
+            spend: PsbtSpend::Unknown,
+            sighash_type: None,
+        }
+    }
+}
+
+/// Exports the unsigned transaction with the data required to sign each input. Witnesses
+/// already present in the transaction are exported as final script witnesses. Inputs without
+/// a matching entry in `inputs` only carry their witness UTXO.
+pub fn export_psbt<T: BaseTransaction + PreSignedTransaction>(
+    tx: &T,
+    inputs: Vec<PsbtInput>,
+) -> Result<Psbt, Error> {
+    let mut unsigned_tx = tx.tx().clone();
+    unsigned_tx
+        .input
+        .iter_mut()
+        .for_each(|input| input.witness = Witness::default());
+    let mut psbt =
+        Psbt::from_unsigned_tx(unsigned_tx).map_err(|e| Error::Psbt(PsbtError::Invalid(e)))?;
+
+    for (input_index, psbt_input) in psbt.inputs.iter_mut().enumerate() {
+        psbt_input.witness_utxo = tx.prev_outs().get(input_index).cloned();
+
+        let witness = &tx.tx().input[input_index].witness;
+        if !witness.is_empty() {
+            psbt_input.final_script_witness = Some(witness.clone());
+        }
+
+        let Some(input) = inputs.get(input_index) else {
+            continue;
+        };
+        psbt_input.sighash_type = input.sighash_type;
+
+        let script = tx.prev_scripts().get(input_index);
+        match &input.spend {
+            PsbtSpend::TaprootScript(spend_info) => {
+                let script = script.ok_or(Error::Psbt(PsbtError::MissingPrevScript(
+                    tx.name(),
+                    input_index,
+                )))?;
+                let control_block = spend_info
+                    .control_block(&(script.clone(), LeafVersion::TapScript))
+                    .ok_or(Error::Psbt(PsbtError::LeafNotFound(tx.name(), input_index)))?;
+
+                psbt_input.tap_internal_key = Some(spend_info.internal_key());
+                psbt_input.tap_merkle_root = spend_info.merkle_root();
+                psbt_input
+                    .tap_scripts
+                    .insert(control_block, (script.clone(), LeafVersion::TapScript));
+            }
+            PsbtSpend::TaprootKey(spend_info) => {
+                psbt_input.tap_internal_key = Some(spend_info.internal_key());
+                psbt_input.tap_merkle_root = spend_info.merkle_root();
+            }
+            PsbtSpend::P2wsh => {
+                psbt_input.witness_script = Some(
+                    script
+                        .ok_or(Error::Psbt(PsbtError::MissingPrevScript(
+                            tx.name(),
+                            input_index,
+                        )))?
+                        .clone(),
+                );
+            }
+            PsbtSpend::Unknown => {}
+        }
+    }
+
+    Ok(psbt)
+}
+
+/// Same as `export_psbt`, additionally exporting the participants, public nonces and partial
+/// signatures collected so far for the verifier inputs as BIP-373 fields.
+pub fn export_musig2_psbt<T: BaseTransaction + PreSignedMusig2Transaction>(
+    tx: &T,
+    inputs: Vec<PsbtInput>,
+    n_of_n_public_keys: &[PublicKey],
+) -> Result<Psbt, Error> {
+    let mut psbt = export_psbt(tx, inputs)?;
+    let (aggregate_public_key, _) = generate_n_of_n_public_key(n_of_n_public_keys);
+    let aggregate_public_key = &aggregate_public_key;
+
+    for input_index in tx.verifier_inputs() {
+        let leaf_hash = tx
+            .prev_scripts()
+            .get(input_index)
+            .map(|script| TapLeafHash::from_script(script, LeafVersion::TapScript));
+        let nonces = tx.musig2_nonces().get(&input_index);
+        let nonce_signatures = tx.musig2_nonce_signatures().get(&input_index);
+        let signatures = tx.musig2_signatures().get(&input_index);
+        let psbt_input = &mut psbt.inputs[input_index];
+
+        // All participants, in key aggregation order, whether or not they contributed yet.
+        psbt_input.unknown.insert(
+            raw::Key {
+                type_value: PSBT_IN_MUSIG2_PARTICIPANT_PUBKEYS,
+                key: aggregate_public_key.to_bytes(),
+            },
+            n_of_n_public_keys
+                .iter()
+                .flat_map(|pubkey| pubkey.to_bytes())
+                .collect(),
+        );
+
+        for (pubkey, nonce) in nonces.into_iter().flatten() {
+            let key = musig2_key_data(pubkey, aggregate_public_key, leaf_hash);
+            if let Some(signature) = nonce_signatures.and_then(|signatures| signatures.get(pubkey))
+            {
+                psbt_input.proprietary.insert(
+                    raw::ProprietaryKey {
+                        prefix: PSBT_PROPRIETARY_PREFIX.to_vec(),
+                        subtype: PSBT_IN_BITVM_MUSIG2_NONCE_SIGNATURE,
+                        key: key.clone(),
+                    },
+                    signature.serialize().to_vec(),
+                );
+            }
+            psbt_input.unknown.insert(
+                raw::Key {
+                    type_value: PSBT_IN_MUSIG2_PUB_NONCE,
+                    key,
+                },
+                nonce.to_bytes().to_vec(),
+            );
+        }
+
+        for (pubkey, signature) in signatures.into_iter().flatten() {
+            psbt_input.unknown.insert(
+                raw::Key {
+                    type_value: PSBT_IN_MUSIG2_PARTIAL_SIG,
+                    key: musig2_key_data(pubkey, aggregate_public_key, leaf_hash),
+                },
+                signature.serialize().to_vec(),
+            );
+        }
+    }
+
+    Ok(psbt)
+}
+
+/// Imports a signed PSBT. The unsigned transaction of the PSBT must match the pre-signed
+/// transaction and `exported` is the PSBT exported for it, which carries the leaves, witness
+/// scripts and sighash types the inputs are expected to be signed with.
+///
+/// Final script witnesses that differ from the witness of the input are checked against its
+/// output: taproot script path spends are executed and the signature of other spends is
+/// verified. Signatures in `tap_script_sigs` and `partial_sigs` are verified against the
+/// sighash of their input, and an input whose script only requires the signature of its key
+/// (optionally behind a relative timelock) is finalized with it. Inputs requiring more witness
+/// data must be finalized by the signer.
+pub fn import_psbt<T: BaseTransaction + PreSignedTransaction>(
+    tx: &mut T,
+    psbt: &Psbt,
+    exported: &Psbt,
+) -> Result<(), Error> {
+    let txid = psbt.unsigned_tx.compute_txid();
+    if txid != tx.tx().compute_txid() || exported.inputs.len() != psbt.inputs.len() {
+        return Err(Error::Psbt(PsbtError::TxMismatch(tx.name(), txid)));
+    }
+
+    for (input_index, psbt_input) in psbt.inputs.iter().enumerate() {
+        let witness = match &psbt_input.final_script_witness {
+            Some(witness) if *witness == tx.tx().input[input_index].witness => None,
+            Some(witness) => {
+                let mut signed_tx = tx.tx().clone();
+                signed_tx.input[input_index].witness = witness.clone();
+                verify_final_witness(tx.name(), &signed_tx, tx.prev_outs(), input_index)?;
+                Some(witness.clone())
+            }
+            None => verify_input_signatures(
+                tx.name(),
+                tx.tx(),
+                tx.prev_outs(),
+                input_index,
+                psbt_input,
+                &exported.inputs[input_index],
+            )?,
+        };
+        if let Some(witness) = witness {
+            tx.tx_mut().input[input_index].witness = witness;
+        }
+    }
+
+    Ok(())
+}
+
+// Verifies that the witness of an input spends its output. Taproot script path spends are
+// executed, key path spends and P2WSH spends of single key scripts have their signature
+// verified. Any other spend is rejected.
+fn verify_final_witness(
+    tx_name: &'static str,
+    tx: &Transaction,
+    prev_outs: &[TxOut],
+    input_index: usize,
+) -> Result<(), Error> {
+    let invalid_witness = || Error::Psbt(PsbtError::InvalidWitness(tx_name, input_index));
+    let prev_out = prev_outs.get(input_index).ok_or_else(invalid_witness)?;
+    let script_pubkey = &prev_out.script_pubkey;
+
+    let mut elements: Vec<&[u8]> = tx.input[input_index].witness.iter().collect();
+    if elements.len() >= 2
+        && elements.last().and_then(|element| element.first()) == Some(&TAPROOT_ANNEX_PREFIX)
+    {
+        elements.pop();
+    }
+
+    let is_valid = if script_pubkey.is_p2tr() {
+        let output_key = XOnlyPublicKey::from_slice(&script_pubkey.as_bytes()[2..])
+            .map_err(|_| invalid_witness())?;
+        match elements.as_slice() {
+            [signature] => {
+                verify_taproot_key_spend(tx, prev_outs, input_index, signature, &output_key)
+            }
+            [.., script, control_block] => {
+                ControlBlock::decode(control_block).is_ok_and(|control_block| {
+                    control_block.verify_taproot_commitment(
+                        SECP256K1,
+                        output_key,
+                        Script::from_bytes(script),
+                    )
+                }) && dry_run_taproot_input(tx, input_index, prev_outs).success
+            }
+            [] => false,
+        }
+    } else if script_pubkey.is_p2wsh() {
+        match elements.as_slice() {
+            [signature, script] => {
+                verify_p2wsh_single_key_spend(tx, prev_out, input_index, signature, script)
+            }
+            _ => false,
+        }
+    } else {
+        false
+    };
+
+    if !is_valid {
+        return Err(invalid_witness());
+    }
+
+    Ok(())
+}
+
+fn verify_taproot_key_spend(
+    tx: &Transaction,
+    prev_outs: &[TxOut],
+    input_index: usize,
+    signature: &[u8],
+    output_key: &XOnlyPublicKey,
+) -> bool {
+    let Ok(signature) = taproot::Signature::from_slice(signature) else {
+        return false;
+    };
+    let prevouts = if is_anyone_can_pay(signature.sighash_type) {
+        Prevouts::One(input_index, prev_outs[input_index].clone())
+    } else {
+        Prevouts::All(prev_outs)
+    };
+    let Ok(sighash) = SighashCache::new(tx).taproot_key_spend_signature_hash(
+        input_index,
+        &prevouts,
+        signature.sighash_type,
+    ) else {
+        return false;
+    };
+
+    SECP256K1
+        .verify_schnorr(&signature.signature, &Message::from(sighash), output_key)
+        .is_ok()
+}
+
+fn verify_p2wsh_single_key_spend(
+    tx: &Transaction,
+    prev_out: &TxOut,
+    input_index: usize,
+    signature: &[u8],
+    script: &[u8],
+) -> bool {
+    let script = Script::from_bytes(script);
+    if ScriptBuf::new_p2wsh(&script.wscript_hash()) != prev_out.script_pubkey {
+        return false;
+    }
+    let Ok(instructions) = script.instructions().collect::<Result<Vec<_>, _>>() else {
+        return false;
+    };
+    let [.., Instruction::PushBytes(public_key), Instruction::Op(checksig)] =
+        instructions.as_slice()
+    else {
+        return false;
+    };
+    let Ok(public_key) = PublicKey::from_slice(public_key.as_bytes()) else {
+        return false;
+    };
+    if *checksig != OP_CHECKSIG
+        || !is_single_key_script(script, &generate_pay_to_pubkey_script(&public_key))
+    {
+        return false;
+    }
+
+    let Ok(signature) = ecdsa::Signature::from_slice(signature) else {
+        return false;
+    };
+    let Ok(sighash) = SighashCache::new(tx).p2wsh_signature_hash(
+        input_index,
+        script,
+        prev_out.value,
+        signature.sighash_type,
+    ) else {
+        return false;
+    };
+
+    SECP256K1
+        .verify_ecdsa(
+            &Message::from(sighash),
+            &signature.signature,
+            &public_key.inner,
+        )
+        .is_ok()
+}
+
+// Verifies the signatures of an input and returns the witness they finalize the input with.
+fn verify_input_signatures(
+    tx_name: &'static str,
+    tx: &Transaction,
+    prev_outs: &[TxOut],
+    input_index: usize,
+    psbt_input: &PsbtInputFields,
+    exported_input: &PsbtInputFields,
+) -> Result<Option<Witness>, Error> {
+    let invalid_signature = || Error::Psbt(PsbtError::InvalidSignature(tx_name, input_index));
+    let prev_out = prev_outs.get(input_index).ok_or_else(invalid_signature)?;
+    let mut witnesses = Vec::new();
+
+    for ((public_key, leaf_hash), signature) in &psbt_input.tap_script_sigs {
+        let expected_sighash_type = exported_input
+            .sighash_type
+            .and_then(|sighash_type| sighash_type.taproot_hash_ty().ok());
+        if expected_sighash_type != Some(signature.sighash_type) {
+            return Err(invalid_signature());
+        }
+        let (control_block, (script, _)) = exported_input
+            .tap_scripts
+            .iter()
+            .find(|(_, (script, leaf_version))| {
+                TapLeafHash::from_script(script, *leaf_version) == *leaf_hash
+            })
+            .ok_or(Error::Psbt(PsbtError::LeafNotFound(tx_name, input_index)))?;
+
+        let prevouts = if is_anyone_can_pay(signature.sighash_type) {
+            Prevouts::One(input_index, prev_out.clone())
+        } else {
+            Prevouts::All(prev_outs)
+        };
+        let sighash = SighashCache::new(tx)
+            .taproot_script_spend_signature_hash(
+                input_index,
+                &prevouts,
+                *leaf_hash,
+                signature.sighash_type,
+            )
+            .map_err(|_| invalid_signature())?;
+        SECP256K1
+            .verify_schnorr(&signature.signature, &Message::from(sighash), public_key)
+            .map_err(|_| invalid_signature())?;
+
+        if is_single_key_script(script, &generate_pay_to_pubkey_taproot_script(public_key)) {
+            witnesses.push(Witness::from_slice(&[
+                signature.to_vec(),
+                script.to_bytes(),
+                control_block.serialize(),
+            ]));
+        }
+    }
+
+    for (public_key, signature) in &psbt_input.partial_sigs {
+        let expected_sighash_type = exported_input
+            .sighash_type
+            .and_then(|sighash_type| sighash_type.ecdsa_hash_ty().ok());
+        if expected_sighash_type != Some(signature.sighash_type) {
+            return Err(invalid_signature());
+        }
+        let script = exported_input.witness_script.as_ref().ok_or(Error::Psbt(
+            PsbtError::MissingPrevScript(tx_name, input_index),
+        ))?;
+
+        let sighash = SighashCache::new(tx)
+            .p2wsh_signature_hash(input_index, script, prev_out.value, signature.sighash_type)
+            .map_err(|_| invalid_signature())?;
+        SECP256K1
+            .verify_ecdsa(
+                &Message::from(sighash),
+                &signature.signature,
+                &public_key.inner,
+            )
+            .map_err(|_| invalid_signature())?;
+
+        if is_single_key_script(script, &generate_pay_to_pubkey_script(public_key)) {
+            witnesses.push(Witness::from_slice(&[
+                signature.to_vec(),
+                script.to_bytes(),
+            ]));
+        }
+    }
+
+    let signature_count = psbt_input.tap_script_sigs.len() + psbt_input.partial_sigs.len();
+    match (signature_count, witnesses.pop()) {
+        (0, _) => Ok(None),
+        (1, Some(witness)) => Ok(Some(witness)),
+        _ => Err(Error::Psbt(PsbtError::CannotFinalize(tx_name, input_index))),
+    }
+}
+
+fn is_anyone_can_pay(sighash_type: TapSighashType) -> bool {
+    sighash_type == TapSighashType::AllPlusAnyoneCanPay
+        || sighash_type == TapSighashType::SinglePlusAnyoneCanPay
+        || sighash_type == TapSighashType::NonePlusAnyoneCanPay
+}
+
+// Whether `script` is `key_script`, i.e. <public key> OP_CHECKSIG, optionally preceded by
+// <n> OP_CSV OP_DROP, so the signature of the key is the only witness element it needs.
+fn is_single_key_script(script: &Script, key_script: &Script) -> bool {
+    let Some(prefix) = script.as_bytes().strip_suffix(key_script.as_bytes()) else {
+        return false;
+    };
+    let Ok(instructions) = Script::from_bytes(prefix)
+        .instructions()
+        .collect::<Result<Vec<_>, _>>()
+    else {
+        return false;
+    };
+
+    match instructions.as_slice() {
+        [] => true,
+        [Instruction::PushBytes(_), Instruction::Op(csv), Instruction::Op(drop)] => {
+            *csv == OP_CSV && *drop == OP_DROP
+        }
+        _ => false,
+    }
+}
+
+/// Same as `import_psbt`, additionally merging the BIP-373 public nonces and partial
+/// signatures of the verifier inputs. Imported nonces are verified against their signatures
+/// and must come from one of the `n_of_n_public_keys`, and imported partial signatures are
+/// verified against the nonces of the input. The key data of both must be for the leaf of the
+/// input.
+pub fn import_musig2_psbt<T: BaseTransaction + PreSignedMusig2Transaction>(
+    tx: &mut T,
+    psbt: &Psbt,
+    exported: &Psbt,
+    n_of_n_public_keys: &[PublicKey],
+) -> Result<(), Error> {
+    import_psbt(tx, psbt, exported)?;
+    let (aggregate_public_key, _) = generate_n_of_n_public_key(n_of_n_public_keys);
+    let aggregate_public_key = &aggregate_public_key;
+
+    for input_index in tx.verifier_inputs() {
+        let Some(psbt_input) = psbt.inputs.get(input_index) else {
+            continue;
+        };
+        let tx_name = tx.name();
+        let invalid_field = || Error::Psbt(PsbtError::InvalidMusig2Field(tx_name, input_index));
+        let invalid_signature = || Error::Psbt(PsbtError::InvalidSignature(tx_name, input_index));
+        let script = tx
+            .prev_scripts()
+            .get(input_index)
+            .cloned()
+            .ok_or(Error::Psbt(PsbtError::MissingPrevScript(
+                tx_name,
+                input_index,
+            )))?;
+        let leaf_hash = TapLeafHash::from_script(&script, LeafVersion::TapScript);
+        let parse_pubkey = |key: &raw::Key| {
+            parse_musig2_key_data(&key.key, aggregate_public_key, Some(leaf_hash))
+                .filter(|pubkey| n_of_n_public_keys.contains(pubkey))
+                .ok_or_else(invalid_field)
+        };
+
+        for (key, value) in &psbt_input.unknown {
+            if key.type_value != PSBT_IN_MUSIG2_PUB_NONCE {
+                continue;
+            }
+            let pubkey = parse_pubkey(key)?;
+            let nonce = PubNonce::from_bytes(value).map_err(|_| invalid_field())?;
+            let nonce_signature = psbt_input
+                .proprietary
+                .get(&raw::ProprietaryKey {
+                    prefix: PSBT_PROPRIETARY_PREFIX.to_vec(),
+                    subtype: PSBT_IN_BITVM_MUSIG2_NONCE_SIGNATURE,
+                    key: key.key.clone(),
+                })
+                .ok_or(Error::Psbt(PsbtError::MissingNonceSignature(
+                    tx_name,
+                    pubkey,
+                    input_index,
+                )))?;
+            let nonce_signature =
+                Signature::from_slice(nonce_signature).map_err(|_| invalid_field())?;
+
+            tx.musig2_nonces_mut()
+                .entry(input_index)
+                .or_default()
+                .insert(pubkey, nonce);
+            tx.musig2_nonce_signatures_mut()
+                .entry(input_index)
+                .or_default()
+                .insert(pubkey, nonce_signature);
+        }
+
+        // Partial signatures are made over the aggregate of all nonces of the input, so they
+        // are verified once all nonces are imported.
+        for (key, value) in &psbt_input.unknown {
+            if key.type_value != PSBT_IN_MUSIG2_PARTIAL_SIG {
+                continue;
+            }
+            let pubkey = parse_pubkey(key)?;
+            let signature = PartialSignature::from_slice(value).map_err(|_| invalid_field())?;
+            let sighash_type = exported.inputs[input_index]
+                .sighash_type
+                .and_then(|sighash_type| sighash_type.taproot_hash_ty().ok())
+                .ok_or_else(invalid_field)?;
+            let nonces = tx
+                .musig2_nonces()
+                .get(&input_index)
+                .ok_or_else(invalid_signature)?;
+            let nonce = nonces.get(&pubkey).ok_or_else(invalid_signature)?;
+
+            verify_taproot_partial_signature(
+                n_of_n_public_keys,
+                tx.tx(),
+                &generate_aggregated_nonce(&nonces.values().cloned().collect()),
+                input_index,
+                tx.prev_outs(),
+                &script,
+                sighash_type,
+                &pubkey,
+                nonce,
+                signature,
+            )
+            .map_err(|_| invalid_signature())?;
+
+            tx.musig2_signatures_mut()
+                .entry(input_index)
+                .or_default()
+                .insert(pubkey, signature);
+        }
+    }
+
+    verify_public_nonces_for_tx(&*tx)
+}
+
+fn musig2_key_data(
+    pubkey: &PublicKey,
+    aggregate_public_key: &PublicKey,
+    leaf_hash: Option<TapLeafHash>,
+) -> Vec<u8> {
+    let mut key = pubkey.to_bytes();
+    key.extend(aggregate_public_key.to_bytes());
+    if let Some(leaf_hash) = leaf_hash {
+        key.extend(leaf_hash.to_byte_array());
+    }
+    key
+}
+
+// Returns the participant public key if the key data is for the given aggregate public key
+// and leaf.
+fn parse_musig2_key_data(
+    key: &[u8],
+    aggregate_public_key: &PublicKey,
+    leaf_hash: Option<TapLeafHash>,
+) -> Option<PublicKey> {
+    if key.len() != 2 * PUBLIC_KEY_SIZE && key.len() != 2 * PUBLIC_KEY_SIZE + TAP_LEAF_HASH_SIZE {
+        return None;
+    }
+    if key[PUBLIC_KEY_SIZE..2 * PUBLIC_KEY_SIZE] != aggregate_public_key.to_bytes() {
+        return None;
+    }
+    let key_leaf_hash = &key[2 * PUBLIC_KEY_SIZE..];
+    match leaf_hash {
+        Some(leaf_hash) if key_leaf_hash != &leaf_hash.to_byte_array()[..] => return None,
+        None if !key_leaf_hash.is_empty() => return None,
+        _ => {}
+    }
+
+    PublicKey::from_slice(&key[..PUBLIC_KEY_SIZE]).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        hex::DisplayHex,
+        key::constants::SECRET_KEY_SIZE,
+        taproot::{LeafVersion, TapLeafHash},
+        Network, ScriptBuf,
+    };
+
+    use crate::contexts::base::generate_keys_from_secret;
+
+    use super::{musig2_key_data, parse_musig2_key_data};
+
+    #[test]
+    fn test_musig2_key_data_round_trip() {
+        let (_, participant) = generate_keys_from_secret(
+            Network::Bitcoin,
+            &[1u8; SECRET_KEY_SIZE].to_lower_hex_string(),
+        );
+        let (_, aggregate) = generate_keys_from_secret(
+            Network::Bitcoin,
+            &[2u8; SECRET_KEY_SIZE].to_lower_hex_string(),
+        );
+        let leaf_hash = TapLeafHash::from_script(&ScriptBuf::new(), LeafVersion::TapScript);
+
+        let other_leaf_hash =
+            TapLeafHash::from_script(&ScriptBuf::from_bytes(vec![0x51]), LeafVersion::TapScript);
+
+        let key = musig2_key_data(&participant, &aggregate, Some(leaf_hash));
+        assert_eq!(
+            parse_musig2_key_data(&key, &aggregate, Some(leaf_hash)),
+            Some(participant)
+        );
+        assert_eq!(
+            parse_musig2_key_data(&key, &aggregate, Some(other_leaf_hash)),
+            None
+        );
+        assert_eq!(parse_musig2_key_data(&key, &aggregate, None), None);
+
+        let key = musig2_key_data(&participant, &aggregate, None);
+        assert_eq!(
+            parse_musig2_key_data(&key, &aggregate, None),
+            Some(participant)
+        );
+        assert_eq!(
+            parse_musig2_key_data(&key, &aggregate, Some(leaf_hash)),
+            None
+        );
+
+        assert_eq!(parse_musig2_key_data(&key, &participant, None), None);
+        assert_eq!(parse_musig2_key_data(&key[1..], &aggregate, None), None);
+    }
+}
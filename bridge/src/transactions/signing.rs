@@ -1,14 +1,15 @@
 use bitcoin::{
-    key::{Keypair, TapTweak},
     secp256k1::Message,
     sighash::{Prevouts, SighashCache},
     taproot::{ControlBlock, LeafVersion, TaprootSpendInfo},
     Amount, EcdsaSighashType, PublicKey, Script, ScriptBuf, TapLeafHash, TapNodeHash,
     TapSighashType, Transaction, TxOut,
 };
-use secp256k1::SECP256K1;
 
-use super::super::{contexts::base::BaseContext, scripts::generate_p2wpkh_address};
+use super::super::{
+    contexts::base::BaseContext, error::Error, scripts::generate_p2wpkh_address,
+    signers::base::Signer,
+};
 
 pub fn generate_p2wsh_schnorr_signature(
     tx: &mut Transaction,
@@ -16,20 +17,22 @@ pub fn generate_p2wsh_schnorr_signature(
     sighash_type: EcdsaSighashType,
     script: &Script,
     value: Amount,
-    keypair: &Keypair,
-) -> bitcoin::ecdsa::Signature {
+    signer: &dyn Signer,
+) -> Result<bitcoin::ecdsa::Signature, Error> {
     let mut sighash_cache = SighashCache::new(tx);
 
     let sighash = sighash_cache
         .p2wsh_signature_hash(input_index, script, value, sighash_type)
         .expect("Failed to construct sighash");
 
-    let signature = keypair.secret_key().sign_ecdsa(Message::from(sighash));
+    let signature = signer
+        .sign_ecdsa(&Message::from(sighash))
+        .map_err(Error::Signer)?;
 
-    bitcoin::ecdsa::Signature {
+    Ok(bitcoin::ecdsa::Signature {
         signature,
         sighash_type,
-    }
+    })
 }
 
 pub fn push_p2wsh_signature_to_witness(
@@ -38,14 +41,16 @@ pub fn push_p2wsh_signature_to_witness(
     sighash_type: EcdsaSighashType,
     script: &Script,
     value: Amount,
-    keypair: &Keypair,
-) {
+    signer: &dyn Signer,
+) -> Result<(), Error> {
     let signature =
-        generate_p2wsh_schnorr_signature(tx, input_index, sighash_type, script, value, keypair);
+        generate_p2wsh_schnorr_signature(tx, input_index, sighash_type, script, value, signer)?;
 
     tx.input[input_index]
         .witness
         .push_ecdsa_signature(&signature);
+
+    Ok(())
 }
 
 pub fn push_p2wsh_script_to_witness(tx: &mut Transaction, input_index: usize, script: &Script) {
@@ -58,12 +63,14 @@ pub fn populate_p2wsh_witness(
     sighash_type: EcdsaSighashType,
     script: &Script,
     value: Amount,
-    keypairs: &Vec<&Keypair>,
-) {
-    for keypair in keypairs {
-        push_p2wsh_signature_to_witness(tx, input_index, sighash_type, script, value, keypair);
+    signers: &Vec<&dyn Signer>,
+) -> Result<(), Error> {
+    for signer in signers {
+        push_p2wsh_signature_to_witness(tx, input_index, sighash_type, script, value, *signer)?;
     }
     push_p2wsh_script_to_witness(tx, input_index, script);
+
+    Ok(())
 }
 
 pub fn populate_p2wsh_witness_with_signatures(
@@ -87,8 +94,8 @@ pub fn generate_p2wpkh_schnorr_signature(
     sighash_type: EcdsaSighashType,
     value: Amount,
     public_key: &PublicKey,
-    keypair: &Keypair,
-) -> bitcoin::ecdsa::Signature {
+    signer: &dyn Signer,
+) -> Result<bitcoin::ecdsa::Signature, Error> {
     let mut sighash_cache = SighashCache::new(tx);
 
     let sighash = sighash_cache
@@ -100,12 +107,14 @@ pub fn generate_p2wpkh_schnorr_signature(
         )
         .expect("Failed to construct sighash");
 
-    let signature = keypair.secret_key().sign_ecdsa(Message::from(sighash));
+    let signature = signer
+        .sign_ecdsa(&Message::from(sighash))
+        .map_err(Error::Signer)?;
 
-    bitcoin::ecdsa::Signature {
+    Ok(bitcoin::ecdsa::Signature {
         signature,
         sighash_type,
-    }
+    })
 }
 
 pub fn push_p2wpkh_signature_to_witness(
@@ -115,8 +124,8 @@ pub fn push_p2wpkh_signature_to_witness(
     sighash_type: EcdsaSighashType,
     value: Amount,
     public_key: &PublicKey,
-    keypair: &Keypair,
-) {
+    signer: &dyn Signer,
+) -> Result<(), Error> {
     let signature = generate_p2wpkh_schnorr_signature(
         context,
         tx,
@@ -124,12 +133,14 @@ pub fn push_p2wpkh_signature_to_witness(
         sighash_type,
         value,
         public_key,
-        keypair,
-    );
+        signer,
+    )?;
 
     tx.input[input_index]
         .witness
         .push_ecdsa_signature(&signature);
+
+    Ok(())
 }
 
 pub fn push_p2wpkh_public_key_to_witness(
@@ -147,8 +158,8 @@ pub fn populate_p2wpkh_witness(
     sighash_type: EcdsaSighashType,
     value: Amount,
     public_key: &PublicKey,
-    keypair: &Keypair,
-) {
+    signer: &dyn Signer,
+) -> Result<(), Error> {
     push_p2wpkh_signature_to_witness(
         context,
         tx,
@@ -156,9 +167,11 @@ pub fn populate_p2wpkh_witness(
         sighash_type,
         value,
         public_key,
-        keypair,
-    );
+        signer,
+    )?;
     push_p2wpkh_public_key_to_witness(tx, input_index, public_key);
+
+    Ok(())
 }
 
 pub fn generate_taproot_leaf_schnorr_signature(
@@ -167,8 +180,8 @@ pub fn generate_taproot_leaf_schnorr_signature(
     input_index: usize,
     sighash_type: TapSighashType,
     script: &Script,
-    keypair: &Keypair,
-) -> bitcoin::taproot::Signature {
+    signer: &dyn Signer,
+) -> Result<bitcoin::taproot::Signature, Error> {
     let leaf_hash = TapLeafHash::from_script(script, LeafVersion::TapScript);
 
     let sighash = if sighash_type == TapSighashType::AllPlusAnyoneCanPay
@@ -194,14 +207,14 @@ pub fn generate_taproot_leaf_schnorr_signature(
             .expect("Failed to construct sighash")
     };
 
-    // If secp256k1 is updated to 0.30.0, the following line can be replaced with
-    // let signature = keypair.sign_schnorr_no_aux_rand(&Message::from(sighash));
-    let signature = SECP256K1.sign_schnorr_no_aux_rand(&Message::from(sighash), keypair);
+    let signature = signer
+        .sign_schnorr(&Message::from(sighash))
+        .map_err(Error::Signer)?;
 
-    bitcoin::taproot::Signature {
+    Ok(bitcoin::taproot::Signature {
         signature,
         sighash_type,
-    }
+    })
 }
 
 pub fn push_taproot_leaf_unlock_data_to_witness(
@@ -284,18 +297,18 @@ pub fn populate_taproot_input_witness_default(
     sighash_type: TapSighashType,
     taproot_spend_info: &TaprootSpendInfo,
     script: &Script,
-    keypairs: &Vec<&Keypair>,
-) {
+    signers: &Vec<&dyn Signer>,
+) -> Result<(), Error> {
     let mut unlock_data: Vec<Vec<u8>> = Vec::new();
-    for keypair in keypairs {
+    for signer in signers {
         let schnorr_signature = generate_taproot_leaf_schnorr_signature(
             tx,
             prevouts,
             input_index,
             sighash_type,
             script,
-            keypair,
-        );
+            *signer,
+        )?;
         unlock_data.push(schnorr_signature.to_vec());
     }
 
@@ -306,6 +319,8 @@ pub fn populate_taproot_input_witness_default(
         taproot_spend_info,
         script,
     );
+
+    Ok(())
 }
 
 pub fn populate_taproot_input_witness_with_signature(
@@ -325,8 +340,8 @@ fn generate_p2tr_key_spend_schnorr_signature(
     prev_outs: &[TxOut],
     sighash_type: TapSighashType,
     merkle_root: Option<TapNodeHash>,
-    keypair: &Keypair,
-) -> bitcoin::taproot::Signature {
+    signer: &dyn Signer,
+) -> Result<bitcoin::taproot::Signature, Error> {
     let sighash = if sighash_type == TapSighashType::AllPlusAnyoneCanPay
         || sighash_type == TapSighashType::SinglePlusAnyoneCanPay
         || sighash_type == TapSighashType::NonePlusAnyoneCanPay
@@ -344,17 +359,14 @@ fn generate_p2tr_key_spend_schnorr_signature(
             .expect("Failed to construct sighash")
     };
 
-    let tweak_keypair = keypair.tap_tweak(SECP256K1, merkle_root);
+    let signature = signer
+        .sign_schnorr_key_spend(&Message::from(sighash), merkle_root)
+        .map_err(Error::Signer)?;
 
-    // If secp256k1 is updated to 0.30.0, the following line can be replaced with
-    // let signature = keypair.sign_schnorr_no_aux_rand(&Message::from(sighash));
-    let signature =
-        SECP256K1.sign_schnorr_no_aux_rand(&Message::from(sighash), &tweak_keypair.to_inner());
-
-    bitcoin::taproot::Signature {
+    Ok(bitcoin::taproot::Signature {
         signature,
         sighash_type,
-    }
+    })
 }
 
 pub fn populate_p2tr_key_spend_witness(
@@ -363,15 +375,17 @@ pub fn populate_p2tr_key_spend_witness(
     prev_outs: &[TxOut],
     sighash_type: TapSighashType,
     merkle_root: Option<TapNodeHash>,
-    keypair: &Keypair,
-) {
+    signer: &dyn Signer,
+) -> Result<(), Error> {
     let signature = generate_p2tr_key_spend_schnorr_signature(
         tx,
         input_index,
         prev_outs,
         sighash_type,
         merkle_root,
-        keypair,
-    );
+        signer,
+    )?;
     tx.input[input_index].witness.push(signature.to_vec());
+
+    Ok(())
 }
@@ -1,22 +1,28 @@
 #![allow(clippy::too_many_arguments)]
 use bitcoin::{
+    secp256k1::Message,
     sighash::{Prevouts, SighashCache},
     taproot::LeafVersion,
-    Script, TapLeafHash, TapSighashType, Transaction, TxOut,
+    PublicKey, Script, TapLeafHash, TapSighashType, Transaction, TxOut, Txid,
 };
 use musig2::{
-    aggregate_partial_signatures,
-    errors::{SigningError, VerifyError},
-    secp::{MaybeScalar, Point},
-    sign_partial, AggNonce, KeyAggContext, LiftedSignature, PartialSignature, PubNonce, SecNonce,
+    aggregate_partial_signatures, errors::VerifyError, secp::Point, verify_partial, AggNonce,
+    KeyAggContext, LiftedSignature, PartialSignature, PubNonce, SecNonce,
 };
 
-use super::super::contexts::{base::BaseContext, verifier::VerifierContext};
+use super::super::{
+    contexts::{base::BaseContext, verifier::VerifierContext},
+    signers::base::Musig2SessionId,
+};
 
 pub fn generate_nonce() -> SecNonce {
     SecNonce::build(&mut rand::rngs::OsRng).build()
 }
 
+pub fn generate_musig2_session_id(txid: Txid, input_index: usize) -> Musig2SessionId {
+    format!("{txid}:{input_index}")
+}
+
 pub fn generate_aggregated_nonce(nonces: &Vec<PubNonce>) -> AggNonce {
     AggNonce::sum(nonces)
 }
@@ -24,21 +30,13 @@ pub fn generate_aggregated_nonce(nonces: &Vec<PubNonce>) -> AggNonce {
 pub fn generate_taproot_partial_signature(
     context: &VerifierContext,
     tx: &Transaction,
-    secret_nonce: &SecNonce,
+    session_id: &str,
     aggregated_nonce: &AggNonce,
     input_index: usize,
     prevouts: &[TxOut],
     script: &Script,
     sighash_type: TapSighashType,
-) -> Result<MaybeScalar, SigningError> {
-    let pubkeys: Vec<Point> = Vec::from_iter(
-        context
-            .n_of_n_public_keys
-            .iter()
-            .map(|&public_key| public_key.inner.into()),
-    ); // TODO: The tests will reveal whether this conversion works as expected.
-    let key_agg_ctx = KeyAggContext::new(pubkeys).unwrap();
-
+) -> Result<PartialSignature, String> {
     let leaf_hash = TapLeafHash::from_script(script, LeafVersion::TapScript);
     let sighash = SighashCache::new(tx)
         .taproot_script_spend_signature_hash(
@@ -49,12 +47,11 @@ pub fn generate_taproot_partial_signature(
         )
         .expect("Failed to construct sighash");
 
-    sign_partial(
-        &key_agg_ctx,
-        context.verifier_keypair.secret_key(),
-        secret_nonce.clone(),
+    context.verifier_signer.sign_musig2_partial(
+        &context.n_of_n_public_keys,
+        session_id,
         aggregated_nonce,
-        sighash,
+        &Message::from(sighash),
     )
 }
 
@@ -94,6 +91,47 @@ pub fn generate_taproot_aggregated_signature(
     )
 }
 
+// Verifies the partial signature of a participant, e.g. one imported from a PSBT, before it
+// is aggregated with the others.
+pub fn verify_taproot_partial_signature(
+    n_of_n_public_keys: &[PublicKey],
+    tx: &Transaction,
+    aggregated_nonce: &AggNonce,
+    input_index: usize,
+    prevouts: &[TxOut],
+    script: &Script,
+    sighash_type: TapSighashType,
+    public_key: &PublicKey,
+    public_nonce: &PubNonce,
+    partial_signature: PartialSignature,
+) -> Result<(), VerifyError> {
+    let pubkeys: Vec<Point> = Vec::from_iter(
+        n_of_n_public_keys
+            .iter()
+            .map(|&public_key| public_key.inner.into()),
+    );
+    let key_agg_ctx = KeyAggContext::new(pubkeys).unwrap();
+
+    let leaf_hash = TapLeafHash::from_script(script, LeafVersion::TapScript);
+    let sighash = SighashCache::new(tx)
+        .taproot_script_spend_signature_hash(
+            input_index,
+            &Prevouts::All(prevouts), // TODO: add Prevouts::One
+            leaf_hash,
+            sighash_type,
+        )
+        .expect("Failed to construct sighash");
+
+    verify_partial(
+        &key_agg_ctx,
+        partial_signature,
+        aggregated_nonce,
+        Point::from(public_key.inner),
+        public_nonce,
+        sighash,
+    )
+}
+
 // TODO: This is currently unused and can be removed. If the conversion at the start of the above functions is incorrect, try this approach.
 // pub fn to_point(public_key: PublicKey) -> Point {
 //     Point::from_slice(&public_key.to_bytes()).unwrap() // TODO: Add error handling. Also, verify this method is correct (otherwise see conversion via secp256k1::PublicKey).
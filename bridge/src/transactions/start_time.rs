@@ -9,17 +9,16 @@ use std::collections::HashMap;
 use crate::connectors::{base::TaprootConnector, connector_2::Connector2};
 
 use super::{
-    super::{contexts::operator::OperatorContext, scripts::*},
+    super::{
+        commitments::CommitmentMessageId, contexts::operator::OperatorContext, error::Error,
+        scripts::*,
+    },
     base::*,
     pre_signed::*,
     pre_signed_musig2::*,
     signing::{generate_taproot_leaf_schnorr_signature, populate_taproot_input_witness},
 };
 
-use bitvm::signatures::signing_winternitz::{
-    generate_winternitz_witness, WinternitzSecret, WinternitzSigningInputs,
-};
-
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone)]
 pub struct StartTimeTransaction {
     #[serde(with = "consensus::serde::With::<consensus::serde::Hex>")]
@@ -131,8 +130,8 @@ impl StartTimeTransaction {
         &mut self,
         context: &OperatorContext,
         connector_2: &Connector2,
-        start_time_signing_inputs: &WinternitzSigningInputs,
-    ) {
+        start_time_message: &[u8],
+    ) -> Result<(), Error> {
         let input_index = 0;
         let script = &self.prev_scripts()[input_index].clone();
         let prev_outs = &self.prev_outs().clone();
@@ -146,12 +145,20 @@ impl StartTimeTransaction {
             input_index,
             TapSighashType::All,
             script,
-            &context.operator_keypair,
-        );
+            context.operator_signer.as_ref(),
+        )?;
         unlock_data.push(schnorr_signature.to_vec());
 
         // get winternitz signature
-        self.start_time_witness = Some(generate_winternitz_witness(start_time_signing_inputs));
+        self.start_time_witness = Some(
+            context
+                .operator_signer
+                .sign_winternitz(
+                    &connector_2.commitment_public_keys[&CommitmentMessageId::StartTime],
+                    start_time_message,
+                )
+                .map_err(Error::Signer)?,
+        );
         unlock_data.extend(self.start_time_witness.as_ref().unwrap().to_vec());
 
         populate_taproot_input_witness(
@@ -161,6 +168,8 @@ impl StartTimeTransaction {
             script,
             unlock_data,
         );
+
+        Ok(())
     }
 
     pub fn sign(
@@ -168,18 +177,10 @@ impl StartTimeTransaction {
         context: &OperatorContext,
         connector_2: &Connector2,
         start_time_block_number: u32,
-        start_time_commitment_secret: &WinternitzSecret,
-    ) {
+    ) -> Result<(), Error> {
         self.tx_mut().lock_time = absolute::LockTime::from_height(start_time_block_number)
             .expect("Failed to set lock time from block.");
-        self.sign_input_0(
-            context,
-            connector_2,
-            &WinternitzSigningInputs {
-                message: &start_time_block_number.to_le_bytes(),
-                signing_key: start_time_commitment_secret,
-            },
-        );
+        self.sign_input_0(context, connector_2, &start_time_block_number.to_le_bytes())
     }
 
     pub fn merge(&mut self, burn: &StartTimeTransaction) {
@@ -1,7 +1,7 @@
 use bitcoin::{
     absolute, consensus, Amount, Network, PublicKey, ScriptBuf, TapSighashType, Transaction, TxOut,
 };
-use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
+use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -9,7 +9,9 @@ use super::{
     super::{
         connectors::{base::*, connector_1::Connector1, connector_2::Connector2},
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
+        error::Error,
         scripts::*,
+        signers::base::Musig2SessionId,
     },
     base::*,
     pre_signed::*,
@@ -147,24 +149,30 @@ impl StartTimeTimeoutTransaction {
         &mut self,
         context: &VerifierContext,
         connector_2: &Connector2,
-        secret_nonce: &SecNonce,
-    ) {
+        session_id: &str,
+    ) -> Result<(), Error> {
         let input_index = 0;
         pre_sign_musig2_taproot_input(
             self,
             context,
             input_index,
             TapSighashType::Single,
-            secret_nonce,
-        );
+            session_id,
+        )?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context, connector_2);
+            self.finalize_input_0(context, connector_2)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_2: &Connector2) {
+    fn finalize_input_0(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_2: &Connector2,
+    ) -> Result<(), Error> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
@@ -172,31 +180,37 @@ impl StartTimeTimeoutTransaction {
             input_index,
             TapSighashType::Single,
             connector_2.generate_taproot_spend_info(),
-        );
+        )
     }
 
     fn sign_input_1(
         &mut self,
         context: &VerifierContext,
         connector_1: &Connector1,
-        secret_nonce: &SecNonce,
-    ) {
+        session_id: &str,
+    ) -> Result<(), Error> {
         let input_index = 1;
         pre_sign_musig2_taproot_input(
             self,
             context,
             input_index,
             TapSighashType::None,
-            secret_nonce,
-        );
+            session_id,
+        )?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_1(context, connector_1);
+            self.finalize_input_1(context, connector_1)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_1(&mut self, context: &dyn BaseContext, connector_1: &Connector1) {
+    fn finalize_input_1(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_1: &Connector1,
+    ) -> Result<(), Error> {
         let input_index = 1;
         finalize_musig2_taproot_input(
             self,
@@ -204,7 +218,7 @@ impl StartTimeTimeoutTransaction {
             input_index,
             TapSighashType::None,
             connector_1.generate_taproot_spend_info(),
-        );
+        )
     }
 
     pub fn pre_sign(
@@ -212,13 +226,13 @@ impl StartTimeTimeoutTransaction {
         context: &VerifierContext,
         connector_1: &Connector1,
         connector_2: &Connector2,
-        secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+        session_ids: &HashMap<usize, Musig2SessionId>,
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_2, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_2, &session_ids[&input_index])?;
 
         let input_index = 1;
-        self.sign_input_1(context, connector_1, &secret_nonces[&input_index]);
+        self.sign_input_1(context, connector_1, &session_ids[&input_index])
     }
 
     pub fn add_output(&mut self, output_script_pubkey: ScriptBuf) {
@@ -2,7 +2,7 @@ use bitcoin::{
     absolute, consensus, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, TapSighashType,
     Transaction, TxOut,
 };
-use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
+use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -13,7 +13,9 @@ use super::{
             connector_b::ConnectorB,
         },
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
+        error::Error,
         scripts::*,
+        signers::base::Musig2SessionId,
     },
     base::*,
     pre_signed::*,
@@ -91,7 +93,7 @@ impl Take1Transaction {
         input_1: Input,
         input_2: Input,
         input_3: Input,
-    ) -> Self {
+    ) -> Result<Self, Error> {
         let mut this = Self::new_for_validation(
             context.network,
             &context.operator_public_key,
@@ -105,10 +107,10 @@ impl Take1Transaction {
             input_3,
         );
 
-        this.sign_input_1(context, connector_a);
-        this.sign_input_2(context);
+        this.sign_input_1(context, connector_a)?;
+        this.sign_input_2(context)?;
 
-        this
+        Ok(this)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -185,24 +187,24 @@ impl Take1Transaction {
         &mut self,
         context: &VerifierContext,
         connector_0: &Connector0,
-        secret_nonce: &SecNonce,
-    ) {
+        session_id: &str,
+    ) -> Result<(), Error> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, TapSighashType::All, session_id)?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context, connector_0);
+            self.finalize_input_0(context, connector_0)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_0: &Connector0) {
+    fn finalize_input_0(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_0: &Connector0,
+    ) -> Result<(), Error> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
@@ -210,52 +212,56 @@ impl Take1Transaction {
             input_index,
             TapSighashType::All,
             connector_0.generate_taproot_spend_info(),
-        );
+        )
     }
 
-    fn sign_input_1(&mut self, context: &OperatorContext, connector_a: &ConnectorA) {
+    fn sign_input_1(
+        &mut self,
+        context: &OperatorContext,
+        connector_a: &ConnectorA,
+    ) -> Result<(), Error> {
         let input_index = 1;
         pre_sign_taproot_input_default(
             self,
             input_index,
             TapSighashType::All,
             connector_a.generate_taproot_spend_info(),
-            &vec![&context.operator_keypair],
-        );
+            &vec![context.operator_signer.as_ref()],
+        )
     }
 
-    fn sign_input_2(&mut self, context: &OperatorContext) {
+    fn sign_input_2(&mut self, context: &OperatorContext) -> Result<(), Error> {
         let input_index = 2;
         pre_sign_p2wsh_input(
             self,
             input_index,
             EcdsaSighashType::All,
-            &vec![&context.operator_keypair],
-        );
+            &vec![context.operator_signer.as_ref()],
+        )
     }
 
     fn sign_input_3(
         &mut self,
         context: &VerifierContext,
         connector_b: &ConnectorB,
-        secret_nonce: &SecNonce,
-    ) {
+        session_id: &str,
+    ) -> Result<(), Error> {
         let input_index = 3;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, TapSighashType::All, session_id)?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_3(context, connector_b);
+            self.finalize_input_3(context, connector_b)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_3(&mut self, context: &dyn BaseContext, connector_b: &ConnectorB) {
+    fn finalize_input_3(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_b: &ConnectorB,
+    ) -> Result<(), Error> {
         let input_index = 3;
         finalize_musig2_taproot_input(
             self,
@@ -263,7 +269,7 @@ impl Take1Transaction {
             input_index,
             TapSighashType::All,
             connector_b.generate_taproot_spend_info(),
-        );
+        )
     }
 
     pub fn pre_sign(
@@ -271,13 +277,13 @@ impl Take1Transaction {
         context: &VerifierContext,
         connector_0: &Connector0,
         connector_b: &ConnectorB,
-        secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+        session_ids: &HashMap<usize, Musig2SessionId>,
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_0, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_0, &session_ids[&input_index])?;
 
         let input_index = 3;
-        self.sign_input_3(context, connector_b, &secret_nonces[&input_index]);
+        self.sign_input_3(context, connector_b, &session_ids[&input_index])
     }
 
     pub fn merge(&mut self, take_1: &Take1Transaction) {
@@ -2,7 +2,7 @@ use bitcoin::{
     absolute, consensus, Amount, EcdsaSighashType, Network, PublicKey, ScriptBuf, TapSighashType,
     Transaction, TxOut,
 };
-use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce, SecNonce};
+use musig2::{secp256k1::schnorr::Signature, PartialSignature, PubNonce};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -13,7 +13,9 @@ use super::{
             connector_c::ConnectorC,
         },
         contexts::{base::BaseContext, operator::OperatorContext, verifier::VerifierContext},
+        error::Error,
         scripts::*,
+        signers::base::Musig2SessionId,
     },
     base::*,
     pre_signed::*,
@@ -92,7 +94,7 @@ impl Take2Transaction {
         input_1: Input,
         input_2: Input,
         input_3: Input,
-    ) -> Self {
+    ) -> Result<Self, Error> {
         let mut this = Self::new_for_validation(
             context.network,
             &context.operator_public_key,
@@ -106,9 +108,9 @@ impl Take2Transaction {
             input_3,
         );
 
-        this.sign_input_1(context);
+        this.sign_input_1(context)?;
 
-        this
+        Ok(this)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -184,24 +186,24 @@ impl Take2Transaction {
         &mut self,
         context: &VerifierContext,
         connector_0: &Connector0,
-        secret_nonce: &SecNonce,
-    ) {
+        session_id: &str,
+    ) -> Result<(), Error> {
         let input_index = 0;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, TapSighashType::All, session_id)?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_0(context, connector_0);
+            self.finalize_input_0(context, connector_0)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_0(&mut self, context: &dyn BaseContext, connector_0: &Connector0) {
+    fn finalize_input_0(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_0: &Connector0,
+    ) -> Result<(), Error> {
         let input_index = 0;
         finalize_musig2_taproot_input(
             self,
@@ -209,41 +211,41 @@ impl Take2Transaction {
             input_index,
             TapSighashType::All,
             connector_0.generate_taproot_spend_info(),
-        );
+        )
     }
 
-    fn sign_input_1(&mut self, context: &OperatorContext) {
+    fn sign_input_1(&mut self, context: &OperatorContext) -> Result<(), Error> {
         let input_index = 1;
         pre_sign_p2wsh_input(
             self,
             input_index,
             EcdsaSighashType::All,
-            &vec![&context.operator_keypair],
-        );
+            &vec![context.operator_signer.as_ref()],
+        )
     }
 
     fn sign_input_2(
         &mut self,
         context: &VerifierContext,
         connector_5: &Connector5,
-        secret_nonce: &SecNonce,
-    ) {
+        session_id: &str,
+    ) -> Result<(), Error> {
         let input_index = 2;
-        pre_sign_musig2_taproot_input(
-            self,
-            context,
-            input_index,
-            TapSighashType::All,
-            secret_nonce,
-        );
+        pre_sign_musig2_taproot_input(self, context, input_index, TapSighashType::All, session_id)?;
 
         // TODO: Consider verifying the final signature against the n-of-n public key and the tx.
         if self.musig2_signatures[&input_index].len() == context.n_of_n_public_keys.len() {
-            self.finalize_input_2(context, connector_5);
+            self.finalize_input_2(context, connector_5)?;
         }
+
+        Ok(())
     }
 
-    fn finalize_input_2(&mut self, context: &dyn BaseContext, connector_5: &Connector5) {
+    fn finalize_input_2(
+        &mut self,
+        context: &dyn BaseContext,
+        connector_5: &Connector5,
+    ) -> Result<(), Error> {
         let input_index = 2;
         finalize_musig2_taproot_input(
             self,
@@ -251,10 +253,14 @@ impl Take2Transaction {
             input_index,
             TapSighashType::All,
             connector_5.generate_taproot_spend_info(),
-        );
+        )
     }
 
-    fn sign_input_3(&mut self, context: &OperatorContext, connector_c: &ConnectorC) {
+    fn sign_input_3(
+        &mut self,
+        context: &OperatorContext,
+        connector_c: &ConnectorC,
+    ) -> Result<(), Error> {
         let input_index = 3;
         let prev_outs = &self.prev_outs().clone();
         let merkle_root = connector_c.taproot_merkle_root();
@@ -265,8 +271,8 @@ impl Take2Transaction {
             prev_outs,
             TapSighashType::All,
             merkle_root,
-            &context.operator_keypair,
-        );
+            context.operator_signer.as_ref(),
+        )
     }
 
     pub fn pre_sign(
@@ -274,17 +280,21 @@ impl Take2Transaction {
         context: &VerifierContext,
         connector_0: &Connector0,
         connector_5: &Connector5,
-        secret_nonces: &HashMap<usize, SecNonce>,
-    ) {
+        session_ids: &HashMap<usize, Musig2SessionId>,
+    ) -> Result<(), Error> {
         let input_index = 0;
-        self.sign_input_0(context, connector_0, &secret_nonces[&input_index]);
+        self.sign_input_0(context, connector_0, &session_ids[&input_index])?;
 
         let input_index = 2;
-        self.sign_input_2(context, connector_5, &secret_nonces[&input_index]);
+        self.sign_input_2(context, connector_5, &session_ids[&input_index])
     }
 
-    pub fn sign(&mut self, context: &OperatorContext, connector_c: &ConnectorC) {
-        self.sign_input_3(context, connector_c);
+    pub fn sign(
+        &mut self,
+        context: &OperatorContext,
+        connector_c: &ConnectorC,
+    ) -> Result<(), Error> {
+        self.sign_input_3(context, connector_c)
     }
 
     pub fn merge(&mut self, take_2: &Take2Transaction) {
@@ -104,7 +104,7 @@ pub fn sb_hash_from_bytes() -> Script {
 }
 
 pub fn write_disk_cache(file_path: &Path, data: &impl Encode) -> std::io::Result<()> {
-    println!("Writing cache to {}...", file_path.display());
+    eprintln!("Writing cache to {}...", file_path.display());
     if let Some(parent) = file_path.parent() {
         if !parent.exists() {
             std::fs::create_dir_all(parent)?;
@@ -119,7 +119,7 @@ pub fn read_disk_cache<T>(file_path: &Path) -> std::io::Result<T>
 where
     T: for<'de> Decode<'de>,
 {
-    println!("Reading cache from {}...", file_path.display());
+    eprintln!("Reading cache from {}...", file_path.display());
     let compressed_data = std::fs::read(file_path)?;
     let encoded_data: Vec<u8> = decompress(&compressed_data)?;
     let decoded = bitcode::decode(&encoded_data).map_err(std::io::Error::other)?;
@@ -146,7 +146,7 @@ pub fn cleanup_cache_files(prefix: &str, cache_location: &Path, max_cache_files:
         let oldest = paths.pop().unwrap();
         std::fs::remove_file(&oldest)
             .inspect_err(|e| eprintln!("Failed to delete the old cache file: {}", e))
-            .inspect(|_| println!("Old cache file deleted: {:?}", oldest))
+            .inspect(|_| eprintln!("Old cache file deleted: {:?}", oldest))
             .ok();
     }
 }
@@ -75,11 +75,15 @@ pub async fn create_and_mine_assert_initial_tx(
         input,
     );
 
-    let secret_nonces_0 = assert_initial_tx.push_nonces(verifier_0_context);
-    let secret_nonces_1 = assert_initial_tx.push_nonces(verifier_1_context);
+    let session_ids_0 = assert_initial_tx.push_nonces(verifier_0_context).unwrap();
+    let session_ids_1 = assert_initial_tx.push_nonces(verifier_1_context).unwrap();
 
-    assert_initial_tx.pre_sign(verifier_0_context, connector_b, &secret_nonces_0);
-    assert_initial_tx.pre_sign(verifier_1_context, connector_b, &secret_nonces_1);
+    assert_initial_tx
+        .pre_sign(verifier_0_context, connector_b, &session_ids_0)
+        .unwrap();
+    assert_initial_tx
+        .pre_sign(verifier_1_context, connector_b, &session_ids_1)
+        .unwrap();
 
     let tx = assert_initial_tx.finalize();
     let tx_id = tx.compute_txid();
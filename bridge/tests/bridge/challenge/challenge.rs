@@ -21,7 +21,7 @@ async fn test_challenge_tx() {
     let config = setup_test().await;
 
     // We re-use the depositor private key to imitate a third-party
-    let crowdfunding_keypair = &config.depositor_context.depositor_keypair;
+    let crowdfunding_signer = config.depositor_context.depositor_signer.as_ref();
     let crowdfunding_public_key = &config.depositor_context.depositor_public_key;
 
     let faucet = Faucet::new(FaucetType::EsploraRegtest);
@@ -65,24 +65,27 @@ async fn test_challenge_tx() {
             amount: amount_0,
         },
         input_amount_crowdfunding_total,
-    );
+    )
+    .unwrap();
 
-    challenge_tx.add_inputs_and_output(
-        &vec![
-            InputWithScript {
-                outpoint: crowdfunding_outpoints[0],
-                amount: amount_1,
-                script: &generate_pay_to_pubkey_script(crowdfunding_public_key),
-            },
-            InputWithScript {
-                outpoint: crowdfunding_outpoints[1],
-                amount: amount_1,
-                script: &generate_pay_to_pubkey_script(crowdfunding_public_key),
-            },
-        ],
-        crowdfunding_keypair,
-        refund_address.script_pubkey(),
-    );
+    challenge_tx
+        .add_inputs_and_output(
+            &vec![
+                InputWithScript {
+                    outpoint: crowdfunding_outpoints[0],
+                    amount: amount_1,
+                    script: &generate_pay_to_pubkey_script(crowdfunding_public_key),
+                },
+                InputWithScript {
+                    outpoint: crowdfunding_outpoints[1],
+                    amount: amount_1,
+                    script: &generate_pay_to_pubkey_script(crowdfunding_public_key),
+                },
+            ],
+            crowdfunding_signer,
+            refund_address.script_pubkey(),
+        )
+        .unwrap();
 
     let tx = challenge_tx.finalize();
     check_tx_output_sum(two_thirds_of_initial_amount * 2 + DUST_AMOUNT, &tx);
@@ -7,7 +7,6 @@ use bridge::{
         },
         client::BitVMClient,
     },
-    commitments::CommitmentMessageId,
     graphs::{
         base::{max, DUST_AMOUNT, MIN_RELAY_FEE_ASSERT_SET, PEG_IN_FEE, PEG_OUT_FEE},
         peg_in::PegInGraph,
@@ -62,7 +61,8 @@ async fn test_peg_in_fees() {
     let peg_in_graph_id = config
         .client_0
         .create_peg_in_graph(peg_in_input, &config.depositor_evm_address)
-        .await;
+        .await
+        .unwrap();
 
     let esplora_client = config.client_0.esplora.clone();
 
@@ -154,15 +154,19 @@ async fn test_peg_out_fees() {
             },
             &config.depositor_evm_address,
         )
-        .await;
-    let peg_out_graph_id = config.client_0.create_peg_out_graph(
-        &peg_in_graph_id,
-        Input {
-            outpoint: peg_out_confirm_outpoint,
-            amount: peg_out_confirm_input_amount,
-        },
-        config.commitment_secrets.clone(),
-    );
+        .await
+        .unwrap();
+    let peg_out_graph_id = config
+        .client_0
+        .create_peg_out_graph(
+            &peg_in_graph_id,
+            Input {
+                outpoint: peg_out_confirm_outpoint,
+                amount: peg_out_confirm_input_amount,
+            },
+            config.commitment_secrets.clone(),
+        )
+        .unwrap();
 
     let esplora_client = config.client_0.esplora.clone();
     config
@@ -210,22 +214,34 @@ async fn test_peg_out_fees() {
         .await;
 
     println!("Verifier 0 push peg-out nonces");
-    config.client_0.push_verifier_nonces(&peg_out_graph_id);
+    config
+        .client_0
+        .push_verifier_nonces(&peg_out_graph_id)
+        .unwrap();
     config.client_0.flush().await;
 
     println!("Verifier 1 push peg-out nonces");
     config.client_1.sync().await;
-    config.client_1.push_verifier_nonces(&peg_out_graph_id);
+    config
+        .client_1
+        .push_verifier_nonces(&peg_out_graph_id)
+        .unwrap();
     config.client_1.flush().await;
 
     println!("Verifier 0 pre-sign peg-out");
     config.client_0.sync().await;
-    config.client_0.push_verifier_signature(&peg_out_graph_id);
+    config
+        .client_0
+        .push_verifier_signature(&peg_out_graph_id)
+        .unwrap();
     config.client_0.flush().await;
 
     println!("Verifier 1 pre-sign peg-out");
     config.client_1.sync().await;
-    config.client_1.push_verifier_signature(&peg_out_graph_id);
+    config
+        .client_1
+        .push_verifier_signature(&peg_out_graph_id)
+        .unwrap();
     config.client_1.flush().await;
     config.client_0.sync().await;
 
@@ -295,18 +311,9 @@ async fn test_peg_out_fees() {
     println!("peg out confirm tx result: {:?}\n", peg_out_confirm_result);
     assert!(peg_out_confirm_result.is_ok());
 
-    let private_data = config.client_0.private_data();
-    let secrets_map = private_data.commitment_secrets[&config.operator_context.operator_public_key]
-        [&peg_out_graph_id]
-        .clone();
     let peg_out_graph = get_peg_out_graph_mut(&mut config.client_0, peg_out_graph_id.clone());
     let kick_off_1_tx = peg_out_graph
-        .kick_off_1(
-            &esplora_client,
-            &config.operator_context,
-            &secrets_map[&CommitmentMessageId::PegOutTxIdSourceNetwork],
-            &secrets_map[&CommitmentMessageId::PegOutTxIdDestinationNetwork],
-        )
+        .kick_off_1(&esplora_client, &config.operator_context)
         .await
         .unwrap();
     check_tx_output_sum(
@@ -323,11 +330,7 @@ async fn test_peg_out_fees() {
     assert!(kick_off_1_result.is_ok());
 
     let start_time_tx = peg_out_graph
-        .start_time(
-            &esplora_client,
-            &config.operator_context,
-            &secrets_map[&CommitmentMessageId::StartTime],
-        )
+        .start_time(&esplora_client, &config.operator_context)
         .await
         .unwrap();
     check_tx_output_sum(DUST_AMOUNT, &start_time_tx);
@@ -380,7 +383,7 @@ async fn test_peg_out_fees() {
         .challenge(
             &esplora_client,
             &challenge_crowdfunding_inputs,
-            &config.depositor_context.depositor_keypair,
+            config.depositor_context.depositor_signer.as_ref(),
             depositor_pubkey_script.clone(),
         )
         .await
@@ -410,12 +413,7 @@ async fn test_peg_out_fees() {
     );
 
     let kick_off_2_tx = peg_out_graph
-        .kick_off_2(
-            &esplora_client,
-            &config.operator_context,
-            &config.commitment_secrets[&CommitmentMessageId::Superblock],
-            &config.commitment_secrets[&CommitmentMessageId::SuperblockHash],
-        )
+        .kick_off_2(&esplora_client, &config.operator_context)
         .await
         .unwrap();
     check_tx_output_sum(
@@ -479,7 +477,7 @@ async fn test_peg_out_fees() {
     let (assert_commit1_tx, assert_commit2_tx) = peg_out_graph
         .assert_commits(
             &esplora_client,
-            &config.commitment_secrets,
+            &config.operator_context,
             &config.invalid_proof,
         )
         .await
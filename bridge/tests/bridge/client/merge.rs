@@ -65,16 +65,20 @@ async fn setup_and_create_graphs() -> (BitVMClient, PegInGraph, PegOutGraph) {
     let peg_in_graph_id = config
         .client_0
         .create_peg_in_graph(input, &config.depositor_evm_address)
-        .await;
+        .await
+        .unwrap();
 
-    config.client_0.create_peg_out_graph(
-        &peg_in_graph_id,
-        Input {
-            outpoint: peg_out_outpoint,
-            amount,
-        },
-        config.commitment_secrets.clone(),
-    );
+    config
+        .client_0
+        .create_peg_out_graph(
+            &peg_in_graph_id,
+            Input {
+                outpoint: peg_out_outpoint,
+                amount,
+            },
+            config.commitment_secrets.clone(),
+        )
+        .unwrap();
 
     let new_peg_in_graph = PegInGraph::new(
         &config.depositor_context,
@@ -83,7 +87,8 @@ async fn setup_and_create_graphs() -> (BitVMClient, PegInGraph, PegOutGraph) {
             amount: Amount::from_sat(INITIAL_AMOUNT),
         },
         &config.depositor_evm_address,
-    );
+    )
+    .unwrap();
 
     let new_peg_out_graph = PegOutGraph::new(
         &config.operator_context,
@@ -93,7 +98,8 @@ async fn setup_and_create_graphs() -> (BitVMClient, PegInGraph, PegOutGraph) {
             amount,
         },
         &config.commitment_secrets,
-    );
+    )
+    .unwrap();
 
     (config.client_0, new_peg_in_graph, new_peg_out_graph)
 }
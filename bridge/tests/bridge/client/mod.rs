@@ -3,5 +3,6 @@ pub mod merge;
 pub mod musig2_keys;
 pub mod musig2_peg_in;
 pub mod musig2_peg_out;
+pub mod policy;
 pub mod sync;
 pub mod validate;
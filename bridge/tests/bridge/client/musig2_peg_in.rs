@@ -41,7 +41,8 @@ async fn test_musig2_peg_in() {
 
     let graph_id = depositor_operator_verifier_0_client
         .create_peg_in_graph(Input { outpoint, amount }, &config.depositor_evm_address)
-        .await;
+        .await
+        .unwrap();
     println!("Depositor: Created new graph {graph_id}");
 
     println!("Depositor: Mining peg in deposit...");
@@ -58,7 +59,9 @@ async fn test_musig2_peg_in() {
     depositor_operator_verifier_0_client.sync().await;
 
     println!("Verifier 0: Generating nonces...");
-    depositor_operator_verifier_0_client.push_verifier_nonces(&graph_id);
+    depositor_operator_verifier_0_client
+        .push_verifier_nonces(&graph_id)
+        .unwrap();
 
     println!("Verifier 0: Saving state changes to remote...");
     depositor_operator_verifier_0_client.flush().await;
@@ -68,7 +71,7 @@ async fn test_musig2_peg_in() {
     verifier_1_client.sync().await;
 
     println!("Verifier 1: Generating nonces...");
-    verifier_1_client.push_verifier_nonces(&graph_id);
+    verifier_1_client.push_verifier_nonces(&graph_id).unwrap();
 
     println!("Verifier 1: Saving state changes to remote...");
     verifier_1_client.flush().await;
@@ -78,7 +81,9 @@ async fn test_musig2_peg_in() {
     depositor_operator_verifier_0_client.sync().await;
 
     println!("Verifier 0: Pre-signing...");
-    depositor_operator_verifier_0_client.push_verifier_signature(&graph_id);
+    depositor_operator_verifier_0_client
+        .push_verifier_signature(&graph_id)
+        .unwrap();
 
     println!("Verifier 0: Saving state changes to remote...");
     depositor_operator_verifier_0_client.flush().await;
@@ -88,7 +93,9 @@ async fn test_musig2_peg_in() {
     verifier_1_client.sync().await;
 
     println!("Verifier 1: Pre-signing...");
-    verifier_1_client.push_verifier_signature(&graph_id);
+    verifier_1_client
+        .push_verifier_signature(&graph_id)
+        .unwrap();
 
     println!("Verifier 1: Saving state changes to remote...");
     verifier_1_client.flush().await;
@@ -497,14 +497,16 @@ async fn create_peg_out_graph() -> (
     .await;
 
     println!("Creating peg-out graph...");
-    let peg_out_graph_id = depositor_operator_verifier_0_client.create_peg_out_graph(
-        &peg_in_graph_id,
-        Input {
-            outpoint: kick_off_outpoint,
-            amount: kick_off_input_amount,
-        },
-        config.commitment_secrets,
-    );
+    let peg_out_graph_id = depositor_operator_verifier_0_client
+        .create_peg_out_graph(
+            &peg_in_graph_id,
+            Input {
+                outpoint: kick_off_outpoint,
+                amount: kick_off_input_amount,
+            },
+            config.commitment_secrets,
+        )
+        .unwrap();
 
     println!("Verifier 0 push peg-in nonces");
     depositor_operator_verifier_0_client
@@ -540,22 +542,30 @@ async fn create_peg_out_graph() -> (
         .await;
 
     println!("Verifier 0 push peg-out nonces");
-    depositor_operator_verifier_0_client.push_verifier_nonces(&peg_out_graph_id);
+    depositor_operator_verifier_0_client
+        .push_verifier_nonces(&peg_out_graph_id)
+        .unwrap();
     depositor_operator_verifier_0_client.flush().await;
 
     println!("Verifier 1 push peg-out nonces");
     verifier_1_client.sync().await;
-    verifier_1_client.push_verifier_nonces(&peg_out_graph_id);
+    verifier_1_client
+        .push_verifier_nonces(&peg_out_graph_id)
+        .unwrap();
     verifier_1_client.flush().await;
 
     println!("Verifier 0 pre-sign peg-out");
     depositor_operator_verifier_0_client.sync().await;
-    depositor_operator_verifier_0_client.push_verifier_signature(&peg_out_graph_id);
+    depositor_operator_verifier_0_client
+        .push_verifier_signature(&peg_out_graph_id)
+        .unwrap();
     depositor_operator_verifier_0_client.flush().await;
 
     println!("Verifier 1 pre-sign peg-out");
     verifier_1_client.sync().await;
-    verifier_1_client.push_verifier_signature(&peg_out_graph_id);
+    verifier_1_client
+        .push_verifier_signature(&peg_out_graph_id)
+        .unwrap();
     verifier_1_client.flush().await;
 
     (
@@ -586,7 +596,8 @@ async fn create_peg_in_graph(
             },
             depositor_evm_address,
         )
-        .await;
+        .await
+        .unwrap();
     println!("Peg in graph created: {}", graph_id);
 
     client_0
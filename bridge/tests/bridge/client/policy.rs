@@ -0,0 +1,198 @@
+use bitcoin::{absolute, hashes::Hash, transaction, Amount, Network, Transaction, Txid};
+use esplora_client::Builder;
+
+use bridge::{
+    client::policy::{BroadcastLimits, Policy, PolicyAction},
+    error::{Error, PolicyError},
+};
+
+#[test]
+fn test_policy_defaults() {
+    let policy = Policy::from_toml("").unwrap();
+
+    assert!(!policy.dry_run);
+    assert!(policy.operator.enabled);
+    assert!(!policy.verifier.challenge);
+    assert!(policy.verifier.disprove);
+    assert!(policy.verifier.disprove_requires_local_validation);
+    assert!(policy.watches(&"any_graph".to_string()));
+    assert_eq!(policy.broadcast_limits(), BroadcastLimits::default());
+    assert_eq!(policy.reward_script(Network::Testnet), Ok(None));
+}
+
+#[test]
+fn test_policy_from_toml() {
+    let policy = Policy::from_toml(
+        r#"
+        dry_run = true
+
+        [graphs]
+        watch = ["graph_0", "graph_1"]
+        ignore = ["graph_1"]
+
+        [fees]
+        max_fee = 500000
+        max_fee_rate = 25.5
+
+        [operator]
+        enabled = false
+
+        [verifier]
+        reward_address = "tb1qlj64u6fqutr0xue85kl55fx0gt4m4urun25p7q"
+        challenge = true
+        max_challenge_contribution = 2000000
+        disprove_requires_local_validation = false
+        "#,
+    )
+    .unwrap();
+
+    assert!(policy.watches(&"graph_0".to_string()));
+    assert!(!policy.watches(&"graph_1".to_string()));
+    assert!(!policy.watches(&"graph_2".to_string()));
+    assert!(!policy.operator.enabled);
+    assert!(policy.verifier.challenge);
+    assert!(policy.verifier.timeouts);
+    assert_eq!(policy.verifier.max_challenge_contribution, Some(2000000));
+    assert!(!policy.verifier.disprove_requires_local_validation);
+    assert_eq!(
+        policy.broadcast_limits(),
+        BroadcastLimits {
+            max_fee: Some(Amount::from_sat(500000)),
+            max_fee_rate: Some(25.5),
+            dry_run: true,
+        }
+    );
+    assert!(policy.reward_script(Network::Testnet).unwrap().is_some());
+    assert!(policy.reward_script(Network::Bitcoin).is_err());
+}
+
+#[test]
+fn test_policy_rejects_unknown_fields() {
+    assert!(Policy::from_toml("[verifier]\nchalenge = true").is_err());
+}
+
+#[test]
+fn test_max_fee_decision() {
+    let limits = BroadcastLimits {
+        max_fee: Some(Amount::from_sat(1000)),
+        ..Default::default()
+    };
+    let txid = Txid::all_zeros();
+
+    assert!(limits.check_fee(txid, Amount::from_sat(1000), 10).is_ok());
+    assert!(matches!(
+        limits.check_fee(txid, Amount::from_sat(1001), 10),
+        Err(Error::Policy(PolicyError::FeeLimitExceeded(_, fee, max_fee)))
+            if fee == Amount::from_sat(1001) && max_fee == Amount::from_sat(1000)
+    ));
+}
+
+#[test]
+fn test_max_fee_rate_decision() {
+    let limits = BroadcastLimits {
+        max_fee_rate: Some(10.0),
+        ..Default::default()
+    };
+    let txid = Txid::all_zeros();
+
+    assert!(limits.check_fee(txid, Amount::from_sat(1000), 100).is_ok());
+    assert!(limits
+        .check_fee(txid, Amount::from_sat(1000000), 100001)
+        .is_ok());
+    assert!(matches!(
+        limits.check_fee(txid, Amount::from_sat(1001), 100),
+        Err(Error::Policy(PolicyError::FeeRateLimitExceeded(_, fee_rate, max_fee_rate)))
+            if fee_rate == 10.01 && max_fee_rate == 10.0
+    ));
+}
+
+#[test]
+fn test_no_fee_limits_decision() {
+    assert!(BroadcastLimits::default()
+        .check_fee(Txid::all_zeros(), Amount::MAX_MONEY, 1)
+        .is_ok());
+}
+
+#[tokio::test]
+async fn test_dry_run_decision() {
+    // Without fee limits the limits are checked without querying esplora
+    let esplora = Builder::new("http://127.0.0.1:1").build_async().unwrap();
+    let tx = Transaction {
+        version: transaction::Version(2),
+        lock_time: absolute::LockTime::ZERO,
+        input: vec![],
+        output: vec![],
+    };
+
+    let dry_run = BroadcastLimits {
+        dry_run: true,
+        ..Default::default()
+    };
+    assert!(matches!(
+        dry_run.check(&esplora, &tx).await,
+        Err(Error::Policy(PolicyError::DryRun(txid))) if txid == tx.compute_txid()
+    ));
+    assert!(BroadcastLimits::default()
+        .check(&esplora, &tx)
+        .await
+        .is_ok());
+}
+
+#[test]
+fn test_disabled_actions() {
+    let policy = Policy::from_toml("").unwrap();
+    assert_eq!(policy.disabled_reason(PolicyAction::KickOff1), None);
+    assert_eq!(policy.disabled_reason(PolicyAction::KickOffTimeout), None);
+    assert_eq!(policy.disabled_reason(PolicyAction::DisproveChain), None);
+    assert_eq!(policy.disabled_reason(PolicyAction::Disprove), None);
+    assert!(policy.disabled_reason(PolicyAction::Challenge).is_some());
+
+    let policy = Policy::from_toml(
+        r#"
+        [operator]
+        enabled = false
+
+        [verifier]
+        timeouts = false
+        challenge = true
+        disprove_chain = false
+        disprove = false
+        "#,
+    )
+    .unwrap();
+    for action in [
+        PolicyAction::PegOutConfirm,
+        PolicyAction::KickOff1,
+        PolicyAction::AssertInitial,
+        PolicyAction::Take2,
+        PolicyAction::StartTimeTimeout,
+        PolicyAction::KickOffTimeout,
+        PolicyAction::DisproveChain,
+        PolicyAction::Disprove,
+    ] {
+        assert!(policy.disabled_reason(action).is_some(), "{action:?}");
+    }
+    assert_eq!(policy.disabled_reason(PolicyAction::Challenge), None);
+}
+
+#[test]
+fn test_challenge_contribution_decision() {
+    let policy = Policy::from_toml("[verifier]\nmax_challenge_contribution = 2000000").unwrap();
+
+    assert_eq!(
+        policy.challenge_denied_reason(&Ok(Amount::from_sat(2000000))),
+        None
+    );
+    assert!(policy
+        .challenge_denied_reason(&Ok(Amount::from_sat(2000001)))
+        .is_some());
+    assert!(policy
+        .challenge_denied_reason(&Err(Error::Other("Peg-out graph not found".to_string())))
+        .is_some());
+
+    let policy = Policy::from_toml("").unwrap();
+    assert_eq!(policy.challenge_denied_reason(&Ok(Amount::MAX_MONEY)), None);
+    assert!(policy
+        .challenge_denied_reason(&Err(Error::Other("Peg-out graph not found".to_string())))
+        .is_some());
+}
@@ -109,7 +109,8 @@ async fn setup_and_create_graphs() -> (AsyncClient, BitVMClientPublicData, OutPo
             amount: amount_0,
         },
         &config.depositor_evm_address,
-    );
+    )
+    .unwrap();
 
     let peg_in_graph_1 = PegInGraph::new(
         &config.depositor_context,
@@ -118,7 +119,8 @@ async fn setup_and_create_graphs() -> (AsyncClient, BitVMClientPublicData, OutPo
             amount: amount_1,
         },
         &config.depositor_evm_address,
-    );
+    )
+    .unwrap();
 
     let peg_out_graph = PegOutGraph::new(
         &config.operator_context,
@@ -128,7 +130,8 @@ async fn setup_and_create_graphs() -> (AsyncClient, BitVMClientPublicData, OutPo
             amount: amount_0,
         },
         &config.commitment_secrets,
-    );
+    )
+    .unwrap();
 
     let data = BitVMClientPublicData {
         version: 1,
@@ -55,19 +55,23 @@ async fn test_disprove_tx_success() {
         },
     );
 
-    let secret_nonces_0 = disprove_tx.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = disprove_tx.push_nonces(&config.verifier_1_context);
-
-    disprove_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_5,
-        &secret_nonces_0,
-    );
-    disprove_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_5,
-        &secret_nonces_1,
-    );
+    let session_ids_0 = disprove_tx.push_nonces(&config.verifier_0_context).unwrap();
+    let session_ids_1 = disprove_tx.push_nonces(&config.verifier_1_context).unwrap();
+
+    disprove_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_5,
+            &session_ids_0,
+        )
+        .unwrap();
+    disprove_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_5,
+            &session_ids_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
@@ -122,19 +126,23 @@ async fn test_disprove_tx_with_verifier_added_to_output_success() {
         },
     );
 
-    let secret_nonces_0 = disprove_tx.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = disprove_tx.push_nonces(&config.verifier_1_context);
-
-    disprove_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_5,
-        &secret_nonces_0,
-    );
-    disprove_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_5,
-        &secret_nonces_1,
-    );
+    let session_ids_0 = disprove_tx.push_nonces(&config.verifier_0_context).unwrap();
+    let session_ids_1 = disprove_tx.push_nonces(&config.verifier_1_context).unwrap();
+
+    disprove_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_5,
+            &session_ids_0,
+        )
+        .unwrap();
+    disprove_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_5,
+            &session_ids_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
@@ -45,19 +45,27 @@ async fn test_disprove_chain_tx_success() {
         Input { outpoint, amount },
     );
 
-    let secret_nonces_0 = disprove_chain_tx.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = disprove_chain_tx.push_nonces(&config.verifier_1_context);
+    let session_ids_0 = disprove_chain_tx
+        .push_nonces(&config.verifier_0_context)
+        .unwrap();
+    let session_ids_1 = disprove_chain_tx
+        .push_nonces(&config.verifier_1_context)
+        .unwrap();
 
-    disprove_chain_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_b,
-        &secret_nonces_0,
-    );
-    disprove_chain_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_b,
-        &secret_nonces_1,
-    );
+    disprove_chain_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_b,
+            &session_ids_0,
+        )
+        .unwrap();
+    disprove_chain_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_b,
+            &session_ids_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
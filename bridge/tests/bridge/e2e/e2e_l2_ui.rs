@@ -208,32 +208,42 @@ async fn create_graph() -> (
 
     depositor_operator_verifier_0_client.sync().await;
     println!("Creating peg-out graph...");
-    let peg_out_graph_id = depositor_operator_verifier_0_client.create_peg_out_graph(
-        &peg_in_graph_id,
-        Input {
-            outpoint: kick_off_outpoint,
-            amount: kick_off_input_amount,
-        },
-        config.commitment_secrets,
-    );
+    let peg_out_graph_id = depositor_operator_verifier_0_client
+        .create_peg_out_graph(
+            &peg_in_graph_id,
+            Input {
+                outpoint: kick_off_outpoint,
+                amount: kick_off_input_amount,
+            },
+            config.commitment_secrets,
+        )
+        .unwrap();
 
     println!("Verifier 0 push peg-out nonces");
-    depositor_operator_verifier_0_client.push_verifier_nonces(&peg_out_graph_id);
+    depositor_operator_verifier_0_client
+        .push_verifier_nonces(&peg_out_graph_id)
+        .unwrap();
     depositor_operator_verifier_0_client.flush().await;
 
     println!("Verifier 1 push peg-out nonces");
     verifier_1_client.sync().await;
-    verifier_1_client.push_verifier_nonces(&peg_out_graph_id);
+    verifier_1_client
+        .push_verifier_nonces(&peg_out_graph_id)
+        .unwrap();
     verifier_1_client.flush().await;
 
     println!("Verifier 0 pre-sign peg-out");
     depositor_operator_verifier_0_client.sync().await;
-    depositor_operator_verifier_0_client.push_verifier_signature(&peg_out_graph_id);
+    depositor_operator_verifier_0_client
+        .push_verifier_signature(&peg_out_graph_id)
+        .unwrap();
     depositor_operator_verifier_0_client.flush().await;
 
     println!("Verifier 1 pre-sign peg-out");
     verifier_1_client.sync().await;
-    verifier_1_client.push_verifier_signature(&peg_out_graph_id);
+    verifier_1_client
+        .push_verifier_signature(&peg_out_graph_id)
+        .unwrap();
     verifier_1_client.flush().await;
 
     (
@@ -262,25 +272,26 @@ async fn create_peg_in_graph(
             },
             depositor_evm_address,
         )
-        .await;
+        .await
+        .unwrap();
 
     client_0
         .broadcast_peg_in_deposit(&graph_id)
         .await
         .expect("Failed to broadcast peg-in deposit");
-    client_0.push_verifier_nonces(&graph_id);
+    client_0.push_verifier_nonces(&graph_id).unwrap();
     client_0.flush().await;
 
     client_1.sync().await;
-    client_1.push_verifier_nonces(&graph_id);
+    client_1.push_verifier_nonces(&graph_id).unwrap();
     client_1.flush().await;
 
     client_0.sync().await;
-    client_0.push_verifier_signature(&graph_id);
+    client_0.push_verifier_signature(&graph_id).unwrap();
     client_0.flush().await;
 
     client_1.sync().await;
-    client_1.push_verifier_signature(&graph_id);
+    client_1.push_verifier_signature(&graph_id).unwrap();
     client_1.flush().await;
 
     // Wait for peg-in deposit transaction to be mined
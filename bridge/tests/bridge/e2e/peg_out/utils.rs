@@ -26,7 +26,8 @@ pub async fn create_peg_in_graph(
     println!("{}", "Creating PEG-IN graph...".bold().yellow());
     let graph_id = depositor_verifier_0
         .create_peg_in_graph(deposit_input, depositor_evm_address)
-        .await;
+        .await
+        .unwrap();
 
     let peg_in_deposit_txid = depositor_verifier_0
         .broadcast_peg_in_deposit(&graph_id)
@@ -41,28 +42,32 @@ pub async fn create_peg_in_graph(
 
     println!("{}", "PEG-IN ceremony start".bold().yellow());
     println!("{}", "Generate verifier 0 nonces".bold().magenta());
-    depositor_verifier_0.push_verifier_nonces(&graph_id);
+    depositor_verifier_0
+        .push_verifier_nonces(&graph_id)
+        .unwrap();
     println!("{}", "Flush verifier 0 nonces".bold().magenta());
     depositor_verifier_0.flush().await;
 
     println!("{}", "Sync verifier 1".bold().blue());
     verifier_1.sync().await;
     println!("{}", "Generate verifier 1 nonces".bold().blue());
-    verifier_1.push_verifier_nonces(&graph_id);
+    verifier_1.push_verifier_nonces(&graph_id).unwrap();
     println!("{}", "Flush verifier 1 nonces".bold().blue());
     verifier_1.flush().await;
 
     println!("{}", "Sync verifier 0".bold().magenta());
     depositor_verifier_0.sync().await;
     println!("{}", "Generate verifier 0 signatures".bold().magenta());
-    depositor_verifier_0.push_verifier_signature(&graph_id);
+    depositor_verifier_0
+        .push_verifier_signature(&graph_id)
+        .unwrap();
     println!("{}", "Flush verifier 0 signatures".bold().magenta());
     depositor_verifier_0.flush().await;
 
     println!("{}", "Sync verifier 1".bold().blue());
     verifier_1.sync().await;
     println!("{}", "Generate verifier 1 signatures".bold().blue());
-    verifier_1.push_verifier_signature(&graph_id);
+    verifier_1.push_verifier_signature(&graph_id).unwrap();
     println!("{}", "Flush verifier 1 signatures".bold().blue());
     verifier_1.flush().await;
     println!("{}", "PEG-IN ceremony finish".bold().yellow());
@@ -160,39 +165,47 @@ pub async fn create_peg_out_graph() -> (
     verifier_0_operator_depositor.sync().await;
 
     println!("{}", "Creating PEG-OUT graph...".bold().yellow());
-    let peg_out_graph_id = verifier_0_operator_depositor.create_peg_out_graph(
-        &peg_in_graph_id,
-        Input {
-            outpoint: peg_out_confirm_outpoint,
-            amount: peg_out_confirm_input_amount,
-        },
-        config.commitment_secrets,
-    );
+    let peg_out_graph_id = verifier_0_operator_depositor
+        .create_peg_out_graph(
+            &peg_in_graph_id,
+            Input {
+                outpoint: peg_out_confirm_outpoint,
+                amount: peg_out_confirm_input_amount,
+            },
+            config.commitment_secrets,
+        )
+        .unwrap();
 
     println!("{}", "PEG-OUT ceremony start".bold().yellow());
     println!("{}", "Generate verifier 0 nonces".bold().magenta());
-    verifier_0_operator_depositor.push_verifier_nonces(&peg_out_graph_id);
+    verifier_0_operator_depositor
+        .push_verifier_nonces(&peg_out_graph_id)
+        .unwrap();
     println!("{}", "Flush verifier 0 nonces".bold().magenta());
     verifier_0_operator_depositor.flush().await;
 
     println!("{}", "Sync verifier 1".bold().blue());
     verifier_1.sync().await;
     println!("{}", "Generate verifier 1 nonces".bold().blue());
-    verifier_1.push_verifier_nonces(&peg_out_graph_id);
+    verifier_1.push_verifier_nonces(&peg_out_graph_id).unwrap();
     println!("{}", "Flush verifier 1 nonces".bold().blue());
     verifier_1.flush().await;
 
     println!("{}", "Sync verifier 0".bold().magenta());
     verifier_0_operator_depositor.sync().await;
     println!("{}", "Generate verifier 0 signatures".bold().magenta());
-    verifier_0_operator_depositor.push_verifier_signature(&peg_out_graph_id);
+    verifier_0_operator_depositor
+        .push_verifier_signature(&peg_out_graph_id)
+        .unwrap();
     println!("{}", "Flush verifier 0 signatures".bold().magenta());
     verifier_0_operator_depositor.flush().await;
 
     println!("{}", "Sync verifier 1".bold().blue());
     verifier_1.sync().await;
     println!("{}", "Generate verifier 1 signatures".bold().blue());
-    verifier_1.push_verifier_signature(&peg_out_graph_id);
+    verifier_1
+        .push_verifier_signature(&peg_out_graph_id)
+        .unwrap();
     println!("{}", "Flush verifier 1 signatures".bold().blue());
     verifier_1.flush().await;
     println!("{}", "PEG-OUT ceremony finish".bold().yellow());
@@ -16,7 +16,7 @@ use bitvm::chunk::api::{NUM_HASH, NUM_PUBS, NUM_U256};
 use bitvm::signatures::HASH_LEN;
 use bridge::client::chain::chain::PegOutEvent;
 use bridge::client::data_store::local_file::TEST_DATA_DIRECTORY_NAME;
-use bridge::proof::get_proof;
+use bridge::proof::get_dummy_proof;
 use bridge::{
     client::client::BitVMClient,
     graphs::{
@@ -306,7 +306,7 @@ pub fn get_intermediate_variables_cached() -> BTreeMap<String, usize> {
 }
 
 pub fn get_valid_proof() -> RawProof {
-    get_proof()
+    get_dummy_proof()
 }
 
 pub fn invalidate_proof(valid_proof: &RawProof) -> RawProof {
@@ -39,7 +39,8 @@ async fn test_peg_in_success() {
         &config.depositor_context,
         &config.connector_z,
         deposit_input,
-    );
+    )
+    .unwrap();
 
     let peg_in_deposit_tx = peg_in_deposit.finalize();
     let deposit_txid = peg_in_deposit_tx.compute_txid();
@@ -65,21 +66,30 @@ async fn test_peg_in_success() {
         &config.connector_0,
         &config.connector_z,
         confirm_input,
-    );
+    )
+    .unwrap();
 
-    let secret_nonces_0 = peg_in_confirm.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = peg_in_confirm.push_nonces(&config.verifier_1_context);
+    let session_ids_0 = peg_in_confirm
+        .push_nonces(&config.verifier_0_context)
+        .unwrap();
+    let session_ids_1 = peg_in_confirm
+        .push_nonces(&config.verifier_1_context)
+        .unwrap();
 
-    peg_in_confirm.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_z,
-        &secret_nonces_0,
-    );
-    peg_in_confirm.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_z,
-        &secret_nonces_1,
-    );
+    peg_in_confirm
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_z,
+            &session_ids_0,
+        )
+        .unwrap();
+    peg_in_confirm
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_z,
+            &session_ids_1,
+        )
+        .unwrap();
 
     let peg_in_confirm_tx = peg_in_confirm.finalize();
     let confirm_txid = peg_in_confirm_tx.compute_txid();
@@ -132,7 +142,8 @@ async fn test_peg_in_time_lock_not_surpassed() {
         &config.depositor_context,
         &config.connector_z,
         deposit_input,
-    );
+    )
+    .unwrap();
     let peg_in_deposit_tx = peg_in_deposit.finalize();
     let deposit_txid = peg_in_deposit_tx.compute_txid();
 
@@ -152,7 +163,8 @@ async fn test_peg_in_time_lock_not_surpassed() {
         amount: peg_in_deposit_tx.output[output_index as usize].value,
     };
     let peg_in_refund =
-        PegInRefundTransaction::new(&config.depositor_context, &config.connector_z, refund_input);
+        PegInRefundTransaction::new(&config.depositor_context, &config.connector_z, refund_input)
+            .unwrap();
     let peg_in_refund_tx = peg_in_refund.finalize();
 
     // mine peg-in refund
@@ -182,7 +194,8 @@ async fn test_peg_in_time_lock_surpassed() {
         &config.depositor_context,
         &config.connector_z,
         deposit_input,
-    );
+    )
+    .unwrap();
     let peg_in_deposit_tx = peg_in_deposit.finalize();
     let deposit_txid = peg_in_deposit_tx.compute_txid();
 
@@ -202,7 +215,8 @@ async fn test_peg_in_time_lock_surpassed() {
         amount: peg_in_deposit_tx.output[output_index as usize].value,
     };
     let peg_in_refund =
-        PegInRefundTransaction::new(&config.depositor_context, &config.connector_z, refund_input);
+        PegInRefundTransaction::new(&config.depositor_context, &config.connector_z, refund_input)
+            .unwrap();
     let peg_in_refund_tx = peg_in_refund.finalize();
     let refund_txid = peg_in_refund_tx.compute_txid();
 
@@ -313,7 +327,8 @@ async fn test_peg_in_graph_automatic_verifier() {
     // create the actual graph & check that status changes to PegInWait
     client_0
         .create_peg_in_graph(deposit_input, "0000000000000000000000000000000000000000")
-        .await;
+        .await
+        .unwrap();
     assert_eq!(
         graph(client_0)
             .verifier_status(&esplora, &config.verifier_0_context, &[])
@@ -58,7 +58,6 @@ async fn test_challenge_success() {
         &config.connector_2,
         &config.connector_6,
         kick_off_1_input_amount,
-        &config.commitment_secrets,
     )
     .await;
 
@@ -89,12 +88,15 @@ async fn test_challenge_success() {
         &config.connector_a,
         challenge_kick_off_input,
         challenge_input_amount,
-    );
-    challenge.add_inputs_and_output(
-        &vec![challenge_crowdfunding_input],
-        &config.depositor_context.depositor_keypair,
-        generate_pay_to_pubkey_script(&config.depositor_context.depositor_public_key),
-    ); // add crowdfunding input
+    )
+    .unwrap();
+    challenge
+        .add_inputs_and_output(
+            &vec![challenge_crowdfunding_input],
+            config.depositor_context.depositor_signer.as_ref(),
+            generate_pay_to_pubkey_script(&config.depositor_context.depositor_public_key),
+        )
+        .unwrap(); // add crowdfunding input
     let challenge_tx = challenge.finalize();
     let challenge_txid = challenge_tx.compute_txid();
 
@@ -70,7 +70,6 @@ async fn test_disprove_success() {
         &config.connector_b,
         &kick_off_2_funding_utxo_address,
         kick_off_2_input_amount,
-        &config.commitment_secrets,
     )
     .await;
 
@@ -206,21 +205,30 @@ async fn test_disprove_success() {
         assert_final_input_0,
         assert_final_input_1,
         assert_final_input_2,
-    );
+    )
+    .unwrap();
 
-    let secret_nonces_0 = assert_final.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = assert_final.push_nonces(&config.verifier_1_context);
+    let session_ids_0 = assert_final
+        .push_nonces(&config.verifier_0_context)
+        .unwrap();
+    let session_ids_1 = assert_final
+        .push_nonces(&config.verifier_1_context)
+        .unwrap();
 
-    assert_final.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_d,
-        &secret_nonces_0,
-    );
-    assert_final.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_d,
-        &secret_nonces_1,
-    );
+    assert_final
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_d,
+            &session_ids_0,
+        )
+        .unwrap();
+    assert_final
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_d,
+            &session_ids_1,
+        )
+        .unwrap();
 
     let assert_final_tx = assert_final.finalize();
     let assert_final_txid = assert_final_tx.compute_txid();
@@ -273,19 +281,23 @@ async fn test_disprove_success() {
         disprove_input_1,
     );
 
-    let secret_nonces_0 = disprove.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = disprove.push_nonces(&config.verifier_1_context);
+    let session_ids_0 = disprove.push_nonces(&config.verifier_0_context).unwrap();
+    let session_ids_1 = disprove.push_nonces(&config.verifier_1_context).unwrap();
 
-    disprove.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_5,
-        &secret_nonces_0,
-    );
-    disprove.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_5,
-        &secret_nonces_1,
-    );
+    disprove
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_5,
+            &session_ids_0,
+        )
+        .unwrap();
+    disprove
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_5,
+            &session_ids_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
@@ -46,7 +46,6 @@ async fn test_disprove_chain_success() {
         &config.connector_b,
         &kick_off_2_funding_utxo_address,
         kick_off_2_input_amount,
-        &config.commitment_secrets,
     )
     .await;
 
@@ -66,19 +65,27 @@ async fn test_disprove_chain_success() {
         disprove_chain_input_0,
     );
 
-    let secret_nonces_0 = disprove_chain.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = disprove_chain.push_nonces(&config.verifier_1_context);
+    let session_ids_0 = disprove_chain
+        .push_nonces(&config.verifier_0_context)
+        .unwrap();
+    let session_ids_1 = disprove_chain
+        .push_nonces(&config.verifier_1_context)
+        .unwrap();
 
-    disprove_chain.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_b,
-        &secret_nonces_0,
-    );
-    disprove_chain.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_b,
-        &secret_nonces_1,
-    );
+    disprove_chain
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_b,
+            &session_ids_0,
+        )
+        .unwrap();
+    disprove_chain
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_b,
+            &session_ids_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
@@ -48,7 +48,6 @@ async fn test_kick_off_timeout_success() {
         &config.connector_2,
         &config.connector_6,
         kick_off_1_input_amount,
-        &config.commitment_secrets,
     )
     .await;
 
@@ -68,8 +67,12 @@ async fn test_kick_off_timeout_success() {
         kick_off_timeout_input_0,
     );
 
-    let secret_nonces_0 = kick_off_timeout.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = kick_off_timeout.push_nonces(&config.verifier_1_context);
+    let session_ids_0 = kick_off_timeout
+        .push_nonces(&config.verifier_0_context)
+        .unwrap();
+    let session_ids_1 = kick_off_timeout
+        .push_nonces(&config.verifier_1_context)
+        .unwrap();
 
     let verifier_0_connector_1 = Connector1::new(
         config.verifier_0_context.network,
@@ -77,22 +80,26 @@ async fn test_kick_off_timeout_success() {
         &config.verifier_0_context.n_of_n_taproot_public_key,
         &config.connector_1.commitment_public_keys, // Verifiers get this via remote storage.
     );
-    kick_off_timeout.pre_sign(
-        &config.verifier_0_context,
-        &verifier_0_connector_1,
-        &secret_nonces_0,
-    );
+    kick_off_timeout
+        .pre_sign(
+            &config.verifier_0_context,
+            &verifier_0_connector_1,
+            &session_ids_0,
+        )
+        .unwrap();
     let verifier_1_connector_1 = Connector1::new(
         config.verifier_0_context.network,
         &config.operator_context.operator_taproot_public_key,
         &config.verifier_0_context.n_of_n_taproot_public_key,
         &config.connector_1.commitment_public_keys,
     );
-    kick_off_timeout.pre_sign(
-        &config.verifier_1_context,
-        &verifier_1_connector_1,
-        &secret_nonces_1,
-    );
+    kick_off_timeout
+        .pre_sign(
+            &config.verifier_1_context,
+            &verifier_1_connector_1,
+            &session_ids_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
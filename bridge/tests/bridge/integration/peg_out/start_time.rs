@@ -1,6 +1,5 @@
 use bitcoin::{Address, Amount, OutPoint};
 use bridge::{
-    commitments::CommitmentMessageId,
     connectors::base::TaprootConnector,
     graphs::base::DUST_AMOUNT,
     superblock::get_start_time_block_number,
@@ -46,7 +45,6 @@ async fn test_start_time_success() {
         &config.connector_2,
         &config.connector_6,
         kick_off_1_input_amount,
-        &config.commitment_secrets,
     )
     .await;
 
@@ -66,12 +64,13 @@ async fn test_start_time_success() {
         start_time_input_0,
     );
 
-    start_time.sign(
-        &config.operator_context,
-        &config.connector_2,
-        get_start_time_block_number(config.network),
-        &config.commitment_secrets[&CommitmentMessageId::StartTime],
-    );
+    start_time
+        .sign(
+            &config.operator_context,
+            &config.connector_2,
+            get_start_time_block_number(config.network),
+        )
+        .unwrap();
 
     let start_time_tx = start_time.finalize();
     // start time output should only have dust left
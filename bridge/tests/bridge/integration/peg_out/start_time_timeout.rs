@@ -52,7 +52,6 @@ async fn test_start_time_timeout_success() {
         &config.connector_2,
         &config.connector_6,
         kick_off_1_input_amount,
-        &config.commitment_secrets,
     )
     .await;
 
@@ -81,21 +80,29 @@ async fn test_start_time_timeout_success() {
         start_time_timeout_input_1,
     );
 
-    let secret_nonces_0 = start_time_timeout.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = start_time_timeout.push_nonces(&config.verifier_1_context);
+    let session_ids_0 = start_time_timeout
+        .push_nonces(&config.verifier_0_context)
+        .unwrap();
+    let session_ids_1 = start_time_timeout
+        .push_nonces(&config.verifier_1_context)
+        .unwrap();
 
-    start_time_timeout.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_1,
-        &config.connector_2,
-        &secret_nonces_0,
-    );
-    start_time_timeout.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_1,
-        &config.connector_2,
-        &secret_nonces_1,
-    );
+    start_time_timeout
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_1,
+            &config.connector_2,
+            &session_ids_0,
+        )
+        .unwrap();
+    start_time_timeout
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_1,
+            &config.connector_2,
+            &session_ids_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
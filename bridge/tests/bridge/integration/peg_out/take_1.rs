@@ -1,7 +1,5 @@
 use bitcoin::{Address, Amount, OutPoint};
-use bitvm::signatures::signing_winternitz::WinternitzSigningInputs;
 use bridge::{
-    commitments::CommitmentMessageId,
     connectors::base::TaprootConnector,
     graphs::base::{DUST_AMOUNT, MIN_RELAY_FEE_ASSERT_SET, PEG_OUT_FEE},
     scripts::generate_pay_to_pubkey_script_address,
@@ -75,7 +73,6 @@ async fn test_take_1_success() {
         &config.connector_2,
         &config.connector_6,
         kick_off_1_input_amount,
-        &config.commitment_secrets,
     )
     .await;
 
@@ -95,18 +92,14 @@ async fn test_take_1_success() {
         kick_off_2_input_0,
     );
     let superblock_header = get_superblock_header();
-    kick_off_2.sign(
-        &config.operator_context,
-        &config.connector_1,
-        &WinternitzSigningInputs {
-            message: &get_superblock_message(&superblock_header),
-            signing_key: &config.commitment_secrets[&CommitmentMessageId::Superblock],
-        },
-        &WinternitzSigningInputs {
-            message: &get_superblock_hash_message(&superblock_header),
-            signing_key: &config.commitment_secrets[&CommitmentMessageId::SuperblockHash],
-        },
-    );
+    kick_off_2
+        .sign(
+            &config.operator_context,
+            &config.connector_1,
+            &get_superblock_message(&superblock_header),
+            &get_superblock_hash_message(&superblock_header),
+        )
+        .unwrap();
     let kick_off_2_tx = kick_off_2.finalize();
     let kick_off_2_txid = kick_off_2_tx.compute_txid();
 
@@ -160,23 +153,28 @@ async fn test_take_1_success() {
         take_1_input_1,
         take_1_input_2,
         take_1_input_3,
-    );
-
-    let secret_nonces_0 = take_1.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = take_1.push_nonces(&config.verifier_1_context);
-
-    take_1.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_0,
-        &config.connector_b,
-        &secret_nonces_0,
-    );
-    take_1.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_0,
-        &config.connector_b,
-        &secret_nonces_1,
-    );
+    )
+    .unwrap();
+
+    let session_ids_0 = take_1.push_nonces(&config.verifier_0_context).unwrap();
+    let session_ids_1 = take_1.push_nonces(&config.verifier_1_context).unwrap();
+
+    take_1
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_b,
+            &session_ids_0,
+        )
+        .unwrap();
+    take_1
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_b,
+            &session_ids_1,
+        )
+        .unwrap();
 
     let take_1_tx = take_1.finalize();
     let take_1_txid = take_1_tx.compute_txid();
@@ -119,25 +119,32 @@ async fn test_take_2_success() {
         take_2_input_1,
         take_2_input_2,
         take_2_input_3,
-    );
-
-    let secret_nonces_0 = take_2.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = take_2.push_nonces(&config.verifier_1_context);
-
-    take_2.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_0,
-        &config.connector_5,
-        &secret_nonces_0,
-    );
-    take_2.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_0,
-        &config.connector_5,
-        &secret_nonces_1,
-    );
+    )
+    .unwrap();
+
+    let session_ids_0 = take_2.push_nonces(&config.verifier_0_context).unwrap();
+    let session_ids_1 = take_2.push_nonces(&config.verifier_1_context).unwrap();
+
+    take_2
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_5,
+            &session_ids_0,
+        )
+        .unwrap();
+    take_2
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_5,
+            &session_ids_1,
+        )
+        .unwrap();
 
-    take_2.sign(&config.operator_context, &config.connector_c);
+    take_2
+        .sign(&config.operator_context, &config.connector_c)
+        .unwrap();
 
     let take_2_tx = take_2.finalize();
     let take_2_txid = take_2_tx.compute_txid();
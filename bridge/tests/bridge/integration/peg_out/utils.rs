@@ -1,10 +1,6 @@
-use std::collections::HashMap;
-
 use bitcoin::{Address, Amount, Transaction, Txid};
-use bitvm::signatures::signing_winternitz::{WinternitzSecret, WinternitzSigningInputs};
 use bridge::{
     client::client::BitVMClient,
-    commitments::CommitmentMessageId,
     connectors::{
         connector_0::Connector0, connector_1::Connector1, connector_2::Connector2,
         connector_4::Connector4, connector_5::Connector5, connector_6::Connector6,
@@ -34,7 +30,6 @@ pub async fn create_and_mine_kick_off_1_tx(
     connector_2: &Connector2,
     connector_6: &Connector6,
     input_amount: Amount,
-    commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
 ) -> (Transaction, Txid) {
     let kick_off_1_funding_outpoint =
         generate_stub_outpoint(client, kick_off_1_funding_utxo_address, input_amount).await;
@@ -52,20 +47,14 @@ pub async fn create_and_mine_kick_off_1_tx(
 
     let ethereum_txid = "8b274fbb76c72f66c467c976c61d5ac212620e036818b5986a33f7b557cb2de8";
     let bitcoin_txid = "8b4cce4a1a9522392c095df6416533d89e1e6ac7bdf8ab3c1685426b321ed182";
-    let source_network_txid_digits = WinternitzSigningInputs {
-        message: bitcoin_txid.as_bytes(),
-        signing_key: &commitment_secrets[&CommitmentMessageId::PegOutTxIdSourceNetwork],
-    };
-    let destination_network_txid_digits = WinternitzSigningInputs {
-        message: ethereum_txid.as_bytes(),
-        signing_key: &commitment_secrets[&CommitmentMessageId::PegOutTxIdDestinationNetwork],
-    };
-    kick_off_1.sign(
-        operator_context,
-        connector_6,
-        &source_network_txid_digits,
-        &destination_network_txid_digits,
-    );
+    kick_off_1
+        .sign(
+            operator_context,
+            connector_6,
+            bitcoin_txid.as_bytes(),
+            ethereum_txid.as_bytes(),
+        )
+        .unwrap();
 
     let kick_off_1_tx = kick_off_1.finalize();
     let kick_off_1_txid = kick_off_1_tx.compute_txid();
@@ -85,7 +74,6 @@ pub async fn create_and_mine_kick_off_2_tx(
     connector_b: &ConnectorB,
     kick_off_2_funding_utxo_address: &Address,
     input_amount: Amount,
-    commitment_secrets: &HashMap<CommitmentMessageId, WinternitzSecret>,
 ) -> (Transaction, Txid) {
     let kick_off_2_funding_outpoint =
         generate_stub_outpoint(client, kick_off_2_funding_utxo_address, input_amount).await;
@@ -100,18 +88,14 @@ pub async fn create_and_mine_kick_off_2_tx(
         kick_off_2_input,
     );
     let superblock_header = get_superblock_header();
-    kick_off_2.sign(
-        operator_context,
-        connector_1,
-        &WinternitzSigningInputs {
-            message: &get_superblock_message(&superblock_header),
-            signing_key: &commitment_secrets[&CommitmentMessageId::Superblock],
-        },
-        &WinternitzSigningInputs {
-            message: &get_superblock_hash_message(&superblock_header),
-            signing_key: &commitment_secrets[&CommitmentMessageId::SuperblockHash],
-        },
-    );
+    kick_off_2
+        .sign(
+            operator_context,
+            connector_1,
+            &get_superblock_message(&superblock_header),
+            &get_superblock_hash_message(&superblock_header),
+        )
+        .unwrap();
     let kick_off_2_tx = kick_off_2.finalize();
     let kick_off_2_txid = kick_off_2_tx.compute_txid();
 
@@ -150,11 +134,15 @@ pub async fn create_and_mine_assert_tx(
         assert_input,
     );
 
-    let secret_nonces_0 = assert.push_nonces(verifier_0_context);
-    let secret_nonces_1 = assert.push_nonces(verifier_1_context);
+    let session_ids_0 = assert.push_nonces(verifier_0_context).unwrap();
+    let session_ids_1 = assert.push_nonces(verifier_1_context).unwrap();
 
-    assert.pre_sign(verifier_0_context, connector_b, &secret_nonces_0);
-    assert.pre_sign(verifier_1_context, connector_b, &secret_nonces_1);
+    assert
+        .pre_sign(verifier_0_context, connector_b, &session_ids_0)
+        .unwrap();
+    assert
+        .pre_sign(verifier_1_context, connector_b, &session_ids_1)
+        .unwrap();
 
     let assert_tx = assert.finalize();
     let assert_txid = assert_tx.compute_txid();
@@ -185,13 +173,18 @@ pub async fn create_and_mine_peg_in_confirm_tx(
         amount: input_amount,
     };
     let mut peg_in_confirm =
-        PegInConfirmTransaction::new(depositor_context, connector_0, connector_z, confirm_input);
-
-    let secret_nonces_0 = peg_in_confirm.push_nonces(verifier_0_context);
-    let secret_nonces_1 = peg_in_confirm.push_nonces(verifier_1_context);
-
-    peg_in_confirm.pre_sign(verifier_0_context, connector_z, &secret_nonces_0);
-    peg_in_confirm.pre_sign(verifier_1_context, connector_z, &secret_nonces_1);
+        PegInConfirmTransaction::new(depositor_context, connector_0, connector_z, confirm_input)
+            .unwrap();
+
+    let session_ids_0 = peg_in_confirm.push_nonces(verifier_0_context).unwrap();
+    let session_ids_1 = peg_in_confirm.push_nonces(verifier_1_context).unwrap();
+
+    peg_in_confirm
+        .pre_sign(verifier_0_context, connector_z, &session_ids_0)
+        .unwrap();
+    peg_in_confirm
+        .pre_sign(verifier_1_context, connector_z, &session_ids_1)
+        .unwrap();
 
     let peg_in_confirm_tx = peg_in_confirm.finalize();
     let peg_in_confirm_txid = peg_in_confirm_tx.compute_txid();
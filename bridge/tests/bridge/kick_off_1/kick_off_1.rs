@@ -1,7 +1,6 @@
 use bitcoin::Amount;
 
 use bridge::{
-    commitments::CommitmentMessageId,
     connectors::base::TaprootConnector,
     graphs::base::DUST_AMOUNT,
     transactions::{
@@ -10,8 +9,6 @@ use bridge::{
     },
 };
 
-use bitvm::signatures::signing_winternitz::WinternitzSigningInputs;
-
 use crate::bridge::{
     faucet::{Faucet, FaucetType},
     helper::{check_tx_output_sum, generate_stub_outpoint},
@@ -49,20 +46,14 @@ async fn test_kick_off_1_tx_success() {
     );
     let ethereum_txid = "8b274fbb76c72f66c467c976c61d5ac212620e036818b5986a33f7b557cb2de8";
     let bitcoin_txid = "8b4cce4a1a9522392c095df6416533d89e1e6ac7bdf8ab3c1685426b321ed182";
-    let source_network_txid_digits = WinternitzSigningInputs {
-        message: bitcoin_txid.as_bytes(),
-        signing_key: &config.commitment_secrets[&CommitmentMessageId::PegOutTxIdSourceNetwork],
-    };
-    let destination_network_txid_digits = WinternitzSigningInputs {
-        message: ethereum_txid.as_bytes(),
-        signing_key: &config.commitment_secrets[&CommitmentMessageId::PegOutTxIdDestinationNetwork],
-    };
-    kick_off_1_tx.sign(
-        &config.operator_context,
-        &config.connector_6,
-        &source_network_txid_digits,
-        &destination_network_txid_digits,
-    );
+    kick_off_1_tx
+        .sign(
+            &config.operator_context,
+            &config.connector_6,
+            bitcoin_txid.as_bytes(),
+            ethereum_txid.as_bytes(),
+        )
+        .unwrap();
 
     let tx = kick_off_1_tx.finalize();
     check_tx_output_sum(INITIAL_AMOUNT + subsequent_tx_fee, &tx);
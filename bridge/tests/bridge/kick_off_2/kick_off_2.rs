@@ -1,7 +1,6 @@
 use bitcoin::Amount;
 
 use bridge::{
-    commitments::CommitmentMessageId,
     connectors::base::TaprootConnector,
     graphs::base::DUST_AMOUNT,
     superblock::{get_superblock_hash_message, get_superblock_message},
@@ -22,7 +21,6 @@ use crate::{
     },
     bridge::setup::ONE_HUNDRED,
 };
-use bitvm::signatures::signing_winternitz::WinternitzSigningInputs;
 
 #[tokio::test]
 async fn test_kick_off_2_tx_success() {
@@ -52,18 +50,14 @@ async fn test_kick_off_2_tx_success() {
     );
 
     let superblock_header = get_superblock_header();
-    kick_off_2_tx.sign(
-        &config.operator_context,
-        &config.connector_1,
-        &WinternitzSigningInputs {
-            message: &get_superblock_message(&superblock_header),
-            signing_key: &config.commitment_secrets[&CommitmentMessageId::Superblock],
-        },
-        &WinternitzSigningInputs {
-            message: &get_superblock_hash_message(&superblock_header),
-            signing_key: &config.commitment_secrets[&CommitmentMessageId::SuperblockHash],
-        },
-    );
+    kick_off_2_tx
+        .sign(
+            &config.operator_context,
+            &config.connector_1,
+            &get_superblock_message(&superblock_header),
+            &get_superblock_hash_message(&superblock_header),
+        )
+        .unwrap();
 
     let tx = kick_off_2_tx.finalize();
     check_tx_output_sum(reward_amount + DUST_AMOUNT, &tx);
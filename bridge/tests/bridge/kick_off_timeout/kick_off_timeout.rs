@@ -46,19 +46,27 @@ async fn test_kick_off_timeout_tx_success() {
         },
     );
 
-    let secret_nonces_0 = kick_off_timeout_tx.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = kick_off_timeout_tx.push_nonces(&config.verifier_1_context);
+    let session_ids_0 = kick_off_timeout_tx
+        .push_nonces(&config.verifier_0_context)
+        .unwrap();
+    let session_ids_1 = kick_off_timeout_tx
+        .push_nonces(&config.verifier_1_context)
+        .unwrap();
 
-    kick_off_timeout_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_1,
-        &secret_nonces_0,
-    );
-    kick_off_timeout_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_1,
-        &secret_nonces_1,
-    );
+    kick_off_timeout_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_1,
+            &session_ids_0,
+        )
+        .unwrap();
+    kick_off_timeout_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_1,
+            &session_ids_1,
+        )
+        .unwrap();
 
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
@@ -13,6 +13,7 @@ pub mod kick_off_1;
 pub mod kick_off_2;
 pub mod kick_off_timeout;
 pub mod peg_in;
+pub mod psbt;
 pub mod serialization;
 pub mod setup;
 pub mod start_time;
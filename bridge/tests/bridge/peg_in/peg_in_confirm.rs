@@ -38,21 +38,30 @@ async fn test_peg_in_confirm_tx_success() {
         &config.connector_0,
         &config.connector_z,
         Input { outpoint, amount },
-    );
+    )
+    .unwrap();
 
-    let secret_nonces_0 = peg_in_confirm_tx.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = peg_in_confirm_tx.push_nonces(&config.verifier_1_context);
+    let session_ids_0 = peg_in_confirm_tx
+        .push_nonces(&config.verifier_0_context)
+        .unwrap();
+    let session_ids_1 = peg_in_confirm_tx
+        .push_nonces(&config.verifier_1_context)
+        .unwrap();
 
-    peg_in_confirm_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_z,
-        &secret_nonces_0,
-    );
-    peg_in_confirm_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_z,
-        &secret_nonces_1,
-    );
+    peg_in_confirm_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_z,
+            &session_ids_0,
+        )
+        .unwrap();
+    peg_in_confirm_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_z,
+            &session_ids_1,
+        )
+        .unwrap();
 
     let tx = peg_in_confirm_tx.finalize();
     check_tx_output_sum(INITIAL_AMOUNT, &tx);
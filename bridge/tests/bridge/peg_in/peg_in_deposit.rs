@@ -31,7 +31,8 @@ async fn test_peg_in_deposit_tx_success() {
         &config.depositor_context,
         &config.connector_z,
         Input { outpoint, amount },
-    );
+    )
+    .unwrap();
 
     println!(
         "Depositor public key: {:?}\n",
@@ -36,7 +36,8 @@ async fn test_peg_in_refund_tx_success() {
         &config.depositor_context,
         &config.connector_z,
         Input { outpoint, amount },
-    );
+    )
+    .unwrap();
 
     let tx = peg_in_refund_tx.finalize();
     check_tx_output_sum(INITIAL_AMOUNT, &tx);
@@ -0,0 +1,451 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use bitcoin::{
+    consensus::encode::deserialize_hex,
+    ecdsa,
+    secp256k1::{Message, Secp256k1},
+    taproot, Amount, EcdsaSighashType, OutPoint, PrivateKey, Psbt, TapLeafHash, TapSighashType,
+    Transaction, Txid, Witness,
+};
+
+use bridge::{
+    contexts::base::generate_keys_from_secret,
+    error::{Error, PsbtError, ValidationError},
+    graphs::{
+        base::{BaseGraph, PEG_IN_FEE},
+        peg_in::PegInGraph,
+        peg_out::PegOutGraph,
+    },
+    transactions::{
+        base::Input,
+        pre_signed::PreSignedTransaction,
+        psbt::{
+            PSBT_IN_BITVM_MUSIG2_NONCE_SIGNATURE, PSBT_IN_MUSIG2_PARTIAL_SIG,
+            PSBT_IN_MUSIG2_PARTICIPANT_PUBKEYS, PSBT_IN_MUSIG2_PUB_NONCE,
+        },
+    },
+};
+
+use crate::bridge::setup::{setup_test, SetupConfig, DEPOSITOR_SECRET, INITIAL_AMOUNT};
+
+#[tokio::test]
+async fn test_psbt_export_peg_in() {
+    let config = setup_test().await;
+    let (peg_in_graph, _) = create_graphs(&config);
+
+    let deposit_txid = peg_in_graph.peg_in_deposit_transaction.tx().compute_txid();
+    let psbt = peg_in_graph.export_psbt(&deposit_txid).unwrap();
+    assert_eq!(psbt.unsigned_tx.compute_txid(), deposit_txid);
+    assert!(psbt.inputs[0].witness_utxo.is_some());
+    assert!(psbt.inputs[0].witness_script.is_some());
+
+    let confirm_txid = peg_in_graph.peg_in_confirm_transaction.tx().compute_txid();
+    let psbt = peg_in_graph.export_psbt(&confirm_txid).unwrap();
+    assert!(psbt.inputs[0].tap_internal_key.is_some());
+    assert_eq!(psbt.inputs[0].tap_scripts.len(), 1);
+    assert!(psbt.inputs[0].final_script_witness.is_none());
+
+    let result = peg_in_graph.export_psbt(&Txid::from_str(DUMMY_TXID).unwrap());
+    assert!(matches!(result, Err(Error::Psbt(PsbtError::TxNotFound(_)))));
+}
+
+#[tokio::test]
+async fn test_psbt_musig2_round_trip() {
+    let config = setup_test().await;
+    let (peg_in_graph, mut peg_out_graph) = create_graphs(&config);
+    let n_of_n_public_keys = peg_in_graph.n_of_n_public_keys();
+
+    // Signer with external tooling
+    let mut signed_peg_out_graph = peg_out_graph.clone();
+    let session_ids_0 = signed_peg_out_graph
+        .push_verifier_nonces(&config.verifier_0_context)
+        .unwrap();
+    signed_peg_out_graph
+        .push_verifier_nonces(&config.verifier_1_context)
+        .unwrap();
+    signed_peg_out_graph
+        .verifier_sign(&config.verifier_0_context, &session_ids_0)
+        .unwrap();
+
+    for txid in session_ids_0.keys() {
+        let psbt = signed_peg_out_graph
+            .export_psbt(txid, n_of_n_public_keys)
+            .unwrap();
+
+        let count_fields = |input_index: usize, type_value: u8| {
+            psbt.inputs[input_index]
+                .unknown
+                .keys()
+                .filter(|key| key.type_value == type_value)
+                .count()
+        };
+        let verifier_inputs: Vec<usize> = (0..psbt.inputs.len())
+            .filter(|input_index| count_fields(*input_index, PSBT_IN_MUSIG2_PUB_NONCE) > 0)
+            .collect();
+        assert!(!verifier_inputs.is_empty());
+        for input_index in verifier_inputs {
+            // Every participant is listed, including verifier 1 which has not signed yet.
+            let participants = psbt.inputs[input_index]
+                .unknown
+                .iter()
+                .find(|(key, _)| key.type_value == PSBT_IN_MUSIG2_PARTICIPANT_PUBKEYS)
+                .unwrap()
+                .1;
+            assert_eq!(
+                *participants,
+                n_of_n_public_keys
+                    .iter()
+                    .flat_map(|pubkey| pubkey.to_bytes())
+                    .collect::<Vec<u8>>()
+            );
+            assert_eq!(psbt.inputs[input_index].tap_scripts.len(), 1);
+            assert_eq!(count_fields(input_index, PSBT_IN_MUSIG2_PUB_NONCE), 2);
+            assert_eq!(count_fields(input_index, PSBT_IN_MUSIG2_PARTIAL_SIG), 1);
+        }
+
+        let imported_txid = peg_out_graph
+            .import_psbt(&psbt, n_of_n_public_keys)
+            .unwrap();
+        assert_eq!(imported_txid, *txid);
+        assert_eq!(
+            peg_out_graph.export_psbt(txid, n_of_n_public_keys).unwrap(),
+            psbt
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_psbt_musig2_round_trip_each_transaction() {
+    let config = setup_test().await;
+    let (peg_in_graph, mut peg_out_graph) = create_graphs(&config);
+    let n_of_n_public_keys = peg_in_graph.n_of_n_public_keys();
+
+    let mut signed_peg_out_graph = peg_out_graph.clone();
+    let session_ids_0 = signed_peg_out_graph
+        .push_verifier_nonces(&config.verifier_0_context)
+        .unwrap();
+    signed_peg_out_graph
+        .push_verifier_nonces(&config.verifier_1_context)
+        .unwrap();
+    signed_peg_out_graph
+        .verifier_sign(&config.verifier_0_context, &session_ids_0)
+        .unwrap();
+
+    for name in [
+        "start_time_timeout_transaction",
+        "kick_off_timeout_transaction",
+        "assert_initial_transaction",
+        "disprove_chain_transaction",
+    ] {
+        let txid = graph_txid(&peg_out_graph, name);
+        assert!(
+            session_ids_0.contains_key(&txid),
+            "{name} is not pre-signed"
+        );
+
+        let psbt = signed_peg_out_graph
+            .export_psbt(&txid, n_of_n_public_keys)
+            .unwrap();
+        let imported_txid = peg_out_graph
+            .import_psbt(&psbt, n_of_n_public_keys)
+            .unwrap();
+        assert_eq!(imported_txid, txid, "{name}");
+        assert_eq!(
+            peg_out_graph
+                .export_psbt(&txid, n_of_n_public_keys)
+                .unwrap(),
+            psbt,
+            "{name}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_psbt_import_invalid_nonce_signature() {
+    let config = setup_test().await;
+    let (peg_in_graph, mut peg_out_graph) = create_graphs(&config);
+    let n_of_n_public_keys = peg_in_graph.n_of_n_public_keys();
+
+    let mut signed_peg_out_graph = peg_out_graph.clone();
+    let session_ids = signed_peg_out_graph
+        .push_verifier_nonces(&config.verifier_0_context)
+        .unwrap();
+    let txid = *session_ids.keys().next().unwrap();
+
+    let mut psbt = signed_peg_out_graph
+        .export_psbt(&txid, n_of_n_public_keys)
+        .unwrap();
+    let input = psbt
+        .inputs
+        .iter_mut()
+        .find(|input| !input.proprietary.is_empty())
+        .unwrap();
+    let nonce_signature = input
+        .proprietary
+        .iter_mut()
+        .find(|(key, _)| key.subtype == PSBT_IN_BITVM_MUSIG2_NONCE_SIGNATURE)
+        .unwrap()
+        .1;
+    nonce_signature[0] ^= 1;
+
+    let result = peg_out_graph.import_psbt(&psbt, n_of_n_public_keys);
+    assert!(matches!(
+        result,
+        Err(Error::Validation(ValidationError::NoncesValidationFailed(
+            _,
+            _,
+            _,
+            _
+        )))
+    ));
+}
+
+#[tokio::test]
+async fn test_psbt_import_externally_signed() {
+    let config = setup_test().await;
+    let (mut peg_in_graph, _) = create_graphs(&config);
+    let deposit_txid = peg_in_graph.peg_in_deposit_transaction.tx().compute_txid();
+
+    // Drop the depositor's witness and have it re-created from a PSBT signed by rust-bitcoin.
+    let expected_witness = peg_in_graph.peg_in_deposit_transaction.tx().input[0]
+        .witness
+        .clone();
+    peg_in_graph.peg_in_deposit_transaction.tx_mut().input[0].witness = Witness::default();
+
+    let mut psbt = peg_in_graph.export_psbt(&deposit_txid).unwrap();
+    let (depositor_keypair, depositor_public_key) =
+        generate_keys_from_secret(config.network, DEPOSITOR_SECRET);
+    let keys = BTreeMap::from([(
+        depositor_public_key,
+        PrivateKey::new(depositor_keypair.secret_key(), config.network),
+    )]);
+    psbt.sign(&keys, &Secp256k1::new()).unwrap();
+    let psbt = Psbt::deserialize(&psbt.serialize()).unwrap();
+    assert_eq!(psbt.inputs[0].partial_sigs.len(), 1);
+    assert!(psbt.inputs[0].final_script_witness.is_none());
+
+    let imported_txid = peg_in_graph.import_psbt(&psbt).unwrap();
+    assert_eq!(imported_txid, deposit_txid);
+    assert_eq!(
+        peg_in_graph.peg_in_deposit_transaction.tx().input[0].witness,
+        expected_witness
+    );
+}
+
+#[tokio::test]
+async fn test_psbt_import_invalid_signature() {
+    let config = setup_test().await;
+    let (mut peg_in_graph, _) = create_graphs(&config);
+    let (depositor_keypair, depositor_public_key) =
+        generate_keys_from_secret(config.network, DEPOSITOR_SECRET);
+    let wrong_message = Message::from_digest([1; 32]);
+
+    // ECDSA signature of the depositor over another message
+    let deposit_txid = peg_in_graph.peg_in_deposit_transaction.tx().compute_txid();
+    peg_in_graph.peg_in_deposit_transaction.tx_mut().input[0].witness = Witness::default();
+    let mut psbt = peg_in_graph.export_psbt(&deposit_txid).unwrap();
+    psbt.inputs[0].partial_sigs.insert(
+        depositor_public_key,
+        ecdsa::Signature {
+            signature: depositor_keypair.secret_key().sign_ecdsa(wrong_message),
+            sighash_type: EcdsaSighashType::All,
+        },
+    );
+    let result = peg_in_graph.import_psbt(&psbt);
+    assert!(matches!(
+        result,
+        Err(Error::Psbt(PsbtError::InvalidSignature(_, 0)))
+    ));
+    assert!(peg_in_graph.peg_in_deposit_transaction.tx().input[0]
+        .witness
+        .is_empty());
+
+    // Schnorr signature of the depositor over another message
+    let refund_txid = peg_in_graph.peg_in_refund_transaction.tx().compute_txid();
+    let mut psbt = peg_in_graph.export_psbt(&refund_txid).unwrap();
+    psbt.inputs[0].final_script_witness = None;
+    let leaf_hash = psbt.inputs[0]
+        .tap_scripts
+        .values()
+        .map(|(script, leaf_version)| TapLeafHash::from_script(script, *leaf_version))
+        .next()
+        .unwrap();
+    psbt.inputs[0].tap_script_sigs.insert(
+        (depositor_keypair.x_only_public_key().0, leaf_hash),
+        taproot::Signature {
+            signature: Secp256k1::new()
+                .sign_schnorr_no_aux_rand(&wrong_message, &depositor_keypair),
+            sighash_type: TapSighashType::All,
+        },
+    );
+    let result = peg_in_graph.import_psbt(&psbt);
+    assert!(matches!(
+        result,
+        Err(Error::Psbt(PsbtError::InvalidSignature(_, 0)))
+    ));
+}
+
+#[tokio::test]
+async fn test_psbt_import_invalid_partial_signature() {
+    let config = setup_test().await;
+    let (peg_in_graph, mut peg_out_graph) = create_graphs(&config);
+    let n_of_n_public_keys = peg_in_graph.n_of_n_public_keys();
+
+    let mut signed_peg_out_graph = peg_out_graph.clone();
+    let session_ids_0 = signed_peg_out_graph
+        .push_verifier_nonces(&config.verifier_0_context)
+        .unwrap();
+    signed_peg_out_graph
+        .push_verifier_nonces(&config.verifier_1_context)
+        .unwrap();
+    signed_peg_out_graph
+        .verifier_sign(&config.verifier_0_context, &session_ids_0)
+        .unwrap();
+    let txid = *session_ids_0.keys().next().unwrap();
+
+    let mut psbt = signed_peg_out_graph
+        .export_psbt(&txid, n_of_n_public_keys)
+        .unwrap();
+    let partial_signature = psbt
+        .inputs
+        .iter_mut()
+        .flat_map(|input| input.unknown.iter_mut())
+        .find(|(key, _)| key.type_value == PSBT_IN_MUSIG2_PARTIAL_SIG)
+        .unwrap()
+        .1;
+    partial_signature[31] ^= 1;
+
+    let result = peg_out_graph.import_psbt(&psbt, n_of_n_public_keys);
+    assert!(matches!(
+        result,
+        Err(Error::Psbt(PsbtError::InvalidSignature(_, _)))
+    ));
+}
+
+#[tokio::test]
+async fn test_psbt_import_musig2_field_of_other_leaf() {
+    let config = setup_test().await;
+    let (peg_in_graph, mut peg_out_graph) = create_graphs(&config);
+    let n_of_n_public_keys = peg_in_graph.n_of_n_public_keys();
+
+    let mut signed_peg_out_graph = peg_out_graph.clone();
+    let session_ids = signed_peg_out_graph
+        .push_verifier_nonces(&config.verifier_0_context)
+        .unwrap();
+    let txid = *session_ids.keys().next().unwrap();
+
+    let mut psbt = signed_peg_out_graph
+        .export_psbt(&txid, n_of_n_public_keys)
+        .unwrap();
+    let input = psbt
+        .inputs
+        .iter_mut()
+        .find(|input| !input.proprietary.is_empty())
+        .unwrap();
+    let (mut key, nonce) = input
+        .unknown
+        .iter()
+        .find(|(key, _)| key.type_value == PSBT_IN_MUSIG2_PUB_NONCE)
+        .map(|(key, nonce)| (key.clone(), nonce.clone()))
+        .unwrap();
+    input.unknown.remove(&key);
+    // The key data ends with the leaf hash
+    *key.key.last_mut().unwrap() ^= 1;
+    input.unknown.insert(key, nonce);
+
+    let result = peg_out_graph.import_psbt(&psbt, n_of_n_public_keys);
+    assert!(matches!(
+        result,
+        Err(Error::Psbt(PsbtError::InvalidMusig2Field(_, _)))
+    ));
+}
+
+#[tokio::test]
+async fn test_psbt_import_invalid_final_witness() {
+    let config = setup_test().await;
+    let (mut peg_in_graph, _) = create_graphs(&config);
+
+    // P2WSH spend of the depositor's key and taproot script path spend of the refund leaf
+    let deposit_txid = peg_in_graph.peg_in_deposit_transaction.tx().compute_txid();
+    let deposit_witness = peg_in_graph.peg_in_deposit_transaction.tx().input[0]
+        .witness
+        .clone();
+    peg_in_graph.peg_in_deposit_transaction.tx_mut().input[0].witness = Witness::default();
+    let refund_txid = peg_in_graph.peg_in_refund_transaction.tx().compute_txid();
+    let refund_witness = peg_in_graph.peg_in_refund_transaction.tx().input[0]
+        .witness
+        .clone();
+    peg_in_graph.peg_in_refund_transaction.tx_mut().input[0].witness = Witness::default();
+
+    for (txid, witness) in [
+        (deposit_txid, deposit_witness),
+        (refund_txid, refund_witness),
+    ] {
+        let mut psbt = peg_in_graph.export_psbt(&txid).unwrap();
+        assert!(psbt.inputs[0].final_script_witness.is_none());
+
+        let mut elements = witness.to_vec();
+        elements[0][10] ^= 1;
+        psbt.inputs[0].final_script_witness = Some(Witness::from_slice(&elements));
+        let result = peg_in_graph.import_psbt(&psbt);
+        assert!(matches!(
+            result,
+            Err(Error::Psbt(PsbtError::InvalidWitness(_, 0)))
+        ));
+
+        psbt.inputs[0].final_script_witness = Some(witness);
+        assert_eq!(peg_in_graph.import_psbt(&psbt).unwrap(), txid);
+    }
+    assert!(!peg_in_graph.peg_in_deposit_transaction.tx().input[0]
+        .witness
+        .is_empty());
+    assert!(!peg_in_graph.peg_in_refund_transaction.tx().input[0]
+        .witness
+        .is_empty());
+}
+
+// The transactions of a peg-out graph are private, so they are read from its serialization.
+fn graph_txid(peg_out_graph: &PegOutGraph, name: &str) -> Txid {
+    let graph = serde_json::to_value(peg_out_graph).unwrap();
+    let tx: Transaction = deserialize_hex(graph[name]["tx"].as_str().unwrap()).unwrap();
+    tx.compute_txid()
+}
+
+const DUMMY_TXID: &str = "5df6e0e2761359d30a8275058e299fcc0381534545f55cf43e41983f5d4c9456";
+
+fn create_graphs(config: &SetupConfig) -> (PegInGraph, PegOutGraph) {
+    let amount = Amount::from_sat(INITIAL_AMOUNT + PEG_IN_FEE);
+    let peg_in_graph = PegInGraph::new(
+        &config.depositor_context,
+        Input {
+            outpoint: OutPoint {
+                txid: Txid::from_str(
+                    "0e6719ac074b0e3cac76d057643506faa1c266b322aa9cf4c6f635fe63b14327",
+                )
+                .unwrap(),
+                vout: 0,
+            },
+            amount,
+        },
+        &config.depositor_evm_address,
+    )
+    .unwrap();
+    let peg_out_graph = PegOutGraph::new(
+        &config.operator_context,
+        &peg_in_graph,
+        Input {
+            outpoint: OutPoint {
+                txid: Txid::from_str(
+                    "4e254eab8a41f14f56491813a7100cebe305d84edf09488001d9dd3d180a4900",
+                )
+                .unwrap(),
+                vout: 0,
+            },
+            amount,
+        },
+        &config.commitment_secrets,
+    )
+    .unwrap();
+
+    (peg_in_graph, peg_out_graph)
+}
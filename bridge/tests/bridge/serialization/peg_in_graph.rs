@@ -33,7 +33,8 @@ async fn test_peg_in_graph_serialization() {
         &config.depositor_context,
         Input { outpoint, amount },
         &config.depositor_evm_address,
-    );
+    )
+    .unwrap();
 
     let json = serialize(&peg_in_graph);
     assert!(!json.is_empty());
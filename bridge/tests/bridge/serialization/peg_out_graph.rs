@@ -44,7 +44,8 @@ async fn test_peg_out_graph_serialization() {
         &config.depositor_context,
         Input { outpoint, amount },
         &config.depositor_evm_address,
-    );
+    )
+    .unwrap();
 
     let kick_off_outpoint =
         generate_stub_outpoint(&config.client_0, &kick_off_address, kick_off_amount).await;
@@ -57,7 +58,8 @@ async fn test_peg_out_graph_serialization() {
             amount: kick_off_amount,
         },
         &config.commitment_secrets,
-    );
+    )
+    .unwrap();
 
     let json = serialize(&peg_out_graph);
     assert!(!json.is_empty());
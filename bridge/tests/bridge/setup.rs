@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use bitcoin::{Network, PublicKey};
 
@@ -24,6 +24,7 @@ use bridge::{
         verifier::VerifierContext, withdrawer::WithdrawerContext,
     },
     serialization::serialize,
+    signers::{config::SignerConfig, local::LocalSigner},
     superblock::{SUPERBLOCK_HASH_MESSAGE_LENGTH, SUPERBLOCK_MESSAGE_LENGTH},
     transactions::assert_transactions::utils::{
         groth16_commitment_secrets_to_public_keys, merge_to_connector_c_commits_public_key,
@@ -42,7 +43,8 @@ use bitvm::{
 const OPERATOR_SECRET: &str = "3076ca1dfc1e383be26d5dd3c0c427340f96139fa8c2520862cf551ec2d670ac";
 const VERIFIER_0_SECRET: &str = "ee0817eac0c13aa8ee2dd3256304041f09f0499d1089b56495310ae8093583e2";
 const VERIFIER_1_SECRET: &str = "fc294c70faf210d4d0807ea7a3dba8f7e41700d90c119e1ae82a0687d89d297f";
-const DEPOSITOR_SECRET: &str = "b8f17ea979be24199e7c3fec71ee88914d92fd4ca508443f765d56ce024ef1d7";
+pub const DEPOSITOR_SECRET: &str =
+    "b8f17ea979be24199e7c3fec71ee88914d92fd4ca508443f765d56ce024ef1d7";
 const WITHDRAWER_SECRET: &str = "fffd54f6d8f8ad470cb507fd4b6e9b3ea26b4221a4900cc5ad5916ce67c02f1e";
 
 const DEPOSITOR_EVM_ADDRESS: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"; // l2 local test network account 1
@@ -199,8 +201,13 @@ pub async fn setup_test() -> SetupConfig {
 
     let depositor_context =
         DepositorContext::new(source_network, DEPOSITOR_SECRET, &n_of_n_public_keys);
-    let operator_context =
-        OperatorContext::new(source_network, OPERATOR_SECRET, &n_of_n_public_keys);
+    let operator_signer = LocalSigner::from_secret(source_network, OPERATOR_SECRET);
+    operator_signer.add_winternitz_secrets(commitment_secrets.values().cloned());
+    let operator_context = OperatorContext::new_with_signer(
+        source_network,
+        Arc::new(operator_signer),
+        &n_of_n_public_keys,
+    );
     let verifier_0_context =
         VerifierContext::new(source_network, VERIFIER_0_SECRET, &n_of_n_public_keys);
     let verifier_1_context =
@@ -217,10 +224,10 @@ pub async fn setup_test() -> SetupConfig {
         destination_network,
         Some(get_chain_adaptor(destination_network, None, None)),
         &n_of_n_public_keys,
-        Some(DEPOSITOR_SECRET),
-        Some(OPERATOR_SECRET),
-        Some(VERIFIER_0_SECRET),
-        Some(WITHDRAWER_SECRET),
+        Some(SignerConfig::from_secret(source_network, DEPOSITOR_SECRET)),
+        Some(SignerConfig::from_secret(source_network, OPERATOR_SECRET)),
+        Some(SignerConfig::from_secret(source_network, VERIFIER_0_SECRET)),
+        Some(SignerConfig::from_secret(source_network, WITHDRAWER_SECRET)),
         Some("test_client_0"),
         Some(valid_proof.vk.clone()),
     )
@@ -232,10 +239,10 @@ pub async fn setup_test() -> SetupConfig {
         destination_network,
         Some(get_chain_adaptor(destination_network, None, None)),
         &n_of_n_public_keys,
-        Some(DEPOSITOR_SECRET),
-        Some(OPERATOR_SECRET),
-        Some(VERIFIER_1_SECRET),
-        Some(WITHDRAWER_SECRET),
+        Some(SignerConfig::from_secret(source_network, DEPOSITOR_SECRET)),
+        Some(SignerConfig::from_secret(source_network, OPERATOR_SECRET)),
+        Some(SignerConfig::from_secret(source_network, VERIFIER_1_SECRET)),
+        Some(SignerConfig::from_secret(source_network, WITHDRAWER_SECRET)),
         Some("test_client_1"),
         Some(valid_proof.vk.clone()),
     )
@@ -1,7 +1,6 @@
 use bitcoin::Amount;
 
 use bridge::{
-    commitments::CommitmentMessageId,
     connectors::base::TaprootConnector,
     graphs::base::DUST_AMOUNT,
     superblock::get_start_time_block_number,
@@ -42,12 +41,13 @@ async fn test_start_time_tx_success() {
         },
     );
 
-    start_time_tx.sign(
-        &config.operator_context,
-        &config.connector_2,
-        get_start_time_block_number(config.network),
-        &config.commitment_secrets[&CommitmentMessageId::StartTime],
-    );
+    start_time_tx
+        .sign(
+            &config.operator_context,
+            &config.connector_2,
+            get_start_time_block_number(config.network),
+        )
+        .unwrap();
 
     let tx = start_time_tx.finalize();
     check_tx_output_sum(DUST_AMOUNT, &tx);
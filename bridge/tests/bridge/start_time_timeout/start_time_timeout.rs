@@ -63,21 +63,29 @@ async fn test_start_time_timeout_tx_success() {
         },
     );
 
-    let secret_nonces_0 = start_time_timeout_tx.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = start_time_timeout_tx.push_nonces(&config.verifier_1_context);
+    let session_ids_0 = start_time_timeout_tx
+        .push_nonces(&config.verifier_0_context)
+        .unwrap();
+    let session_ids_1 = start_time_timeout_tx
+        .push_nonces(&config.verifier_1_context)
+        .unwrap();
 
-    start_time_timeout_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_1,
-        &config.connector_2,
-        &secret_nonces_0,
-    );
-    start_time_timeout_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_1,
-        &config.connector_2,
-        &secret_nonces_1,
-    );
+    start_time_timeout_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_1,
+            &config.connector_2,
+            &session_ids_0,
+        )
+        .unwrap();
+    start_time_timeout_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_1,
+            &config.connector_2,
+            &session_ids_1,
+        )
+        .unwrap();
     let reward_address = generate_pay_to_pubkey_script_address(
         config.withdrawer_context.network,
         &config.withdrawer_context.withdrawer_public_key,
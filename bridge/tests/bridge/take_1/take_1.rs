@@ -74,23 +74,28 @@ async fn test_take_1_tx_success() {
             outpoint: funding_outpoint3,
             amount: input_value3,
         },
-    );
+    )
+    .unwrap();
 
-    let secret_nonces_0 = take_1_tx.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = take_1_tx.push_nonces(&config.verifier_1_context);
+    let session_ids_0 = take_1_tx.push_nonces(&config.verifier_0_context).unwrap();
+    let session_ids_1 = take_1_tx.push_nonces(&config.verifier_1_context).unwrap();
 
-    take_1_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_0,
-        &config.connector_b,
-        &secret_nonces_0,
-    );
-    take_1_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_0,
-        &config.connector_b,
-        &secret_nonces_1,
-    );
+    take_1_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_b,
+            &session_ids_0,
+        )
+        .unwrap();
+    take_1_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_b,
+            &session_ids_1,
+        )
+        .unwrap();
 
     let tx = take_1_tx.finalize();
     check_tx_output_sum(ONE_HUNDRED + reward_amount + DUST_AMOUNT * 2, &tx);
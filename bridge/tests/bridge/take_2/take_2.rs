@@ -77,25 +77,32 @@ async fn test_take_2_tx_success() {
             outpoint: funding_outpoint3,
             amount: input_value3,
         },
-    );
+    )
+    .unwrap();
 
-    let secret_nonces_0 = take_2_tx.push_nonces(&config.verifier_0_context);
-    let secret_nonces_1 = take_2_tx.push_nonces(&config.verifier_1_context);
+    let session_ids_0 = take_2_tx.push_nonces(&config.verifier_0_context).unwrap();
+    let session_ids_1 = take_2_tx.push_nonces(&config.verifier_1_context).unwrap();
 
-    take_2_tx.pre_sign(
-        &config.verifier_0_context,
-        &config.connector_0,
-        &config.connector_5,
-        &secret_nonces_0,
-    );
-    take_2_tx.pre_sign(
-        &config.verifier_1_context,
-        &config.connector_0,
-        &config.connector_5,
-        &secret_nonces_1,
-    );
+    take_2_tx
+        .pre_sign(
+            &config.verifier_0_context,
+            &config.connector_0,
+            &config.connector_5,
+            &session_ids_0,
+        )
+        .unwrap();
+    take_2_tx
+        .pre_sign(
+            &config.verifier_1_context,
+            &config.connector_0,
+            &config.connector_5,
+            &session_ids_1,
+        )
+        .unwrap();
 
-    take_2_tx.sign(&config.operator_context, &config.connector_c);
+    take_2_tx
+        .sign(&config.operator_context, &config.connector_c)
+        .unwrap();
 
     let tx = take_2_tx.finalize();
     check_tx_output_sum(ONE_HUNDRED + reward_amount + DUST_AMOUNT * 2, &tx);
@@ -189,7 +189,8 @@ async fn setup_and_create_graphs() -> (PegInGraph, PegOutGraph, OutPoint, AsyncC
             amount,
         },
         &config.depositor_evm_address,
-    );
+    )
+    .unwrap();
 
     let peg_out_graph = PegOutGraph::new(
         &config.operator_context,
@@ -199,7 +200,8 @@ async fn setup_and_create_graphs() -> (PegInGraph, PegOutGraph, OutPoint, AsyncC
             amount,
         },
         &config.commitment_secrets,
-    );
+    )
+    .unwrap();
 
     (
         peg_in_graph,
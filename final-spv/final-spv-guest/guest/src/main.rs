@@ -2,5 +2,5 @@
 final_spv::risc0_zkvm::guest::entry!(main);
 fn main() {
     let zkvm_guest = final_spv::zkvm::Risc0Guest::new();
-    final_spv::final_circuit(&zkvm_guest);
-}
\ No newline at end of file
+    final_spv::final_spv_circuit(&zkvm_guest);
+}
@@ -0,0 +1,176 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use header_chain::header_chain::BlockHeaderCircuitOutput;
+use serde::{Deserialize, Serialize};
+
+use crate::{spv::SPV, utils::calculate_sha256};
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+const ROOT_PREFIX: u8 = 0x02;
+
+#[derive(Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
+pub struct BatchFinalCircuitInput {
+    pub block_header_circuit_output: BlockHeaderCircuitOutput,
+    pub spvs: Vec<SPV>,
+}
+
+/// Merkle tree of the txids verified by the batch final circuit.
+///
+/// Leaves and internal nodes are hashed with distinct prefixes, and the last node of an odd level
+/// is moved up as is, so neither an internal node nor a duplicated txid can be opened as a leaf.
+/// The root also commits to the number of txids.
+#[derive(Debug, Clone)]
+pub struct BatchMerkleTree {
+    nodes: Vec<Vec<[u8; 32]>>,
+}
+
+impl BatchMerkleTree {
+    pub fn new(txids: &[[u8; 32]]) -> Self {
+        assert!(!txids.is_empty(), "Batch is empty");
+        let mut nodes = vec![txids.iter().map(hash_leaf).collect::<Vec<[u8; 32]>>()];
+        while nodes[nodes.len() - 1].len() > 1 {
+            let level = nodes[nodes.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_node(left, right),
+                    [last] => *last,
+                    _ => unreachable!(),
+                })
+                .collect();
+            nodes.push(level);
+        }
+        BatchMerkleTree { nodes }
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        hash_root(
+            self.nodes[0].len() as u32,
+            &self.nodes[self.nodes.len() - 1][0],
+        )
+    }
+
+    /// Returns the opening of the txid at `idx`.
+    pub fn opening(&self, idx: u32) -> BatchOpening {
+        assert!(idx < self.nodes[0].len() as u32, "Index out of bounds");
+        let mut siblings = vec![];
+        let mut i = idx as usize;
+        for level in &self.nodes[..self.nodes.len() - 1] {
+            let sibling = i ^ 1;
+            if sibling < level.len() {
+                siblings.push(level[sibling]);
+            }
+            i /= 2;
+        }
+        BatchOpening {
+            idx,
+            size: self.nodes[0].len() as u32,
+            siblings,
+        }
+    }
+}
+
+/// Proof that a txid is one of the txids committed by the batch final circuit.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
+pub struct BatchOpening {
+    pub idx: u32,
+    /// Number of txids in the batch.
+    pub size: u32,
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl BatchOpening {
+    /// Calculates the batch root given the txid at `idx`.
+    pub fn get_root(&self, txid: [u8; 32]) -> Option<[u8; 32]> {
+        if self.idx >= self.size {
+            return None;
+        }
+        let mut siblings = self.siblings.iter();
+        let mut current = hash_leaf(&txid);
+        let mut idx = self.idx;
+        let mut size = self.size;
+        while size > 1 {
+            if idx % 2 == 1 {
+                current = hash_node(siblings.next()?, &current);
+            } else if idx + 1 < size {
+                current = hash_node(&current, siblings.next()?);
+            }
+            idx /= 2;
+            size = size.div_ceil(2);
+        }
+        if siblings.next().is_some() {
+            return None;
+        }
+        Some(hash_root(self.size, &current))
+    }
+
+    pub fn verify(&self, txid: [u8; 32], root: [u8; 32]) -> bool {
+        self.get_root(txid) == Some(root)
+    }
+}
+
+fn hash_leaf(txid: &[u8; 32]) -> [u8; 32] {
+    let mut preimage = [0u8; 33];
+    preimage[0] = LEAF_PREFIX;
+    preimage[1..].copy_from_slice(txid);
+    calculate_sha256(&preimage)
+}
+
+fn hash_root(size: u32, tree_root: &[u8; 32]) -> [u8; 32] {
+    let mut preimage = [0u8; 37];
+    preimage[0] = ROOT_PREFIX;
+    preimage[1..5].copy_from_slice(&size.to_le_bytes());
+    preimage[5..].copy_from_slice(tree_root);
+    calculate_sha256(&preimage)
+}
+
+fn hash_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut preimage = [0u8; 65];
+    preimage[0] = NODE_PREFIX;
+    preimage[1..33].copy_from_slice(left);
+    preimage[33..].copy_from_slice(right);
+    calculate_sha256(&preimage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_merkle_tree() {
+        for size in 1..20u32 {
+            let txids = (0..size).map(|i| [i as u8; 32]).collect::<Vec<[u8; 32]>>();
+            let tree = BatchMerkleTree::new(&txids);
+            let root = tree.root();
+
+            for (idx, txid) in txids.iter().enumerate() {
+                let opening = tree.opening(idx as u32);
+                assert!(opening.verify(*txid, root));
+                assert!(!opening.verify([0xff; 32], root));
+
+                let mut wrong_size = opening.clone();
+                wrong_size.size += 1;
+                assert!(!wrong_size.verify(*txid, root));
+            }
+        }
+    }
+
+    #[test]
+    fn test_batch_merkle_tree_odd_level() {
+        let tree = BatchMerkleTree::new(&[[1; 32], [2; 32], [3; 32]]);
+        // The third leaf is moved up instead of being paired with itself.
+        assert_eq!(
+            tree.root(),
+            hash_root(
+                3,
+                &hash_node(
+                    &hash_node(&hash_leaf(&[1; 32]), &hash_leaf(&[2; 32])),
+                    &hash_leaf(&[3; 32])
+                )
+            )
+        );
+        assert_ne!(
+            tree.root(),
+            BatchMerkleTree::new(&[[1; 32], [2; 32], [3; 32], [3; 32]]).root()
+        );
+    }
+}
@@ -1,7 +1,11 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use header_chain::header_chain::BlockHeaderCircuitOutput;
 
-use crate::spv::SPV;
+use crate::{
+    batch_spv::BatchFinalCircuitInput,
+    spv::SPV,
+    witness_spv::{WitnessSPV, WitnessSPVClaimedData},
+};
 
 #[derive(Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
 
@@ -9,3 +13,27 @@ pub struct FinalCircuitInput {
     pub block_header_circuit_output: BlockHeaderCircuitOutput,
     pub spv: SPV,
 }
+
+#[derive(Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
+pub struct WitnessFinalCircuitInput {
+    pub block_header_circuit_output: BlockHeaderCircuitOutput,
+    pub spv: WitnessSPV,
+}
+
+/// The journal of the witness final circuit.
+#[derive(Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
+pub struct WitnessFinalCircuitOutput {
+    pub txid: [u8; 32],
+    pub best_block_hash: [u8; 32],
+    pub total_work: [u8; 32],
+    pub claimed_data: WitnessSPVClaimedData,
+}
+
+/// The input of the final-spv guest. The borsh variant index is the tag selecting the circuit
+/// `final_spv_circuit` runs.
+#[derive(Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
+pub enum FinalSpvCircuitInput {
+    Final(FinalCircuitInput),
+    WitnessFinal(WitnessFinalCircuitInput),
+    BatchFinal(BatchFinalCircuitInput),
+}
@@ -0,0 +1,506 @@
+use std::fmt;
+
+use bitcoin::{consensus::encode, Block, Txid};
+use borsh::BorshDeserialize;
+use header_chain::{
+    header_chain::{
+        BlockHeaderCircuitOutput, CircuitBlockHeader, HeaderChainCircuitInput,
+        HeaderChainPrevProofType,
+    },
+    mmr_native::{MMRInclusionProof, MMRNative},
+};
+
+use crate::{
+    batch_spv::{BatchFinalCircuitInput, BatchMerkleTree, BatchOpening},
+    final_circuit::{FinalCircuitInput, WitnessFinalCircuitInput},
+    merkle_tree::BitcoinMerkleTree,
+    spv::SPV,
+    transaction::CircuitTransaction,
+    witness_spv::{WitnessInclusionProof, WitnessSPV, WitnessSPVClaim},
+};
+
+/// Size of a serialized Bitcoin block header.
+pub const BLOCK_HEADER_SIZE: usize = 80;
+
+#[derive(Debug)]
+pub enum HostError {
+    /// The raw headers are not a multiple of `BLOCK_HEADER_SIZE` bytes.
+    InvalidHeadersLength(usize),
+    /// There is no header at this height.
+    HeightOutOfRange(u32),
+    InvalidBlock(encode::Error),
+    /// The block header differs from the header at this height.
+    BlockNotInChain(u32),
+    TxNotFound(Txid),
+    /// The claim is not part of the transaction, or the transaction is the coinbase.
+    InvalidClaim(Txid, WitnessSPVClaim),
+}
+
+impl fmt::Display for HostError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for HostError {}
+
+/// Builds header chain and final circuit inputs natively from raw Bitcoin data.
+#[derive(Clone, Debug)]
+pub struct HeaderChainHost {
+    headers: Vec<CircuitBlockHeader>,
+    mmr: MMRNative,
+}
+
+impl HeaderChainHost {
+    /// Creates a host from concatenated raw 80-byte block headers, starting at the genesis block.
+    pub fn from_raw_headers(raw_headers: &[u8]) -> Result<Self, HostError> {
+        if raw_headers.len() % BLOCK_HEADER_SIZE != 0 {
+            return Err(HostError::InvalidHeadersLength(raw_headers.len()));
+        }
+        let headers = raw_headers
+            .chunks(BLOCK_HEADER_SIZE)
+            .map(|header| CircuitBlockHeader::try_from_slice(header).unwrap())
+            .collect::<Vec<CircuitBlockHeader>>();
+
+        let mut mmr = MMRNative::new();
+        for header in &headers {
+            mmr.append(header.compute_block_hash());
+        }
+
+        Ok(HeaderChainHost { headers, mmr })
+    }
+
+    pub fn headers(&self) -> &[CircuitBlockHeader] {
+        &self.headers
+    }
+
+    pub fn header(&self, height: u32) -> Result<&CircuitBlockHeader, HostError> {
+        self.headers
+            .get(height as usize)
+            .ok_or(HostError::HeightOutOfRange(height))
+    }
+
+    /// Builds the input of the header chain circuit proving the next `batch_size` headers after
+    /// `prev_proof`, or from the genesis block if there is no previous proof.
+    pub fn header_chain_input(
+        &self,
+        method_id: [u32; 8],
+        prev_proof: Option<BlockHeaderCircuitOutput>,
+        batch_size: u32,
+    ) -> Result<HeaderChainCircuitInput, HostError> {
+        let start = prev_proof
+            .as_ref()
+            .map_or(0, |output| output.chain_state.block_height.wrapping_add(1));
+        self.header(start)?;
+        let end = (start as usize)
+            .saturating_add(batch_size as usize)
+            .min(self.headers.len());
+
+        Ok(HeaderChainCircuitInput {
+            method_id,
+            prev_proof: match prev_proof {
+                Some(output) => HeaderChainPrevProofType::PrevProof(output),
+                None => HeaderChainPrevProofType::GenesisBlock,
+            },
+            block_headers: self.headers[start as usize..end].to_vec(),
+        })
+    }
+
+    /// Returns the inclusion proof of the block at `height` in the MMR of a chain state whose tip
+    /// is at `tip_height`.
+    pub fn mmr_inclusion_proof(
+        &self,
+        height: u32,
+        tip_height: u32,
+    ) -> Result<MMRInclusionProof, HostError> {
+        self.header(tip_height)?;
+        if height > tip_height {
+            return Err(HostError::HeightOutOfRange(height));
+        }
+        let (_, proof) = self.mmr.generate_proof_for_size(height, tip_height + 1);
+        Ok(proof)
+    }
+
+    /// Builds the SPV proof of `txid` in the raw block at `height`, against the MMR of a chain
+    /// state whose tip is at `tip_height`.
+    pub fn spv(
+        &self,
+        height: u32,
+        tip_height: u32,
+        raw_block: &[u8],
+        txid: Txid,
+    ) -> Result<SPV, HostError> {
+        let block: Block = encode::deserialize(raw_block).map_err(HostError::InvalidBlock)?;
+        let block_header = CircuitBlockHeader::from(block.header);
+        if *self.header(height)? != block_header {
+            return Err(HostError::BlockNotInChain(height));
+        }
+
+        let tx_index = block
+            .txdata
+            .iter()
+            .position(|tx| tx.compute_txid() == txid)
+            .ok_or(HostError::TxNotFound(txid))?;
+        let transactions = block
+            .txdata
+            .into_iter()
+            .map(CircuitTransaction)
+            .collect::<Vec<CircuitTransaction>>();
+        let block_inclusion_proof =
+            BitcoinMerkleTree::new_mid_state(&transactions).generate_proof(tx_index as u32);
+
+        Ok(SPV::new(
+            transactions[tx_index].clone(),
+            block_inclusion_proof,
+            block_header,
+            self.mmr_inclusion_proof(height, tip_height)?,
+        ))
+    }
+
+    /// Builds the input of the batch final circuit proving that each `(height, raw_block, txid)`
+    /// transaction is in the chain proven by `block_header_circuit_output`, with the opening of
+    /// each txid in the committed batch root.
+    pub fn batch_final_circuit_input(
+        &self,
+        block_header_circuit_output: BlockHeaderCircuitOutput,
+        transactions: &[(u32, &[u8], Txid)],
+    ) -> Result<(BatchFinalCircuitInput, Vec<BatchOpening>), HostError> {
+        let tip_height = block_header_circuit_output.chain_state.block_height;
+        let spvs = transactions
+            .iter()
+            .map(|(height, raw_block, txid)| self.spv(*height, tip_height, raw_block, *txid))
+            .collect::<Result<Vec<SPV>, HostError>>()?;
+
+        let txids = spvs
+            .iter()
+            .map(|spv| spv.transaction.txid())
+            .collect::<Vec<[u8; 32]>>();
+        let batch_merkle_tree = BatchMerkleTree::new(&txids);
+        let openings = (0..txids.len() as u32)
+            .map(|idx| batch_merkle_tree.opening(idx))
+            .collect();
+
+        Ok((
+            BatchFinalCircuitInput {
+                block_header_circuit_output,
+                spvs,
+            },
+            openings,
+        ))
+    }
+
+    /// Builds the witness SPV proof of the `claim` part of `txid` in the raw block at `height`,
+    /// against the MMR of a chain state whose tip is at `tip_height`.
+    pub fn witness_spv(
+        &self,
+        height: u32,
+        tip_height: u32,
+        raw_block: &[u8],
+        txid: Txid,
+        claim: WitnessSPVClaim,
+    ) -> Result<WitnessSPV, HostError> {
+        let block: Block = encode::deserialize(raw_block).map_err(HostError::InvalidBlock)?;
+        let block_header = CircuitBlockHeader::from(block.header);
+        if *self.header(height)? != block_header {
+            return Err(HostError::BlockNotInChain(height));
+        }
+
+        let tx_index = block
+            .txdata
+            .iter()
+            .position(|tx| tx.compute_txid() == txid)
+            .ok_or(HostError::TxNotFound(txid))?;
+        let transactions = block
+            .txdata
+            .into_iter()
+            .map(CircuitTransaction)
+            .collect::<Vec<CircuitTransaction>>();
+        let wtxids = std::iter::once([0u8; 32])
+            .chain(transactions[1..].iter().map(|tx| tx.wtxid()))
+            .collect::<Vec<[u8; 32]>>();
+
+        let spv = WitnessSPV::new(
+            transactions[tx_index].clone(),
+            claim.clone(),
+            WitnessInclusionProof::generate(wtxids, tx_index as u32),
+            transactions[0].clone(),
+            BitcoinMerkleTree::new_mid_state(&transactions).generate_proof(0),
+            block_header,
+            self.mmr_inclusion_proof(height, tip_height)?,
+        );
+        if tx_index == 0 || spv.claimed_data().is_none() {
+            return Err(HostError::InvalidClaim(txid, claim));
+        }
+        Ok(spv)
+    }
+
+    /// Builds the input of the witness final circuit proving the `claim` part of `txid` in the
+    /// raw block at `height` of the chain proven by `block_header_circuit_output`.
+    pub fn witness_final_circuit_input(
+        &self,
+        block_header_circuit_output: BlockHeaderCircuitOutput,
+        height: u32,
+        raw_block: &[u8],
+        txid: Txid,
+        claim: WitnessSPVClaim,
+    ) -> Result<WitnessFinalCircuitInput, HostError> {
+        let tip_height = block_header_circuit_output.chain_state.block_height;
+        let spv = self.witness_spv(height, tip_height, raw_block, txid, claim)?;
+        Ok(WitnessFinalCircuitInput {
+            block_header_circuit_output,
+            spv,
+        })
+    }
+
+    /// Builds the input of the final circuit proving that `txid` is in the raw block at `height`
+    /// of the chain proven by `block_header_circuit_output`.
+    pub fn final_circuit_input(
+        &self,
+        block_header_circuit_output: BlockHeaderCircuitOutput,
+        height: u32,
+        raw_block: &[u8],
+        txid: Txid,
+    ) -> Result<FinalCircuitInput, HostError> {
+        let tip_height = block_header_circuit_output.chain_state.block_height;
+        let spv = self.spv(height, tip_height, raw_block, txid)?;
+        Ok(FinalCircuitInput {
+            block_header_circuit_output,
+            spv,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{hashes::Hash, hex::FromHex};
+    use header_chain::{
+        header_chain::{ChainState, NETWORK_PARAMS},
+        header_chain_circuit,
+    };
+    use hex_literal::hex;
+
+    use super::*;
+    use crate::{
+        final_circuit,
+        final_circuit::FinalSpvCircuitInput,
+        final_spv_circuit,
+        merkle_tree::BlockInclusionProof,
+        zkvm::{NativeHost, Proof, ZkvmHost},
+        HEADER_CHAIN_GUEST_ID,
+    };
+
+    const FINAL_CIRCUIT_METHOD_ID: [u32; 8] = [0; 8];
+    const FINAL_SPV_CIRCUIT_METHOD_ID: [u32; 8] = [1; 8];
+
+    // Mainnet block headers from 0 to 2
+    const MAINNET_BLOCK_HEADERS: [[u8; 80]; 3] = [
+        hex!("0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a29ab5f49ffff001d1dac2b7c"),
+        hex!("010000006fe28c0ab6f1b372c1a6a246ae63f74f931e8365e15a089c68d6190000000000982051fd1e4ba744bbbe680e1fee14677ba1a3c3540bf7b1cdb606e857233e0e61bc6649ffff001d01e36299"),
+        hex!("010000004860eb18bf1b1620e37e9490fc8a427514416fd75159ab86688e9a8300000000d5fdcc541e25de1c7a5addedf24858b8bb665c9f36ef744ee42c316022c90f9bb0bc6649ffff001d08d2bd61"),
+    ];
+
+    // Coinbase transactions of the mainnet blocks from 0 to 2, the only transaction of each
+    const MAINNET_COINBASES: [&str; 3] = [
+        "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000",
+        "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff0704ffff001d0104ffffffff0100f2052a0100000043410496b538e853519c726a2c91e61ec11600ae1390813a627c66fb8be7947be63c52da7589379515d4e0a604f8141781e62294721166bf621e73a82cbf2342c858eeac00000000",
+        "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff0704ffff001d010bffffffff0100f2052a010000004341047211a824f55b505228e4c3d5194c1fcfaa15a456abdf37f9b9d97a4040afc073dee6c89064984f03385237d92167c13e236446b417ab79a0fcae412ae3316b77ac00000000",
+    ];
+
+    fn mainnet_block(height: usize) -> Vec<u8> {
+        let mut raw_block = MAINNET_BLOCK_HEADERS[height].to_vec();
+        raw_block.push(1);
+        raw_block.extend(Vec::<u8>::from_hex(MAINNET_COINBASES[height]).unwrap());
+        raw_block
+    }
+
+    fn genesis_block() -> Vec<u8> {
+        mainnet_block(0)
+    }
+
+    #[test]
+    fn test_header_chain_input() {
+        let host = HeaderChainHost::from_raw_headers(&MAINNET_BLOCK_HEADERS.concat()).unwrap();
+        assert!(HeaderChainHost::from_raw_headers(&[0u8; 81]).is_err());
+
+        let input = host
+            .header_chain_input(HEADER_CHAIN_GUEST_ID, None, 2)
+            .unwrap();
+        assert_eq!(input.prev_proof, HeaderChainPrevProofType::GenesisBlock);
+        assert_eq!(input.block_headers, host.headers()[..2]);
+
+        let mut chain_state = ChainState::new(&NETWORK_PARAMS);
+        chain_state.apply_blocks(&NETWORK_PARAMS, input.block_headers);
+        let output = BlockHeaderCircuitOutput {
+            method_id: HEADER_CHAIN_GUEST_ID,
+            network_params_hash: NETWORK_PARAMS.hash(),
+            chain_state,
+        };
+        let input = host
+            .header_chain_input(HEADER_CHAIN_GUEST_ID, Some(output.clone()), 10)
+            .unwrap();
+        assert_eq!(input.block_headers, host.headers()[2..]);
+
+        let mut chain_state = output.chain_state;
+        chain_state.apply_blocks(&NETWORK_PARAMS, input.block_headers);
+        let output = BlockHeaderCircuitOutput {
+            chain_state,
+            ..output
+        };
+        assert!(host
+            .header_chain_input(HEADER_CHAIN_GUEST_ID, Some(output), 10)
+            .is_err());
+    }
+
+    #[test]
+    fn test_final_circuit_input() {
+        let host = HeaderChainHost::from_raw_headers(&MAINNET_BLOCK_HEADERS.concat()).unwrap();
+        let zkvm_host = NativeHost::new()
+            .with_circuit(HEADER_CHAIN_GUEST_ID, header_chain_circuit)
+            .with_circuit(FINAL_CIRCUIT_METHOD_ID, final_circuit);
+
+        zkvm_host.write(
+            &host
+                .header_chain_input(HEADER_CHAIN_GUEST_ID, None, 3)
+                .unwrap(),
+        );
+        let header_chain_proof = zkvm_host.prove(&HEADER_CHAIN_GUEST_ID);
+        let output = BlockHeaderCircuitOutput::try_from_slice(&header_chain_proof.journal).unwrap();
+
+        let raw_block = genesis_block();
+        let block: Block = encode::deserialize(&raw_block).unwrap();
+        let txid = block.txdata[0].compute_txid();
+
+        assert!(matches!(
+            host.final_circuit_input(output.clone(), 1, &raw_block, txid),
+            Err(HostError::BlockNotInChain(1))
+        ));
+        assert!(matches!(
+            host.final_circuit_input(
+                output.clone(),
+                0,
+                &raw_block,
+                Txid::from_byte_array([0; 32])
+            ),
+            Err(HostError::TxNotFound(_))
+        ));
+        assert!(matches!(
+            host.witness_final_circuit_input(
+                output.clone(),
+                0,
+                &raw_block,
+                txid,
+                WitnessSPVClaim::Output { vout: 0 }
+            ),
+            Err(HostError::InvalidClaim(_, _))
+        ));
+
+        let input = host
+            .final_circuit_input(output.clone(), 0, &raw_block, txid)
+            .unwrap();
+        zkvm_host.write(&input);
+        zkvm_host.add_assumption(header_chain_proof);
+        let final_proof = zkvm_host.prove(&FINAL_CIRCUIT_METHOD_ID);
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&input.spv.transaction.txid());
+        hasher.update(&output.chain_state.best_block_hash);
+        hasher.update(&output.chain_state.total_work);
+        assert_eq!(final_proof.journal, hasher.finalize().as_bytes());
+    }
+
+    // Proves the header chain of the three mainnet blocks and builds the batch final circuit
+    // input for the coinbases of all of them.
+    fn batch_final_circuit_setup() -> (
+        NativeHost,
+        Proof,
+        BlockHeaderCircuitOutput,
+        BatchFinalCircuitInput,
+        Vec<BatchOpening>,
+        Vec<Txid>,
+    ) {
+        let host = HeaderChainHost::from_raw_headers(&MAINNET_BLOCK_HEADERS.concat()).unwrap();
+        let zkvm_host = NativeHost::new()
+            .with_circuit(HEADER_CHAIN_GUEST_ID, header_chain_circuit)
+            .with_circuit(FINAL_SPV_CIRCUIT_METHOD_ID, final_spv_circuit);
+
+        zkvm_host.write(
+            &host
+                .header_chain_input(HEADER_CHAIN_GUEST_ID, None, 3)
+                .unwrap(),
+        );
+        let header_chain_proof = zkvm_host.prove(&HEADER_CHAIN_GUEST_ID);
+        let output = BlockHeaderCircuitOutput::try_from_slice(&header_chain_proof.journal).unwrap();
+
+        let raw_blocks = (0..3).map(mainnet_block).collect::<Vec<Vec<u8>>>();
+        let txids = raw_blocks
+            .iter()
+            .map(|raw_block| {
+                encode::deserialize::<Block>(raw_block).unwrap().txdata[0].compute_txid()
+            })
+            .collect::<Vec<Txid>>();
+        let transactions = (0..3)
+            .map(|height| (height as u32, raw_blocks[height].as_slice(), txids[height]))
+            .collect::<Vec<(u32, &[u8], Txid)>>();
+        let (input, openings) = host
+            .batch_final_circuit_input(output.clone(), &transactions)
+            .unwrap();
+
+        (
+            zkvm_host,
+            header_chain_proof,
+            output,
+            input,
+            openings,
+            txids,
+        )
+    }
+
+    #[test]
+    fn test_batch_final_circuit_input() {
+        let (zkvm_host, header_chain_proof, output, input, openings, txids) =
+            batch_final_circuit_setup();
+        assert_eq!(input.spvs.len(), 3);
+
+        zkvm_host.write(&FinalSpvCircuitInput::BatchFinal(input));
+        zkvm_host.add_assumption(header_chain_proof);
+        let final_proof = zkvm_host.prove(&FINAL_SPV_CIRCUIT_METHOD_ID);
+
+        let batch_root = openings[0].get_root(txids[0].to_byte_array()).unwrap();
+        for (opening, txid) in openings.iter().zip(txids.iter()) {
+            assert_eq!(opening.get_root(txid.to_byte_array()).unwrap(), batch_root);
+        }
+        assert_ne!(
+            openings[1].get_root(txids[0].to_byte_array()),
+            Some(batch_root)
+        );
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&batch_root);
+        hasher.update(&output.chain_state.best_block_hash);
+        hasher.update(&output.chain_state.total_work);
+        assert_eq!(final_proof.journal, hasher.finalize().as_bytes());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_batch_final_circuit_tampered_block_inclusion_proof() {
+        let (zkvm_host, header_chain_proof, _, mut input, _, _) = batch_final_circuit_setup();
+        // Block 1 only has its coinbase, so any sibling changes the Merkle root.
+        input.spvs[1].block_inclusion_proof = BlockInclusionProof::new(0, vec![[0; 32]]);
+
+        zkvm_host.write(&FinalSpvCircuitInput::BatchFinal(input));
+        zkvm_host.add_assumption(header_chain_proof);
+        zkvm_host.prove(&FINAL_SPV_CIRCUIT_METHOD_ID);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_batch_final_circuit_tampered_mmr_inclusion_proof() {
+        let (zkvm_host, header_chain_proof, _, mut input, _, _) = batch_final_circuit_setup();
+        input.spvs[0].mmr_inclusion_proof.inclusion_proof[0][0] ^= 1;
+
+        zkvm_host.write(&FinalSpvCircuitInput::BatchFinal(input));
+        zkvm_host.add_assumption(header_chain_proof);
+        zkvm_host.prove(&FINAL_SPV_CIRCUIT_METHOD_ID);
+    }
+}
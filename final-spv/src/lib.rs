@@ -1,53 +1,80 @@
-use final_circuit::FinalCircuitInput;
-use risc0_zkvm::guest::env;
+use batch_spv::{BatchFinalCircuitInput, BatchMerkleTree};
+use final_circuit::{
+    FinalCircuitInput, FinalSpvCircuitInput, WitnessFinalCircuitInput, WitnessFinalCircuitOutput,
+};
+use header_chain::header_chain::NETWORK_PARAMS;
 use zkvm::ZkvmGuest;
 
+pub mod batch_spv;
 pub mod final_circuit;
+pub mod host;
 pub mod merkle_tree;
 pub mod spv;
 pub mod transaction;
 pub mod utils;
+pub mod witness_spv;
 pub mod zkvm;
 pub use risc0_zkvm;
 
+/// The method IDs of the header chain guest ELFs in `prover/elfs`, one per network. They must
+/// be updated whenever the guests are rebuilt with `REPR_GUEST_BUILD=1`.
+pub const MAINNET_HEADER_CHAIN_GUEST_ID: [u32; 8] = [
+    1089137192, 2499827081, 1648551528, 3898441744, 1241508107, 1212614376, 523594555, 2385906108,
+];
+pub const TESTNET4_HEADER_CHAIN_GUEST_ID: [u32; 8] = [
+    1866422223, 3011666006, 1737793548, 1848006863, 1320341210, 1623031594, 1418162769, 4138395741,
+];
+pub const SIGNET_HEADER_CHAIN_GUEST_ID: [u32; 8] = [
+    3760542186, 3452311364, 636757141, 2341919608, 3798837687, 3808746669, 4002435111, 318058396,
+];
+pub const REGTEST_HEADER_CHAIN_GUEST_ID: [u32; 8] = [
+    1361809821, 2352021461, 2194279832, 977027345, 356844560, 3592195208, 1085394392, 1717039563,
+];
+
 /// The method ID for the header chain circuit.
-const HEADER_CHAIN_GUEST_ID: [u32; 8] = {
+pub const HEADER_CHAIN_GUEST_ID: [u32; 8] = {
     match option_env!("BITCOIN_NETWORK") {
-        Some(network) if matches!(network.as_bytes(), b"mainnet") => [
-            1089137192, 2499827081, 1648551528, 3898441744, 1241508107, 1212614376, 523594555,
-            2385906108,
-        ],
-        Some(network) if matches!(network.as_bytes(), b"testnet4") => [
-            1866422223, 3011666006, 1737793548, 1848006863, 1320341210, 1623031594, 1418162769,
-            4138395741,
-        ],
-        Some(network) if matches!(network.as_bytes(), b"signet") => [
-            3760542186, 3452311364, 636757141, 2341919608, 3798837687, 3808746669, 4002435111,
-            318058396,
-        ],
-        Some(network) if matches!(network.as_bytes(), b"regtest") => [
-            1361809821, 2352021461, 2194279832, 977027345, 356844560, 3592195208, 1085394392,
-            1717039563,
-        ],
-        None => [
-            1089137192, 2499827081, 1648551528, 3898441744, 1241508107, 1212614376, 523594555,
-            2385906108,
-        ],
+        Some(network) if matches!(network.as_bytes(), b"mainnet") => MAINNET_HEADER_CHAIN_GUEST_ID,
+        Some(network) if matches!(network.as_bytes(), b"testnet4") => {
+            TESTNET4_HEADER_CHAIN_GUEST_ID
+        }
+        Some(network) if matches!(network.as_bytes(), b"signet") => SIGNET_HEADER_CHAIN_GUEST_ID,
+        Some(network) if matches!(network.as_bytes(), b"regtest") => REGTEST_HEADER_CHAIN_GUEST_ID,
+        None => MAINNET_HEADER_CHAIN_GUEST_ID,
         _ => panic!("Invalid network type"),
     }
 };
 
+/// The entry point of the final-spv guest, running the circuit selected by the tag of the
+/// `FinalSpvCircuitInput`.
+pub fn final_spv_circuit(guest: &impl ZkvmGuest) {
+    match guest.read_from_host::<FinalSpvCircuitInput>() {
+        FinalSpvCircuitInput::Final(input) => verify_final_circuit_input(guest, input),
+        FinalSpvCircuitInput::WitnessFinal(input) => {
+            verify_witness_final_circuit_input(guest, input)
+        }
+        FinalSpvCircuitInput::BatchFinal(input) => verify_batch_final_circuit_input(guest, input),
+    }
+}
+
 /// The final circuit that verifies the output of the header chain circuit.
 pub fn final_circuit(guest: &impl ZkvmGuest) {
-    let start = env::cycle_count();
     let input: FinalCircuitInput = guest.read_from_host::<FinalCircuitInput>();
+    verify_final_circuit_input(guest, input);
+}
+
+fn verify_final_circuit_input(guest: &impl ZkvmGuest, input: FinalCircuitInput) {
     guest.verify(HEADER_CHAIN_GUEST_ID, &input.block_header_circuit_output);
-    input.spv.verify(
+    assert_eq!(
+        input.block_header_circuit_output.network_params_hash,
+        NETWORK_PARAMS.hash()
+    );
+    assert!(input.spv.verify(
         input
             .block_header_circuit_output
             .chain_state
             .block_hashes_mmr,
-    );
+    ));
     let mut hasher = blake3::Hasher::new();
 
     hasher.update(&input.spv.transaction.txid());
@@ -60,6 +87,60 @@ pub fn final_circuit(guest: &impl ZkvmGuest) {
     hasher.update(&input.block_header_circuit_output.chain_state.total_work);
     let final_output = hasher.finalize();
     guest.commit(final_output.as_bytes());
-    let end = env::cycle_count();
-    println!("Final circuit took {:?} cycles", end - start);
+}
+
+/// The final circuit that verifies the output of the header chain circuit and a witness SPV
+/// proof, and exposes the claimed part of the transaction.
+pub fn witness_final_circuit(guest: &impl ZkvmGuest) {
+    let input: WitnessFinalCircuitInput = guest.read_from_host::<WitnessFinalCircuitInput>();
+    verify_witness_final_circuit_input(guest, input);
+}
+
+fn verify_witness_final_circuit_input(guest: &impl ZkvmGuest, input: WitnessFinalCircuitInput) {
+    guest.verify(HEADER_CHAIN_GUEST_ID, &input.block_header_circuit_output);
+    assert_eq!(
+        input.block_header_circuit_output.network_params_hash,
+        NETWORK_PARAMS.hash()
+    );
+    let claimed_data = input
+        .spv
+        .claimed_data()
+        .expect("Claimed data is not in the transaction");
+    let chain_state = input.block_header_circuit_output.chain_state;
+    assert!(input.spv.verify(chain_state.block_hashes_mmr));
+
+    guest.commit(&WitnessFinalCircuitOutput {
+        txid: input.spv.transaction.txid(),
+        best_block_hash: chain_state.best_block_hash,
+        total_work: chain_state.total_work,
+        claimed_data,
+    });
+}
+
+/// The final circuit that verifies the output of the header chain circuit and a batch of SPV
+/// proofs, committing to the `BatchMerkleTree` root of their txids.
+pub fn batch_final_circuit(guest: &impl ZkvmGuest) {
+    let input: BatchFinalCircuitInput = guest.read_from_host::<BatchFinalCircuitInput>();
+    verify_batch_final_circuit_input(guest, input);
+}
+
+fn verify_batch_final_circuit_input(guest: &impl ZkvmGuest, input: BatchFinalCircuitInput) {
+    guest.verify(HEADER_CHAIN_GUEST_ID, &input.block_header_circuit_output);
+    assert_eq!(
+        input.block_header_circuit_output.network_params_hash,
+        NETWORK_PARAMS.hash()
+    );
+    let chain_state = input.block_header_circuit_output.chain_state;
+    let mut txids = Vec::with_capacity(input.spvs.len());
+    for spv in input.spvs {
+        assert!(spv.verify(chain_state.block_hashes_mmr.clone()));
+        txids.push(spv.transaction.txid());
+    }
+    let mut hasher = blake3::Hasher::new();
+
+    hasher.update(&BatchMerkleTree::new(&txids).root());
+    hasher.update(&chain_state.best_block_hash);
+    hasher.update(&chain_state.total_work);
+    let final_output = hasher.finalize();
+    guest.commit(final_output.as_bytes());
 }
@@ -161,7 +161,7 @@ impl BitcoinMerkleTree {
         tree
     }
 
-    fn get_idx_path(&self, index: u32) -> Vec<[u8; 32]> {
+    pub(crate) fn get_idx_path(&self, index: u32) -> Vec<[u8; 32]> {
         assert!(index < self.nodes[0].len() as u32, "Index out of bounds");
         let mut path = vec![];
         let mut level = 0;
@@ -222,6 +222,15 @@ impl BlockInclusionProof {
         BlockInclusionProof { idx, merkle_proof }
     }
 
+    pub fn idx(&self) -> u32 {
+        self.idx
+    }
+
+    /// Returns the depth of the Merkle tree the proof is for.
+    pub fn depth(&self) -> usize {
+        self.merkle_proof.len()
+    }
+
     /// Calculates the Merkle root given a leaf transaction ID (`txid`, which is a `mid_state_txid`)
     /// and the Merkle proof path (sibling nodes from the "mid-state" tree).
     ///
@@ -10,7 +10,7 @@ use bitcoin::transaction::Version;
 use bitcoin::{Amount, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness};
 use borsh::{BorshDeserialize, BorshSerialize};
 
-use crate::utils::calculate_sha256;
+use crate::utils::{calculate_double_sha256, calculate_sha256};
 
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
 pub struct CircuitTransaction(pub Transaction);
@@ -31,6 +31,11 @@ impl CircuitTransaction {
         calculate_sha256(&mid_state)
     }
 
+    /// Returns the witness transaction id, in the same byte order as `txid`.
+    pub fn wtxid(&self) -> [u8; 32] {
+        calculate_double_sha256(&bitcoin::consensus::serialize(self.inner()))
+    }
+
     /// Returns the first digest of the transaction to be used in SPV
     pub fn mid_state_txid(&self) -> [u8; 32] {
         let mut tx_bytes_vec = vec![];
@@ -0,0 +1,370 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use header_chain::{
+    header_chain::CircuitBlockHeader, mmr_guest::MMRGuest, mmr_native::MMRInclusionProof,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    merkle_tree::{BitcoinMerkleTree, BlockInclusionProof},
+    transaction::CircuitTransaction,
+    utils::calculate_double_sha256,
+};
+
+/// Prefix of the coinbase output script committing to the witness Merkle root (BIP-141).
+const WITNESS_COMMITMENT_HEADER: [u8; 6] = [0x6a, 0x24, 0xaa, 0x21, 0xa9, 0xed];
+
+/// Merkle path of a wtxid in the witness Merkle tree of a block.
+///
+/// Unlike `BlockInclusionProof`, this is a plain Bitcoin Merkle path, as the coinbase wtxid leaf
+/// has no mid-state. `WitnessSPV` pins its depth to the depth of the coinbase proof instead, so an
+/// internal node cannot pass as a wtxid.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
+pub struct WitnessInclusionProof {
+    idx: u32,
+    merkle_proof: Vec<[u8; 32]>,
+}
+
+impl WitnessInclusionProof {
+    pub fn new(idx: u32, merkle_proof: Vec<[u8; 32]>) -> Self {
+        WitnessInclusionProof { idx, merkle_proof }
+    }
+
+    /// Generates the proof of the wtxid at `idx`. The first wtxid is the coinbase one, all zeros.
+    pub fn generate(wtxids: Vec<[u8; 32]>, idx: u32) -> Self {
+        let merkle_tree = BitcoinMerkleTree::new(wtxids);
+        WitnessInclusionProof::new(idx, merkle_tree.get_idx_path(idx))
+    }
+
+    /// Calculates the witness Merkle root given the wtxid of the leaf.
+    pub fn get_root(&self, wtxid: [u8; 32]) -> [u8; 32] {
+        let mut preimage: [u8; 64] = [0; 64];
+        let mut combined_hash = wtxid;
+        let mut index = self.idx;
+        for sibling in &self.merkle_proof {
+            if index % 2 == 0 {
+                preimage[..32].copy_from_slice(&combined_hash);
+                preimage[32..].copy_from_slice(sibling);
+            } else {
+                preimage[..32].copy_from_slice(sibling);
+                preimage[32..].copy_from_slice(&combined_hash);
+            }
+            combined_hash = calculate_double_sha256(&preimage);
+            index /= 2;
+        }
+        combined_hash
+    }
+}
+
+/// The part of the transaction a `WitnessSPV` proves.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
+pub enum WitnessSPVClaim {
+    Output { vout: u32 },
+    WitnessItem { vin: u32, index: u32 },
+}
+
+/// The verified data selected by a `WitnessSPVClaim`.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
+pub enum WitnessSPVClaimedData {
+    Output {
+        vout: u32,
+        value: u64,
+        script_pubkey: Vec<u8>,
+    },
+    WitnessItem {
+        vin: u32,
+        index: u32,
+        item: Vec<u8>,
+    },
+}
+
+/// SPV proof of a transaction including its witness, through the coinbase witness commitment.
+#[derive(Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
+pub struct WitnessSPV {
+    pub transaction: CircuitTransaction,
+    pub claim: WitnessSPVClaim,
+    pub wtxid_inclusion_proof: WitnessInclusionProof,
+    pub coinbase_transaction: CircuitTransaction,
+    pub coinbase_inclusion_proof: BlockInclusionProof,
+    pub block_header: CircuitBlockHeader,
+    pub mmr_inclusion_proof: MMRInclusionProof,
+}
+
+impl WitnessSPV {
+    pub fn new(
+        transaction: CircuitTransaction,
+        claim: WitnessSPVClaim,
+        wtxid_inclusion_proof: WitnessInclusionProof,
+        coinbase_transaction: CircuitTransaction,
+        coinbase_inclusion_proof: BlockInclusionProof,
+        block_header: CircuitBlockHeader,
+        mmr_inclusion_proof: MMRInclusionProof,
+    ) -> Self {
+        WitnessSPV {
+            transaction,
+            claim,
+            wtxid_inclusion_proof,
+            coinbase_transaction,
+            coinbase_inclusion_proof,
+            block_header,
+            mmr_inclusion_proof,
+        }
+    }
+
+    /// Returns the data selected by the claim, if the transaction has it.
+    pub fn claimed_data(&self) -> Option<WitnessSPVClaimedData> {
+        match self.claim {
+            WitnessSPVClaim::Output { vout } => {
+                let output = self.transaction.output.get(vout as usize)?;
+                Some(WitnessSPVClaimedData::Output {
+                    vout,
+                    value: output.value.to_sat(),
+                    script_pubkey: output.script_pubkey.to_bytes(),
+                })
+            }
+            WitnessSPVClaim::WitnessItem { vin, index } => {
+                let input = self.transaction.input.get(vin as usize)?;
+                let item = input.witness.nth(index as usize)?;
+                Some(WitnessSPVClaimedData::WitnessItem {
+                    vin,
+                    index,
+                    item: item.to_vec(),
+                })
+            }
+        }
+    }
+
+    pub fn verify(&self, mmr_guest: MMRGuest) -> bool {
+        // The coinbase transaction is the first one of the block.
+        if !self.coinbase_transaction.is_coinbase() || self.coinbase_inclusion_proof.idx() != 0 {
+            return false;
+        }
+        let block_merkle_root = self
+            .coinbase_inclusion_proof
+            .get_root(self.coinbase_transaction.txid());
+        if block_merkle_root != self.block_header.merkle_root {
+            return false;
+        }
+
+        let (Some(witness_commitment), Some(witness_reserved_value)) = (
+            witness_commitment(&self.coinbase_transaction),
+            witness_reserved_value(&self.coinbase_transaction),
+        ) else {
+            return false;
+        };
+
+        // Both trees have the same leaves, so the proofs must have the same depth. The coinbase
+        // wtxid is not a transaction commitment.
+        if self.wtxid_inclusion_proof.idx == 0
+            || self.wtxid_inclusion_proof.merkle_proof.len()
+                != self.coinbase_inclusion_proof.depth()
+        {
+            return false;
+        }
+        let witness_root = self
+            .wtxid_inclusion_proof
+            .get_root(self.transaction.wtxid());
+        let mut preimage: [u8; 64] = [0; 64];
+        preimage[..32].copy_from_slice(&witness_root);
+        preimage[32..].copy_from_slice(&witness_reserved_value);
+        if calculate_double_sha256(&preimage) != witness_commitment {
+            return false;
+        }
+
+        let block_hash = self.block_header.compute_block_hash();
+        mmr_guest.verify_proof(block_hash, &self.mmr_inclusion_proof)
+    }
+}
+
+/// Returns the witness commitment of the coinbase transaction, from its last commitment output.
+fn witness_commitment(coinbase_transaction: &CircuitTransaction) -> Option<[u8; 32]> {
+    coinbase_transaction
+        .output
+        .iter()
+        .rev()
+        .map(|output| output.script_pubkey.as_bytes())
+        .find(|script| script.len() >= 38 && script[..6] == WITNESS_COMMITMENT_HEADER)
+        .map(|script| script[6..38].try_into().unwrap())
+}
+
+/// Returns the witness reserved value, the only witness item of the coinbase input.
+fn witness_reserved_value(coinbase_transaction: &CircuitTransaction) -> Option<[u8; 32]> {
+    let witness = &coinbase_transaction.input[0].witness;
+    if witness.len() != 1 {
+        return None;
+    }
+    witness.nth(0)?.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        absolute::LockTime, hashes::Hash, transaction::Version, Amount, OutPoint, ScriptBuf,
+        Sequence, Transaction, TxIn, TxOut, Txid, Witness,
+    };
+    use header_chain::{
+        header_chain::{BlockHeaderCircuitOutput, ChainState, NETWORK_PARAMS},
+        mmr_native::MMRNative,
+    };
+
+    use super::*;
+    use crate::{
+        final_circuit::{WitnessFinalCircuitInput, WitnessFinalCircuitOutput},
+        witness_final_circuit,
+        zkvm::{NativeGuest, Proof},
+        HEADER_CHAIN_GUEST_ID,
+    };
+
+    const WITNESS_RESERVED_VALUE: [u8; 32] = [0; 32];
+
+    fn transaction(input: TxIn, output: Vec<TxOut>) -> CircuitTransaction {
+        CircuitTransaction(Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![input],
+            output,
+        })
+    }
+
+    /// Builds a block with a coinbase and a segwit transaction, and returns the SPV proof of the
+    /// latter, with the MMR of the block.
+    fn witness_spv(claim: WitnessSPVClaim) -> (WitnessSPV, MMRGuest) {
+        let spending_transaction = transaction(
+            TxIn {
+                previous_output: OutPoint::new(Txid::from_byte_array([1; 32]), 0),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::from_slice(&[b"signature".to_vec(), b"commitment".to_vec()]),
+            },
+            vec![TxOut {
+                value: Amount::from_sat(100_000),
+                script_pubkey: ScriptBuf::from_bytes(vec![0x51]),
+            }],
+        );
+
+        let wtxids = vec![[0; 32], spending_transaction.wtxid()];
+        let witness_root = BitcoinMerkleTree::new(wtxids.clone()).root();
+        let mut preimage = witness_root.to_vec();
+        preimage.extend(WITNESS_RESERVED_VALUE);
+        let mut commitment_script = WITNESS_COMMITMENT_HEADER.to_vec();
+        commitment_script.extend(calculate_double_sha256(&preimage));
+
+        let coinbase_transaction = transaction(
+            TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: ScriptBuf::from_bytes(vec![0x51, 0x51]),
+                sequence: Sequence::MAX,
+                witness: Witness::from_slice(&[WITNESS_RESERVED_VALUE]),
+            },
+            vec![
+                TxOut {
+                    value: Amount::from_sat(5_000_000_000),
+                    script_pubkey: ScriptBuf::from_bytes(vec![0x51]),
+                },
+                TxOut {
+                    value: Amount::ZERO,
+                    script_pubkey: ScriptBuf::from_bytes(commitment_script),
+                },
+            ],
+        );
+
+        let transactions = vec![coinbase_transaction.clone(), spending_transaction.clone()];
+        let block_header = CircuitBlockHeader {
+            version: 4,
+            prev_block_hash: [0; 32],
+            merkle_root: BitcoinMerkleTree::new(transactions.iter().map(|tx| tx.txid()).collect())
+                .root(),
+            time: 1_700_000_000,
+            bits: 0x207fffff,
+            nonce: 0,
+        };
+
+        let mut mmr_native = MMRNative::new();
+        let mut mmr_guest = MMRGuest::new();
+        mmr_native.append(block_header.compute_block_hash());
+        mmr_guest.append(block_header.compute_block_hash());
+        let (_, mmr_inclusion_proof) = mmr_native.generate_proof(0);
+
+        let spv = WitnessSPV::new(
+            spending_transaction,
+            claim,
+            WitnessInclusionProof::generate(wtxids, 1),
+            coinbase_transaction,
+            BitcoinMerkleTree::new_mid_state(&transactions).generate_proof(0),
+            block_header,
+            mmr_inclusion_proof,
+        );
+        (spv, mmr_guest)
+    }
+
+    #[test]
+    fn test_witness_spv() {
+        let (spv, mmr_guest) = witness_spv(WitnessSPVClaim::WitnessItem { vin: 0, index: 1 });
+        assert!(spv.verify(mmr_guest.clone()));
+        assert_eq!(
+            spv.claimed_data(),
+            Some(WitnessSPVClaimedData::WitnessItem {
+                vin: 0,
+                index: 1,
+                item: b"commitment".to_vec(),
+            })
+        );
+
+        let (spv, mmr_guest) = witness_spv(WitnessSPVClaim::Output { vout: 0 });
+        assert!(spv.verify(mmr_guest.clone()));
+        assert_eq!(
+            spv.claimed_data(),
+            Some(WitnessSPVClaimedData::Output {
+                vout: 0,
+                value: 100_000,
+                script_pubkey: vec![0x51],
+            })
+        );
+
+        let (mut spv, _) = witness_spv(WitnessSPVClaim::Output { vout: 1 });
+        assert_eq!(spv.claimed_data(), None);
+
+        // A different witness is not committed to.
+        spv.transaction.input[0].witness = Witness::from_slice(&[b"other".to_vec()]);
+        assert!(!spv.verify(mmr_guest.clone()));
+
+        // Neither is the coinbase wtxid leaf.
+        let (mut spv, _) = witness_spv(WitnessSPVClaim::Output { vout: 0 });
+        spv.wtxid_inclusion_proof = WitnessInclusionProof::generate(vec![[0; 32], [1; 32]], 0);
+        assert!(!spv.verify(mmr_guest));
+    }
+
+    #[test]
+    fn test_witness_final_circuit() {
+        let (spv, mmr_guest) = witness_spv(WitnessSPVClaim::WitnessItem { vin: 0, index: 1 });
+        let mut chain_state = ChainState::new(&NETWORK_PARAMS);
+        chain_state.block_height = 0;
+        chain_state.best_block_hash = spv.block_header.compute_block_hash();
+        chain_state.block_hashes_mmr = mmr_guest;
+        let block_header_circuit_output = BlockHeaderCircuitOutput {
+            method_id: HEADER_CHAIN_GUEST_ID,
+            network_params_hash: NETWORK_PARAMS.hash(),
+            chain_state,
+        };
+
+        let guest = NativeGuest::new(
+            borsh::to_vec(&WitnessFinalCircuitInput {
+                block_header_circuit_output: block_header_circuit_output.clone(),
+                spv: spv.clone(),
+            })
+            .unwrap(),
+            vec![Proof {
+                method_id: HEADER_CHAIN_GUEST_ID,
+                journal: borsh::to_vec(&block_header_circuit_output).unwrap(),
+            }],
+        );
+        witness_final_circuit(&guest);
+
+        let output = WitnessFinalCircuitOutput::try_from_slice(&guest.journal()).unwrap();
+        assert_eq!(output.txid, spv.transaction.txid());
+        assert_eq!(
+            output.best_block_hash,
+            block_header_circuit_output.chain_state.best_block_hash
+        );
+        assert_eq!(output.claimed_data, spv.claimed_data().unwrap());
+    }
+}
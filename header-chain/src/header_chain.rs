@@ -4,7 +4,7 @@
 /// This module contains the implementation of the header chain circuit, which is basically
 /// the Bitcoin header chain verification logic.
 /// WARNING: This implementation is not a word-to-word translation of the Bitcoin Core source code.
-use crate::mmr_guest::MMRGuest;
+use crate::{mmr_guest::MMRGuest, mmr_native::MMRInclusionProof, utils::calculate_sha256};
 use bitcoin::{
     block::{Header, Version},
     hashes::Hash,
@@ -14,15 +14,101 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use crypto_bigint::{Encoding, U256};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// Consensus parameters of a Bitcoin network used for header chain verification.
+#[derive(
+    Serialize, Deserialize, Eq, PartialEq, Clone, Copy, Debug, BorshDeserialize, BorshSerialize,
+)]
+pub struct NetworkParams {
+    /// Compact representation of `pow_limit`.
+    pub pow_limit_bits: u32,
+    /// The highest allowed target, big-endian.
+    pub pow_limit: [u8; 32],
+    /// Number of blocks between difficulty adjustments.
+    pub blocks_per_epoch: u32,
+    /// Expected duration of an epoch in seconds.
+    pub expected_epoch_timespan: u32,
+    /// Testnet4 rule: a block more than 20 minutes after its parent may use the `pow_limit`
+    /// target, unless it starts an epoch.
+    pub allow_min_difficulty_blocks: bool,
+    /// Regtest rule: the target is always `pow_limit`.
+    pub no_retargeting: bool,
+}
+
+/// An epoch should be two weeks (represented as number of seconds)
+/// seconds/minute * minutes/hour * hours/day * 14 days
+const EXPECTED_EPOCH_TIMESPAN: u32 = 60 * 60 * 24 * 14;
+
+/// Number of blocks per epoch
+const BLOCKS_PER_EPOCH: u32 = 2016;
+
+/// Seconds after the previous block after which a testnet4 block may use the minimum difficulty.
+const MIN_DIFFICULTY_BLOCK_SPACING: u32 = 20 * 60;
+
+/// Seconds a block's time may be ahead of the network time.
+pub const MAX_FUTURE_BLOCK_TIME: u32 = 2 * 60 * 60;
+
+impl NetworkParams {
+    pub const MAINNET: NetworkParams = NetworkParams {
+        pow_limit_bits: 0x1D00FFFF,
+        pow_limit: [
+            0, 0, 0, 0, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0,
+        ],
+        blocks_per_epoch: BLOCKS_PER_EPOCH,
+        expected_epoch_timespan: EXPECTED_EPOCH_TIMESPAN,
+        allow_min_difficulty_blocks: false,
+        no_retargeting: false,
+    };
+
+    pub const TESTNET4: NetworkParams = NetworkParams {
+        allow_min_difficulty_blocks: true,
+        ..NetworkParams::MAINNET
+    };
+
+    pub const SIGNET: NetworkParams = NetworkParams {
+        pow_limit_bits: 0x1E0377AE,
+        pow_limit: [
+            0, 0, 3, 119, 174, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0,
+        ],
+        ..NetworkParams::MAINNET
+    };
 
-/// Network configuration holder for Bitcoin-specific constants
-#[derive(Debug)]
-pub struct NetworkConstants {
-    pub max_bits: u32,
-    pub max_target: U256,
-    pub max_target_bytes: [u8; 32],
+    pub const REGTEST: NetworkParams = NetworkParams {
+        pow_limit_bits: 0x207FFFFF,
+        pow_limit: [
+            127, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0,
+        ],
+        no_retargeting: true,
+        ..NetworkParams::MAINNET
+    };
+
+    /// Returns the parameters of `mainnet`, `testnet4`, `signet` or `regtest`.
+    pub const fn from_network_name(network: &str) -> Option<NetworkParams> {
+        match network.as_bytes() {
+            b"mainnet" => Some(NetworkParams::MAINNET),
+            b"testnet4" => Some(NetworkParams::TESTNET4),
+            b"signet" => Some(NetworkParams::SIGNET),
+            b"regtest" => Some(NetworkParams::REGTEST),
+            _ => None,
+        }
+    }
+
+    /// Sha256 of the Borsh encoding. Committed by the header chain circuit, so that proofs for
+    /// different networks can not be mixed.
+    pub fn hash(&self) -> [u8; 32] {
+        calculate_sha256(&borsh::to_vec(self).expect("Serialization to vec is infallible"))
+    }
+
+    fn pow_limit_u256(&self) -> U256 {
+        U256::from_be_bytes(self.pow_limit)
+    }
 }
 
+/// The network the guest is built for. The ELF of each network is pinned to its parameters.
 pub const NETWORK_TYPE: &str = {
     match option_env!("BITCOIN_NETWORK") {
         Some(network) if matches!(network.as_bytes(), b"mainnet") => "mainnet",
@@ -34,76 +120,11 @@ pub const NETWORK_TYPE: &str = {
     }
 };
 
-// Const evaluation of network type from environment
-const IS_REGTEST: bool = matches!(NETWORK_TYPE.as_bytes(), b"regtest");
-const IS_TESTNET4: bool = matches!(NETWORK_TYPE.as_bytes(), b"testnet4");
-const MINIMUM_WORK_TESTNET: U256 =
-    U256::from_be_hex("0000000000000000000000000000000000000000000000000000000100010001");
-
-pub const NETWORK_CONSTANTS: NetworkConstants = {
-    match option_env!("BITCOIN_NETWORK") {
-        Some(n) if matches!(n.as_bytes(), b"signet") => NetworkConstants {
-            max_bits: 0x1E0377AE,
-            max_target: U256::from_be_hex(
-                "00000377AE000000000000000000000000000000000000000000000000000000",
-            ),
-            max_target_bytes: [
-                0, 0, 3, 119, 174, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-                0, 0, 0, 0, 0, 0,
-            ],
-        },
-        Some(n) if matches!(n.as_bytes(), b"regtest") => NetworkConstants {
-            max_bits: 0x207FFFFF,
-            max_target: U256::from_be_hex(
-                "7FFFFF0000000000000000000000000000000000000000000000000000000000",
-            ),
-            max_target_bytes: [
-                127, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-                0, 0, 0, 0, 0, 0,
-            ],
-        },
-        Some(n) if matches!(n.as_bytes(), b"testnet4") => NetworkConstants {
-            max_bits: 0x1D00FFFF,
-            max_target: U256::from_be_hex(
-                "00000000FFFF0000000000000000000000000000000000000000000000000000",
-            ),
-            max_target_bytes: [
-                0, 0, 0, 0, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-                0, 0, 0, 0, 0, 0,
-            ],
-        },
-        Some(n) if matches!(n.as_bytes(), b"mainnet") => NetworkConstants {
-            max_bits: 0x1D00FFFF,
-            max_target: U256::from_be_hex(
-                "00000000FFFF0000000000000000000000000000000000000000000000000000",
-            ),
-            max_target_bytes: [
-                0, 0, 0, 0, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-                0, 0, 0, 0, 0, 0,
-            ],
-        },
-        // Default to mainnet for None
-        None => NetworkConstants {
-            max_bits: 0x1D00FFFF,
-            max_target: U256::from_be_hex(
-                "00000000FFFF0000000000000000000000000000000000000000000000000000",
-            ),
-            max_target_bytes: [
-                0, 0, 0, 0, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-                0, 0, 0, 0, 0, 0,
-            ],
-        },
-        _ => panic!("Unsupported network"),
-    }
+pub const NETWORK_PARAMS: NetworkParams = match NetworkParams::from_network_name(NETWORK_TYPE) {
+    Some(params) => params,
+    None => panic!("Unsupported network"),
 };
 
-/// An epoch should be two weeks (represented as number of seconds)
-/// seconds/minute * minutes/hour * hours/day * 14 days
-const EXPECTED_EPOCH_TIMESPAN: u32 = 60 * 60 * 24 * 14;
-
-/// Number of blocks per epoch
-const BLOCKS_PER_EPOCH: u32 = 2016;
-
 /// Bitcoin block header.
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
 pub struct CircuitBlockHeader {
@@ -163,6 +184,85 @@ impl Into<Header> for CircuitBlockHeader {
     }
 }
 
+/// The reason a block header was rejected by `ChainState::try_apply_blocks`.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum HeaderChainError {
+    /// The header does not build on the current best block.
+    PrevHashMismatch { height: u32 },
+    /// The header's `bits` differ from the difficulty required at this height.
+    BadBits {
+        height: u32,
+        expected: u32,
+        actual: u32,
+    },
+    /// The block hash is above the target.
+    InsufficientPoW { height: u32 },
+    /// The header's time is not greater than the median time of the previous 11 blocks.
+    TimestampTooOld {
+        height: u32,
+        time: u32,
+        median_time_past: u32,
+    },
+    /// The header's time is more than `MAX_FUTURE_BLOCK_TIME` ahead of the network time.
+    TimestampTooFarInFuture {
+        height: u32,
+        time: u32,
+        max_time: u32,
+    },
+    /// The fork point of a reorg is not a block of the current chain.
+    ForkPointNotInChain { height: u32 },
+    /// The competing branch does not have more cumulative work than the current chain.
+    InsufficientBranchWork,
+}
+
+impl fmt::Display for HeaderChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeaderChainError::PrevHashMismatch { height } => {
+                write!(f, "Previous block hash mismatch at height {}", height)
+            }
+            HeaderChainError::BadBits {
+                height,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Bad bits at height {}: expected {:#010x}, got {:#010x}",
+                height, expected, actual
+            ),
+            HeaderChainError::InsufficientPoW { height } => {
+                write!(f, "Hash is not valid at height {}", height)
+            }
+            HeaderChainError::TimestampTooOld {
+                height,
+                time,
+                median_time_past,
+            } => write!(
+                f,
+                "Timestamp is not valid at height {}: {} is not after the median time past {}",
+                height, time, median_time_past
+            ),
+            HeaderChainError::TimestampTooFarInFuture {
+                height,
+                time,
+                max_time,
+            } => write!(
+                f,
+                "Timestamp is too far in the future at height {}: {} is after {}",
+                height, time, max_time
+            ),
+            HeaderChainError::ForkPointNotInChain { height } => {
+                write!(f, "Fork point at height {} is not in the chain", height)
+            }
+            HeaderChainError::InsufficientBranchWork => {
+                write!(f, "Branch does not have more work than the chain")
+            }
+        }
+    }
+}
+
+impl std::error::Error for HeaderChainError {}
+
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
 pub struct ChainState {
     pub block_height: u32,
@@ -175,111 +275,173 @@ pub struct ChainState {
 }
 
 impl ChainState {
-    pub fn new() -> Self {
+    pub fn new(params: &NetworkParams) -> Self {
         ChainState {
             block_height: u32::MAX,
             total_work: [0u8; 32],
             best_block_hash: [0u8; 32],
-            current_target_bits: NETWORK_CONSTANTS.max_bits,
+            current_target_bits: params.pow_limit_bits,
             epoch_start_time: 0,
             prev_11_timestamps: [0u32; 11],
             block_hashes_mmr: MMRGuest::new(),
         }
     }
 
-    pub fn apply_blocks(&mut self, block_headers: Vec<CircuitBlockHeader>) {
-        let mut current_target_bytes = if IS_REGTEST {
-            NETWORK_CONSTANTS.max_target.to_be_bytes()
+    /// Applies the block headers to the chain state, panicking on the first invalid header.
+    pub fn apply_blocks(&mut self, params: &NetworkParams, block_headers: Vec<CircuitBlockHeader>) {
+        if let Err(err) = self.try_apply_blocks(params, block_headers, None) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Applies the block headers to the chain state, returning the reason of the first invalid
+    /// header instead of panicking. The chain state is left untouched if any header is invalid.
+    ///
+    /// `network_time` is only known to the host; when given, headers more than
+    /// `MAX_FUTURE_BLOCK_TIME` seconds ahead of it are rejected.
+    pub fn try_apply_blocks(
+        &mut self,
+        params: &NetworkParams,
+        block_headers: Vec<CircuitBlockHeader>,
+        network_time: Option<u32>,
+    ) -> Result<(), HeaderChainError> {
+        let mut state = self.clone();
+        let mut current_target_bytes = if params.no_retargeting {
+            params.pow_limit
         } else {
-            bits_to_target(self.current_target_bits)
+            bits_to_target(state.current_target_bits)
         };
-        let mut current_work: U256 = U256::from_be_bytes(self.total_work);
+        let mut current_work: U256 = U256::from_be_bytes(state.total_work);
 
-        let mut last_block_time = if IS_TESTNET4 {
-            if self.block_height == u32::MAX {
+        let mut last_block_time = if params.allow_min_difficulty_blocks {
+            if state.block_height == u32::MAX {
                 0
             } else {
-                self.prev_11_timestamps[self.block_height as usize % 11]
+                state.prev_11_timestamps[state.block_height as usize % 11]
             }
         } else {
             0
         };
 
         for block_header in block_headers {
-            self.block_height = self.block_height.wrapping_add(1);
-
-            let (target_to_use, expected_bits, work_to_add) = if IS_TESTNET4 {
-                if block_header.time > last_block_time + 1200 {
-                    // If the block is an epoch block, then it still has to have the real target.
-                    if self.block_height % BLOCKS_PER_EPOCH == 0 {
-                        (
-                            current_target_bytes,
-                            self.current_target_bits,
-                            calculate_work(&current_target_bytes),
-                        )
-                    } else {
-                        (
-                            NETWORK_CONSTANTS.max_target_bytes,
-                            NETWORK_CONSTANTS.max_bits,
-                            MINIMUM_WORK_TESTNET,
-                        )
-                    }
-                } else {
-                    (
-                        current_target_bytes,
-                        self.current_target_bits,
-                        calculate_work(&current_target_bytes),
-                    )
-                }
+            state.block_height = state.block_height.wrapping_add(1);
+            let height = state.block_height;
+
+            // If the block is an epoch block, then it still has to have the real target.
+            let (target_to_use, expected_bits) = if params.allow_min_difficulty_blocks
+                && block_header.time > last_block_time + MIN_DIFFICULTY_BLOCK_SPACING
+                && state.block_height % params.blocks_per_epoch != 0
+            {
+                (params.pow_limit, params.pow_limit_bits)
             } else {
-                (
-                    current_target_bytes,
-                    self.current_target_bits,
-                    calculate_work(&current_target_bytes),
-                )
+                (current_target_bytes, state.current_target_bits)
             };
+            let work_to_add = calculate_work(&target_to_use);
 
             let new_block_hash = block_header.compute_block_hash();
 
-            assert_eq!(block_header.prev_block_hash, self.best_block_hash);
+            if block_header.prev_block_hash != state.best_block_hash {
+                return Err(HeaderChainError::PrevHashMismatch { height });
+            }
 
-            if IS_REGTEST {
-                assert_eq!(block_header.bits, NETWORK_CONSTANTS.max_bits);
+            let expected_bits = if params.no_retargeting {
+                params.pow_limit_bits
             } else {
-                assert_eq!(block_header.bits, expected_bits);
+                expected_bits
+            };
+            if block_header.bits != expected_bits {
+                return Err(HeaderChainError::BadBits {
+                    height,
+                    expected: expected_bits,
+                    actual: block_header.bits,
+                });
             }
 
-            check_hash_valid(&new_block_hash, &target_to_use);
+            if !check_hash_valid(&new_block_hash, &target_to_use) {
+                return Err(HeaderChainError::InsufficientPoW { height });
+            }
+
+            if !validate_timestamp(block_header.time, state.prev_11_timestamps) {
+                return Err(HeaderChainError::TimestampTooOld {
+                    height,
+                    time: block_header.time,
+                    median_time_past: median(state.prev_11_timestamps),
+                });
+            }
 
-            if !validate_timestamp(block_header.time, self.prev_11_timestamps) {
-                panic!("Timestamp is not valid");
+            if let Some(network_time) = network_time {
+                let max_time = network_time.saturating_add(MAX_FUTURE_BLOCK_TIME);
+                if block_header.time > max_time {
+                    return Err(HeaderChainError::TimestampTooFarInFuture {
+                        height,
+                        time: block_header.time,
+                        max_time,
+                    });
+                }
             }
 
-            self.block_hashes_mmr.append(new_block_hash);
-            self.best_block_hash = new_block_hash;
+            state.block_hashes_mmr.append(new_block_hash);
+            state.best_block_hash = new_block_hash;
             current_work = current_work.wrapping_add(&work_to_add);
 
-            if !IS_REGTEST && self.block_height % BLOCKS_PER_EPOCH == 0 {
-                self.epoch_start_time = block_header.time;
+            if !params.no_retargeting && state.block_height % params.blocks_per_epoch == 0 {
+                state.epoch_start_time = block_header.time;
             }
 
-            self.prev_11_timestamps[self.block_height as usize % 11] = block_header.time;
+            state.prev_11_timestamps[state.block_height as usize % 11] = block_header.time;
 
-            if IS_TESTNET4 {
+            if params.allow_min_difficulty_blocks {
                 last_block_time = block_header.time;
             }
 
-            if !IS_REGTEST && self.block_height % BLOCKS_PER_EPOCH == BLOCKS_PER_EPOCH - 1 {
+            if !params.no_retargeting
+                && state.block_height % params.blocks_per_epoch == params.blocks_per_epoch - 1
+            {
                 current_target_bytes = calculate_new_difficulty(
-                    self.epoch_start_time,
+                    params,
+                    state.epoch_start_time,
                     block_header.time,
-                    self.current_target_bits,
+                    state.current_target_bits,
                 );
-                self.current_target_bits = target_to_bits(&current_target_bytes);
+                state.current_target_bits = target_to_bits(&current_target_bytes);
             }
         }
 
-        self.total_work = current_work.to_be_bytes();
+        state.total_work = current_work.to_be_bytes();
+        *self = state;
+        Ok(())
+    }
+
+    /// Builds the chain state of a competing branch and returns it if the branch has more
+    /// cumulative work than `self`.
+    ///
+    /// `fork_point` is the chain state at the last block shared by both chains, and
+    /// `fork_point_proof` proves that block is in `self.block_hashes_mmr`. `branch` holds the
+    /// headers of the competing chain, starting right after the fork point.
+    pub fn try_reorg(
+        &self,
+        params: &NetworkParams,
+        fork_point: &ChainState,
+        fork_point_proof: &MMRInclusionProof,
+        branch: Vec<CircuitBlockHeader>,
+    ) -> Result<ChainState, HeaderChainError> {
+        if fork_point.block_height > self.block_height
+            || !self
+                .block_hashes_mmr
+                .verify_proof(fork_point.best_block_hash, fork_point_proof)
+        {
+            return Err(HeaderChainError::ForkPointNotInChain {
+                height: fork_point.block_height,
+            });
+        }
+
+        let mut new_state = fork_point.clone();
+        new_state.try_apply_blocks(params, branch, None)?;
+
+        if U256::from_be_bytes(new_state.total_work) <= U256::from_be_bytes(self.total_work) {
+            return Err(HeaderChainError::InsufficientBranchWork);
+        }
+        Ok(new_state)
     }
 }
 
@@ -320,36 +482,40 @@ fn target_to_bits(target: &[u8; 32]) -> u32 {
 }
 
 fn calculate_new_difficulty(
+    params: &NetworkParams,
     epoch_start_time: u32,
     last_timestamp: u32,
     current_target: u32,
 ) -> [u8; 32] {
+    let expected_epoch_timespan = params.expected_epoch_timespan;
     let mut actual_timespan = last_timestamp - epoch_start_time;
-    if actual_timespan < EXPECTED_EPOCH_TIMESPAN / 4 {
-        actual_timespan = EXPECTED_EPOCH_TIMESPAN / 4;
-    } else if actual_timespan > EXPECTED_EPOCH_TIMESPAN * 4 {
-        actual_timespan = EXPECTED_EPOCH_TIMESPAN * 4;
+    if actual_timespan < expected_epoch_timespan / 4 {
+        actual_timespan = expected_epoch_timespan / 4;
+    } else if actual_timespan > expected_epoch_timespan * 4 {
+        actual_timespan = expected_epoch_timespan * 4;
     }
 
     let new_target_bytes = bits_to_target(current_target);
     let mut new_target = U256::from_be_bytes(new_target_bytes)
         .wrapping_mul(&U256::from(actual_timespan))
-        .wrapping_div(&U256::from(EXPECTED_EPOCH_TIMESPAN));
+        .wrapping_div(&U256::from(expected_epoch_timespan));
 
-    if new_target > NETWORK_CONSTANTS.max_target {
-        new_target = NETWORK_CONSTANTS.max_target;
+    let pow_limit = params.pow_limit_u256();
+    if new_target > pow_limit {
+        new_target = pow_limit;
     }
     new_target.to_be_bytes()
 }
 
-fn check_hash_valid(hash: &[u8; 32], target_bytes: &[u8; 32]) {
+fn check_hash_valid(hash: &[u8; 32], target_bytes: &[u8; 32]) -> bool {
     for i in 0..32 {
         if hash[31 - i] < target_bytes[i] {
-            return;
+            return true;
         } else if hash[31 - i] > target_bytes[i] {
-            panic!("Hash is not valid");
+            return false;
         }
     }
+    true
 }
 
 fn calculate_work(target: &[u8; 32]) -> U256 {
@@ -363,6 +529,8 @@ fn calculate_work(target: &[u8; 32]) -> U256 {
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
 pub struct BlockHeaderCircuitOutput {
     pub method_id: [u32; 8],
+    /// `NetworkParams::hash` of the network the chain state was verified with.
+    pub network_params_hash: [u8; 32],
     pub chain_state: ChainState,
 }
 
@@ -372,6 +540,20 @@ pub struct BlockHeaderCircuitOutput {
 pub enum HeaderChainPrevProofType {
     GenesisBlock,
     PrevProof(BlockHeaderCircuitOutput),
+    /// Replaces a proven tip with a competing branch with more work.
+    Reorg(HeaderChainReorgProof),
+}
+
+/// The proofs a reorg starts from. The block headers of the circuit input are the competing
+/// branch, starting right after the fork point.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
+pub struct HeaderChainReorgProof {
+    /// The proven tip that is being replaced.
+    pub tip: BlockHeaderCircuitOutput,
+    /// A proof of the chain ending at the last block shared with the competing branch.
+    pub fork_point: BlockHeaderCircuitOutput,
+    /// Inclusion proof of the fork point block hash in the tip's MMR.
+    pub fork_point_proof: MMRInclusionProof,
 }
 
 /// The input of the header chain circuit.
@@ -385,6 +567,7 @@ pub struct HeaderChainCircuitInput {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mmr_native::MMRNative;
     use hex_literal::hex;
 
     // From block 800000 to 800015
@@ -872,6 +1055,49 @@ mod tests {
         }
     }
 
+    const MAINNET_GENESIS_HEADER: [u8; 80] = hex!("0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a29ab5f49ffff001d1dac2b7c");
+
+    #[test]
+    fn test_network_params() {
+        let networks = ["mainnet", "testnet4", "signet", "regtest"];
+        let params = networks
+            .iter()
+            .map(|network| NetworkParams::from_network_name(network).unwrap())
+            .collect::<Vec<NetworkParams>>();
+        assert_eq!(NetworkParams::from_network_name("testnet3"), None);
+
+        for (i, params_i) in params.iter().enumerate() {
+            assert_eq!(target_to_bits(&params_i.pow_limit), params_i.pow_limit_bits);
+            for params_j in &params[i + 1..] {
+                assert_ne!(params_i.hash(), params_j.hash());
+            }
+        }
+    }
+
+    #[test]
+    fn test_apply_genesis_block() {
+        let genesis_header = CircuitBlockHeader::try_from_slice(&MAINNET_GENESIS_HEADER).unwrap();
+        let mut chain_state = ChainState::new(&NetworkParams::MAINNET);
+
+        chain_state.apply_blocks(&NetworkParams::MAINNET, vec![genesis_header.clone()]);
+
+        assert_eq!(chain_state.block_height, 0);
+        assert_eq!(
+            chain_state.best_block_hash,
+            genesis_header.compute_block_hash()
+        );
+        assert_eq!(chain_state.current_target_bits, 0x1D00FFFF);
+    }
+
+    #[test]
+    #[should_panic(expected = "Bad bits at height 0: expected 0x207fffff, got 0x1d00ffff")]
+    fn test_apply_genesis_block_with_other_network_params() {
+        let genesis_header = CircuitBlockHeader::try_from_slice(&MAINNET_GENESIS_HEADER).unwrap();
+        let mut chain_state = ChainState::new(&NetworkParams::REGTEST);
+
+        chain_state.apply_blocks(&NetworkParams::REGTEST, vec![genesis_header]);
+    }
+
     #[test]
     fn test_median() {
         let arr = [3, 7, 2, 10, 1, 5, 9, 4, 8, 6, 11];
@@ -922,7 +1148,187 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Hash is not valid")]
+    fn test_try_apply_blocks_errors() {
+        let genesis_header = CircuitBlockHeader::try_from_slice(&MAINNET_GENESIS_HEADER).unwrap();
+        let initial_state = ChainState::new(&NetworkParams::MAINNET);
+        let mut chain_state = initial_state.clone();
+
+        assert_eq!(
+            chain_state.try_apply_blocks(
+                &NetworkParams::REGTEST,
+                vec![genesis_header.clone()],
+                None
+            ),
+            Err(HeaderChainError::BadBits {
+                height: 0,
+                expected: 0x207FFFFF,
+                actual: 0x1D00FFFF,
+            })
+        );
+
+        let mut low_work_header = genesis_header.clone();
+        low_work_header.nonce += 1;
+        assert_eq!(
+            chain_state.try_apply_blocks(&NetworkParams::MAINNET, vec![low_work_header], None),
+            Err(HeaderChainError::InsufficientPoW { height: 0 })
+        );
+
+        let network_time = genesis_header.time - MAX_FUTURE_BLOCK_TIME - 1;
+        assert_eq!(
+            chain_state.try_apply_blocks(
+                &NetworkParams::MAINNET,
+                vec![genesis_header.clone()],
+                Some(network_time)
+            ),
+            Err(HeaderChainError::TimestampTooFarInFuture {
+                height: 0,
+                time: genesis_header.time,
+                max_time: genesis_header.time - 1,
+            })
+        );
+
+        // A failed batch must leave the chain state untouched.
+        assert_eq!(
+            chain_state.try_apply_blocks(
+                &NetworkParams::MAINNET,
+                vec![genesis_header.clone(), genesis_header.clone()],
+                None
+            ),
+            Err(HeaderChainError::PrevHashMismatch { height: 1 })
+        );
+        assert_eq!(chain_state, initial_state);
+
+        assert_eq!(
+            chain_state.try_apply_blocks(
+                &NetworkParams::MAINNET,
+                vec![genesis_header.clone()],
+                Some(genesis_header.time)
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            chain_state.best_block_hash,
+            genesis_header.compute_block_hash()
+        );
+
+        // After blocks 0..=10 the median time past is the timestamp of block 5.
+        let params = NetworkParams::REGTEST;
+        let time = 1_700_000_000;
+        let chain = mine_regtest_branch([0u8; 32], time, 11, 0);
+        let mut chain_state = ChainState::new(&params);
+        chain_state.apply_blocks(&params, chain.clone());
+        let tip_hash = chain[10].compute_block_hash();
+        for block_time in [time + 4, time + 5] {
+            assert_eq!(
+                chain_state.try_apply_blocks(
+                    &params,
+                    vec![mine_regtest_block(tip_hash, block_time, 0)],
+                    None
+                ),
+                Err(HeaderChainError::TimestampTooOld {
+                    height: 11,
+                    time: block_time,
+                    median_time_past: time + 5,
+                })
+            );
+        }
+        assert_eq!(
+            chain_state.try_apply_blocks(
+                &params,
+                vec![mine_regtest_block(tip_hash, time + 6, 0)],
+                None
+            ),
+            Ok(())
+        );
+    }
+
+    /// Mines a regtest block on top of `prev_block_hash`; `salt` tells sibling blocks apart.
+    fn mine_regtest_block(prev_block_hash: [u8; 32], time: u32, salt: u8) -> CircuitBlockHeader {
+        let mut header = CircuitBlockHeader {
+            version: 4,
+            prev_block_hash,
+            merkle_root: [salt; 32],
+            time,
+            bits: NetworkParams::REGTEST.pow_limit_bits,
+            nonce: 0,
+        };
+        while !check_hash_valid(
+            &header.compute_block_hash(),
+            &NetworkParams::REGTEST.pow_limit,
+        ) {
+            header.nonce += 1;
+        }
+        header
+    }
+
+    fn mine_regtest_branch(
+        mut prev_block_hash: [u8; 32],
+        start_time: u32,
+        length: u32,
+        salt: u8,
+    ) -> Vec<CircuitBlockHeader> {
+        (0..length)
+            .map(|i| {
+                let header = mine_regtest_block(prev_block_hash, start_time + i, salt);
+                prev_block_hash = header.compute_block_hash();
+                header
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reorg() {
+        let params = NetworkParams::REGTEST;
+        let time = 1_700_000_000;
+
+        // Blocks 0..=4 of the proven chain.
+        let chain = mine_regtest_branch([0u8; 32], time, 5, 0);
+        let mut fork_point = ChainState::new(&params);
+        fork_point.apply_blocks(&params, chain[..3].to_vec());
+        let mut tip = fork_point.clone();
+        tip.apply_blocks(&params, chain[3..].to_vec());
+
+        let mut mmr_native = MMRNative::new();
+        for header in &chain {
+            mmr_native.append(header.compute_block_hash());
+        }
+        let (_, fork_point_proof) = mmr_native.generate_proof(2);
+
+        // A competing branch forking after block 2 with the same length does not replace the tip.
+        let fork_hash = chain[2].compute_block_hash();
+        let short_branch = mine_regtest_branch(fork_hash, time + 3, 2, 1);
+        assert_eq!(
+            tip.try_reorg(&params, &fork_point, &fork_point_proof, short_branch),
+            Err(HeaderChainError::InsufficientBranchWork)
+        );
+
+        // A longer one does.
+        let branch = mine_regtest_branch(fork_hash, time + 3, 3, 1);
+        let new_tip = tip
+            .try_reorg(&params, &fork_point, &fork_point_proof, branch.clone())
+            .unwrap();
+        assert_eq!(new_tip.block_height, 5);
+        assert_eq!(new_tip.best_block_hash, branch[2].compute_block_hash());
+
+        let mut expected = fork_point.clone();
+        expected.apply_blocks(&params, branch.clone());
+        assert_eq!(new_tip, expected);
+
+        // The fork point has to be a block of the proven chain.
+        let (_, wrong_proof) = mmr_native.generate_proof(1);
+        assert_eq!(
+            tip.try_reorg(&params, &fork_point, &wrong_proof, branch.clone()),
+            Err(HeaderChainError::ForkPointNotInChain { height: 2 })
+        );
+        let mut other_fork_point = ChainState::new(&params);
+        other_fork_point.apply_blocks(&params, mine_regtest_branch([0u8; 32], time, 3, 2));
+        assert_eq!(
+            tip.try_reorg(&params, &other_fork_point, &fork_point_proof, branch),
+            Err(HeaderChainError::ForkPointNotInChain { height: 2 })
+        );
+    }
+
+    #[test]
     fn test_hash_check_fail() {
         let block_headers = BLOCK_HEADERS
             .iter()
@@ -934,13 +1340,12 @@ mod tests {
             .map(|header| header.compute_block_hash())
             .collect::<Vec<[u8; 32]>>();
 
-        // The validation is expected to panic
-        check_hash_valid(
+        assert!(!check_hash_valid(
             &first_15_hashes[0],
             &U256::from_be_hex("00000000FFFF0000000000000000000000000000000000000000000000000000")
                 .wrapping_div(&(U256::ONE << 157))
                 .to_be_bytes(),
-        );
+        ));
     }
 
     #[test]
@@ -956,7 +1361,10 @@ mod tests {
             .collect::<Vec<[u8; 32]>>();
 
         for (i, hash) in first_15_hashes.into_iter().enumerate() {
-            check_hash_valid(&hash, &bits_to_target(block_headers[i].bits));
+            assert!(check_hash_valid(
+                &hash,
+                &bits_to_target(block_headers[i].bits)
+            ));
         }
     }
 
@@ -986,7 +1394,12 @@ mod tests {
     #[test]
     fn test_difficulty_adjustments() {
         for (start_time, end_time, start_target, end_target) in DIFFICULTY_ADJUSTMENTS {
-            let new_target_bytes = calculate_new_difficulty(start_time, end_time, start_target);
+            let new_target_bytes = calculate_new_difficulty(
+                &NetworkParams::MAINNET,
+                start_time,
+                end_time,
+                start_target,
+            );
             let bits = target_to_bits(&new_target_bytes);
             assert_eq!(bits, end_target);
         }
@@ -1,37 +1,60 @@
 use header_chain::{
     BlockHeaderCircuitOutput, ChainState, HeaderChainCircuitInput, HeaderChainPrevProofType,
+    NETWORK_PARAMS,
 };
 use zkvm::ZkvmGuest;
 
 pub mod header_chain;
 pub mod mmr_guest;
 pub mod mmr_native;
+#[cfg(feature = "mmr-store")]
+pub mod mmr_store;
 pub mod utils;
 pub mod zkvm;
 pub use risc0_zkvm;
 
 /// The main entry point of the header chain circuit.
 pub fn header_chain_circuit(guest: &impl ZkvmGuest) {
-    let start = risc0_zkvm::guest::env::cycle_count();
-
     let input: HeaderChainCircuitInput = guest.read_from_host();
     // println!("Detected network: {:?}", NETWORK_TYPE);
-    // println!("NETWORK_CONSTANTS: {:?}", NETWORK_CONSTANTS);
-    let mut chain_state = match input.prev_proof {
-        HeaderChainPrevProofType::GenesisBlock => ChainState::new(),
+    // println!("NETWORK_PARAMS: {:?}", NETWORK_PARAMS);
+    let network_params_hash = NETWORK_PARAMS.hash();
+    let chain_state = match input.prev_proof {
+        HeaderChainPrevProofType::GenesisBlock => {
+            let mut chain_state = ChainState::new(&NETWORK_PARAMS);
+            chain_state.apply_blocks(&NETWORK_PARAMS, input.block_headers);
+            chain_state
+        }
         HeaderChainPrevProofType::PrevProof(prev_proof) => {
             assert_eq!(prev_proof.method_id, input.method_id);
+            assert_eq!(prev_proof.network_params_hash, network_params_hash);
             guest.verify(input.method_id, &prev_proof);
-            prev_proof.chain_state
+            let mut chain_state = prev_proof.chain_state;
+            chain_state.apply_blocks(&NETWORK_PARAMS, input.block_headers);
+            chain_state
+        }
+        HeaderChainPrevProofType::Reorg(reorg_proof) => {
+            for prev_proof in [&reorg_proof.tip, &reorg_proof.fork_point] {
+                assert_eq!(prev_proof.method_id, input.method_id);
+                assert_eq!(prev_proof.network_params_hash, network_params_hash);
+                guest.verify(input.method_id, prev_proof);
+            }
+            reorg_proof
+                .tip
+                .chain_state
+                .try_reorg(
+                    &NETWORK_PARAMS,
+                    &reorg_proof.fork_point.chain_state,
+                    &reorg_proof.fork_point_proof,
+                    input.block_headers,
+                )
+                .unwrap_or_else(|err| panic!("{}", err))
         }
     };
 
-    chain_state.apply_blocks(input.block_headers);
-
     guest.commit(&BlockHeaderCircuitOutput {
         method_id: input.method_id,
+        network_params_hash,
         chain_state,
     });
-    let end = risc0_zkvm::guest::env::cycle_count();
-    println!("Header chain circuit took {:?} cycles", end - start);
 }
@@ -57,19 +57,23 @@ impl MMRNative {
 
     /// Generates a proof for a given index. Returns the leaf as well.
     pub fn generate_proof(&self, index: u32) -> ([u8; 32], MMRInclusionProof) {
-        if self.nodes[0].len() == 0 {
+        self.generate_proof_for_size(index, self.nodes[0].len() as u32)
+    }
+
+    /// Generates a proof for a given index against the MMR of the first `size` leaves, e.g. the
+    /// `MMRGuest` of a chain state that is behind the native MMR. Returns the leaf as well.
+    pub fn generate_proof_for_size(&self, index: u32, size: u32) -> ([u8; 32], MMRInclusionProof) {
+        if size == 0 || self.nodes[0].len() == 0 {
             panic!("MMR is empty");
         }
-        if self.nodes[0].len() <= index as usize {
+        if size <= index || self.nodes[0].len() < size as usize {
             panic!("Index out of bounds");
         }
         let mut proof: Vec<[u8; 32]> = vec![];
         let mut current_index = index;
         let mut current_level = 0;
         // Returns the subtree proof for the subroot.
-        while !(current_index == self.nodes[current_level].len() as u32 - 1
-            && self.nodes[current_level].len() % 2 == 1)
-        {
+        while !(current_index == (size >> current_level) - 1 && (size >> current_level) % 2 == 1) {
             let sibling_index = if current_index % 2 == 0 {
                 current_index + 1
             } else {
@@ -79,26 +83,11 @@ impl MMRNative {
             current_index = current_index / 2;
             current_level += 1;
         }
-        let (subroot_idx, internal_idx) = self.get_helpers_from_index(index);
+        let (subroot_idx, internal_idx) = get_helpers_from_index(index, size);
         let mmr_proof = MMRInclusionProof::new(subroot_idx, internal_idx, proof);
         (self.nodes[0][index as usize], mmr_proof)
     }
 
-    /// Given an index, returns the subroot index (which subtree the index is in), subtree size, and internal index (of the subtree that the index belongs to).
-    fn get_helpers_from_index(&self, index: u32) -> (usize, u32) {
-        let xor = (self.nodes[0].len() as u32) ^ index;
-        let xor_leading_digit = 31 - xor.leading_zeros() as usize;
-        let internal_idx = index & ((1 << xor_leading_digit) - 1);
-        let leading_zeros_size = 31 - (self.nodes[0].len() as u32).leading_zeros() as usize;
-        let mut subtree_idx = 0;
-        for i in xor_leading_digit + 1..=leading_zeros_size {
-            if self.nodes[0].len() & (1 << i) != 0 {
-                subtree_idx += 1;
-            }
-        }
-        (subtree_idx, internal_idx)
-    }
-
     /// Verifies an inclusion proof against the current MMR root.
     pub fn verify_proof(&self, leaf: [u8; 32], mmr_proof: &MMRInclusionProof) -> bool {
         println!("NATIVE: inclusion_proof: {:?}", mmr_proof);
@@ -111,6 +100,21 @@ impl MMRNative {
     }
 }
 
+/// Given an index and the MMR size, returns the subroot index (which subtree the index is in) and internal index (of the subtree that the index belongs to).
+pub(crate) fn get_helpers_from_index(index: u32, size: u32) -> (usize, u32) {
+    let xor = size ^ index;
+    let xor_leading_digit = 31 - xor.leading_zeros() as usize;
+    let internal_idx = index & ((1 << xor_leading_digit) - 1);
+    let leading_zeros_size = 31 - size.leading_zeros() as usize;
+    let mut subtree_idx = 0;
+    for i in xor_leading_digit + 1..=leading_zeros_size {
+        if size & (1 << i) != 0 {
+            subtree_idx += 1;
+        }
+    }
+    (subtree_idx, internal_idx)
+}
+
 #[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
 pub struct MMRInclusionProof {
     pub subroot_idx: usize,
@@ -181,6 +185,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_mmr_native_proof_for_size() {
+        let mut mmr_native = MMRNative::new();
+        let mut mmr_guest = MMRGuest::new();
+        let mut guests = vec![];
+
+        for i in 0..42 {
+            mmr_native.append([i as u8; 32]);
+            mmr_guest.append([i as u8; 32]);
+            guests.push(mmr_guest.clone());
+        }
+
+        for (size, mmr_guest) in (1..=42).zip(guests) {
+            for j in 0..size {
+                let (leaf, mmr_proof) = mmr_native.generate_proof_for_size(j, size);
+                assert_eq!(leaf, [j as u8; 32]);
+                assert!(
+                    mmr_guest.verify_proof(leaf, &mmr_proof),
+                    "Failed to verify proof for leaf {} in guest MMR of size {}",
+                    j,
+                    size
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_mmr_crosscheck() {
         let mut mmr_native = MMRNative::new();
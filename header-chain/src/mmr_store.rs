@@ -0,0 +1,280 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    mmr_guest::MMRGuest,
+    mmr_native::{get_helpers_from_index, MMRInclusionProof},
+    utils::hash_pair,
+};
+
+const NODE_SIZE: u64 = 32;
+
+/// Append-only MMR persisted on disk, for when `MMRNative` would not fit in memory.
+///
+/// The nodes are stored in post-order in a single file, so appending a leaf only writes the leaf
+/// and the parents it completes at the end of the file, and a proof reads one node per level.
+/// The number of leaves is committed to a separate file by `sync`; anything written after the
+/// last `sync` is cut off when the store is opened again.
+#[derive(Debug)]
+pub struct MMRStore {
+    file: File,
+    size_path: PathBuf,
+    size: u32,
+    subroots: Vec<[u8; 32]>,
+}
+
+impl MMRStore {
+    /// Opens the store at `path`, creating it if it does not exist.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let size_path = path.with_extension("size");
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        let size = match fs::read(&size_path) {
+            Ok(bytes) => u32::from_le_bytes(bytes.try_into().map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidData, "Invalid MMR size file")
+            })?),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => 0,
+            Err(err) => return Err(err),
+        };
+
+        let committed_len = node_count(size) * NODE_SIZE;
+        if file.metadata()?.len() < committed_len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "MMR file is shorter than its committed size",
+            ));
+        }
+        // Drop the nodes of leaves appended after the last sync.
+        file.set_len(committed_len)?;
+
+        let mut store = MMRStore {
+            file,
+            size_path,
+            size,
+            subroots: vec![],
+        };
+        store.subroots = store.subroots_for_size(size)?;
+        Ok(store)
+    }
+
+    /// Returns the number of leaves.
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Appends a new leaf to the MMR. The leaf is persisted by the next `sync`.
+    pub fn append(&mut self, leaf: [u8; 32]) -> io::Result<()> {
+        let mut nodes = vec![leaf];
+        let mut current = leaf;
+        let mut size = self.size;
+        while size % 2 == 1 {
+            let sibling = self.subroots.pop().unwrap();
+            current = hash_pair(sibling, current);
+            nodes.push(current);
+            size /= 2;
+        }
+
+        self.file
+            .seek(SeekFrom::Start(node_count(self.size) * NODE_SIZE))?;
+        self.file.write_all(&nodes.concat())?;
+        self.subroots.push(current);
+        self.size += 1;
+        Ok(())
+    }
+
+    /// Makes every appended leaf durable.
+    pub fn sync(&mut self) -> io::Result<()> {
+        self.file.sync_data()?;
+
+        // Replace the size file atomically, so a crash leaves either the old or the new size.
+        let tmp_path = self.size_path.with_extension("size.tmp");
+        let mut tmp_file = File::create(&tmp_path)?;
+        tmp_file.write_all(&self.size.to_le_bytes())?;
+        tmp_file.sync_all()?;
+        fs::rename(&tmp_path, &self.size_path)?;
+
+        // The rename, and the creation of the MMR file, are only durable once the directory
+        // holding them is synced.
+        let dir = match self.size_path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        File::open(dir)?.sync_all()
+    }
+
+    /// Returns the subroots of the MMR, in the same order as `MMRGuest::subroots`.
+    pub fn subroots(&self) -> &[[u8; 32]] {
+        &self.subroots
+    }
+
+    /// Returns the `MMRGuest` with the same subroots.
+    pub fn to_guest(&self) -> MMRGuest {
+        MMRGuest {
+            subroots: self.subroots.clone(),
+            size: self.size,
+        }
+    }
+
+    /// Checks that `mmr_guest` is the MMR of the first `mmr_guest.size` leaves of the store.
+    pub fn is_consistent_with(&self, mmr_guest: &MMRGuest) -> io::Result<bool> {
+        if mmr_guest.size > self.size {
+            return Ok(false);
+        }
+        Ok(self.subroots_for_size(mmr_guest.size)? == mmr_guest.subroots)
+    }
+
+    /// Generates a proof for a given index. Returns the leaf as well.
+    pub fn generate_proof(&self, index: u32) -> io::Result<([u8; 32], MMRInclusionProof)> {
+        self.generate_proof_for_size(index, self.size)
+    }
+
+    /// Generates a proof for a given index against the MMR of the first `size` leaves.
+    /// Returns the leaf as well.
+    pub fn generate_proof_for_size(
+        &self,
+        index: u32,
+        size: u32,
+    ) -> io::Result<([u8; 32], MMRInclusionProof)> {
+        if size <= index || self.size < size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Index out of bounds",
+            ));
+        }
+        let leaf_pos = node_count(index);
+        let mut proof: Vec<[u8; 32]> = vec![];
+        let mut current_pos = leaf_pos;
+        let mut current_index = index;
+        let mut current_level = 0;
+        // Returns the subtree proof for the subroot.
+        while !(current_index == (size >> current_level) - 1 && (size >> current_level) % 2 == 1) {
+            let subtree_nodes = (1u64 << (current_level + 1)) - 1;
+            if current_index % 2 == 0 {
+                let sibling_pos = current_pos + subtree_nodes;
+                proof.push(self.read_node(sibling_pos)?);
+                current_pos = sibling_pos + 1;
+            } else {
+                proof.push(self.read_node(current_pos - subtree_nodes)?);
+                current_pos += 1;
+            }
+            current_index /= 2;
+            current_level += 1;
+        }
+        let (subroot_idx, internal_idx) = get_helpers_from_index(index, size);
+        let mmr_proof = MMRInclusionProof::new(subroot_idx, internal_idx, proof);
+        Ok((self.read_node(leaf_pos)?, mmr_proof))
+    }
+
+    /// Reads the subroots of the MMR of the first `size` leaves, from the largest subtree.
+    fn subroots_for_size(&self, size: u32) -> io::Result<Vec<[u8; 32]>> {
+        let mut subroots = vec![];
+        let mut subtree_start = 0;
+        for level in (0..32).rev() {
+            if size & (1 << level) != 0 {
+                let subtree_nodes = (1u64 << (level + 1)) - 1;
+                subroots.push(self.read_node(subtree_start + subtree_nodes - 1)?);
+                subtree_start += subtree_nodes;
+            }
+        }
+        Ok(subroots)
+    }
+
+    fn read_node(&self, pos: u64) -> io::Result<[u8; 32]> {
+        let mut file = &self.file;
+        let mut node = [0u8; 32];
+        file.seek(SeekFrom::Start(pos * NODE_SIZE))?;
+        file.read_exact(&mut node)?;
+        Ok(node)
+    }
+}
+
+/// Number of nodes of an MMR with `size` leaves, which is also the position of the next leaf.
+fn node_count(size: u32) -> u64 {
+    2 * size as u64 - size.count_ones() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mmr_native::MMRNative;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_mmr_store_crosscheck() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("mmr");
+        let mut mmr_store = MMRStore::open(&path).unwrap();
+        let mut mmr_native = MMRNative::new();
+        let mut mmr_guest = MMRGuest::new();
+
+        for i in 0..42 {
+            let leaf = [i as u8; 32];
+            mmr_store.append(leaf).unwrap();
+            mmr_native.append(leaf);
+            mmr_guest.append(leaf);
+
+            assert_eq!(mmr_store.to_guest(), mmr_guest);
+            assert!(mmr_store.is_consistent_with(&mmr_guest).unwrap());
+
+            for j in 0..=i {
+                let (leaf, mmr_proof) = mmr_store.generate_proof(j).unwrap();
+                assert_eq!((leaf, mmr_proof.clone()), mmr_native.generate_proof(j));
+                assert!(mmr_guest.verify_proof(leaf, &mmr_proof));
+            }
+        }
+        let mut other_guest = MMRGuest::new();
+        other_guest.append([1; 32]);
+        assert!(!mmr_store.is_consistent_with(&other_guest).unwrap());
+        assert!(mmr_store.generate_proof(42).is_err());
+    }
+
+    #[test]
+    fn test_mmr_store_reopen() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("mmr");
+        let mut mmr_guest = MMRGuest::new();
+        {
+            let mut mmr_store = MMRStore::open(&path).unwrap();
+            for i in 0..21 {
+                mmr_store.append([i as u8; 32]).unwrap();
+                mmr_guest.append([i as u8; 32]);
+            }
+            mmr_store.sync().unwrap();
+
+            // Not synced, so lost on reopen.
+            mmr_store.append([21; 32]).unwrap();
+        }
+        // A torn write of a node.
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(&[0xff; 7])
+            .unwrap();
+
+        let mut mmr_store = MMRStore::open(&path).unwrap();
+        assert_eq!(mmr_store.size(), 21);
+        assert_eq!(mmr_store.to_guest(), mmr_guest);
+
+        for i in 21..42 {
+            mmr_store.append([i as u8; 32]).unwrap();
+            mmr_guest.append([i as u8; 32]);
+        }
+        mmr_store.sync().unwrap();
+        let mmr_store = MMRStore::open(&path).unwrap();
+        assert_eq!(mmr_store.to_guest(), mmr_guest);
+        for j in 0..42 {
+            let (leaf, mmr_proof) = mmr_store.generate_proof(j).unwrap();
+            assert!(mmr_guest.verify_proof(leaf, &mmr_proof));
+        }
+    }
+}
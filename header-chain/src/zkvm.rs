@@ -1,4 +1,4 @@
-use std::io::Write;
+use std::{cell::RefCell, io::Cursor, io::Write};
 
 use borsh::BorshDeserialize;
 use risc0_zkvm::guest::env::{self};
@@ -52,3 +52,285 @@ impl ZkvmGuest for Risc0Guest {
         env::verify(method_id, &borsh::to_vec(output).unwrap()).unwrap();
     }
 }
+
+/// Runs circuits natively, without the zkVM. Circuits are registered by method ID, which takes
+/// the place of the ELF in `prove`. Proofs are not proven, only their journals are recorded.
+#[derive(Debug, Clone, Default)]
+pub struct NativeHost {
+    input: RefCell<Vec<u8>>,
+    assumptions: RefCell<Vec<Proof>>,
+    circuits: Vec<([u32; 8], fn(&NativeGuest))>,
+}
+
+impl NativeHost {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the circuit to run when proving `method_id`.
+    pub fn with_circuit(mut self, method_id: [u32; 8], circuit: fn(&NativeGuest)) -> Self {
+        self.circuits.push((method_id, circuit));
+        self
+    }
+}
+
+impl ZkvmHost for NativeHost {
+    fn write<T: borsh::BorshSerialize>(&self, value: &T) {
+        borsh::to_writer(&mut *self.input.borrow_mut(), value)
+            .expect("Serialization to vec is infallible");
+    }
+
+    fn add_assumption(&self, proof: Proof) {
+        self.assumptions.borrow_mut().push(proof);
+    }
+
+    /// Runs the circuit registered for the method ID `elf` and consumes the input and assumptions.
+    fn prove(&self, elf: &[u32]) -> Proof {
+        let method_id: [u32; 8] = elf.try_into().expect("Method ID should be 8 words");
+        let (_, circuit) = self
+            .circuits
+            .iter()
+            .find(|(id, _)| *id == method_id)
+            .expect("No circuit registered for the method ID");
+
+        let guest = NativeGuest::new(self.input.take(), self.assumptions.take());
+        circuit(&guest);
+        Proof {
+            method_id,
+            journal: guest.journal(),
+        }
+    }
+}
+
+/// The guest side of `NativeHost`, backed by in-memory buffers.
+#[derive(Debug, Clone)]
+pub struct NativeGuest {
+    input: RefCell<Cursor<Vec<u8>>>,
+    assumptions: Vec<Proof>,
+    journal: RefCell<Vec<u8>>,
+}
+
+impl NativeGuest {
+    pub fn new(input: Vec<u8>, assumptions: Vec<Proof>) -> Self {
+        Self {
+            input: RefCell::new(Cursor::new(input)),
+            assumptions,
+            journal: RefCell::new(vec![]),
+        }
+    }
+
+    /// Returns everything committed so far.
+    pub fn journal(&self) -> Vec<u8> {
+        self.journal.borrow().clone()
+    }
+}
+
+impl ZkvmGuest for NativeGuest {
+    fn read_from_host<T: borsh::BorshDeserialize>(&self) -> T {
+        BorshDeserialize::deserialize_reader(&mut *self.input.borrow_mut())
+            .expect("Failed to deserialize input from host")
+    }
+
+    fn commit<T: borsh::BorshSerialize>(&self, item: &T) {
+        borsh::to_writer(&mut *self.journal.borrow_mut(), item)
+            .expect("Serialization to vec is infallible");
+    }
+
+    fn verify<T: borsh::BorshSerialize>(&self, method_id: [u32; 8], output: &T) {
+        let journal = borsh::to_vec(output).unwrap();
+        assert!(
+            self.assumptions
+                .iter()
+                .any(|proof| proof.method_id == method_id && proof.journal == journal),
+            "No assumption for method ID {:?} with the given journal",
+            method_id
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh::BorshDeserialize;
+    use hex_literal::hex;
+
+    use super::*;
+    use crate::{
+        header_chain::{
+            BlockHeaderCircuitOutput, ChainState, CircuitBlockHeader, HeaderChainCircuitInput,
+            HeaderChainPrevProofType, HeaderChainReorgProof, NETWORK_PARAMS,
+        },
+        header_chain_circuit,
+        mmr_native::MMRNative,
+    };
+
+    const METHOD_ID: [u32; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    // Mainnet block headers from 0 to 2
+    const MAINNET_BLOCK_HEADERS: [[u8; 80]; 3] = [
+        hex!("0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a29ab5f49ffff001d1dac2b7c"),
+        hex!("010000006fe28c0ab6f1b372c1a6a246ae63f74f931e8365e15a089c68d6190000000000982051fd1e4ba744bbbe680e1fee14677ba1a3c3540bf7b1cdb606e857233e0e61bc6649ffff001d01e36299"),
+        hex!("010000004860eb18bf1b1620e37e9490fc8a427514416fd75159ab86688e9a8300000000d5fdcc541e25de1c7a5addedf24858b8bb665c9f36ef744ee42c316022c90f9bb0bc6649ffff001d08d2bd61"),
+    ];
+
+    fn block_headers() -> Vec<CircuitBlockHeader> {
+        MAINNET_BLOCK_HEADERS
+            .iter()
+            .map(|header| CircuitBlockHeader::try_from_slice(header).unwrap())
+            .collect()
+    }
+
+    fn prove_from_genesis(
+        host: &NativeHost,
+        block_headers: &[CircuitBlockHeader],
+    ) -> (Proof, BlockHeaderCircuitOutput) {
+        host.write(&HeaderChainCircuitInput {
+            method_id: METHOD_ID,
+            prev_proof: HeaderChainPrevProofType::GenesisBlock,
+            block_headers: block_headers.to_vec(),
+        });
+        let proof = host.prove(&METHOD_ID);
+        let output = BlockHeaderCircuitOutput::try_from_slice(&proof.journal).unwrap();
+        (proof, output)
+    }
+
+    /// Writes the input of a reorg of the chain of `tip_headers` to `branch`, forking after the
+    /// block at the height of `fork_point`.
+    fn write_reorg_input(
+        host: &NativeHost,
+        tip: BlockHeaderCircuitOutput,
+        fork_point: BlockHeaderCircuitOutput,
+        tip_headers: &[CircuitBlockHeader],
+        branch: &[CircuitBlockHeader],
+    ) {
+        let mut mmr_native = MMRNative::new();
+        for header in tip_headers {
+            mmr_native.append(header.compute_block_hash());
+        }
+        let (_, fork_point_proof) = mmr_native.generate_proof(fork_point.chain_state.block_height);
+
+        host.write(&HeaderChainCircuitInput {
+            method_id: METHOD_ID,
+            prev_proof: HeaderChainPrevProofType::Reorg(HeaderChainReorgProof {
+                tip,
+                fork_point,
+                fork_point_proof,
+            }),
+            block_headers: branch.to_vec(),
+        });
+    }
+
+    #[test]
+    fn test_native_recursive_header_chain_proof() {
+        let host = NativeHost::new().with_circuit(METHOD_ID, header_chain_circuit);
+        let block_headers = block_headers();
+
+        host.write(&HeaderChainCircuitInput {
+            method_id: METHOD_ID,
+            prev_proof: HeaderChainPrevProofType::GenesisBlock,
+            block_headers: block_headers[..2].to_vec(),
+        });
+        let proof = host.prove(&METHOD_ID);
+        let output = BlockHeaderCircuitOutput::try_from_slice(&proof.journal).unwrap();
+        assert_eq!(output.chain_state.block_height, 1);
+
+        host.write(&HeaderChainCircuitInput {
+            method_id: METHOD_ID,
+            prev_proof: HeaderChainPrevProofType::PrevProof(output),
+            block_headers: block_headers[2..].to_vec(),
+        });
+        host.add_assumption(proof);
+        let proof = host.prove(&METHOD_ID);
+        let output = BlockHeaderCircuitOutput::try_from_slice(&proof.journal).unwrap();
+
+        let mut chain_state = ChainState::new(&NETWORK_PARAMS);
+        chain_state.apply_blocks(&NETWORK_PARAMS, block_headers);
+        assert_eq!(output.method_id, METHOD_ID);
+        assert_eq!(output.network_params_hash, NETWORK_PARAMS.hash());
+        assert_eq!(output.chain_state, chain_state);
+    }
+
+    #[test]
+    #[should_panic(expected = "No assumption for method ID")]
+    fn test_native_proof_without_assumption() {
+        let host = NativeHost::new().with_circuit(METHOD_ID, header_chain_circuit);
+        let block_headers = block_headers();
+
+        host.write(&HeaderChainCircuitInput {
+            method_id: METHOD_ID,
+            prev_proof: HeaderChainPrevProofType::GenesisBlock,
+            block_headers: block_headers[..2].to_vec(),
+        });
+        let proof = host.prove(&METHOD_ID);
+        let output = BlockHeaderCircuitOutput::try_from_slice(&proof.journal).unwrap();
+
+        host.write(&HeaderChainCircuitInput {
+            method_id: METHOD_ID,
+            prev_proof: HeaderChainPrevProofType::PrevProof(output),
+            block_headers: block_headers[2..].to_vec(),
+        });
+        host.prove(&METHOD_ID);
+    }
+
+    #[test]
+    fn test_native_reorg_proof() {
+        let host = NativeHost::new().with_circuit(METHOD_ID, header_chain_circuit);
+        let block_headers = block_headers();
+
+        // The branch after the genesis block has more work than the tip at block 1.
+        let (tip_proof, tip) = prove_from_genesis(&host, &block_headers[..2]);
+        let (fork_point_proof, fork_point) = prove_from_genesis(&host, &block_headers[..1]);
+        write_reorg_input(
+            &host,
+            tip,
+            fork_point,
+            &block_headers[..2],
+            &block_headers[1..],
+        );
+        host.add_assumption(tip_proof);
+        host.add_assumption(fork_point_proof);
+        let proof = host.prove(&METHOD_ID);
+        let output = BlockHeaderCircuitOutput::try_from_slice(&proof.journal).unwrap();
+
+        let mut chain_state = ChainState::new(&NETWORK_PARAMS);
+        chain_state.apply_blocks(&NETWORK_PARAMS, block_headers);
+        assert_eq!(output.method_id, METHOD_ID);
+        assert_eq!(output.network_params_hash, NETWORK_PARAMS.hash());
+        assert_eq!(output.chain_state, chain_state);
+    }
+
+    #[test]
+    #[should_panic(expected = "Branch does not have more work than the chain")]
+    fn test_native_reorg_proof_with_less_work() {
+        let host = NativeHost::new().with_circuit(METHOD_ID, header_chain_circuit);
+        let block_headers = block_headers();
+
+        let (tip_proof, tip) = prove_from_genesis(&host, &block_headers);
+        let (fork_point_proof, fork_point) = prove_from_genesis(&host, &block_headers[..1]);
+        write_reorg_input(&host, tip, fork_point, &block_headers, &block_headers[1..2]);
+        host.add_assumption(tip_proof);
+        host.add_assumption(fork_point_proof);
+        host.prove(&METHOD_ID);
+    }
+
+    #[test]
+    #[should_panic(expected = "No assumption for method ID")]
+    fn test_native_reorg_proof_with_unproven_fork_point() {
+        let host = NativeHost::new().with_circuit(METHOD_ID, header_chain_circuit);
+        let block_headers = block_headers();
+
+        // The fork point does not match the journal of its proof.
+        let (tip_proof, tip) = prove_from_genesis(&host, &block_headers[..2]);
+        let (fork_point_proof, mut fork_point) = prove_from_genesis(&host, &block_headers[..1]);
+        fork_point.chain_state.total_work = tip.chain_state.total_work;
+        write_reorg_input(
+            &host,
+            tip,
+            fork_point,
+            &block_headers[..2],
+            &block_headers[1..],
+        );
+        host.add_assumption(tip_proof);
+        host.add_assumption(fork_point_proof);
+        host.prove(&METHOD_ID);
+    }
+}
@@ -0,0 +1,288 @@
+use std::{
+    fmt,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use final_spv::final_spv_circuit;
+use header_chain::{
+    header_chain_circuit,
+    zkvm::{NativeGuest, Proof},
+};
+use risc0_groth16::Seal;
+use risc0_zkvm::{
+    default_executor, default_prover, sha::Digestible, ExecutorEnv, FakeReceipt, InnerReceipt,
+    ProverOpts, Receipt, ReceiptClaim,
+};
+
+use crate::{
+    calculate_groth16_output,
+    docker::{is_docker_installed, is_x86_architecture, stark_to_succinct},
+    image_id,
+};
+
+#[derive(Debug)]
+pub enum BackendError {
+    /// The file is not a guest ELF.
+    InvalidElf(String),
+    /// The guest failed to execute or to be proven.
+    Prove(String),
+    /// The backend can't wrap receipts into Groth16 proofs.
+    WrapUnsupported(&'static str),
+    /// The receipt is not succinct, or the Groth16 prover failed.
+    Wrap(String),
+    /// The receipt has no claim, or its claim is pruned.
+    InvalidReceipt(String),
+    /// The receipt is a proof of another guest than the one it is wrapped for.
+    ImageIdMismatch {
+        expected: [u32; 8],
+        actual: [u32; 8],
+    },
+    /// The guest has no native circuit, only `header-chain` and `final-spv` have one.
+    UnknownGuest(String),
+    /// There is no backend with this name.
+    UnknownBackend(String),
+}
+
+impl fmt::Display for BackendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for BackendError {}
+
+/// Proves guest programs and wraps their receipts into the Groth16 proofs verified by BitVM.
+pub trait ProverBackend {
+    /// Proves `elf` with `input` as its stdin. `assumptions` resolve the `env::verify` calls of
+    /// the guest.
+    fn prove(
+        &self,
+        elf: &[u8],
+        input: &[u8],
+        assumptions: Vec<Receipt>,
+    ) -> Result<Receipt, BackendError>;
+
+    /// Wraps the succinct `receipt` of the guest `image_id` into a Groth16 proof. Returns the
+    /// seal and the 31-byte public output.
+    fn wrap(&self, receipt: &Receipt, image_id: [u32; 8])
+        -> Result<(Seal, [u8; 31]), BackendError>;
+}
+
+/// Proves with the local risc0 prover. Can't wrap receipts, see `DockerBackend`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Risc0Backend;
+
+impl ProverBackend for Risc0Backend {
+    fn prove(
+        &self,
+        elf: &[u8],
+        input: &[u8],
+        assumptions: Vec<Receipt>,
+    ) -> Result<Receipt, BackendError> {
+        let env = executor_env(input, assumptions)?;
+        default_prover()
+            .prove_with_opts(env, elf, &ProverOpts::succinct())
+            .map(|prove_info| prove_info.receipt)
+            .map_err(|err| BackendError::Prove(err.to_string()))
+    }
+
+    fn wrap(
+        &self,
+        _receipt: &Receipt,
+        _image_id: [u32; 8],
+    ) -> Result<(Seal, [u8; 31]), BackendError> {
+        Err(BackendError::WrapUnsupported(
+            "The local backend can't wrap receipts, use the Docker backend",
+        ))
+    }
+}
+
+/// Proves with the local risc0 prover and wraps receipts with the Groth16 prover image.
+/// Wrapping requires Docker on x86.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DockerBackend;
+
+impl ProverBackend for DockerBackend {
+    fn prove(
+        &self,
+        elf: &[u8],
+        input: &[u8],
+        assumptions: Vec<Receipt>,
+    ) -> Result<Receipt, BackendError> {
+        Risc0Backend.prove(elf, input, assumptions)
+    }
+
+    fn wrap(
+        &self,
+        receipt: &Receipt,
+        image_id: [u32; 8],
+    ) -> Result<(Seal, [u8; 31]), BackendError> {
+        check_image_id(receipt, image_id)?;
+        if !is_x86_architecture() {
+            return Err(BackendError::WrapUnsupported(
+                "Wrapping receipts is only supported on x86",
+            ));
+        }
+        if !is_docker_installed() {
+            return Err(BackendError::WrapUnsupported(
+                "Wrapping receipts requires Docker",
+            ));
+        }
+        let succinct_receipt = receipt
+            .inner
+            .succinct()
+            .map_err(|err| BackendError::Wrap(err.to_string()))?
+            .clone();
+        Ok(stark_to_succinct(succinct_receipt, &receipt.journal.bytes))
+    }
+}
+
+/// Deterministic backend for development and CI, like `RISC0_DEV_MODE`.
+///
+/// Guests are executed without being proven and fake receipts are returned. Circuits registered
+/// with `with_circuit` run natively instead of in the executor, so they don't need an up-to-date
+/// ELF, only one to derive the image ID from. Wrapping returns an all-zero seal with the public
+/// output the Groth16 prover would commit to.
+#[derive(Debug, Clone, Default)]
+pub struct MockBackend {
+    circuits: Vec<([u32; 8], fn(&NativeGuest))>,
+}
+
+impl MockBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the circuit to run natively when proving the guest `image_id`.
+    pub fn with_circuit(mut self, image_id: [u32; 8], circuit: fn(&NativeGuest)) -> Self {
+        self.circuits.push((image_id, circuit));
+        self
+    }
+
+    /// Registers the native circuit of `guest` (`header-chain` or `final-spv`) to run when
+    /// proving `elf`.
+    pub fn with_guest(self, guest: &str, elf: &[u8]) -> Result<Self, BackendError> {
+        let circuit: fn(&NativeGuest) = match guest {
+            "header-chain" => header_chain_circuit,
+            "final-spv" => final_spv_circuit,
+            _ => return Err(BackendError::UnknownGuest(guest.to_string())),
+        };
+        Ok(self.with_circuit(image_id(elf)?, circuit))
+    }
+}
+
+impl ProverBackend for MockBackend {
+    fn prove(
+        &self,
+        elf: &[u8],
+        input: &[u8],
+        assumptions: Vec<Receipt>,
+    ) -> Result<Receipt, BackendError> {
+        let image_id = image_id(elf)?;
+
+        let journal = match self.circuits.iter().find(|(id, _)| *id == image_id) {
+            Some((_, circuit)) => {
+                let assumptions = assumptions
+                    .iter()
+                    .map(|receipt| {
+                        Ok(Proof {
+                            method_id: receipt_image_id(receipt)?,
+                            journal: receipt.journal.bytes.clone(),
+                        })
+                    })
+                    .collect::<Result<Vec<Proof>, BackendError>>()?;
+                let guest = NativeGuest::new(input.to_vec(), assumptions);
+                // The circuits panic on invalid inputs, like the guests do.
+                catch_unwind(AssertUnwindSafe(|| circuit(&guest)))
+                    .map_err(|_| BackendError::Prove("The native circuit panicked".to_string()))?;
+                guest.journal()
+            }
+            None => {
+                let env = executor_env(input, assumptions)?;
+                default_executor()
+                    .execute(env, elf)
+                    .map_err(|err| BackendError::Prove(err.to_string()))?
+                    .journal
+                    .bytes
+            }
+        };
+
+        let claim = ReceiptClaim::ok(image_id, journal.clone());
+        Ok(Receipt::new(
+            InnerReceipt::Fake(FakeReceipt::new(claim)),
+            journal,
+        ))
+    }
+
+    fn wrap(
+        &self,
+        receipt: &Receipt,
+        image_id: [u32; 8],
+    ) -> Result<(Seal, [u8; 31]), BackendError> {
+        check_image_id(receipt, image_id)?;
+        let seal = Seal {
+            a: vec![vec![0; 32]; 2],
+            b: vec![vec![vec![0; 32]; 2]; 2],
+            c: vec![vec![0; 32]; 2],
+        };
+        Ok((
+            seal,
+            calculate_groth16_output(image_id, &receipt.journal.bytes),
+        ))
+    }
+}
+
+/// Returns the backend named `name`: `local`, `docker` or `mock`. The mock backend runs the
+/// native circuits of the `(guest, elf)` pairs of `guests`, see `MockBackend::with_guest`.
+pub fn backend_from_name(
+    name: &str,
+    guests: &[(&str, &[u8])],
+) -> Result<Box<dyn ProverBackend>, BackendError> {
+    match name {
+        "local" => Ok(Box::new(Risc0Backend)),
+        "docker" => Ok(Box::new(DockerBackend)),
+        "mock" => {
+            let mut backend = MockBackend::new();
+            for (guest, elf) in guests {
+                backend = backend.with_guest(guest, elf)?;
+            }
+            Ok(Box::new(backend))
+        }
+        _ => Err(BackendError::UnknownBackend(name.to_string())),
+    }
+}
+
+/// Returns the image ID of the guest that produced `receipt`.
+pub fn receipt_image_id(receipt: &Receipt) -> Result<[u32; 8], BackendError> {
+    let claim = receipt
+        .claim()
+        .map_err(|err| BackendError::InvalidReceipt(err.to_string()))?;
+    let claim = claim
+        .as_value()
+        .map_err(|_| BackendError::InvalidReceipt("The receipt claim is pruned".to_string()))?;
+    Ok(claim.pre.digest().as_words().try_into().unwrap())
+}
+
+fn check_image_id(receipt: &Receipt, image_id: [u32; 8]) -> Result<(), BackendError> {
+    let actual = receipt_image_id(receipt)?;
+    if actual != image_id {
+        return Err(BackendError::ImageIdMismatch {
+            expected: image_id,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+fn executor_env(
+    input: &[u8],
+    assumptions: Vec<Receipt>,
+) -> Result<ExecutorEnv<'static>, BackendError> {
+    let mut binding = ExecutorEnv::builder();
+    let mut env = binding.write_slice(input);
+    for receipt in assumptions {
+        env = env.add_assumption(receipt);
+    }
+    env.build()
+        .map_err(|err| BackendError::Prove(err.to_string()))
+}
@@ -0,0 +1,427 @@
+use bitcoin::{hashes::Hash, BlockHash, Txid};
+use borsh::BorshDeserialize;
+use clap::{arg, command, value_parser, ArgMatches, Command};
+use header_chain::header_chain::{BlockHeaderCircuitOutput, NetworkParams};
+use hex_conservative::DisplayHex;
+use risc0_zkvm::Receipt;
+use serde_json::{json, Value};
+use std::{error::Error, fs, io::Write, str::FromStr};
+
+use crate::{
+    backend::{backend_from_name, receipt_image_id, ProverBackend},
+    default_elf_path, default_headers_path, image_id, prove_final_spv, prove_header_chain,
+};
+
+const NETWORKS: [&str; 4] = ["mainnet", "testnet4", "signet", "regtest"];
+const BACKENDS: [&str; 3] = ["local", "docker", "mock"];
+
+pub fn get_command() -> Command {
+    command!()
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .after_help("Every command prints one JSON document to stdout: {\"command\":\"inspect\",\"status\":\"ok\",\"data\":{...},\"error\":null}. `data` is set if `status` is `ok`, `error` is set if `status` is `error`. Errors exit with code 1.")
+        .arg(
+            arg!(-n --network <NETWORK> "Bitcoin network the guests were built for")
+                .required(false)
+                .global(true)
+                .default_value("mainnet")
+                .value_parser(NETWORKS)
+                .env("BITCOIN_NETWORK"),
+        )
+        .arg(
+            arg!(-b --backend <BACKEND> "Proving backend (local, docker, mock)")
+                .required(false)
+                .global(true)
+                .default_value("local")
+                .value_parser(BACKENDS)
+                .env("PROVER_BACKEND"),
+        )
+        .subcommand(
+            Command::new("header-chain")
+                .about("Prove the next batch of block headers")
+                .arg(arg!(--headers <FILE> "Concatenated raw block headers from the genesis block. Defaults to prover/data/<NETWORK>-headers.bin").required(false))
+                .arg(arg!(--elf <FILE> "Header chain guest ELF. Defaults to prover/elfs/<NETWORK>-header-chain-guest.bin").required(false))
+                .arg(arg!(--"prev-proof" <FILE> "Header chain proof to resume from. Starts from the genesis block if omitted").required(false))
+                .arg(arg!(--"batch-size" <COUNT> "Number of headers to prove").value_parser(value_parser!(u32)))
+                .arg(arg!(-o --output <FILE> "Output receipt file")),
+        )
+        .subcommand(
+            Command::new("final-spv")
+                .about("Prove that a transaction is in a block of a proven header chain")
+                .arg(arg!(--headers <FILE> "Concatenated raw block headers from the genesis block. Defaults to prover/data/<NETWORK>-headers.bin").required(false))
+                .arg(arg!(--elf <FILE> "Final circuit guest ELF. Defaults to prover/elfs/<NETWORK>-final-spv-guest.bin").required(false))
+                .arg(arg!(--"header-chain-proof" <FILE> "Header chain proof of the chain containing the block"))
+                .arg(arg!(--block <FILE> "Raw block containing the transaction"))
+                .arg(arg!(--height <HEIGHT> "Height of the block").value_parser(value_parser!(u32)))
+                .arg(arg!(--txid <TXID> "Transaction ID").value_parser(Txid::from_str))
+                .arg(arg!(-o --output <FILE> "Output receipt file")),
+        )
+        .subcommand(
+            Command::new("wrap")
+                .about("Wrap a succinct receipt into a Groth16 proof")
+                .arg(arg!(--receipt <FILE> "Succinct receipt"))
+                .arg(arg!(-o --output <FILE> "Output Groth16 seal file")),
+        )
+        .subcommand(
+            Command::new("inspect")
+                .about("Decode the journal of a receipt")
+                .arg(arg!(--receipt <FILE> "Receipt")),
+        )
+        .subcommand(
+            Command::new("verify")
+                .about("Verify a receipt against a guest ELF")
+                .arg(arg!(--receipt <FILE> "Receipt"))
+                .arg(arg!(--elf <FILE> "Guest ELF the receipt should be a proof of")),
+        )
+}
+
+/// Runs the command and writes its JSON document to `writer`. Returns whether it succeeded.
+pub fn handle_command(matches: &ArgMatches, writer: &mut impl Write) -> bool {
+    let (name, sub_matches) = matches.subcommand().unwrap();
+    let network = matches.get_one::<String>("network").unwrap();
+    let result = match name {
+        "header-chain" => handle_header_chain(matches, sub_matches, network),
+        "final-spv" => handle_final_spv(matches, sub_matches, network),
+        "wrap" => handle_wrap(matches, sub_matches),
+        "inspect" => handle_inspect(sub_matches),
+        "verify" => handle_verify(sub_matches),
+        _ => unreachable!(),
+    };
+
+    let document = match &result {
+        Ok(data) => json!({ "command": name, "status": "ok", "data": data, "error": null }),
+        Err(err) => {
+            json!({ "command": name, "status": "error", "data": null, "error": err.to_string() })
+        }
+    };
+    writeln!(writer, "{}", document).is_ok() && result.is_ok()
+}
+
+fn handle_header_chain(
+    matches: &ArgMatches,
+    sub_matches: &ArgMatches,
+    network: &str,
+) -> Result<Value, Box<dyn Error>> {
+    let elf = read_elf(sub_matches, network, "header-chain")?;
+    let raw_headers = read_raw_headers(sub_matches, network)?;
+    let prev_receipt = sub_matches
+        .get_one::<String>("prev-proof")
+        .map(|path| read_receipt(path))
+        .transpose()?;
+    let batch_size = *sub_matches.get_one::<u32>("batch-size").unwrap();
+
+    let receipt = prove_header_chain(
+        get_backend(matches, &[("header-chain", elf.as_slice())])?.as_ref(),
+        network,
+        &elf,
+        &raw_headers,
+        prev_receipt,
+        batch_size,
+    )?;
+    write_receipt(sub_matches, &receipt)
+}
+
+fn handle_final_spv(
+    matches: &ArgMatches,
+    sub_matches: &ArgMatches,
+    network: &str,
+) -> Result<Value, Box<dyn Error>> {
+    let elf = read_elf(sub_matches, network, "final-spv")?;
+    let raw_headers = read_raw_headers(sub_matches, network)?;
+    let header_chain_receipt =
+        read_receipt(sub_matches.get_one::<String>("header-chain-proof").unwrap())?;
+    let raw_block = fs::read(sub_matches.get_one::<String>("block").unwrap())?;
+    let height = *sub_matches.get_one::<u32>("height").unwrap();
+    let txid = *sub_matches.get_one::<Txid>("txid").unwrap();
+
+    let receipt = prove_final_spv(
+        get_backend(matches, &[("final-spv", elf.as_slice())])?.as_ref(),
+        network,
+        &elf,
+        &raw_headers,
+        header_chain_receipt,
+        height,
+        &raw_block,
+        txid,
+    )?;
+    write_receipt(sub_matches, &receipt)
+}
+
+fn handle_wrap(matches: &ArgMatches, sub_matches: &ArgMatches) -> Result<Value, Box<dyn Error>> {
+    let receipt = read_receipt(sub_matches.get_one::<String>("receipt").unwrap())?;
+    let image_id = receipt_image_id(&receipt)?;
+    let (seal, output) = get_backend(matches, &[])?.wrap(&receipt, image_id)?;
+
+    let output_path = sub_matches.get_one::<String>("output").unwrap();
+    fs::write(output_path, serde_json::to_string_pretty(&seal)?)?;
+    Ok(json!({
+        "seal": output_path,
+        "image_id": image_id_json(image_id),
+        "output": output.to_lower_hex_string(),
+    }))
+}
+
+fn handle_inspect(sub_matches: &ArgMatches) -> Result<Value, Box<dyn Error>> {
+    let receipt = read_receipt(sub_matches.get_one::<String>("receipt").unwrap())?;
+    receipt_json(&receipt)
+}
+
+fn handle_verify(sub_matches: &ArgMatches) -> Result<Value, Box<dyn Error>> {
+    let receipt = read_receipt(sub_matches.get_one::<String>("receipt").unwrap())?;
+    let elf = fs::read(sub_matches.get_one::<String>("elf").unwrap())?;
+    let image_id = image_id(&elf)?;
+    receipt.verify(image_id).map_err(|err| {
+        format!(
+            "The receipt is not a valid proof of the guest {}: {}",
+            image_id_json(image_id),
+            err
+        )
+    })?;
+    Ok(json!({ "image_id": image_id_json(image_id) }))
+}
+
+/// Returns the backend selected by `--backend`. The mock backend runs the native circuits of the
+/// `(guest, elf)` pairs of `guests`.
+fn get_backend(
+    matches: &ArgMatches,
+    guests: &[(&str, &[u8])],
+) -> Result<Box<dyn ProverBackend>, Box<dyn Error>> {
+    Ok(backend_from_name(
+        matches.get_one::<String>("backend").unwrap(),
+        guests,
+    )?)
+}
+
+fn read_elf(
+    sub_matches: &ArgMatches,
+    network: &str,
+    guest: &str,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let path = sub_matches
+        .get_one::<String>("elf")
+        .cloned()
+        .unwrap_or_else(|| default_elf_path(network, guest));
+    fs::read(&path).map_err(|err| format!("Failed to read ELF file {}: {}", path, err).into())
+}
+
+fn read_raw_headers(sub_matches: &ArgMatches, network: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let path = sub_matches
+        .get_one::<String>("headers")
+        .cloned()
+        .unwrap_or_else(|| default_headers_path(network));
+    fs::read(&path).map_err(|err| format!("Failed to read headers file {}: {}", path, err).into())
+}
+
+fn read_receipt(path: &str) -> Result<Receipt, Box<dyn Error>> {
+    let bytes =
+        fs::read(path).map_err(|err| format!("Failed to read receipt file {}: {}", path, err))?;
+    Ok(Receipt::try_from_slice(&bytes)?)
+}
+
+fn write_receipt(sub_matches: &ArgMatches, receipt: &Receipt) -> Result<Value, Box<dyn Error>> {
+    let output_path = sub_matches.get_one::<String>("output").unwrap();
+    fs::write(output_path, borsh::to_vec(receipt)?)?;
+    let mut data = receipt_json(receipt)?;
+    data["receipt"] = output_path.as_str().into();
+    Ok(data)
+}
+
+/// Describes the receipt, decoding its journal if it is a header chain proof.
+fn receipt_json(receipt: &Receipt) -> Result<Value, Box<dyn Error>> {
+    let header_chain_output = BlockHeaderCircuitOutput::try_from_slice(&receipt.journal.bytes)
+        .ok()
+        .map(|output| header_chain_output_json(&output));
+    Ok(json!({
+        "image_id": image_id_json(receipt_image_id(receipt)?),
+        "journal": receipt.journal.bytes.to_lower_hex_string(),
+        "header_chain_output": header_chain_output,
+    }))
+}
+
+fn header_chain_output_json(output: &BlockHeaderCircuitOutput) -> Value {
+    let chain_state = &output.chain_state;
+    let network = NETWORKS.into_iter().find(|network| {
+        NetworkParams::from_network_name(network).unwrap().hash() == output.network_params_hash
+    });
+    json!({
+        "method_id": image_id_json(output.method_id),
+        "network": network,
+        "network_params_hash": output.network_params_hash.to_lower_hex_string(),
+        "block_height": chain_state.block_height,
+        "best_block_hash": BlockHash::from_byte_array(chain_state.best_block_hash).to_string(),
+        "total_work": chain_state.total_work.to_lower_hex_string(),
+        "current_target_bits": chain_state.current_target_bits,
+        "epoch_start_time": chain_state.epoch_start_time,
+        "mmr_size": chain_state.block_hashes_mmr.size,
+    })
+}
+
+fn image_id_json(image_id: [u32; 8]) -> String {
+    risc0_zkvm::sha::Digest::from(image_id)
+        .as_bytes()
+        .to_lower_hex_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command() {
+        get_command().debug_assert();
+
+        let matches = get_command()
+            .try_get_matches_from([
+                "prover",
+                "--network",
+                "regtest",
+                "header-chain",
+                "--batch-size",
+                "10",
+                "-o",
+                "out.bin",
+            ])
+            .unwrap();
+        assert_eq!(matches.get_one::<String>("network").unwrap(), "regtest");
+        let (name, sub_matches) = matches.subcommand().unwrap();
+        assert_eq!(name, "header-chain");
+        assert_eq!(*sub_matches.get_one::<u32>("batch-size").unwrap(), 10);
+
+        assert!(get_command()
+            .try_get_matches_from([
+                "prover",
+                "--network",
+                "testnet3",
+                "inspect",
+                "--receipt",
+                "r"
+            ])
+            .is_err());
+    }
+
+    /// Runs the command line and returns its exit status and JSON document.
+    fn run(args: &[&str]) -> (bool, Value) {
+        let matches = get_command()
+            .try_get_matches_from(std::iter::once("prover").chain(args.iter().copied()))
+            .unwrap();
+        let mut output = Vec::new();
+        let success = handle_command(&matches, &mut output);
+        (success, serde_json::from_slice(&output).unwrap())
+    }
+
+    #[test]
+    fn test_handle_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = |name: &str| dir.path().join(name).to_str().unwrap().to_string();
+
+        let (success, document) = run(&[
+            "--backend",
+            "mock",
+            "header-chain",
+            "--batch-size",
+            "3",
+            "-o",
+            &path("first_3.bin"),
+        ]);
+        assert!(success);
+        assert_eq!(document["command"], "header-chain");
+        assert_eq!(document["status"], "ok");
+        assert_eq!(document["error"], Value::Null);
+        assert_eq!(document["data"]["receipt"], path("first_3.bin"));
+        assert_eq!(
+            document["data"]["header_chain_output"]["network"],
+            "mainnet"
+        );
+        assert_eq!(document["data"]["header_chain_output"]["block_height"], 2);
+
+        let (success, document) = run(&["inspect", "--receipt", &path("first_3.bin")]);
+        assert!(success);
+        assert_eq!(document["data"]["header_chain_output"]["block_height"], 2);
+
+        let (success, document) = run(&[
+            "--backend",
+            "mock",
+            "wrap",
+            "--receipt",
+            &path("first_3.bin"),
+            "-o",
+            &path("seal.json"),
+        ]);
+        assert!(success);
+        assert_eq!(document["data"]["output"].as_str().unwrap().len(), 62);
+
+        // The local backend can't wrap receipts.
+        let (success, document) = run(&[
+            "--backend",
+            "local",
+            "wrap",
+            "--receipt",
+            &path("first_3.bin"),
+            "-o",
+            &path("seal.json"),
+        ]);
+        assert!(!success);
+        assert_eq!(document["status"], "error");
+        assert_eq!(document["data"], Value::Null);
+        assert!(document["error"]
+            .as_str()
+            .unwrap()
+            .contains("WrapUnsupported"));
+
+        // A mainnet proof can't be continued on regtest.
+        let (success, document) = run(&[
+            "--network",
+            "regtest",
+            "--backend",
+            "mock",
+            "header-chain",
+            "--prev-proof",
+            &path("first_3.bin"),
+            "--batch-size",
+            "3",
+            "-o",
+            &path("next_3.bin"),
+        ]);
+        assert!(!success);
+        assert_eq!(document["command"], "header-chain");
+        assert!(document["error"]
+            .as_str()
+            .unwrap()
+            .contains("NetworkMismatch"));
+
+        let (success, document) = run(&["inspect", "--receipt", &path("missing.bin")]);
+        assert!(!success);
+        assert_eq!(document["command"], "inspect");
+        assert!(document["error"]
+            .as_str()
+            .unwrap()
+            .contains("Failed to read receipt file"));
+
+        let (success, document) = run(&[
+            "verify",
+            "--receipt",
+            &path("first_3.bin"),
+            "--elf",
+            &path("seal.json"),
+        ]);
+        assert!(!success);
+        assert!(document["error"].as_str().unwrap().contains("InvalidElf"));
+
+        // Receipts of the mock backend are fake, so they don't verify outside of dev mode.
+        let (success, document) = run(&[
+            "verify",
+            "--receipt",
+            &path("first_3.bin"),
+            "--elf",
+            &default_elf_path("mainnet", "header-chain"),
+        ]);
+        assert!(!success);
+        assert_eq!(document["command"], "verify");
+        assert_eq!(document["status"], "error");
+        assert_eq!(document["data"], Value::Null);
+        assert!(document["error"]
+            .as_str()
+            .unwrap()
+            .contains("The receipt is not a valid proof of the guest"));
+    }
+}
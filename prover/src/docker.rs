@@ -165,7 +165,7 @@ pub fn stark_to_succinct(
     (proof_json.try_into().unwrap(), output_bytes)
 }
 
-fn is_docker_installed() -> bool {
+pub(crate) fn is_docker_installed() -> bool {
     Command::new("docker")
         .arg("--version")
         .output()
@@ -173,7 +173,7 @@ fn is_docker_installed() -> bool {
         .unwrap_or(false)
 }
 
-fn is_x86_architecture() -> bool {
+pub(crate) fn is_x86_architecture() -> bool {
     ARCH == "x86_64" || ARCH == "x86"
 }
 
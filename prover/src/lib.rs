@@ -1,132 +1,143 @@
+use bitcoin::Txid;
 use borsh::BorshDeserialize;
-use header_chain::header_chain::{
-    BlockHeaderCircuitOutput, CircuitBlockHeader, HeaderChainCircuitInput, HeaderChainPrevProofType,
+use final_spv::{
+    final_circuit::FinalSpvCircuitInput,
+    host::{HeaderChainHost, HostError},
 };
-use risc0_zkvm::{default_prover, ExecutorEnv};
-
+use header_chain::header_chain::{BlockHeaderCircuitOutput, NetworkParams};
 use risc0_circuit_recursion::control_id::BN254_IDENTITY_CONTROL_ID;
 use risc0_zkvm::{compute_image_id, sha::Digestible};
-use risc0_zkvm::{ProverOpts, Receipt, SuccinctReceiptVerifierParameters, SystemState};
+use risc0_zkvm::{Receipt, SuccinctReceiptVerifierParameters, SystemState};
 use sha2::Digest;
 use sha2::Sha256;
-use std::{env, fs};
+use std::fmt;
 
+pub mod backend;
+pub mod cli;
 pub mod docker;
 
-const HEADER_CHAIN_GUEST_ELF: &[u8] = {
-    match option_env!("BITCOIN_NETWORK") {
-        Some(network) if matches!(network.as_bytes(), b"mainnet") => {
-            include_bytes!("../elfs/mainnet-header-chain-guest.bin")
-        }
-        Some(network) if matches!(network.as_bytes(), b"testnet4") => {
-            include_bytes!("../elfs/testnet4-header-chain-guest.bin")
-        }
-        Some(network) if matches!(network.as_bytes(), b"signet") => {
-            include_bytes!("../elfs/signet-header-chain-guest.bin")
-        }
-        Some(network) if matches!(network.as_bytes(), b"regtest") => {
-            include_bytes!("../elfs/regtest-header-chain-guest.bin")
-        }
-        None => include_bytes!("../elfs/mainnet-header-chain-guest.bin"),
-        _ => panic!("Invalid path or ELF file"),
-    }
-};
+use backend::{BackendError, ProverBackend};
 
-const HEADERS: &[u8] = {
-    match option_env!("BITCOIN_NETWORK") {
-        Some(network) if matches!(network.as_bytes(), b"mainnet") => {
-            include_bytes!("../data/mainnet-headers.bin")
-        }
-        Some(network) if matches!(network.as_bytes(), b"testnet4") => {
-            include_bytes!("../data/testnet4-headers.bin")
-        }
-        Some(network) if matches!(network.as_bytes(), b"signet") => {
-            include_bytes!("../data/signet-headers.bin")
-        }
-        Some(network) if matches!(network.as_bytes(), b"regtest") => {
-            include_bytes!("../data/regtest-headers.bin")
-        }
-        None => include_bytes!("../data/mainnet-headers.bin"),
-        _ => panic!("Invalid network type"),
-    }
-};
+#[derive(Debug)]
+pub enum ProverError {
+    /// The headers, block or transaction can't be turned into a circuit input.
+    Host(HostError),
+    Backend(BackendError),
+    /// The receipt journal is not a header chain output.
+    InvalidHeaderChainProof(String),
+    UnknownNetwork(String),
+    /// The header chain proof was generated for another network than this one.
+    NetworkMismatch(String),
+}
 
-pub fn prove() {
-    // Parse command-line arguments
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 4 {
-        eprintln!("Usage: <program> <input_proof> <output_file_path> <batch_size>");
-        return;
+impl fmt::Display for ProverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
     }
+}
 
-    let input_proof = &args[1];
-    let output_file_path = &args[2];
-    let batch_size: usize = args[3].parse().expect("Batch size should be a number");
+impl std::error::Error for ProverError {}
 
-    let headers = HEADERS
-        .chunks(80)
-        .map(|header| CircuitBlockHeader::try_from_slice(header).unwrap())
-        .collect::<Vec<CircuitBlockHeader>>();
+impl From<HostError> for ProverError {
+    fn from(err: HostError) -> Self {
+        ProverError::Host(err)
+    }
+}
 
-    let HEADER_CHAIN_GUEST_ID: [u32; 8] = compute_image_id(HEADER_CHAIN_GUEST_ELF)
-        .unwrap()
-        .as_words()
-        .try_into()
-        .unwrap();
+impl From<BackendError> for ProverError {
+    fn from(err: BackendError) -> Self {
+        ProverError::Backend(err)
+    }
+}
 
-    // Set the previous proof type based on input_proof argument
-    let prev_receipt = if input_proof.to_lowercase() == "none" {
-        None
-    } else {
-        let proof_bytes = fs::read(input_proof).expect("Failed to read input proof file");
-        let receipt: Receipt = Receipt::try_from_slice(&proof_bytes).unwrap();
-        Some(receipt)
-    };
+/// Path of the guest ELF `guest` (`header-chain` or `final-spv`) built for the given network.
+pub fn default_elf_path(network: &str, guest: &str) -> String {
+    format!(
+        "{}/elfs/{}-{}-guest.bin",
+        env!("CARGO_MANIFEST_DIR"),
+        network,
+        guest
+    )
+}
 
-    let mut start = 0;
-    let prev_proof = match prev_receipt.clone() {
-        Some(receipt) => {
-            let output =
-                BlockHeaderCircuitOutput::try_from_slice(&receipt.journal.bytes.clone()).unwrap();
-            start = output.chain_state.block_height as usize + 1;
-            HeaderChainPrevProofType::PrevProof(output)
-        }
-        None => HeaderChainPrevProofType::GenesisBlock,
-    };
+/// Path of the serialized block headers of the given network.
+pub fn default_headers_path(network: &str) -> String {
+    format!(
+        "{}/data/{}-headers.bin",
+        env!("CARGO_MANIFEST_DIR"),
+        network
+    )
+}
 
-    // Prepare the input for the circuit
-    let input = HeaderChainCircuitInput {
-        method_id: HEADER_CHAIN_GUEST_ID,
-        prev_proof,
-        block_headers: headers[start..start + batch_size].to_vec(),
-    };
+/// Returns the image ID of the guest ELF.
+pub fn image_id(elf: &[u8]) -> Result<[u32; 8], BackendError> {
+    let image_id =
+        compute_image_id(elf).map_err(|err| BackendError::InvalidElf(err.to_string()))?;
+    Ok(image_id.as_words().try_into().unwrap())
+}
 
-    // Build ENV
-    let mut binding = ExecutorEnv::builder();
-    let mut env = binding.write_slice(&borsh::to_vec(&input).unwrap());
-    if let Some(receipt) = prev_receipt {
-        env = env.add_assumption(receipt);
+/// Decodes the output of a header chain proof, checking it was generated for `network`.
+fn header_chain_output(
+    receipt: &Receipt,
+    network: &str,
+) -> Result<BlockHeaderCircuitOutput, ProverError> {
+    let output = BlockHeaderCircuitOutput::try_from_slice(&receipt.journal.bytes)
+        .map_err(|err| ProverError::InvalidHeaderChainProof(err.to_string()))?;
+    let network_params = NetworkParams::from_network_name(network)
+        .ok_or_else(|| ProverError::UnknownNetwork(network.to_string()))?;
+    if output.network_params_hash != network_params.hash() {
+        return Err(ProverError::NetworkMismatch(network.to_string()));
     }
-    let env = env.build().unwrap();
-
-    // Obtain the default prover.
-    let prover = default_prover();
+    Ok(output)
+}
 
-    // Produce a receipt by proving the specified ELF binary.
-    let receipt = prover
-        .prove_with_opts(env, HEADER_CHAIN_GUEST_ELF, &ProverOpts::succinct())
-        .unwrap()
-        .receipt;
+/// Proves the next `batch_size` headers of `raw_headers` with the header chain guest `elf`, on
+/// top of `prev_receipt`, or from the genesis block if there is none.
+pub fn prove_header_chain(
+    backend: &dyn ProverBackend,
+    network: &str,
+    elf: &[u8],
+    raw_headers: &[u8],
+    prev_receipt: Option<Receipt>,
+    batch_size: u32,
+) -> Result<Receipt, ProverError> {
+    let host = HeaderChainHost::from_raw_headers(raw_headers)?;
+    let prev_output = prev_receipt
+        .as_ref()
+        .map(|receipt| header_chain_output(receipt, network))
+        .transpose()?;
+    let input = host.header_chain_input(image_id(elf)?, prev_output, batch_size)?;
 
-    // Extract journal of receipt
-    let output = BlockHeaderCircuitOutput::try_from_slice(&receipt.journal.bytes).unwrap();
+    Ok(backend.prove(
+        elf,
+        &borsh::to_vec(&input).unwrap(),
+        prev_receipt.into_iter().collect(),
+    )?)
+}
 
-    println!("Output: {:#?}", output.method_id);
+/// Proves with the final circuit guest `elf` that `txid` is in the raw block at `height` of the
+/// chain proven by `header_chain_receipt`.
+#[allow(clippy::too_many_arguments)]
+pub fn prove_final_spv(
+    backend: &dyn ProverBackend,
+    network: &str,
+    elf: &[u8],
+    raw_headers: &[u8],
+    header_chain_receipt: Receipt,
+    height: u32,
+    raw_block: &[u8],
+    txid: Txid,
+) -> Result<Receipt, ProverError> {
+    let host = HeaderChainHost::from_raw_headers(raw_headers)?;
+    let output = header_chain_output(&header_chain_receipt, network)?;
+    let input =
+        FinalSpvCircuitInput::Final(host.final_circuit_input(output, height, raw_block, txid)?);
 
-    // Save the receipt to the specified output file path
-    let receipt_bytes = borsh::to_vec(&receipt).unwrap();
-    fs::write(output_file_path, &receipt_bytes).expect("Failed to write receipt to output file");
-    println!("Receipt saved to {}", output_file_path);
+    Ok(backend.prove(
+        elf,
+        &borsh::to_vec(&input).unwrap(),
+        vec![header_chain_receipt],
+    )?)
 }
 
 /// Sha256(control_root, pre_state_digest, post_state_digest, id_bn254_fr)
@@ -160,17 +171,32 @@ pub fn calculate_succinct_output_prefix(method_id: &[u8]) -> [u8; 32] {
 
     result
 }
+
+/// Blake3(succinct output prefix, journal), truncated to the 31 bytes the Groth16 proof commits.
+pub fn calculate_groth16_output(image_id: [u32; 8], journal: &[u8]) -> [u8; 31] {
+    let image_id = risc0_zkp::core::digest::Digest::from(image_id);
+    let constants_digest = calculate_succinct_output_prefix(image_id.as_bytes());
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&constants_digest);
+    hasher.update(journal);
+    let output: [u8; 32] = hasher.finalize().into();
+    output[..31].try_into().unwrap()
+}
+
 #[cfg(test)]
 mod tests {
-    use bitcoin::hex::FromHex;
+    use backend::{backend_from_name, receipt_image_id, MockBackend, Risc0Backend};
+    use bitcoin::{hashes::Hash, hex::FromHex};
     use docker::stark_to_succinct;
     use final_spv::{
         final_circuit::FinalCircuitInput, merkle_tree::BitcoinMerkleTree, spv::SPV,
         transaction::CircuitTransaction,
     };
-    use header_chain::mmr_native::MMRNative;
+    use header_chain::{
+        header_chain::CircuitBlockHeader, header_chain_circuit, mmr_native::MMRNative,
+    };
     use hex_literal::hex;
-    use risc0_zkvm::compute_image_id;
+    use risc0_zkvm::{compute_image_id, default_prover, ExecutorEnv, ProverOpts};
 
     const MAINNET_BLOCK_HASHES: [[u8; 32]; 11] = [
         hex!("6fe28c0ab6f1b372c1a6a246ae63f74f931e8365e15a089c68d6190000000000"),
@@ -186,7 +212,215 @@ mod tests {
         hex!("e915d9a478e3adf3186c07c61a22228b10fd87df343c92782ecc052c00000000"),
     ];
 
+    const MAINNET_GENESIS_COINBASE: &str = "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000";
+
     use super::*;
+
+    // Fails when a guest changed without its ELF being rebuilt with `REPR_GUEST_BUILD=1`, or
+    // without the method ID in `final_spv` being updated to the rebuilt ELF.
+    #[test]
+    fn test_header_chain_guest_elfs_match_image_ids() {
+        for (network, expected_image_id) in [
+            ("mainnet", final_spv::MAINNET_HEADER_CHAIN_GUEST_ID),
+            ("testnet4", final_spv::TESTNET4_HEADER_CHAIN_GUEST_ID),
+            ("signet", final_spv::SIGNET_HEADER_CHAIN_GUEST_ID),
+            ("regtest", final_spv::REGTEST_HEADER_CHAIN_GUEST_ID),
+        ] {
+            let elf = std::fs::read(default_elf_path(network, "header-chain")).unwrap();
+            assert_eq!(
+                image_id(&elf).unwrap(),
+                expected_image_id,
+                "{network} header chain guest ELF does not match its method ID"
+            );
+        }
+    }
+
+    // The method IDs alone don't show that the ELFs are the current guests, so the guests are
+    // executed and their journals decoded with the current output layout. The final-spv guests
+    // read the tagged input and only accept proofs of the header chain guest they pin.
+    #[test]
+    fn test_guest_elfs_execute_current_circuits() {
+        // Without registered circuits, the mock backend executes the ELFs.
+        let backend = MockBackend::new();
+        for network in ["mainnet", "signet", "regtest"] {
+            let header_chain_elf =
+                std::fs::read(default_elf_path(network, "header-chain")).unwrap();
+            let final_spv_elf = std::fs::read(default_elf_path(network, "final-spv")).unwrap();
+            let raw_headers = std::fs::read(default_headers_path(network)).unwrap();
+
+            let header_chain_receipt =
+                prove_header_chain(&backend, network, &header_chain_elf, &raw_headers, None, 2)
+                    .unwrap();
+            let output = header_chain_output(&header_chain_receipt, network).unwrap();
+            assert_eq!(
+                output.method_id,
+                image_id(&header_chain_elf).unwrap(),
+                "{network}"
+            );
+            assert_eq!(output.chain_state.block_height, 1, "{network}");
+
+            // The genesis blocks of these networks share the coinbase transaction.
+            let mut raw_block = raw_headers[..80].to_vec();
+            raw_block.push(1);
+            raw_block.extend(Vec::<u8>::from_hex(MAINNET_GENESIS_COINBASE).unwrap());
+            let txid = bitcoin::consensus::deserialize::<bitcoin::Block>(&raw_block)
+                .unwrap()
+                .txdata[0]
+                .compute_txid();
+            let receipt = prove_final_spv(
+                &backend,
+                network,
+                &final_spv_elf,
+                &raw_headers,
+                header_chain_receipt,
+                0,
+                &raw_block,
+                txid,
+            )
+            .unwrap();
+            assert_eq!(receipt.journal.bytes.len(), 32, "{network}");
+        }
+    }
+
+    // Regenerates `data/mainnet_first_10.bin` after the mainnet header chain guest was rebuilt.
+    // Proving takes a while and needs the risc0 prover.
+    #[test]
+    #[ignore = "regenerates a data fixture"]
+    fn regenerate_mainnet_first_10() {
+        let elf = std::fs::read(default_elf_path("mainnet", "header-chain")).unwrap();
+        let raw_headers = std::fs::read(default_headers_path("mainnet")).unwrap();
+        let receipt = prove_header_chain(
+            &Risc0Backend,
+            "mainnet",
+            &elf,
+            &raw_headers,
+            None,
+            MAINNET_BLOCK_HASHES.len() as u32,
+        )
+        .unwrap();
+        std::fs::write(
+            format!("{}/data/mainnet_first_10.bin", env!("CARGO_MANIFEST_DIR")),
+            borsh::to_vec(&receipt).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_prove_header_chain_with_mock_backend() {
+        let elf = std::fs::read(default_elf_path("mainnet", "header-chain")).unwrap();
+        let raw_headers = std::fs::read(default_headers_path("mainnet")).unwrap();
+        let header_chain_guest_id = image_id(&elf).unwrap();
+        let backend = MockBackend::new().with_circuit(header_chain_guest_id, header_chain_circuit);
+        let prove = |prev_receipt, batch_size| {
+            prove_header_chain(
+                &backend,
+                "mainnet",
+                &elf,
+                &raw_headers,
+                prev_receipt,
+                batch_size,
+            )
+            .unwrap()
+        };
+
+        let receipt = prove(None, 5);
+        let output = BlockHeaderCircuitOutput::try_from_slice(&receipt.journal.bytes).unwrap();
+        assert_eq!(output.method_id, header_chain_guest_id);
+        assert_eq!(output.chain_state.block_height, 4);
+
+        let receipt = prove(Some(receipt), 6);
+        let output = BlockHeaderCircuitOutput::try_from_slice(&receipt.journal.bytes).unwrap();
+        assert_eq!(output.chain_state.block_height, 10);
+        assert_eq!(output.chain_state.best_block_hash, MAINNET_BLOCK_HASHES[10]);
+
+        // The mock proofs are deterministic.
+        let first = prove(None, 5);
+        let second = prove(None, 5);
+        assert_eq!(
+            borsh::to_vec(&first).unwrap(),
+            borsh::to_vec(&second).unwrap()
+        );
+
+        let (_, output) = backend.wrap(&receipt, header_chain_guest_id).unwrap();
+        assert_eq!(
+            output,
+            calculate_groth16_output(header_chain_guest_id, &receipt.journal.bytes)
+        );
+        assert!(matches!(
+            backend.wrap(&receipt, [0; 8]),
+            Err(BackendError::ImageIdMismatch { .. })
+        ));
+        assert!(matches!(
+            Risc0Backend.wrap(&receipt, header_chain_guest_id),
+            Err(BackendError::WrapUnsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_prove_final_spv_with_mock_backend() {
+        let header_chain_elf = std::fs::read(default_elf_path("mainnet", "header-chain")).unwrap();
+        let final_spv_elf = std::fs::read(default_elf_path("mainnet", "final-spv")).unwrap();
+        let raw_headers = std::fs::read(default_headers_path("mainnet")).unwrap();
+        let backend = backend_from_name(
+            "mock",
+            &[
+                ("header-chain", header_chain_elf.as_slice()),
+                ("final-spv", final_spv_elf.as_slice()),
+            ],
+        )
+        .unwrap();
+
+        let header_chain_receipt = prove_header_chain(
+            backend.as_ref(),
+            "mainnet",
+            &header_chain_elf,
+            &raw_headers,
+            None,
+            3,
+        )
+        .unwrap();
+
+        let mut raw_block = raw_headers[..80].to_vec();
+        raw_block.push(1);
+        raw_block.extend(Vec::<u8>::from_hex(MAINNET_GENESIS_COINBASE).unwrap());
+        let txid = bitcoin::consensus::deserialize::<bitcoin::Block>(&raw_block)
+            .unwrap()
+            .txdata[0]
+            .compute_txid();
+
+        let receipt = prove_final_spv(
+            backend.as_ref(),
+            "mainnet",
+            &final_spv_elf,
+            &raw_headers,
+            header_chain_receipt.clone(),
+            0,
+            &raw_block,
+            txid,
+        )
+        .unwrap();
+        assert_eq!(
+            receipt_image_id(&receipt).unwrap(),
+            image_id(&final_spv_elf).unwrap()
+        );
+        assert_eq!(receipt.journal.bytes.len(), 32);
+
+        // The final circuit rejects transactions that are not in the block.
+        assert!(matches!(
+            prove_final_spv(
+                backend.as_ref(),
+                "mainnet",
+                &final_spv_elf,
+                &raw_headers,
+                header_chain_receipt,
+                0,
+                &raw_block,
+                Txid::all_zeros(),
+            ),
+            Err(ProverError::Host(HostError::TxNotFound(_)))
+        ));
+    }
+
     // #[ignore = "This is to only test final proof generation"]
     /// Run this test only when build for the mainnet
     #[test]
@@ -210,7 +444,12 @@ mod tests {
         }
 
         let output = BlockHeaderCircuitOutput::try_from_slice(&receipt.journal.bytes).unwrap();
-        let tx: CircuitTransaction = CircuitTransaction(bitcoin::consensus::deserialize(&Vec::<u8>::from_hex("01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000").unwrap()).unwrap());
+        let tx: CircuitTransaction = CircuitTransaction(
+            bitcoin::consensus::deserialize(
+                &Vec::<u8>::from_hex(MAINNET_GENESIS_COINBASE).unwrap(),
+            )
+            .unwrap(),
+        );
         let block_header: CircuitBlockHeader = CircuitBlockHeader::try_from_slice(Vec::<u8>::from_hex("0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a29ab5f49ffff001d1dac2b7c").unwrap().as_slice()).unwrap();
         let bitcoin_merkle_tree: BitcoinMerkleTree = BitcoinMerkleTree::new(vec![tx.txid()]);
         let bitcoin_inclusion_proof = bitcoin_merkle_tree.generate_proof(0);
@@ -221,10 +460,10 @@ mod tests {
             block_header,
             mmr_inclusion_proof,
         );
-        let final_circuit_input: FinalCircuitInput = FinalCircuitInput {
+        let final_circuit_input = FinalSpvCircuitInput::Final(FinalCircuitInput {
             block_header_circuit_output: output,
             spv: spv,
-        };
+        });
         let env = ExecutorEnv::builder()
             .write_slice(&borsh::to_vec(&final_circuit_input).unwrap())
             .add_assumption(receipt)
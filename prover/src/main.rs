@@ -1,5 +1,11 @@
-use prover::prove;
+use prover::cli::{get_command, handle_command};
+use std::{io, process::ExitCode};
 
-fn main() {
-    prove();
+fn main() -> ExitCode {
+    let matches = get_command().get_matches();
+    if handle_command(&matches, &mut io::stdout()) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
 }